@@ -0,0 +1,281 @@
+//! Statistics about the ad breaks in a [`MediaPlaylist`].
+//!
+//! # Example
+//!
+//! ```
+//! use hls_m3u8::{ads, MediaPlaylist};
+//! use std::convert::TryFrom;
+//! use std::time::Duration;
+//!
+//! let playlist = MediaPlaylist::try_from(concat!(
+//!     "#EXTM3U\n",
+//!     "#EXT-X-TARGETDURATION:10\n",
+//!     "#EXTINF:10,\n",
+//!     "content1.ts\n",
+//!     "#EXT-X-DATERANGE:ID=\"ad1\",START-DATE=\"2020-01-01T00:00:10Z\",",
+//!     "PLANNED-DURATION=20,SCTE35-OUT=0xFC002F\n",
+//!     "#EXTINF:10,\n",
+//!     "ad1.ts\n",
+//!     "#EXTINF:10,\n",
+//!     "ad2.ts\n",
+//!     "#EXTINF:10,\n",
+//!     "content2.ts\n",
+//! ))?;
+//!
+//! let report = ads::report(&playlist);
+//!
+//! assert_eq!(report.breaks().len(), 1);
+//! assert_eq!(report.ad_duration(), Duration::from_secs(20));
+//! assert_eq!(report.content_duration(), Duration::from_secs(20));
+//! assert_eq!(
+//!     report.average_break_duration(),
+//!     Some(Duration::from_secs(20))
+//! );
+//! # Ok::<(), hls_m3u8::Error>(())
+//! ```
+use std::time::Duration;
+
+use shorthand::ShortHand;
+
+use crate::tags::CueMarker;
+use crate::MediaPlaylist;
+
+/// A single ad break.
+#[derive(ShortHand, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[shorthand(enable(must_use, copy), disable(set))]
+pub struct AdBreak {
+    /// The [`MediaSegment::number`] of the first segment of the break.
+    ///
+    /// [`MediaSegment::number`]: crate::MediaSegment::number
+    first_segment: usize,
+    /// The number of segments in the break.
+    segments: usize,
+    /// The sum of the segment durations in the break.
+    duration: Duration,
+}
+
+/// The ad load of a [`MediaPlaylist`], see [`report`].
+#[derive(ShortHand, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[shorthand(enable(must_use), disable(set))]
+pub struct AdReport {
+    /// All ad breaks in the order they appear in the playlist.
+    breaks: Vec<AdBreak>,
+    /// The total duration of all ad breaks.
+    #[shorthand(enable(copy))]
+    ad_duration: Duration,
+    /// The total duration of all segments, that are not part of an ad break.
+    #[shorthand(enable(copy))]
+    content_duration: Duration,
+}
+
+impl AdReport {
+    /// Returns the average duration of an ad break or `None` if there are no
+    /// breaks.
+    #[must_use]
+    pub fn average_break_duration(&self) -> Option<Duration> {
+        if self.breaks.is_empty() {
+            None
+        } else {
+            Some(self.ad_duration / self.breaks.len() as u32)
+        }
+    }
+}
+
+/// Computes the [`AdReport`] of a [`MediaPlaylist`].
+///
+/// An ad break starts at a segment with an [`ExtXDateRange`], that has an
+/// [`ExtXDateRange::scte35_out`] attribute. It ends before the next segment
+/// with an [`ExtXDateRange::scte35_in`] attribute, after the
+/// [`ExtXDateRange::duration`] (or [`ExtXDateRange::planned_duration`]) of
+/// the range has elapsed or at the end of the playlist.
+///
+/// The non-standard [`MediaSegment::cue_markers`] are handled like the
+/// equivalent date ranges (see [`CueMarker::from_date_range`]).
+///
+/// # Note
+///
+/// The cue markers are only parsed, if
+/// [`ParseOptions::parse_cue_markers`] is enabled. Otherwise they are kept
+/// in [`MediaPlaylist::unknown`] without their position, so they can not be
+/// considered.
+///
+/// [`MediaSegment::cue_markers`]: crate::MediaSegment::cue_markers
+/// [`CueMarker::from_date_range`]: crate::tags::CueMarker::from_date_range
+/// [`ParseOptions::parse_cue_markers`]: crate::ParseOptions::parse_cue_markers
+/// [`ExtXDateRange`]: crate::tags::ExtXDateRange
+/// [`ExtXDateRange::scte35_out`]: crate::tags::ExtXDateRange::scte35_out
+/// [`ExtXDateRange::scte35_in`]: crate::tags::ExtXDateRange::scte35_in
+/// [`ExtXDateRange::duration`]: crate::tags::ExtXDateRange::duration
+/// [`ExtXDateRange::planned_duration`]:
+/// crate::tags::ExtXDateRange::planned_duration
+#[must_use]
+pub fn report(playlist: &MediaPlaylist<'_>) -> AdReport {
+    let mut report = AdReport::default();
+    // the current break and its expected duration:
+    let mut current: Option<(AdBreak, Option<Duration>)> = None;
+
+    for segment in playlist.segments.values() {
+        let markers = segment.cue_markers.iter().cloned().chain(
+            segment
+                .date_range
+                .iter()
+                .filter_map(CueMarker::from_date_range),
+        );
+
+        for marker in markers {
+            match marker {
+                CueMarker::In => {
+                    report.breaks.extend(current.take().map(|(value, _)| value));
+                }
+                CueMarker::Out { duration } => {
+                    report.breaks.extend(current.take().map(|(value, _)| value));
+
+                    let ad_break = AdBreak {
+                        first_segment: segment.number,
+                        ..AdBreak::default()
+                    };
+
+                    current = Some((ad_break, duration));
+                }
+                _ => {}
+            }
+        }
+
+        let duration = segment.duration.duration();
+
+        if let Some((ad_break, expected)) = &mut current {
+            ad_break.segments += 1;
+            ad_break.duration += duration;
+            report.ad_duration += duration;
+
+            // allow the same rounding as for the target duration
+            if expected.is_some_and(|expected| {
+                ad_break.duration.as_secs_f64() + 0.5 > expected.as_secs_f64()
+            }) {
+                report.breaks.extend(current.take().map(|(value, _)| value));
+            }
+        } else {
+            report.content_duration += duration;
+        }
+    }
+
+    report.breaks.extend(current.map(|(value, _)| value));
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_report() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-MEDIA-SEQUENCE:10\n",
+            "#EXTINF:10,\n",
+            "content1.ts\n",
+            "#EXT-X-DATERANGE:ID=\"ad1\",START-DATE=\"2020-01-01T00:00:10Z\",",
+            "SCTE35-OUT=0xFC002F\n",
+            "#EXTINF:10,\n",
+            "ad1.ts\n",
+            "#EXTINF:5,\n",
+            "ad2.ts\n",
+            "#EXT-X-DATERANGE:ID=\"ad1\",START-DATE=\"2020-01-01T00:00:10Z\",",
+            "SCTE35-IN=0xFC002F\n",
+            "#EXTINF:10,\n",
+            "content2.ts\n",
+            "#EXT-X-DATERANGE:ID=\"ad2\",START-DATE=\"2020-01-01T00:00:35Z\",",
+            "DURATION=9.9,SCTE35-OUT=0xFC002F\n",
+            "#EXTINF:9.9,\n",
+            "ad3.ts\n",
+            "#EXTINF:10,\n",
+            "content3.ts\n",
+            "#EXT-X-DATERANGE:ID=\"ad3\",START-DATE=\"2020-01-01T00:00:55Z\",",
+            "SCTE35-OUT=0xFC002F\n",
+            "#EXTINF:10,\n",
+            "ad4.ts\n",
+        ))
+        .unwrap();
+
+        let report = report(&playlist);
+
+        assert_eq!(
+            report.breaks(),
+            &vec![
+                AdBreak {
+                    first_segment: 11,
+                    segments: 2,
+                    duration: Duration::from_secs(15),
+                },
+                AdBreak {
+                    first_segment: 14,
+                    segments: 1,
+                    duration: Duration::from_secs_f64(9.9),
+                },
+                AdBreak {
+                    first_segment: 16,
+                    segments: 1,
+                    duration: Duration::from_secs(10),
+                },
+            ]
+        );
+        assert_eq!(report.ad_duration(), Duration::from_secs_f64(34.9));
+        assert_eq!(report.content_duration(), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_no_breaks() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXTINF:10,\n",
+            "content1.ts\n",
+        ))
+        .unwrap();
+
+        let report = report(&playlist);
+
+        assert_eq!(report.breaks(), &vec![]);
+        assert_eq!(report.average_break_duration(), None);
+        assert_eq!(report.content_duration(), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_cue_markers() {
+        let mut options = crate::ParseOptions::new();
+        options.set_parse_cue_markers(true);
+
+        let (playlist, _) = options
+            .parse_media_playlist(concat!(
+                "#EXTM3U\n",
+                "#EXT-X-TARGETDURATION:10\n",
+                "#EXTINF:10,\n",
+                "content1.ts\n",
+                "#EXT-X-CUE-OUT:20\n",
+                "#EXTINF:10,\n",
+                "ad1.ts\n",
+                "#EXT-X-CUE-OUT-CONT:10/20\n",
+                "#EXTINF:10,\n",
+                "ad2.ts\n",
+                "#EXTINF:10,\n",
+                "content2.ts\n",
+            ))
+            .unwrap();
+
+        let report = report(&playlist);
+
+        assert_eq!(
+            report.breaks(),
+            &vec![AdBreak {
+                first_segment: 1,
+                segments: 2,
+                duration: Duration::from_secs(20),
+            }]
+        );
+        assert_eq!(report.content_duration(), Duration::from_secs(20));
+    }
+}
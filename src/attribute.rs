@@ -1,12 +1,42 @@
+//! Parsing of attribute lists (`KEY=VALUE,KEY="VALUE"`), which are used by
+//! most tags.
+//!
+//! This is the same parser, that is used for the tags of this crate, so
+//! custom tags (for example the [`MediaPlaylist::unknown`] tags) can be parsed
+//! consistently.
+//!
+//! [`MediaPlaylist::unknown`]: crate::MediaPlaylist::unknown
 use core::iter::FusedIterator;
 
+/// An iterator over the `(key, value)` pairs of an attribute list.
+///
+/// The pairs are separated by commas, that are not inside a quoted string, so
+/// quoted values may contain commas. Whitespace around keys and values is
+/// removed, but the quotes of a quoted value are kept, so it can be
+/// distinguished from an enumerated string.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::attribute::AttributePairs;
+///
+/// let mut pairs = AttributePairs::new("ID=\"a,b\",SCTE35-OUT=0xFC, DURATION=15.0");
+///
+/// assert_eq!(pairs.next(), Some(("ID", "\"a,b\"")));
+/// assert_eq!(pairs.next(), Some(("SCTE35-OUT", "0xFC")));
+/// assert_eq!(pairs.next(), Some(("DURATION", "15.0")));
+/// assert_eq!(pairs.next(), None);
+/// ```
 #[derive(Clone, Debug)]
-pub(crate) struct AttributePairs<'a> {
+pub struct AttributePairs<'a> {
     string: &'a str,
     index: usize,
 }
 
 impl<'a> AttributePairs<'a> {
+    /// Makes a new iterator over the attribute list `string` (without the
+    /// name of the tag).
+    #[must_use]
     pub const fn new(string: &'a str) -> Self { Self { string, index: 0 } }
 }
 
@@ -0,0 +1,494 @@
+//! A [`MasterPlaylist`] together with the [`MediaPlaylist`]s of its variant
+//! streams, which allows to compare the variants with each other.
+//!
+//! # Example
+//!
+//! ```
+//! use hls_m3u8::bundle::Bundle;
+//! use hls_m3u8::{MasterPlaylist, MediaPlaylist};
+//! use std::convert::TryFrom;
+//!
+//! let master = MasterPlaylist::try_from(concat!(
+//!     "#EXTM3U\n",
+//!     "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+//!     "low.m3u8\n",
+//!     "#EXT-X-STREAM-INF:BANDWIDTH=500000\n",
+//!     "high.m3u8\n",
+//! ))?;
+//!
+//! let low = MediaPlaylist::try_from(concat!(
+//!     "#EXTM3U\n",
+//!     "#EXT-X-TARGETDURATION:4\n",
+//!     "#EXT-X-PROGRAM-DATE-TIME:2020-01-01T00:00:00Z\n",
+//!     "#EXTINF:4,\n",
+//!     "low1.ts\n",
+//!     "#EXTINF:4,\n",
+//!     "low2.ts\n",
+//! ))?;
+//!
+//! let high = MediaPlaylist::try_from(concat!(
+//!     "#EXTM3U\n",
+//!     "#EXT-X-TARGETDURATION:4\n",
+//!     "#EXT-X-MEDIA-SEQUENCE:10\n",
+//!     "#EXT-X-PROGRAM-DATE-TIME:2020-01-01T00:00:04Z\n",
+//!     "#EXTINF:4,\n",
+//!     "high1.ts\n",
+//! ))?;
+//!
+//! let bundle = Bundle::new(master, vec![low, high])?;
+//! let alignment = bundle.alignment_by_pdt();
+//!
+//! assert_eq!(alignment.len(), 2);
+//! assert_eq!(alignment[0].segments(), &[Some(0), None]);
+//! assert_eq!(alignment[1].segments(), &[Some(1), Some(10)]);
+//! # Ok::<(), hls_m3u8::Error>(())
+//! ```
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime};
+
+use shorthand::ShortHand;
+
+use crate::{Error, MasterPlaylist, MediaPlaylist, MediaSegment};
+
+/// A [`MasterPlaylist`] and the [`MediaPlaylist`] of each of its
+/// [`MasterPlaylist::variant_streams`].
+#[derive(ShortHand, Debug, Clone, PartialEq, Eq)]
+#[shorthand(enable(must_use), disable(set))]
+pub struct Bundle<'a> {
+    /// The [`MasterPlaylist`].
+    master_playlist: MasterPlaylist<'a>,
+    /// The [`MediaPlaylist`]s in the same order as the
+    /// [`MasterPlaylist::variant_streams`].
+    media_playlists: Vec<MediaPlaylist<'a>>,
+}
+
+impl<'a> Bundle<'a> {
+    /// Makes a new [`Bundle`].
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the number of [`MediaPlaylist`]s differs from the
+    /// number of [`MasterPlaylist::variant_streams`].
+    pub fn new(
+        master_playlist: MasterPlaylist<'a>,
+        media_playlists: Vec<MediaPlaylist<'a>>,
+    ) -> crate::Result<Self> {
+        if master_playlist.variant_streams.len() != media_playlists.len() {
+            return Err(Error::custom(format!(
+                "expected {} media playlists, but got {}",
+                master_playlist.variant_streams.len(),
+                media_playlists.len()
+            )));
+        }
+
+        Ok(Self {
+            master_playlist,
+            media_playlists,
+        })
+    }
+
+    /// Maps the wallclock time at the start of each segment (derived from the
+    /// `#EXT-X-PROGRAM-DATE-TIME` tags) to the [`MediaSegment::number`] of
+    /// the segment, that plays at this instant in every variant.
+    ///
+    /// The returned list is sorted by [`PdtAlignment::date_time`] and contains
+    /// the start times of the segments of all variants. A variant, that has no
+    /// segment at an instant (or whose segments can not be mapped to a
+    /// wallclock time), is `None` at this instant.
+    ///
+    /// The date time of a segment without a program date time is the end of
+    /// the previous segment, unless there is a discontinuity between them.
+    ///
+    /// [`MediaSegment::number`]: crate::MediaSegment::number
+    #[must_use]
+    pub fn alignment_by_pdt(&self) -> Vec<PdtAlignment> {
+        let timelines = self
+            .media_playlists
+            .iter()
+            .map(timeline)
+            .collect::<Vec<_>>();
+
+        let mut instants = timelines
+            .iter()
+            .flatten()
+            .map(|&(start, ..)| start)
+            .collect::<Vec<_>>();
+
+        instants.sort();
+        instants.dedup();
+
+        instants
+            .into_iter()
+            .map(|date_time| {
+                PdtAlignment {
+                    date_time,
+                    segments: timelines
+                        .iter()
+                        .map(|timeline| {
+                            timeline
+                                .iter()
+                                .find(|&&(start, duration, _)| {
+                                    start <= date_time && date_time < start + duration
+                                })
+                                .map(|&(_, _, number)| number)
+                        })
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Computes the bitrate of every variant and compares it with the
+    /// `BANDWIDTH` and `AVERAGE-BANDWIDTH` of the [`MasterPlaylist`].
+    ///
+    /// The bitrate of a segment is computed from its size in bytes, which is
+    /// returned by `segment_size` for the index of the variant and the
+    /// segment. If the size is unknown (`None`), the declared
+    /// [`MediaSegment::bitrate`] is used instead. Segments without a known
+    /// bitrate are ignored.
+    ///
+    /// The returned list is in the order of the
+    /// [`MasterPlaylist::variant_streams`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::bundle::Bundle;
+    /// use hls_m3u8::{MasterPlaylist, MediaPlaylist};
+    /// use std::convert::TryFrom;
+    ///
+    /// let master = MasterPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-STREAM-INF:BANDWIDTH=850000,AVERAGE-BANDWIDTH=500000\n",
+    ///     "low.m3u8\n",
+    /// ))?;
+    ///
+    /// let low = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:4\n",
+    ///     "#EXT-X-BITRATE:800\n",
+    ///     "#EXTINF:4,\n",
+    ///     "low1.ts\n",
+    ///     "#EXT-X-BITRATE:400\n",
+    ///     "#EXTINF:4,\n",
+    ///     "low2.ts\n",
+    /// ))?;
+    ///
+    /// let bundle = Bundle::new(master, vec![low])?;
+    /// let stats = bundle.bitrate_stats(|_, _| None);
+    ///
+    /// assert_eq!(stats[0].average(), Some(600_000));
+    /// assert_eq!(stats[0].peak(), Some(800_000));
+    /// assert!(!stats[0].has_bandwidth_mismatch());
+    /// assert!(stats[0].has_average_bandwidth_mismatch());
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub fn bitrate_stats<F>(&self, mut segment_size: F) -> Vec<BitrateStats>
+    where
+        F: FnMut(usize, &MediaSegment<'a>) -> Option<u64>,
+    {
+        self.master_playlist
+            .variant_streams
+            .iter()
+            .zip(&self.media_playlists)
+            .enumerate()
+            .map(|(i, (variant_stream, playlist))| {
+                let mut bits = 0_u128;
+                let mut duration = Duration::ZERO;
+                let mut peak = None;
+
+                for segment in playlist.segments.values() {
+                    let segment_duration = segment.duration.duration();
+
+                    if segment_duration == Duration::ZERO {
+                        continue;
+                    }
+
+                    let segment_bits = match segment_size(i, segment) {
+                        Some(size) => u128::from(size) * 8,
+                        None => {
+                            match segment.bitrate {
+                                // the bitrate is in kilobits per second
+                                Some(value) => {
+                                    u128::from(value) * 1000 * segment_duration.as_millis() / 1000
+                                }
+                                None => continue,
+                            }
+                        }
+                    };
+
+                    let bitrate = to_u64(segment_bits * 1000 / segment_duration.as_millis().max(1));
+
+                    peak = peak.max(Some(bitrate));
+                    bits += segment_bits;
+                    duration += segment_duration;
+                }
+
+                BitrateStats {
+                    bandwidth: variant_stream.bandwidth(),
+                    average_bandwidth: variant_stream.average_bandwidth(),
+                    average: Some(duration)
+                        .filter(|value| *value > Duration::ZERO)
+                        .map(|value| to_u64(bits * 1000 / value.as_millis().max(1))),
+                    peak,
+                }
+            })
+            .collect()
+    }
+}
+
+fn to_u64(value: u128) -> u64 { u64::try_from(value).unwrap_or(u64::MAX) }
+
+/// The measured bitrate of a variant of a [`Bundle`] together with the
+/// bandwidth claimed by the [`MasterPlaylist`], see [`Bundle::bitrate_stats`].
+///
+/// All values are in bits per second.
+#[derive(ShortHand, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[shorthand(enable(must_use, copy), disable(set))]
+pub struct BitrateStats {
+    /// The `BANDWIDTH` of the variant stream.
+    bandwidth: u64,
+    /// The `AVERAGE-BANDWIDTH` of the variant stream.
+    average_bandwidth: Option<u64>,
+    /// The duration-weighted average bitrate of the segments or `None` if no
+    /// segment has a known bitrate.
+    average: Option<u64>,
+    /// The highest bitrate of a single segment.
+    peak: Option<u64>,
+}
+
+impl BitrateStats {
+    /// Returns `true`, if the [`BitrateStats::peak`] differs by more than 10%
+    /// from the [`BitrateStats::bandwidth`], which violates the HLS authoring
+    /// specification.
+    #[must_use]
+    pub fn has_bandwidth_mismatch(&self) -> bool {
+        self.peak
+            .is_some_and(|peak| is_mismatch(peak, self.bandwidth))
+    }
+
+    /// Returns `true`, if the [`BitrateStats::average`] differs by more than
+    /// 10% from the [`BitrateStats::average_bandwidth`], which violates the
+    /// HLS authoring specification.
+    #[must_use]
+    pub fn has_average_bandwidth_mismatch(&self) -> bool {
+        match (self.average, self.average_bandwidth) {
+            (Some(average), Some(claimed)) => is_mismatch(average, claimed),
+            _ => false,
+        }
+    }
+}
+
+fn is_mismatch(measured: u64, claimed: u64) -> bool {
+    u128::from(measured.abs_diff(claimed)) * 10 > u128::from(claimed)
+}
+
+/// The segments of all variants of a [`Bundle`], that play at a wallclock
+/// instant, see [`Bundle::alignment_by_pdt`].
+#[derive(ShortHand, Debug, Clone, PartialEq, Eq, Hash)]
+#[shorthand(enable(must_use), disable(set))]
+pub struct PdtAlignment {
+    /// The wallclock instant.
+    #[shorthand(enable(copy))]
+    date_time: SystemTime,
+    /// The [`MediaSegment::number`] of the segment of each variant, in the
+    /// order of the [`MasterPlaylist::variant_streams`].
+    ///
+    /// [`MediaSegment::number`]: crate::MediaSegment::number
+    segments: Vec<Option<usize>>,
+}
+
+/// Returns the start, the duration and the number of every segment, that can
+/// be mapped to a wallclock time.
+fn timeline(playlist: &MediaPlaylist<'_>) -> Vec<(SystemTime, Duration, usize)> {
+    let mut result = Vec::new();
+    let mut next = None;
+
+    for segment in playlist.segments.values() {
+        if segment.has_discontinuity {
+            next = None;
+        }
+
+        if let Some(time) = segment
+            .program_date_time
+            .as_ref()
+            .and_then(|value| value.system_time())
+        {
+            next = Some(time);
+        }
+
+        if let Some(start) = next {
+            let duration = segment.duration.duration();
+
+            result.push((start, duration, segment.number));
+            next = Some(start + duration);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::convert::TryFrom;
+
+    fn media_playlist(input: &str) -> MediaPlaylist<'_> { MediaPlaylist::try_from(input).unwrap() }
+
+    #[test]
+    fn test_new() {
+        let master = MasterPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+            "low.m3u8\n",
+        ))
+        .unwrap();
+
+        assert!(Bundle::new(master.clone(), vec![]).is_err());
+        assert!(Bundle::new(
+            master,
+            vec![media_playlist("#EXTM3U\n#EXT-X-TARGETDURATION:4\n")]
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_bitrate_stats() {
+        let master = MasterPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=200000,AVERAGE-BANDWIDTH=150000\n",
+            "low.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=500000\n",
+            "high.m3u8\n",
+        ))
+        .unwrap();
+
+        let low = media_playlist(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXTINF:4,\n",
+            "low1.ts\n",
+            "#EXTINF:2,\n",
+            "low2.ts\n",
+        ));
+
+        let high = media_playlist(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-BITRATE:400\n",
+            "#EXTINF:4,\n",
+            "high1.ts\n",
+            "#EXTINF:4,\n",
+            "high2.ts\n",
+        ));
+
+        let bundle = Bundle::new(master, vec![low, high]).unwrap();
+
+        // 4s with 200 kbit/s and 2s with 50 kbit/s
+        let stats = bundle.bitrate_stats(|_, segment| {
+            match segment.uri().as_str() {
+                "low1.ts" => Some(100_000),
+                "low2.ts" => Some(12_500),
+                _ => None,
+            }
+        });
+
+        assert_eq!(
+            stats,
+            vec![
+                BitrateStats {
+                    bandwidth: 200_000,
+                    average_bandwidth: Some(150_000),
+                    average: Some(150_000),
+                    peak: Some(200_000),
+                },
+                BitrateStats {
+                    bandwidth: 500_000,
+                    average_bandwidth: None,
+                    average: Some(400_000),
+                    peak: Some(400_000),
+                },
+            ]
+        );
+
+        assert!(!stats[0].has_bandwidth_mismatch());
+        assert!(!stats[0].has_average_bandwidth_mismatch());
+        assert!(stats[1].has_bandwidth_mismatch());
+        assert!(!stats[1].has_average_bandwidth_mismatch());
+
+        assert_eq!(
+            bundle.bitrate_stats(|_, _| None)[0],
+            BitrateStats {
+                bandwidth: 200_000,
+                average_bandwidth: Some(150_000),
+                average: None,
+                peak: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_alignment_by_pdt() {
+        let master = MasterPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+            "low.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=500000\n",
+            "high.m3u8\n",
+        ))
+        .unwrap();
+
+        let low = media_playlist(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:6\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-01T00:00:00Z\n",
+            "#EXTINF:6,\n",
+            "low1.ts\n",
+            "#EXTINF:6,\n",
+            "low2.ts\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:6,\n",
+            "low3.ts\n",
+        ));
+
+        let high = media_playlist(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-MEDIA-SEQUENCE:5\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-01T00:00:00Z\n",
+            "#EXTINF:4,\n",
+            "high1.ts\n",
+            "#EXTINF:4,\n",
+            "high2.ts\n",
+            "#EXTINF:4,\n",
+            "high3.ts\n",
+        ));
+
+        let bundle = Bundle::new(master, vec![low, high]).unwrap();
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_577_836_800);
+
+        assert_eq!(
+            bundle.alignment_by_pdt(),
+            vec![
+                PdtAlignment {
+                    date_time: start,
+                    segments: vec![Some(0), Some(5)],
+                },
+                PdtAlignment {
+                    date_time: start + Duration::from_secs(4),
+                    segments: vec![Some(0), Some(6)],
+                },
+                PdtAlignment {
+                    date_time: start + Duration::from_secs(6),
+                    segments: vec![Some(1), Some(6)],
+                },
+                PdtAlignment {
+                    date_time: start + Duration::from_secs(8),
+                    segments: vec![Some(1), Some(7)],
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,497 @@
+//! Chapters of a presentation, that can be shown by the user interface of a
+//! player.
+//!
+//! The chapters can be stored in two ways:
+//!
+//! - as a json file, that is referenced by an [`ExtXSessionData`] with the data
+//!   id [`ExtXSessionData::CHAPTERS`] in the [`MasterPlaylist`] (see
+//!   [`session_data`] and [`from_master_playlist`]). If the `serde` feature is
+//!   enabled, a list of [`Chapter`]s can be (de)serialized from the json file.
+//! - as [`ExtXDateRange`]s with the class [`CLASS`] in the [`MediaPlaylist`]
+//!   (see [`insert_into_media_playlist`] and [`from_media_playlist`]).
+//!
+//! # Example
+//!
+//! ```
+//! use hls_m3u8::chapters::{self, Chapter};
+//! use hls_m3u8::MediaPlaylist;
+//! use std::convert::TryFrom;
+//! use std::time::Duration;
+//!
+//! let mut playlist = MediaPlaylist::try_from(concat!(
+//!     "#EXTM3U\n",
+//!     "#EXT-X-TARGETDURATION:10\n",
+//!     "#EXT-X-PROGRAM-DATE-TIME:2010-02-19T14:54:23.031+08:00\n",
+//!     "#EXTINF:10,\n",
+//!     "1.ts\n",
+//!     "#EXTINF:10,\n",
+//!     "2.ts\n",
+//! ))?;
+//!
+//! let chapters = vec![
+//!     Chapter::new(Duration::from_secs(0), "Intro"),
+//!     Chapter::new(Duration::from_secs(10), "Main"),
+//! ];
+//!
+//! chapters::insert_into_media_playlist(&mut playlist, &chapters)?;
+//! assert_eq!(chapters::from_media_playlist(&playlist), chapters);
+//! # Ok::<(), hls_m3u8::Error>(())
+//! ```
+use std::borrow::Cow;
+use std::time::{Duration, SystemTime};
+
+use shorthand::ShortHand;
+
+use crate::tags::{ExtXDateRange, ExtXSessionData, SessionData};
+use crate::types::Value;
+use crate::{Error, MasterPlaylist, MediaPlaylist};
+
+/// The [`ExtXDateRange::class`] of the date ranges, that mark the start of a
+/// [`Chapter`] in a [`MediaPlaylist`].
+pub const CLASS: &str = "com.apple.hls.chapters";
+
+/// The client attribute of a chapter date range with the
+/// [`Chapter::title`].
+const TITLE: &str = "X-TITLE";
+/// The client attribute of a chapter date range with the
+/// [`Chapter::image`].
+const IMAGE: &str = "X-IMAGE";
+
+/// A single chapter of a presentation.
+///
+/// If the `serde` feature is enabled, this type can be (de)serialized, for
+/// example with `serde_json`:
+///
+/// ```json
+/// [{ "start-time": 0.0, "title": "Intro", "image": "intro.jpg" }]
+/// ```
+#[derive(ShortHand, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[shorthand(enable(must_use, into))]
+pub struct Chapter {
+    /// The offset of the start of the chapter from the start of the
+    /// presentation.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "start-time", with = "crate::utils::duration_secs")
+    )]
+    #[shorthand(enable(copy), disable(into))]
+    start: Duration,
+    /// The human-readable title of the chapter.
+    title: String,
+    /// The uri of an image, that represents the chapter.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    image: Option<String>,
+}
+
+impl Chapter {
+    /// Makes a new [`Chapter`] without an image.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::chapters::Chapter;
+    /// use std::time::Duration;
+    ///
+    /// let chapter = Chapter::new(Duration::from_secs(90), "The Beginning");
+    /// ```
+    #[must_use]
+    pub fn new<T: Into<String>>(start: Duration, title: T) -> Self {
+        Self {
+            start,
+            title: title.into(),
+            image: None,
+        }
+    }
+}
+
+/// Makes a new [`ExtXSessionData`], that references a json file with the
+/// chapters of a presentation.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::chapters;
+///
+/// let mut session_data = chapters::session_data("chapters-en.json");
+/// session_data.set_language(Some("en"));
+///
+/// assert_eq!(
+///     session_data.to_string(),
+///     concat!(
+///         "#EXT-X-SESSION-DATA:DATA-ID=\"com.apple.hls.chapters\",",
+///         "URI=\"chapters-en.json\",LANGUAGE=\"en\""
+///     )
+/// );
+/// ```
+#[must_use]
+pub fn session_data<'a, T: Into<Cow<'a, str>>>(uri: T) -> ExtXSessionData<'a> {
+    ExtXSessionData::new(ExtXSessionData::CHAPTERS, SessionData::Uri(uri.into()))
+}
+
+/// Returns the chapters, that are referenced by the
+/// [`MasterPlaylist::chapters_session_data`].
+///
+/// The session data with the given `language` is preferred, otherwise the
+/// first one is used. `fetch` is called with its uri and should download and
+/// deserialize the json file. If there is no session data for the chapters,
+/// an empty list is returned.
+///
+/// # Errors
+///
+/// Returns the error of `fetch`.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::chapters::{self, Chapter};
+/// use hls_m3u8::MasterPlaylist;
+/// use std::convert::TryFrom;
+/// use std::time::Duration;
+///
+/// let playlist = MasterPlaylist::try_from(concat!(
+///     "#EXTM3U\n",
+///     "#EXT-X-SESSION-DATA:DATA-ID=\"com.apple.hls.chapters\",URI=\"en.json\",LANGUAGE=\"en\"\n",
+///     "#EXT-X-SESSION-DATA:DATA-ID=\"com.apple.hls.chapters\",URI=\"de.json\",LANGUAGE=\"de\"\n",
+///     "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+///     "low.m3u8\n",
+/// ))?;
+///
+/// let chapters = chapters::from_master_playlist(&playlist, Some("de"), |uri| {
+///     assert_eq!(uri, "de.json");
+///     // download and deserialize the chapters here
+///     Ok::<_, hls_m3u8::Error>(vec![Chapter::new(Duration::from_secs(0), "Anfang")])
+/// })?;
+///
+/// assert_eq!(chapters.len(), 1);
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+pub fn from_master_playlist<F, E>(
+    playlist: &MasterPlaylist<'_>,
+    language: Option<&str>,
+    fetch: F,
+) -> Result<Vec<Chapter>, E>
+where
+    F: FnOnce(&str) -> Result<Vec<Chapter>, E>,
+{
+    let uris = || {
+        playlist.chapters_session_data().filter_map(|value| {
+            match &value.data {
+                SessionData::Uri(uri) => Some((value.language(), uri)),
+                SessionData::Value(_) => None,
+            }
+        })
+    };
+
+    let selected = uris()
+        .find(|(value, _)| {
+            language.is_some_and(|language| {
+                value
+                    .as_ref()
+                    .is_some_and(|value| value.eq_ignore_ascii_case(language))
+            })
+        })
+        .or_else(|| uris().next());
+
+    match selected {
+        Some((_, uri)) => fetch(uri),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Returns the chapters, that are stored as [`ExtXDateRange`]s with the class
+/// [`CLASS`] in the [`MediaPlaylist`].
+///
+/// The start of a chapter is the offset of the segment, the date range
+/// belongs to, plus the offset of the [`ExtXDateRange::start_date`] from the
+/// program date time of that segment. The title falls back to the
+/// [`ExtXDateRange::id`].
+#[must_use]
+pub fn from_media_playlist(playlist: &MediaPlaylist<'_>) -> Vec<Chapter> {
+    let mut result = Vec::new();
+    let mut offset = Duration::ZERO;
+    let mut time = None;
+
+    for segment in playlist.segments.values() {
+        if let Some(value) = segment
+            .program_date_time
+            .as_ref()
+            .and_then(|value| value.system_time())
+        {
+            time = Some(value);
+        }
+
+        if let Some(date_range) = segment.date_range.as_ref().filter(|v| is_chapter(v)) {
+            let delta = date_range
+                .start_time()
+                .zip(time)
+                .and_then(|(start, time)| start.duration_since(time).ok())
+                .unwrap_or_default();
+
+            let attribute = |name: &str| {
+                date_range
+                    .client_attributes
+                    .get(name)
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+            };
+
+            result.push(Chapter {
+                start: offset + delta,
+                title: attribute(TITLE).unwrap_or_else(|| date_range.id().to_string()),
+                image: attribute(IMAGE),
+            });
+        }
+
+        let duration = segment.duration.duration();
+
+        offset += duration;
+        time = time.map(|time| time + duration);
+    }
+
+    result
+}
+
+/// Replaces the chapters of the [`MediaPlaylist`] with the given `chapters`,
+/// which are stored as [`ExtXDateRange`]s with the class [`CLASS`] (see
+/// [`from_media_playlist`]).
+///
+/// Every chapter is attached to the segment, in which it starts.
+///
+/// # Errors
+///
+/// Fails without changing the playlist, if
+/// - a chapter starts after the end of the playlist,
+/// - the start date of a chapter can not be derived, because there is no
+///   [`MediaSegment::program_date_time`] at or before the segment,
+/// - a segment already has an [`ExtXDateRange`], that is not a chapter, or
+/// - two chapters start in the same segment.
+///
+/// [`MediaSegment::program_date_time`]:
+/// crate::MediaSegment::program_date_time
+pub fn insert_into_media_playlist(
+    playlist: &mut MediaPlaylist<'_>,
+    chapters: &[Chapter],
+) -> crate::Result<()> {
+    let mut chapters = chapters.iter().collect::<Vec<_>>();
+    chapters.sort_by_key(|chapter| chapter.start);
+    let mut chapters = chapters.into_iter().peekable();
+
+    let mut date_ranges = Vec::new();
+    let mut offset = Duration::ZERO;
+    let mut time = None;
+
+    for (index, segment) in playlist.segments.iter() {
+        if let Some(value) = segment
+            .program_date_time
+            .as_ref()
+            .and_then(|value| value.system_time())
+        {
+            time = Some(value);
+        }
+
+        let duration = segment.duration.duration();
+        let is_in_segment = |chapter: &&Chapter| chapter.start < offset + duration;
+
+        if let Some(chapter) = chapters.next_if(is_in_segment) {
+            if chapters.peek().is_some_and(is_in_segment) {
+                return Err(Error::custom(format!(
+                    "the segment {} contains the start of multiple chapters",
+                    segment.number()
+                )));
+            }
+
+            if segment.date_range.as_ref().is_some_and(|v| !is_chapter(v)) {
+                return Err(Error::custom(format!(
+                    "the segment {} already has a date range",
+                    segment.number()
+                )));
+            }
+
+            let start = time.ok_or_else(|| {
+                Error::custom(format!(
+                    "the segment {} has no program date time for the chapter {:?}",
+                    segment.number(),
+                    chapter.title
+                ))
+            })? + (chapter.start - offset);
+
+            let mut date_range = ExtXDateRange::new(
+                format!("chapter-{}", date_ranges.len() + 1),
+                start_date(start),
+            );
+            date_range.set_class(Some(CLASS));
+            date_range
+                .client_attributes
+                .insert(TITLE.into(), Value::string(chapter.title.clone()));
+
+            if let Some(image) = &chapter.image {
+                date_range
+                    .client_attributes
+                    .insert(IMAGE.into(), Value::string(image.clone()));
+            }
+
+            date_ranges.push((index, date_range));
+        }
+
+        offset += duration;
+        time = time.map(|time| time + duration);
+    }
+
+    if let Some(chapter) = chapters.next() {
+        return Err(Error::custom(format!(
+            "the chapter {:?} starts after the end of the playlist",
+            chapter.title
+        )));
+    }
+
+    for segment in playlist.segments.values_mut() {
+        if segment.date_range.as_ref().is_some_and(is_chapter) {
+            segment.date_range = None;
+        }
+    }
+
+    for (index, date_range) in date_ranges {
+        playlist.segments[index].date_range = Some(date_range);
+    }
+
+    Ok(())
+}
+
+fn is_chapter(date_range: &ExtXDateRange<'_>) -> bool {
+    date_range.class().map(AsRef::as_ref) == Some(CLASS)
+}
+
+#[cfg(feature = "chrono")]
+fn start_date(time: SystemTime) -> chrono::DateTime<chrono::FixedOffset> {
+    chrono::DateTime::<chrono::Utc>::from(time).into()
+}
+
+#[cfg(not(feature = "chrono"))]
+fn start_date(time: SystemTime) -> String { crate::utils::format_date_time(time) }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::convert::TryFrom;
+
+    fn playlist() -> MediaPlaylist<'static> {
+        MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2010-02-19T14:54:23.031+08:00\n",
+            "#EXTINF:10,\n",
+            "1.ts\n",
+            "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2010-02-19T14:54:33.031+08:00\"\n",
+            "#EXTINF:10,\n",
+            "2.ts\n",
+            "#EXTINF:10,\n",
+            "3.ts\n",
+        ))
+        .unwrap()
+        .into_owned()
+    }
+
+    #[test]
+    fn test_media_playlist() {
+        let mut playlist = playlist();
+        let mut chapter = Chapter::new(Duration::from_millis(22_500), "Second");
+        chapter.set_image(Some("second.jpg"));
+
+        let chapters = vec![chapter, Chapter::new(Duration::from_secs(5), "First")];
+
+        assert!(insert_into_media_playlist(&mut playlist, &chapters).is_ok());
+        assert_eq!(
+            from_media_playlist(&playlist),
+            vec![chapters[1].clone(), chapters[0].clone()]
+        );
+        assert_eq!(
+            playlist.segments[2]
+                .date_range
+                .as_ref()
+                .unwrap()
+                .start_time(),
+            playlist.segments[0]
+                .program_date_time
+                .as_ref()
+                .unwrap()
+                .system_time()
+                .map(|time| time + Duration::from_millis(22_500))
+        );
+
+        // the old chapters are replaced
+        let chapters = vec![Chapter::new(Duration::from_secs(0), "Only")];
+        assert!(insert_into_media_playlist(&mut playlist, &chapters).is_ok());
+        assert_eq!(from_media_playlist(&playlist), chapters);
+        assert!(playlist.segments[2].date_range.is_none());
+    }
+
+    #[test]
+    fn test_insert_errors() {
+        let mut playlist = playlist();
+        let original = playlist.clone();
+
+        for chapters in &[
+            // after the end of the playlist:
+            vec![Chapter::new(Duration::from_secs(30), "a")],
+            // the segment has a date range:
+            vec![Chapter::new(Duration::from_secs(10), "a")],
+            // two chapters in the same segment:
+            vec![
+                Chapter::new(Duration::from_secs(0), "a"),
+                Chapter::new(Duration::from_secs(5), "b"),
+            ],
+        ] {
+            assert!(insert_into_media_playlist(&mut playlist, chapters).is_err());
+            assert_eq!(playlist, original);
+        }
+
+        let mut playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXTINF:10,\n",
+            "1.ts\n",
+        ))
+        .unwrap();
+
+        assert!(insert_into_media_playlist(
+            &mut playlist,
+            &[Chapter::new(Duration::from_secs(0), "a")]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_master_playlist() {
+        let playlist = MasterPlaylist::builder()
+            .session_data(vec![
+                session_data("en.json"),
+                ExtXSessionData::with_language(
+                    ExtXSessionData::CHAPTERS,
+                    SessionData::Uri("de.json".into()),
+                    "de",
+                ),
+            ])
+            .build()
+            .unwrap();
+
+        let fetch = |uri: &str| Ok::<_, ()>(vec![Chapter::new(Duration::ZERO, uri)]);
+
+        assert_eq!(
+            from_master_playlist(&playlist, Some("DE"), fetch),
+            Ok(vec![Chapter::new(Duration::ZERO, "de.json")])
+        );
+        assert_eq!(
+            from_master_playlist(&playlist, Some("fr"), fetch),
+            Ok(vec![Chapter::new(Duration::ZERO, "en.json")])
+        );
+        assert_eq!(
+            from_master_playlist(&MasterPlaylist::default(), None, fetch),
+            Ok(vec![])
+        );
+    }
+}
@@ -0,0 +1,174 @@
+//! Conversions between the playlists of this crate and the playlists of the
+//! [`m3u8-rs`] crate.
+//!
+//! The playlists are converted by writing them with one crate and parsing
+//! them with the other one, so every tag, that is supported by both crates,
+//! is preserved. The conversions fail, if the target crate can not parse the
+//! playlist.
+//!
+//! [`m3u8-rs`]: https://github.com/rutgersc/m3u8-rs
+use std::convert::TryFrom;
+
+use crate::{Error, MasterPlaylist, MediaPlaylist};
+
+/// Writes a playlist of the `m3u8-rs` crate.
+fn write<F>(write_to: F) -> crate::Result<String>
+where
+    F: FnOnce(&mut Vec<u8>) -> std::io::Result<()>,
+{
+    let mut output = Vec::new();
+    write_to(&mut output).map_err(Error::custom)?;
+
+    String::from_utf8(output).map_err(Error::custom)
+}
+
+impl TryFrom<&m3u8_rs::MediaPlaylist> for MediaPlaylist<'static> {
+    type Error = Error;
+
+    fn try_from(playlist: &m3u8_rs::MediaPlaylist) -> Result<Self, Self::Error> {
+        let input = write(|output| playlist.write_to(output))?;
+
+        MediaPlaylist::try_from(input.as_str()).map(MediaPlaylist::into_owned)
+    }
+}
+
+impl TryFrom<m3u8_rs::MediaPlaylist> for MediaPlaylist<'static> {
+    type Error = Error;
+
+    fn try_from(playlist: m3u8_rs::MediaPlaylist) -> Result<Self, Self::Error> {
+        Self::try_from(&playlist)
+    }
+}
+
+impl<'a> TryFrom<&MediaPlaylist<'a>> for m3u8_rs::MediaPlaylist {
+    type Error = Error;
+
+    fn try_from(playlist: &MediaPlaylist<'a>) -> Result<Self, Self::Error> {
+        m3u8_rs::parse_media_playlist_res(playlist.to_string().as_bytes()).map_err(|error| {
+            // the error contains the remaining input, which is omitted:
+            Error::custom(format_args!(
+                "m3u8-rs failed to parse the playlist: {}",
+                error.map(|error| error.code)
+            ))
+        })
+    }
+}
+
+impl<'a> TryFrom<MediaPlaylist<'a>> for m3u8_rs::MediaPlaylist {
+    type Error = Error;
+
+    fn try_from(playlist: MediaPlaylist<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(&playlist)
+    }
+}
+
+impl TryFrom<&m3u8_rs::MasterPlaylist> for MasterPlaylist<'static> {
+    type Error = Error;
+
+    fn try_from(playlist: &m3u8_rs::MasterPlaylist) -> Result<Self, Self::Error> {
+        let input = write(|output| playlist.write_to(output))?;
+
+        MasterPlaylist::try_from(input.as_str()).map(MasterPlaylist::into_owned)
+    }
+}
+
+impl TryFrom<m3u8_rs::MasterPlaylist> for MasterPlaylist<'static> {
+    type Error = Error;
+
+    fn try_from(playlist: m3u8_rs::MasterPlaylist) -> Result<Self, Self::Error> {
+        Self::try_from(&playlist)
+    }
+}
+
+impl<'a> TryFrom<&MasterPlaylist<'a>> for m3u8_rs::MasterPlaylist {
+    type Error = Error;
+
+    fn try_from(playlist: &MasterPlaylist<'a>) -> Result<Self, Self::Error> {
+        m3u8_rs::parse_master_playlist_res(playlist.to_string().as_bytes()).map_err(|error| {
+            // the error contains the remaining input, which is omitted:
+            Error::custom(format_args!(
+                "m3u8-rs failed to parse the playlist: {}",
+                error.map(|error| error.code)
+            ))
+        })
+    }
+}
+
+impl<'a> TryFrom<MasterPlaylist<'a>> for m3u8_rs::MasterPlaylist {
+    type Error = Error;
+
+    fn try_from(playlist: MasterPlaylist<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(&playlist)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_media_playlist() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-VERSION:3\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-MEDIA-SEQUENCE:5\n",
+            "#EXTINF:9.009,\n",
+            "first.ts\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:9.009,\n",
+            "second.ts\n",
+            "#EXT-X-ENDLIST\n",
+        );
+
+        let playlist = MediaPlaylist::try_from(input).unwrap();
+        let other = m3u8_rs::MediaPlaylist::try_from(&playlist).unwrap();
+
+        assert_eq!(other.target_duration, 10);
+        assert_eq!(other.media_sequence, 5);
+        assert_eq!(other.segments.len(), 2);
+        assert!(other.segments[1].discontinuity);
+        assert!(other.end_list);
+
+        assert_eq!(MediaPlaylist::try_from(other).unwrap(), playlist);
+    }
+
+    #[test]
+    fn test_master_playlist() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"English\",URI=\"en.m3u8\"\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=150000,CODECS=\"avc1.42e00a,mp4a.40.2\",AUDIO=\"aac\"\n",
+            "low/index.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=240000,RESOLUTION=416x234,AUDIO=\"aac\"\n",
+            "mid/index.m3u8\n",
+        );
+
+        let playlist = MasterPlaylist::try_from(input).unwrap();
+        let other = m3u8_rs::MasterPlaylist::try_from(&playlist).unwrap();
+
+        assert_eq!(other.variants.len(), 2);
+        assert_eq!(other.variants[0].uri, "low/index.m3u8");
+        assert_eq!(other.variants[1].bandwidth, 240_000);
+        assert_eq!(other.alternatives.len(), 1);
+
+        assert_eq!(MasterPlaylist::try_from(other).unwrap(), playlist);
+    }
+
+    #[test]
+    fn test_errors() {
+        // closed captions require an INSTREAM-ID in this crate:
+        let playlist = m3u8_rs::MasterPlaylist {
+            alternatives: vec![m3u8_rs::AlternativeMedia {
+                media_type: m3u8_rs::AlternativeMediaType::ClosedCaptions,
+                group_id: "cc".to_string(),
+                name: "English".to_string(),
+                ..m3u8_rs::AlternativeMedia::default()
+            }],
+            ..m3u8_rs::MasterPlaylist::default()
+        };
+
+        assert!(MasterPlaylist::try_from(playlist).is_err());
+    }
+}
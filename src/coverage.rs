@@ -0,0 +1,550 @@
+use shorthand::ShortHand;
+
+use crate::types::ProtocolVersion;
+
+/// An attribute of a tag and whether this crate supports it.
+#[derive(ShortHand, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[shorthand(enable(must_use, copy), disable(set))]
+pub struct AttributeCoverage {
+    /// The name of the attribute (for example `"BANDWIDTH"`).
+    name: &'static str,
+    /// Whether the attribute is parsed and written by this crate.
+    is_supported: bool,
+}
+
+/// A tag and whether this crate supports it.
+#[derive(ShortHand, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[shorthand(enable(must_use, copy), disable(set))]
+pub struct TagCoverage {
+    /// The name of the tag without the leading `#` (for example
+    /// `"EXT-X-STREAM-INF"`).
+    name: &'static str,
+    /// Whether the tag is parsed and written by this crate.
+    ///
+    /// Unsupported tags are still parsed, but are kept as unknown tags.
+    is_supported: bool,
+    /// The attributes defined for this tag.
+    attributes: &'static [AttributeCoverage],
+}
+
+/// Describes which tags and attributes of the [latest draft] are supported by
+/// this crate.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::ProtocolCoverage;
+///
+/// let coverage = ProtocolCoverage::report();
+///
+/// assert!(coverage.supports_tag("EXT-X-STREAM-INF"));
+/// assert!(coverage.supports_attribute("EXT-X-STREAM-INF", "BANDWIDTH"));
+///
+/// for tag in coverage.tags().iter().filter(|tag| !tag.is_supported()) {
+///     println!("unsupported tag: {}", tag.name());
+/// }
+/// ```
+///
+/// [latest draft]: https://tools.ietf.org/html/draft-pantos-hls-rfc8216bis-13
+#[derive(ShortHand, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[shorthand(enable(must_use, copy), disable(set))]
+pub struct ProtocolCoverage {
+    /// The name of the specification the report refers to.
+    specification: &'static str,
+    /// All tags defined by the specification.
+    tags: &'static [TagCoverage],
+}
+
+impl ProtocolCoverage {
+    /// Returns the coverage report of this crate.
+    #[must_use]
+    pub const fn report() -> Self {
+        Self {
+            specification: "draft-pantos-hls-rfc8216bis-13",
+            tags: TAGS,
+        }
+    }
+
+    /// Returns the [`TagCoverage`] of the tag with the given name.
+    ///
+    /// The leading `#` of the name is optional.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::ProtocolCoverage;
+    ///
+    /// let coverage = ProtocolCoverage::report();
+    ///
+    /// assert_eq!(coverage.tag("#EXT-X-KEY"), coverage.tag("EXT-X-KEY"));
+    /// assert_eq!(coverage.tag("EXT-X-UNKNOWN"), None);
+    /// ```
+    #[must_use]
+    pub fn tag(&self, name: &str) -> Option<&'static TagCoverage> {
+        let name = name.strip_prefix('#').unwrap_or(name);
+
+        self.tags.iter().find(|tag| tag.name == name)
+    }
+
+    /// Returns `true`, if the tag with the given name is supported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::ProtocolCoverage;
+    ///
+    /// let coverage = ProtocolCoverage::report();
+    ///
+    /// assert!(coverage.supports_tag("EXT-X-MEDIA"));
+    /// assert!(!coverage.supports_tag("EXT-X-DEFINE"));
+    /// ```
+    #[must_use]
+    pub fn supports_tag(&self, name: &str) -> bool {
+        self.tag(name).is_some_and(|tag| tag.is_supported)
+    }
+
+    /// Returns `true`, if the attribute of the tag is supported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::ProtocolCoverage;
+    ///
+    /// let coverage = ProtocolCoverage::report();
+    ///
+    /// assert!(coverage.supports_attribute("EXT-X-MEDIA", "INSTREAM-ID"));
+    /// assert!(coverage.supports_attribute("EXT-X-MEDIA", "BIT-DEPTH"));
+    /// assert!(!coverage.supports_attribute("EXT-X-STREAM-INF", "ALLOWED-CPC"));
+    /// ```
+    #[must_use]
+    pub fn supports_attribute(&self, tag: &str, attribute: &str) -> bool {
+        self.tag(tag).is_some_and(|tag| {
+            tag.is_supported
+                && tag
+                    .attributes
+                    .iter()
+                    .any(|value| value.name == attribute && value.is_supported)
+        })
+    }
+}
+
+/// The capabilities of the current build of this crate.
+///
+/// Contains the supported tags, attributes and protocol versions, as well as
+/// the enabled crate features. If the `serde` feature is enabled, it can be
+/// serialized, so that host applications can advertise the manifest features
+/// they support.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::types::ProtocolVersion;
+///
+/// let capabilities = hls_m3u8::capabilities();
+///
+/// assert!(capabilities.supports_version(ProtocolVersion::V7));
+/// assert!(capabilities.coverage().supports_tag("EXT-X-SKIP"));
+/// assert_eq!(capabilities.has_feature("chrono"), cfg!(feature = "chrono"));
+/// ```
+#[derive(ShortHand, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[shorthand(enable(must_use, copy), disable(set))]
+pub struct Capabilities {
+    /// The version of this crate.
+    crate_version: &'static str,
+    /// The protocol versions, that can be parsed and written.
+    protocol_versions: &'static [ProtocolVersion],
+    /// The enabled crate features (for example `"chrono"`).
+    features: &'static [&'static str],
+    /// The supported tags and attributes.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    coverage: ProtocolCoverage,
+}
+
+impl Capabilities {
+    /// Returns `true`, if the [`ProtocolVersion`] is supported.
+    #[must_use]
+    pub fn supports_version(&self, version: ProtocolVersion) -> bool {
+        self.protocol_versions.contains(&version)
+    }
+
+    /// Returns `true`, if the crate feature with the given name is enabled.
+    #[must_use]
+    pub fn has_feature(&self, name: &str) -> bool { self.features.contains(&name) }
+}
+
+/// Returns the [`Capabilities`] of the current build of this crate.
+#[must_use]
+pub const fn capabilities() -> Capabilities {
+    Capabilities {
+        crate_version: env!("CARGO_PKG_VERSION"),
+        protocol_versions: PROTOCOL_VERSIONS,
+        features: FEATURES,
+        coverage: ProtocolCoverage::report(),
+    }
+}
+
+const PROTOCOL_VERSIONS: &[ProtocolVersion] = &[
+    ProtocolVersion::V1,
+    ProtocolVersion::V2,
+    ProtocolVersion::V3,
+    ProtocolVersion::V4,
+    ProtocolVersion::V5,
+    ProtocolVersion::V6,
+    ProtocolVersion::V7,
+    ProtocolVersion::V8,
+    ProtocolVersion::V9,
+    ProtocolVersion::V10,
+    ProtocolVersion::V11,
+    ProtocolVersion::V12,
+];
+
+const FEATURES: &[&str] = &[
+    #[cfg(feature = "backtrace")]
+    "backtrace",
+    #[cfg(feature = "chrono")]
+    "chrono",
+    #[cfg(feature = "flate2")]
+    "flate2",
+    #[cfg(feature = "intern")]
+    "intern",
+    #[cfg(feature = "language-tags")]
+    "language-tags",
+    #[cfg(feature = "m3u8-rs-compat")]
+    "m3u8-rs-compat",
+    #[cfg(feature = "perf")]
+    "perf",
+    #[cfg(feature = "scte35")]
+    "scte35",
+    #[cfg(feature = "serde")]
+    "serde",
+];
+
+const fn tag(
+    name: &'static str,
+    is_supported: bool,
+    attributes: &'static [AttributeCoverage],
+) -> TagCoverage {
+    TagCoverage {
+        name,
+        is_supported,
+        attributes,
+    }
+}
+
+const fn attr(name: &'static str, is_supported: bool) -> AttributeCoverage {
+    AttributeCoverage { name, is_supported }
+}
+
+const KEY_ATTRIBUTES: &[AttributeCoverage] = &[
+    attr("METHOD", true),
+    attr("URI", true),
+    attr("IV", true),
+    attr("KEYFORMAT", true),
+    attr("KEYFORMATVERSIONS", true),
+];
+
+const TAGS: &[TagCoverage] = &[
+    // 4.4.1. Basic Tags
+    tag("EXTM3U", true, &[]),
+    tag("EXT-X-VERSION", true, &[]),
+    // 4.4.2. Media or Multivariant Playlist Tags
+    tag("EXT-X-INDEPENDENT-SEGMENTS", true, &[]),
+    tag(
+        "EXT-X-START",
+        true,
+        &[attr("TIME-OFFSET", true), attr("PRECISE", true)],
+    ),
+    tag(
+        "EXT-X-DEFINE",
+        false,
+        &[
+            attr("NAME", false),
+            attr("VALUE", false),
+            attr("IMPORT", false),
+            attr("QUERYPARAM", false),
+        ],
+    ),
+    // 4.4.3. Media Playlist Tags
+    tag("EXT-X-TARGETDURATION", true, &[]),
+    tag("EXT-X-MEDIA-SEQUENCE", true, &[]),
+    tag("EXT-X-DISCONTINUITY-SEQUENCE", true, &[]),
+    tag("EXT-X-ENDLIST", true, &[]),
+    tag("EXT-X-PLAYLIST-TYPE", true, &[]),
+    tag("EXT-X-I-FRAMES-ONLY", true, &[]),
+    tag("EXT-X-PART-INF", true, &[attr("PART-TARGET", true)]),
+    tag(
+        "EXT-X-SERVER-CONTROL",
+        true,
+        &[
+            attr("CAN-SKIP-UNTIL", true),
+            attr("CAN-SKIP-DATERANGES", true),
+            attr("HOLD-BACK", true),
+            attr("PART-HOLD-BACK", true),
+            attr("CAN-BLOCK-RELOAD", true),
+        ],
+    ),
+    // 4.4.4. Media Segment Tags
+    tag("EXTINF", true, &[]),
+    tag("EXT-X-BYTERANGE", true, &[]),
+    tag("EXT-X-DISCONTINUITY", true, &[]),
+    tag("EXT-X-KEY", true, KEY_ATTRIBUTES),
+    tag(
+        "EXT-X-MAP",
+        true,
+        &[attr("URI", true), attr("BYTERANGE", true)],
+    ),
+    tag("EXT-X-PROGRAM-DATE-TIME", true, &[]),
+    tag("EXT-X-GAP", true, &[]),
+    tag("EXT-X-BITRATE", true, &[]),
+    tag(
+        "EXT-X-PART",
+        true,
+        &[
+            attr("URI", true),
+            attr("DURATION", true),
+            attr("INDEPENDENT", true),
+            attr("BYTERANGE", true),
+            attr("GAP", true),
+        ],
+    ),
+    // 4.4.5. Media Metadata Tags
+    tag(
+        "EXT-X-DATERANGE",
+        true,
+        &[
+            attr("ID", true),
+            attr("CLASS", true),
+            attr("START-DATE", true),
+            attr("CUE", true),
+            attr("END-DATE", true),
+            attr("DURATION", true),
+            attr("PLANNED-DURATION", true),
+            attr("X-<client-attribute>", true),
+            attr("SCTE35-CMD", true),
+            attr("SCTE35-OUT", true),
+            attr("SCTE35-IN", true),
+            attr("END-ON-NEXT", true),
+        ],
+    ),
+    tag(
+        "EXT-X-SKIP",
+        true,
+        &[
+            attr("SKIPPED-SEGMENTS", true),
+            attr("RECENTLY-REMOVED-DATERANGES", true),
+        ],
+    ),
+    tag(
+        "EXT-X-PRELOAD-HINT",
+        true,
+        &[
+            attr("TYPE", true),
+            attr("URI", true),
+            attr("BYTERANGE-START", true),
+            attr("BYTERANGE-LENGTH", true),
+        ],
+    ),
+    tag(
+        "EXT-X-RENDITION-REPORT",
+        true,
+        &[
+            attr("URI", true),
+            attr("LAST-MSN", true),
+            attr("LAST-PART", true),
+        ],
+    ),
+    // 4.4.6. Multivariant Playlist Tags
+    tag(
+        "EXT-X-MEDIA",
+        true,
+        &[
+            attr("TYPE", true),
+            attr("URI", true),
+            attr("GROUP-ID", true),
+            attr("LANGUAGE", true),
+            attr("ASSOC-LANGUAGE", true),
+            attr("NAME", true),
+            attr("STABLE-RENDITION-ID", true),
+            attr("DEFAULT", true),
+            attr("AUTOSELECT", true),
+            attr("FORCED", true),
+            attr("INSTREAM-ID", true),
+            attr("BIT-DEPTH", true),
+            attr("SAMPLE-RATE", true),
+            attr("CHARACTERISTICS", true),
+            attr("CHANNELS", true),
+        ],
+    ),
+    tag(
+        "EXT-X-STREAM-INF",
+        true,
+        &[
+            attr("BANDWIDTH", true),
+            attr("AVERAGE-BANDWIDTH", true),
+            attr("SCORE", true),
+            attr("CODECS", true),
+            attr("SUPPLEMENTAL-CODECS", false),
+            attr("RESOLUTION", true),
+            attr("FRAME-RATE", true),
+            attr("HDCP-LEVEL", true),
+            attr("ALLOWED-CPC", false),
+            attr("VIDEO-RANGE", true),
+            attr("REQ-VIDEO-LAYOUT", true),
+            attr("STABLE-VARIANT-ID", false),
+            attr("AUDIO", true),
+            attr("VIDEO", true),
+            attr("SUBTITLES", true),
+            attr("CLOSED-CAPTIONS", true),
+            attr("PATHWAY-ID", false),
+            attr("PROGRAM-ID", true),
+            attr("NAME", true),
+        ],
+    ),
+    tag(
+        "EXT-X-I-FRAME-STREAM-INF",
+        true,
+        &[
+            attr("BANDWIDTH", true),
+            attr("AVERAGE-BANDWIDTH", true),
+            attr("SCORE", true),
+            attr("CODECS", true),
+            attr("SUPPLEMENTAL-CODECS", false),
+            attr("RESOLUTION", true),
+            attr("HDCP-LEVEL", true),
+            attr("ALLOWED-CPC", false),
+            attr("VIDEO-RANGE", true),
+            attr("REQ-VIDEO-LAYOUT", true),
+            attr("STABLE-VARIANT-ID", false),
+            attr("VIDEO", true),
+            attr("PATHWAY-ID", false),
+            attr("URI", true),
+            attr("PROGRAM-ID", true),
+            attr("NAME", true),
+        ],
+    ),
+    tag(
+        "EXT-X-SESSION-DATA",
+        true,
+        &[
+            attr("DATA-ID", true),
+            attr("VALUE", true),
+            attr("URI", true),
+            attr("FORMAT", true),
+            attr("LANGUAGE", true),
+        ],
+    ),
+    tag("EXT-X-SESSION-KEY", true, KEY_ATTRIBUTES),
+    tag(
+        "EXT-X-CONTENT-STEERING",
+        true,
+        &[attr("SERVER-URI", true), attr("PATHWAY-ID", true)],
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::line::Tag;
+    use crate::tags::ExtM3u;
+    use pretty_assertions::assert_eq;
+    use std::convert::TryFrom;
+
+    fn sample(name: &str) -> &'static str {
+        match name {
+            "EXTM3U" => "#EXTM3U",
+            "EXT-X-VERSION" => "#EXT-X-VERSION:7",
+            "EXT-X-INDEPENDENT-SEGMENTS" => "#EXT-X-INDEPENDENT-SEGMENTS",
+            "EXT-X-START" => "#EXT-X-START:TIME-OFFSET=1,PRECISE=YES",
+            "EXT-X-DEFINE" => "#EXT-X-DEFINE:NAME=\"a\",VALUE=\"b\"",
+            "EXT-X-TARGETDURATION" => "#EXT-X-TARGETDURATION:10",
+            "EXT-X-MEDIA-SEQUENCE" => "#EXT-X-MEDIA-SEQUENCE:1",
+            "EXT-X-DISCONTINUITY-SEQUENCE" => "#EXT-X-DISCONTINUITY-SEQUENCE:1",
+            "EXT-X-ENDLIST" => "#EXT-X-ENDLIST",
+            "EXT-X-PLAYLIST-TYPE" => "#EXT-X-PLAYLIST-TYPE:VOD",
+            "EXT-X-I-FRAMES-ONLY" => "#EXT-X-I-FRAMES-ONLY",
+            "EXT-X-PART-INF" => "#EXT-X-PART-INF:PART-TARGET=1.0",
+            "EXT-X-SERVER-CONTROL" => "#EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=YES",
+            "EXTINF" => "#EXTINF:10,",
+            "EXT-X-BYTERANGE" => "#EXT-X-BYTERANGE:10@0",
+            "EXT-X-DISCONTINUITY" => "#EXT-X-DISCONTINUITY",
+            "EXT-X-KEY" => "#EXT-X-KEY:METHOD=AES-128,URI=\"key\"",
+            "EXT-X-MAP" => "#EXT-X-MAP:URI=\"init.mp4\"",
+            "EXT-X-PROGRAM-DATE-TIME" => "#EXT-X-PROGRAM-DATE-TIME:2010-02-19T14:54:23.031+08:00",
+            "EXT-X-GAP" => "#EXT-X-GAP",
+            "EXT-X-BITRATE" => "#EXT-X-BITRATE:1000",
+            "EXT-X-PART" => "#EXT-X-PART:DURATION=1.0,URI=\"part.mp4\"",
+            "EXT-X-DATERANGE" => {
+                "#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2010-02-19T14:54:23.031+08:00\""
+            }
+            "EXT-X-SKIP" => "#EXT-X-SKIP:SKIPPED-SEGMENTS=1",
+            "EXT-X-PRELOAD-HINT" => "#EXT-X-PRELOAD-HINT:TYPE=PART,URI=\"part.mp4\"",
+            "EXT-X-RENDITION-REPORT" => "#EXT-X-RENDITION-REPORT:URI=\"a.m3u8\",LAST-MSN=1",
+            "EXT-X-MEDIA" => "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"a\",NAME=\"b\"",
+            "EXT-X-STREAM-INF" => "#EXT-X-STREAM-INF:BANDWIDTH=1000\nlow.m3u8",
+            "EXT-X-I-FRAME-STREAM-INF" => "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=1000,URI=\"a.m3u8\"",
+            "EXT-X-SESSION-DATA" => "#EXT-X-SESSION-DATA:DATA-ID=\"a\",VALUE=\"b\"",
+            "EXT-X-SESSION-KEY" => "#EXT-X-SESSION-KEY:METHOD=AES-128,URI=\"key\"",
+            "EXT-X-CONTENT-STEERING" => "#EXT-X-CONTENT-STEERING:SERVER-URI=\"a.json\"",
+            _ => panic!("missing sample for {}", name),
+        }
+    }
+
+    #[test]
+    fn test_report_matches_parser() {
+        for tag in ProtocolCoverage::report().tags() {
+            let input = sample(tag.name());
+
+            let is_supported = if tag.name() == "EXTM3U" {
+                ExtM3u::try_from(input).is_ok()
+            } else {
+                !matches!(Tag::try_from(input), Ok(Tag::Unknown(_)) | Err(_))
+            };
+
+            assert_eq!(tag.is_supported(), is_supported, "{}", tag.name());
+        }
+    }
+
+    #[test]
+    fn test_tag() {
+        let coverage = ProtocolCoverage::report();
+
+        assert_eq!(coverage.tag("#EXTINF").unwrap().name(), "EXTINF");
+        assert_eq!(coverage.tag("EXT-X-MAP").unwrap().attributes().len(), 2);
+        assert_eq!(coverage.tag("EXT-X-NOT-A-TAG"), None);
+
+        assert!(!coverage.supports_attribute("EXT-X-DEFINE", "NAME"));
+        assert!(!coverage.supports_attribute("EXT-X-KEY", "NOT-AN-ATTRIBUTE"));
+        assert!(coverage.supports_attribute("EXT-X-SESSION-KEY", "KEYFORMAT"));
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let capabilities = capabilities();
+
+        assert_eq!(capabilities.crate_version(), env!("CARGO_PKG_VERSION"));
+        assert_eq!(
+            capabilities.protocol_versions().last(),
+            Some(&ProtocolVersion::latest())
+        );
+        assert_eq!(capabilities.coverage(), ProtocolCoverage::report());
+        assert_eq!(capabilities.has_feature("serde"), cfg!(feature = "serde"));
+        assert!(!capabilities.has_feature("unknown"));
+    }
+
+    #[test]
+    fn test_unique_names() {
+        let tags = ProtocolCoverage::report().tags();
+
+        for (i, tag) in tags.iter().enumerate() {
+            assert!(
+                tags[i + 1..].iter().all(|other| other.name() != tag.name()),
+                "{}",
+                tag.name()
+            );
+        }
+    }
+}
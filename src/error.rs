@@ -4,7 +4,8 @@ use std::fmt;
 use backtrace::Backtrace;
 use thiserror::Error;
 
-//use crate::types::ProtocolVersion;
+use crate::types::ProtocolVersion;
+use crate::VersionReason;
 
 /// This crate specific `Result` type.
 pub type Result<T> = std::result::Result<T, Error>;
@@ -41,22 +42,40 @@ enum ErrorKind {
     #[error("{0}")]
     Custom(String),
 
+    #[error("{0}")]
+    InvalidAttribute(String),
+
     #[error("unmatched group: {0:?}")]
     UnmatchedGroup(String),
 
     #[error("unknown protocol version {0:?}")]
     UnknownProtocolVersion(String),
 
-    // #[error("required_version: {:?}, specified_version: {:?}", _0, _1)]
-    // VersionError(ProtocolVersion, ProtocolVersion),
+    #[error(
+        "the playlist requires V{required}, but V{specified} has been specified{}",
+        DisplayReasons(reasons)
+    )]
+    VersionError {
+        required: ProtocolVersion,
+        specified: ProtocolVersion,
+        reasons: Vec<VersionReason>,
+    },
+
     #[error("missing attribute: {attribute:?}")]
     MissingAttribute { attribute: String },
 
     #[error("unexpected attribute: {attribute:?}")]
     UnexpectedAttribute { attribute: String },
 
-    #[error("unexpected tag: {tag:?}")]
-    UnexpectedTag { tag: String },
+    #[error("the tag {tag} must not appear more than once")]
+    DuplicateTag { tag: String },
+
+    #[error("the tag {tag} in line {line} {rule}")]
+    MisplacedTag {
+        tag: String,
+        line: usize,
+        rule: &'static str,
+    },
 
     #[error("{source}")]
     #[cfg(feature = "chrono")]
@@ -69,6 +88,31 @@ enum ErrorKind {
     Hex { source: hex::FromHexError },
 }
 
+/// The rule of a [`ErrorKind::MisplacedTag`] for a master playlist tag, that
+/// has been found in a media playlist.
+pub(crate) const NOT_IN_MEDIA_PLAYLIST: &str = "must not appear in a media playlist";
+
+/// The rule of a [`ErrorKind::MisplacedTag`] for a media playlist tag, that
+/// has been found in a master playlist.
+pub(crate) const NOT_IN_MASTER_PLAYLIST: &str = "must not appear in a master playlist";
+
+struct DisplayReasons<'a>(&'a [VersionReason]);
+
+impl<'a> fmt::Display for DisplayReasons<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for reason in self.0 {
+            write!(
+                f,
+                "; {}; drop {} or raise version",
+                reason,
+                reason.feature()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
 /// The Error type of this library.
 #[derive(Debug)]
 pub struct Error {
@@ -81,7 +125,14 @@ impl PartialEq for Error {
     fn eq(&self, other: &Self) -> bool { self.inner == other.inner }
 }
 
-impl std::error::Error for Error {}
+/// The underlying error (for example a [`ParseIntError`]) is returned by
+/// [`source`], so it is not necessary to downcast the [`Error`](crate::Error).
+///
+/// [`ParseIntError`]: std::num::ParseIntError
+/// [`source`]: std::error::Error::source
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> { self.inner.source() }
+}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { self.inner.fmt(f) }
@@ -101,6 +152,116 @@ impl Error {
         Self::new(ErrorKind::Custom(value.to_string()))
     }
 
+    pub(crate) fn invalid_attribute<T: fmt::Display>(value: T) -> Self {
+        Self::new(ErrorKind::InvalidAttribute(value.to_string()))
+    }
+
+    pub(crate) fn version_error(
+        required: ProtocolVersion,
+        specified: ProtocolVersion,
+        reasons: Vec<VersionReason>,
+    ) -> Self {
+        Self::new(ErrorKind::VersionError {
+            required,
+            specified,
+            reasons,
+        })
+    }
+
+    /// Returns the features, that have to be removed from a playlist, so it
+    /// can be written with a lower [`ProtocolVersion`].
+    ///
+    /// This is empty, if the error is not caused by a
+    /// [`Writer::version_override`], that is lower than the required version.
+    ///
+    /// [`Writer::version_override`]: crate::Writer::version_override
+    #[must_use]
+    pub fn version_reasons(&self) -> &[VersionReason] {
+        if let ErrorKind::VersionError { reasons, .. } = &self.inner {
+            reasons
+        } else {
+            &[]
+        }
+    }
+
+    /// Returns the number of the line (starting at 1), that caused the error.
+    ///
+    /// This is only known for tags, that appear in the wrong place of a
+    /// playlist (for example a master playlist tag in a media playlist).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use std::convert::TryFrom;
+    ///
+    /// let error = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXT-X-SESSION-DATA:DATA-ID=\"com.example.title\",VALUE=\"title\"\n",
+    /// ))
+    /// .unwrap_err();
+    ///
+    /// assert_eq!(error.line(), Some(3));
+    /// ```
+    #[must_use]
+    pub fn line(&self) -> Option<usize> {
+        if let ErrorKind::MisplacedTag { line, .. } = &self.inner {
+            Some(*line)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true`, if the error only affects a single tag or attribute,
+    /// so the rest of the playlist is still usable, if the affected tag is
+    /// skipped.
+    ///
+    /// Errors, that are caused by the structure of the playlist (for example a
+    /// missing `#EXTM3U` header, a segment without an uri or a master playlist
+    /// tag in a media playlist) are not recoverable.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::tags::ExtXStart;
+    /// use std::convert::TryFrom;
+    ///
+    /// // an invalid attribute value:
+    /// let error = ExtXStart::try_from("#EXT-X-START:TIME-OFFSET=x").unwrap_err();
+    /// assert!(error.is_recoverable());
+    ///
+    /// // the `#EXTM3U` header is missing:
+    /// let error = MediaPlaylist::try_from("#EXT-X-TARGETDURATION:10\n").unwrap_err();
+    /// assert!(!error.is_recoverable());
+    /// ```
+    #[must_use]
+    pub fn is_recoverable(&self) -> bool {
+        match &self.inner {
+            ErrorKind::MissingValue { .. }
+            | ErrorKind::ParseIntError { .. }
+            | ErrorKind::ParseFloatError { .. }
+            | ErrorKind::InvalidAttribute(_)
+            | ErrorKind::UnmatchedGroup(_)
+            | ErrorKind::UnknownProtocolVersion(_)
+            | ErrorKind::MissingAttribute { .. }
+            | ErrorKind::UnexpectedAttribute { .. }
+            | ErrorKind::DuplicateTag { .. }
+            | ErrorKind::Hex { .. } => true,
+            ErrorKind::MisplacedTag { rule, .. } => {
+                *rule != NOT_IN_MEDIA_PLAYLIST && *rule != NOT_IN_MASTER_PLAYLIST
+            }
+            #[cfg(feature = "chrono")]
+            ErrorKind::Chrono { .. } => true,
+            ErrorKind::InvalidInput
+            | ErrorKind::MissingTag { .. }
+            | ErrorKind::Custom(_)
+            | ErrorKind::VersionError { .. }
+            | ErrorKind::Builder { .. } => false,
+        }
+    }
+
     pub(crate) fn missing_value<T: ToString>(value: T) -> Self {
         Self::new(ErrorKind::MissingValue {
             value: value.to_string(),
@@ -120,12 +281,20 @@ impl Error {
         })
     }
 
-    pub(crate) fn unexpected_tag<T: ToString>(value: T) -> Self {
-        Self::new(ErrorKind::UnexpectedTag {
+    pub(crate) fn duplicate_tag<T: ToString>(value: T) -> Self {
+        Self::new(ErrorKind::DuplicateTag {
             tag: value.to_string(),
         })
     }
 
+    pub(crate) fn misplaced_tag<T: ToString>(tag: T, line: usize, rule: &'static str) -> Self {
+        Self::new(ErrorKind::MisplacedTag {
+            tag: tag.to_string(),
+            line,
+            rule,
+        })
+    }
+
     pub(crate) fn invalid_input() -> Self { Self::new(ErrorKind::InvalidInput) }
 
     pub(crate) fn parse_int<T: fmt::Display>(input: T, source: ::std::num::ParseIntError) -> Self {
@@ -192,7 +361,7 @@ impl Error {
     }
 
     pub(crate) fn strum(value: strum::ParseError) -> Self {
-        Self::new(ErrorKind::Custom(value.to_string()))
+        Self::new(ErrorKind::InvalidAttribute(value.to_string()))
     }
 }
 
@@ -220,6 +389,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_source() {
+        use std::error::Error as _;
+
+        let error = Error::parse_int("1x", "1x".parse::<usize>().unwrap_err());
+
+        assert_eq!(
+            error.source().map(ToString::to_string),
+            Some("invalid digit found in string".to_string())
+        );
+        assert!(error
+            .source()
+            .unwrap()
+            .downcast_ref::<std::num::ParseIntError>()
+            .is_some());
+
+        assert!(Error::custom("message").source().is_none());
+        assert!(Error::missing_attribute("URI").source().is_none());
+    }
+
+    #[test]
+    fn test_is_recoverable() {
+        assert!(Error::parse_int("1x", "1x".parse::<usize>().unwrap_err()).is_recoverable());
+        assert!(Error::missing_attribute("URI").is_recoverable());
+        assert!(Error::duplicate_tag("EXT-X-VERSION").is_recoverable());
+        assert!(Error::invalid_attribute("invalid INSTREAM-ID").is_recoverable());
+        assert!(
+            Error::misplaced_tag("EXT-X-KEY", 3, "must be followed by a media segment")
+                .is_recoverable()
+        );
+
+        assert!(!Error::missing_tag("#EXTM3U", "").is_recoverable());
+        assert!(!Error::builder("missing field").is_recoverable());
+        assert!(!Error::custom("message").is_recoverable());
+        assert!(!Error::misplaced_tag("EXT-X-MEDIA", 3, NOT_IN_MEDIA_PLAYLIST).is_recoverable());
+        assert!(!Error::misplaced_tag("EXTINF", 3, NOT_IN_MASTER_PLAYLIST).is_recoverable());
+    }
+
     #[test]
     fn test_parse_int_error() {
         assert_eq!(
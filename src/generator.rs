@@ -0,0 +1,271 @@
+//! Deterministic live playlists for testing players and proxies.
+//!
+//! [`synthetic_live`] simulates a live stream, by producing the
+//! [`MediaPlaylist`]s a server would offer over time: every playlist contains
+//! one more segment than the previous one, while the oldest segment is
+//! removed from the sliding window. Optionally, the encryption key is rotated
+//! and discontinuities are inserted in a fixed interval.
+//!
+//! # Example
+//!
+//! ```
+//! use hls_m3u8::generator::{self, Config};
+//!
+//! let mut config = Config::new();
+//! config.set_window(2);
+//! config.set_segment_count(Some(3));
+//!
+//! let playlists = generator::synthetic_live(config).collect::<Result<Vec<_>, _>>()?;
+//!
+//! assert_eq!(playlists.len(), 2);
+//! assert_eq!(
+//!     playlists[1].to_string(),
+//!     concat!(
+//!         "#EXTM3U\n",
+//!         "#EXT-X-TARGETDURATION:6\n",
+//!         "#EXT-X-MEDIA-SEQUENCE:1\n",
+//!         "#EXTINF:6,\n",
+//!         "segment-1.ts\n",
+//!         "#EXTINF:6,\n",
+//!         "segment-2.ts\n",
+//!         "#EXT-X-ENDLIST\n",
+//!     )
+//! );
+//! # Ok::<(), hls_m3u8::Error>(())
+//! ```
+use std::time::Duration;
+
+use shorthand::ShortHand;
+
+use crate::tags::ExtXKey;
+use crate::types::{DecryptionKey, EncryptionMethod};
+use crate::{Error, MediaPlaylist, MediaSegment};
+
+/// The configuration of [`synthetic_live`].
+#[derive(ShortHand, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[shorthand(enable(must_use, copy))]
+#[non_exhaustive]
+pub struct Config {
+    /// The duration of every segment, which is also used as the
+    /// [`MediaPlaylist::target_duration`].
+    ///
+    /// By default this is 6 seconds.
+    segment_duration: Duration,
+    /// The maximum number of segments in a playlist.
+    ///
+    /// By default this is 5.
+    window: usize,
+    /// The total number of segments, after which the stream ends. The last
+    /// playlist will have an `#EXT-X-ENDLIST` tag (see
+    /// [`MediaPlaylist::has_end_list`]).
+    ///
+    /// By default this is `None` and the stream never ends.
+    segment_count: Option<usize>,
+    /// The number of segments, that are encrypted with the same key, before a
+    /// new key is used.
+    ///
+    /// By default this is `None` and the segments are not encrypted.
+    key_rotation: Option<usize>,
+    /// The number of segments between two discontinuities.
+    ///
+    /// By default this is `None` and there are no discontinuities.
+    discontinuity_interval: Option<usize>,
+}
+
+impl Config {
+    /// Makes a new [`Config`] with the default settings.
+    #[must_use]
+    pub fn new() -> Self { Self::default() }
+
+    fn segment(&self, number: usize) -> crate::Result<MediaSegment<'static>> {
+        let mut builder = MediaSegment::builder();
+
+        builder
+            .duration(self.segment_duration)
+            .uri(format!("segment-{}.ts", number));
+
+        if let Some(interval) = self.discontinuity_interval.filter(|&n| n > 0) {
+            builder.has_discontinuity(number > 0 && number.is_multiple_of(interval));
+        }
+
+        if let Some(rotation) = self.key_rotation.filter(|&n| n > 0) {
+            builder.push_key(ExtXKey::new(DecryptionKey::new(
+                EncryptionMethod::Aes128,
+                format!("key-{}.bin", number / rotation),
+            )));
+        }
+
+        builder.build().map_err(Error::builder)
+    }
+
+    fn playlist(&self, media_sequence: usize) -> crate::Result<MediaPlaylist<'static>> {
+        let window = self.window.max(1);
+        let end = media_sequence + window;
+
+        let (end, has_end_list) = match self.segment_count {
+            Some(count) if end >= count => (count, true),
+            _ => (end, false),
+        };
+
+        // every discontinuity, that has been removed from the window, increments
+        // the discontinuity sequence:
+        let discontinuity_sequence = self
+            .discontinuity_interval
+            .filter(|&n| n > 0)
+            .map_or(0, |n| media_sequence.saturating_sub(1) / n);
+
+        MediaPlaylist::builder()
+            .target_duration(self.segment_duration)
+            .media_sequence(media_sequence)
+            .discontinuity_sequence(discontinuity_sequence)
+            .has_end_list(has_end_list)
+            .segments(
+                (media_sequence..end)
+                    .map(|number| self.segment(number))
+                    .collect::<crate::Result<Vec<_>>>()?,
+            )
+            .build()
+            .map_err(Error::builder)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            segment_duration: Duration::from_secs(6),
+            window: 5,
+            segment_count: None,
+            key_rotation: None,
+            discontinuity_interval: None,
+        }
+    }
+}
+
+/// Returns the [`MediaPlaylist`]s of a simulated live stream.
+///
+/// The n-th playlist has the [`MediaPlaylist::media_sequence`] n and the
+/// segments `segment-n.ts` up to `segment-{n + window - 1}.ts`. If
+/// [`Config::segment_count`] is set, the iterator ends with the playlist, that
+/// contains the last segment. Otherwise it is infinite.
+///
+/// The output only depends on the [`Config`], so the same [`Config`] will
+/// always produce the same playlists.
+///
+/// # Errors
+///
+/// An item is an error, if the [`MediaPlaylist`] can not be built from the
+/// [`Config`] (for example, a [`Config::segment_duration`] of 6.7 seconds is
+/// rounded up to 7 seconds, which exceeds the target duration).
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::generator::{self, Config};
+///
+/// let mut config = Config::new();
+/// config.set_key_rotation(Some(4));
+/// config.set_discontinuity_interval(Some(10));
+///
+/// let playlist = generator::synthetic_live(config).nth(8).unwrap()?;
+///
+/// assert_eq!(playlist.media_sequence, 8);
+/// assert_eq!(playlist.segments.num_elements(), 5);
+/// assert!(playlist.segments[2].has_discontinuity);
+/// assert_eq!(
+///     playlist.segments[2].keys[0]
+///         .0
+///         .as_ref()
+///         .map(|key| key.uri().as_str()),
+///     Some("key-2.bin")
+/// );
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+pub fn synthetic_live(
+    config: Config,
+) -> impl Iterator<Item = crate::Result<MediaPlaylist<'static>>> {
+    let last = config
+        .segment_count
+        .map(|count| count.saturating_sub(config.window.max(1)));
+
+    (0..)
+        .take_while(move |&media_sequence| last.is_none_or(|last| media_sequence <= last))
+        .map(move |media_sequence| config.playlist(media_sequence))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_synthetic_live() {
+        let mut config = Config::new();
+        config.set_window(3);
+        config.set_segment_count(Some(6));
+        config.set_key_rotation(Some(2));
+        config.set_discontinuity_interval(Some(3));
+
+        let playlists = synthetic_live(config)
+            .collect::<crate::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(playlists.len(), 4);
+        assert_eq!(
+            playlists,
+            synthetic_live(config)
+                .collect::<crate::Result<Vec<_>>>()
+                .unwrap()
+        );
+
+        assert_eq!(
+            playlists[2].to_string(),
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-VERSION:2\n",
+                "#EXT-X-TARGETDURATION:6\n",
+                "#EXT-X-MEDIA-SEQUENCE:2\n",
+                "#EXT-X-KEY:METHOD=AES-128,URI=\"key-1.bin\"\n",
+                "#EXTINF:6,\n",
+                "segment-2.ts\n",
+                "#EXT-X-DISCONTINUITY\n",
+                "#EXTINF:6,\n",
+                "segment-3.ts\n",
+                "#EXT-X-KEY:METHOD=AES-128,URI=\"key-2.bin\"\n",
+                "#EXTINF:6,\n",
+                "segment-4.ts\n",
+            )
+        );
+
+        assert_eq!(playlists[3].discontinuity_sequence, 0);
+        assert!(playlists[3].has_end_list);
+        assert!(playlists[..3].iter().all(|p| !p.has_end_list));
+
+        config.set_window(2);
+        let playlist = synthetic_live(config).last().unwrap().unwrap();
+
+        assert_eq!(playlist.media_sequence, 4);
+        assert_eq!(playlist.discontinuity_sequence, 1);
+    }
+
+    #[test]
+    fn test_short_stream() {
+        let mut config = Config::new();
+        config.set_segment_count(Some(2));
+
+        let playlists = synthetic_live(config)
+            .collect::<crate::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(playlists.len(), 1);
+        assert_eq!(playlists[0].segments.num_elements(), 2);
+        assert!(playlists[0].has_end_list);
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let mut config = Config::new();
+        config.set_segment_duration(Duration::from_millis(6_700));
+
+        assert!(synthetic_live(config).next().unwrap().is_err());
+    }
+}
@@ -0,0 +1,475 @@
+//! A compact index of the [`MediaSegment`]s of a [`MediaPlaylist`], that can
+//! be stored next to the segments (for example in an object storage), so the
+//! playlist does not have to be parsed for every request.
+//!
+//! The index can be encoded in a binary format with [`SegmentIndex::to_bytes`]
+//! or as JSON, if the `serde` feature is enabled.
+//!
+//! # Example
+//!
+//! ```
+//! use hls_m3u8::index::SegmentIndex;
+//! use hls_m3u8::MediaPlaylist;
+//! use std::convert::TryFrom;
+//! use std::time::Duration;
+//!
+//! let playlist = MediaPlaylist::try_from(concat!(
+//!     "#EXTM3U\n",
+//!     "#EXT-X-TARGETDURATION:10\n",
+//!     "#EXT-X-MEDIA-SEQUENCE:5\n",
+//!     "#EXTINF:9.5,\n",
+//!     "1.ts\n",
+//!     "#EXTINF:10,\n",
+//!     "2.ts\n",
+//! ))?;
+//!
+//! let index = SegmentIndex::from(&playlist);
+//! let entry = &index.entries()[1];
+//!
+//! assert_eq!(entry.sequence(), 6);
+//! assert_eq!(entry.uri(), "2.ts");
+//! assert_eq!(entry.pts_offset(), Duration::from_secs_f64(9.5));
+//!
+//! let bytes = index.to_bytes();
+//! assert_eq!(SegmentIndex::from_bytes(&bytes)?, index);
+//!
+//! assert_eq!(index.to_media_playlist()?.to_string(), playlist.to_string());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [`MediaSegment`]: crate::MediaSegment
+use std::convert::TryFrom;
+use std::time::Duration;
+
+use shorthand::ShortHand;
+
+use crate::types::ByteRange;
+use crate::{Error, MediaPlaylist, MediaSegment};
+
+/// The magic bytes at the start of the binary format.
+const MAGIC: &[u8; 4] = b"HLSI";
+/// The version of the binary format.
+const FORMAT_VERSION: u8 = 1;
+
+const HAS_BYTE_RANGE: u8 = 0b01;
+const HAS_BYTE_RANGE_START: u8 = 0b10;
+
+/// A single [`MediaSegment`] in a [`SegmentIndex`].
+///
+/// [`MediaSegment`]: crate::MediaSegment
+#[derive(ShortHand, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[shorthand(enable(must_use), disable(set))]
+pub struct IndexEntry {
+    /// The [`MediaSegment::number`] of the segment.
+    ///
+    /// [`MediaSegment::number`]: crate::MediaSegment::number
+    #[shorthand(enable(copy))]
+    sequence: usize,
+    /// The uri of the segment.
+    uri: String,
+    /// The byte range of the segment.
+    #[shorthand(enable(copy))]
+    byte_range: Option<ByteRange>,
+    /// The duration of the segment.
+    #[shorthand(enable(copy))]
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::duration_secs"))]
+    duration: Duration,
+    /// The sum of the durations of all previous segments in the index.
+    #[shorthand(enable(copy))]
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::duration_secs"))]
+    pts_offset: Duration,
+}
+
+/// An index of the [`MediaSegment`]s of a [`MediaPlaylist`].
+///
+/// [`MediaSegment`]: crate::MediaSegment
+#[derive(ShortHand, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[shorthand(enable(must_use), disable(set))]
+pub struct SegmentIndex {
+    /// The [`MediaPlaylist::target_duration`] of the playlist.
+    #[shorthand(enable(copy))]
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::duration_secs"))]
+    target_duration: Duration,
+    /// The [`MediaPlaylist::media_sequence`] of the playlist.
+    #[shorthand(enable(copy))]
+    media_sequence: usize,
+    /// The segments in the order they appear in the playlist.
+    entries: Vec<IndexEntry>,
+}
+
+impl SegmentIndex {
+    /// Returns the entry with the given [`IndexEntry::sequence`].
+    #[must_use]
+    pub fn get(&self, sequence: usize) -> Option<&IndexEntry> {
+        self.entries.iter().find(|entry| entry.sequence == sequence)
+    }
+
+    /// Returns the entry of the segment, that is played at the given time.
+    #[must_use]
+    pub fn find_by_time(&self, time: Duration) -> Option<&IndexEntry> {
+        self.entries
+            .iter()
+            .find(|entry| time >= entry.pts_offset && time < entry.pts_offset + entry.duration)
+    }
+
+    /// Makes a new [`MediaPlaylist`] with the [`MediaSegment`]s of the index.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the [`MediaPlaylist`] can not be built (for example, because
+    /// a segment is longer than the target duration).
+    ///
+    /// [`MediaSegment`]: crate::MediaSegment
+    pub fn to_media_playlist(&self) -> crate::Result<MediaPlaylist<'static>> {
+        let segments = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut builder = MediaSegment::builder();
+
+                builder.uri(entry.uri.clone()).duration(entry.duration);
+
+                if let Some(range) = entry.byte_range {
+                    builder.byte_range(range);
+                }
+
+                builder.build().map_err(Error::builder)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        MediaPlaylist::builder()
+            .target_duration(self.target_duration)
+            .media_sequence(self.media_sequence)
+            .segments(segments)
+            .build()
+            .map_err(Error::builder)
+    }
+
+    /// Encodes the index in a compact binary format.
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(16 + self.entries.len() * 32);
+
+        result.extend_from_slice(MAGIC);
+        result.push(FORMAT_VERSION);
+
+        write_duration(&mut result, self.target_duration);
+        write_varint(&mut result, self.media_sequence as u64);
+        write_varint(&mut result, self.entries.len() as u64);
+
+        for entry in &self.entries {
+            write_varint(&mut result, entry.sequence as u64);
+            write_varint(&mut result, entry.uri.len() as u64);
+            result.extend_from_slice(entry.uri.as_bytes());
+
+            match entry.byte_range {
+                Some(range) => {
+                    if let Some(start) = range.start() {
+                        result.push(HAS_BYTE_RANGE | HAS_BYTE_RANGE_START);
+                        write_varint(&mut result, start as u64);
+                    } else {
+                        result.push(HAS_BYTE_RANGE);
+                    }
+
+                    write_varint(&mut result, range.len() as u64);
+                }
+                None => result.push(0),
+            }
+
+            write_duration(&mut result, entry.duration);
+        }
+
+        result
+    }
+
+    /// Decodes an index, that has been encoded with
+    /// [`SegmentIndex::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the input is not a valid index.
+    pub fn from_bytes(input: &[u8]) -> crate::Result<Self> {
+        let mut reader = Reader(input);
+
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(Error::custom("invalid segment index: missing magic bytes"));
+        }
+
+        let version = reader.take(1)?[0];
+        if version != FORMAT_VERSION {
+            return Err(Error::custom(format!(
+                "invalid segment index: unsupported version {}",
+                version
+            )));
+        }
+
+        let target_duration = reader.duration()?;
+        let media_sequence = reader.usize()?;
+        let len = reader.usize()?;
+
+        // every entry needs at least 4 bytes, so a corrupt length can not cause
+        // a huge allocation:
+        let mut entries = Vec::with_capacity(len.min(input.len() / 4));
+        let mut pts_offset = Duration::from_secs(0);
+
+        for _ in 0..len {
+            let sequence = reader.usize()?;
+            let uri_len = reader.usize()?;
+            let uri = String::from_utf8(reader.take(uri_len)?.to_vec())
+                .map_err(|e| Error::custom(format!("invalid segment index: {}", e)))?;
+
+            let flags = reader.take(1)?[0];
+            let byte_range = if flags & HAS_BYTE_RANGE == 0 {
+                None
+            } else {
+                let start = if flags & HAS_BYTE_RANGE_START == 0 {
+                    None
+                } else {
+                    Some(reader.usize()?)
+                };
+                let length = reader.usize()?;
+
+                Some(match start {
+                    Some(start) => ByteRange::from(start..start.saturating_add(length)),
+                    None => ByteRange::from(..length),
+                })
+            };
+
+            let duration = reader.duration()?;
+
+            entries.push(IndexEntry {
+                sequence,
+                uri,
+                byte_range,
+                duration,
+                pts_offset,
+            });
+
+            pts_offset = pts_offset
+                .checked_add(duration)
+                .ok_or_else(|| Error::custom("invalid segment index: the durations overflow"))?;
+        }
+
+        if !reader.0.is_empty() {
+            return Err(Error::custom("invalid segment index: trailing data"));
+        }
+
+        Ok(Self {
+            target_duration,
+            media_sequence,
+            entries,
+        })
+    }
+}
+
+impl<'a, 'b> From<&'b MediaPlaylist<'a>> for SegmentIndex {
+    fn from(playlist: &'b MediaPlaylist<'a>) -> Self {
+        let mut pts_offset = Duration::from_secs(0);
+        let mut entries = Vec::with_capacity(playlist.segments.num_elements());
+
+        for segment in playlist.segments.values() {
+            let duration = segment.duration.duration();
+
+            entries.push(IndexEntry {
+                sequence: segment.number(),
+                uri: segment.uri().to_string(),
+                byte_range: segment.byte_range.map(Into::into),
+                duration,
+                pts_offset,
+            });
+
+            pts_offset = pts_offset.saturating_add(duration);
+        }
+
+        Self {
+            target_duration: playlist.target_duration,
+            media_sequence: playlist.media_sequence,
+            entries,
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for SegmentIndex {
+    type Error = Error;
+
+    fn try_from(input: &[u8]) -> Result<Self, Self::Error> { Self::from_bytes(input) }
+}
+
+fn write_varint(output: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        output.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+
+    output.push(value as u8);
+}
+
+fn write_duration(output: &mut Vec<u8>, value: Duration) {
+    write_varint(output, value.as_secs());
+    write_varint(output, u64::from(value.subsec_nanos()));
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> crate::Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(Error::custom("invalid segment index: unexpected end"));
+        }
+
+        let (result, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(result)
+    }
+
+    fn varint(&mut self) -> crate::Result<u64> {
+        let mut result = 0_u64;
+
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            result |= u64::from(byte & 0x7F) << shift;
+
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+
+        Err(Error::custom("invalid segment index: varint is too long"))
+    }
+
+    fn usize(&mut self) -> crate::Result<usize> {
+        usize::try_from(self.varint()?)
+            .map_err(|_| Error::custom("invalid segment index: number is too large"))
+    }
+
+    fn duration(&mut self) -> crate::Result<Duration> {
+        let secs = self.varint()?;
+        let nanos = u32::try_from(self.varint()?)
+            .ok()
+            .filter(|nanos| *nanos < 1_000_000_000)
+            .ok_or_else(|| Error::custom("invalid segment index: invalid duration"))?;
+
+        Ok(Duration::new(secs, nanos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn playlist() -> MediaPlaylist<'static> {
+        MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-VERSION:4\n",
+            "#EXT-X-MEDIA-SEQUENCE:300\n",
+            "#EXTINF:9.009,\n",
+            "#EXT-X-BYTERANGE:1000@0\n",
+            "https://example.com/video.ts\n",
+            "#EXTINF:9.009,\n",
+            "#EXT-X-BYTERANGE:500\n",
+            "https://example.com/video.ts\n",
+            "#EXTINF:3.003,\n",
+            "https://example.com/end.ts\n",
+        ))
+        .unwrap()
+        .into_owned()
+    }
+
+    #[test]
+    fn test_from_playlist() {
+        let index = SegmentIndex::from(&playlist());
+
+        assert_eq!(index.target_duration(), Duration::from_secs(10));
+        assert_eq!(index.media_sequence(), 300);
+        assert_eq!(
+            index.entries()[1],
+            IndexEntry {
+                sequence: 301,
+                uri: "https://example.com/video.ts".into(),
+                byte_range: Some(ByteRange::from(1000..1500)),
+                duration: Duration::from_secs_f64(9.009),
+                pts_offset: Duration::from_secs_f64(9.009),
+            }
+        );
+
+        assert_eq!(
+            index.get(302).map(IndexEntry::uri),
+            Some(&"https://example.com/end.ts".to_string())
+        );
+        assert_eq!(index.get(303), None);
+        assert_eq!(
+            index
+                .find_by_time(Duration::from_secs(10))
+                .map(IndexEntry::sequence),
+            Some(301)
+        );
+        assert_eq!(index.find_by_time(Duration::from_secs(30)), None);
+    }
+
+    #[test]
+    fn test_bytes() {
+        let index = SegmentIndex::from(&playlist());
+        let bytes = index.to_bytes();
+
+        assert_eq!(SegmentIndex::from_bytes(&bytes).unwrap(), index);
+        assert_eq!(SegmentIndex::try_from(bytes.as_slice()).unwrap(), index);
+
+        for len in 0..bytes.len() {
+            assert!(SegmentIndex::from_bytes(&bytes[..len]).is_err());
+        }
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(SegmentIndex::from_bytes(&trailing).is_err());
+
+        let mut version = bytes;
+        version[4] = 2;
+        assert!(SegmentIndex::from_bytes(&version).is_err());
+
+        // the sum of the durations does not fit into a `Duration`:
+        let mut overflow = MAGIC.to_vec();
+        overflow.push(FORMAT_VERSION);
+        write_duration(&mut overflow, Duration::from_secs(10));
+        write_varint(&mut overflow, 0);
+        write_varint(&mut overflow, 2);
+
+        for sequence in 0..2 {
+            write_varint(&mut overflow, sequence);
+            write_varint(&mut overflow, 0);
+            overflow.push(0);
+            write_duration(&mut overflow, Duration::from_secs(u64::MAX));
+        }
+
+        assert_eq!(
+            SegmentIndex::from_bytes(&overflow).unwrap_err().to_string(),
+            "invalid segment index: the durations overflow"
+        );
+    }
+
+    #[test]
+    fn test_to_media_playlist() {
+        let playlist = playlist();
+        let index = SegmentIndex::from(&playlist);
+
+        assert_eq!(
+            index.to_media_playlist().unwrap().to_string(),
+            playlist.to_string()
+        );
+    }
+
+    #[test]
+    fn test_varint() {
+        for value in &[0, 1, 127, 128, 300, u64::from(u32::MAX), u64::MAX] {
+            let mut output = Vec::new();
+            write_varint(&mut output, *value);
+
+            assert_eq!(Reader(&output).varint().unwrap(), *value);
+        }
+
+        assert!(Reader(&[0xFF; 11]).varint().is_err());
+    }
+}
@@ -0,0 +1,583 @@
+//! [HLS Interstitials] are [`ExtXDateRange`]s with the class
+//! [`CLASS`], that schedule the playback of other assets (for example ads)
+//! on the timeline of the primary asset.
+//!
+//! [HLS Interstitials]:
+//! https://developer.apple.com/streaming/GettingStartedWithHLSInterstitials.pdf
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::Duration;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, FixedOffset};
+use derive_more::{AsRef, Deref};
+use shorthand::ShortHand;
+use strum::{Display, EnumString};
+
+use crate::builder::tags::ExtXDateRangeBuilder;
+use crate::tags::ExtXDateRange;
+use crate::types::{CueHint, Float, Value};
+use crate::Error;
+
+/// The [`ExtXDateRange::class`] of an [`Interstitial`].
+pub const CLASS: &str = "com.apple.hls.interstitial";
+
+/// A single asset of an [`Interstitial`].
+#[derive(ShortHand, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[shorthand(enable(must_use, into))]
+pub struct Asset {
+    /// The uri of the primary playlist of the asset.
+    #[cfg_attr(feature = "serde", serde(rename = "URI"))]
+    uri: String,
+    /// The duration of the asset.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "DURATION", with = "crate::utils::duration_secs")
+    )]
+    #[shorthand(enable(copy))]
+    duration: Duration,
+}
+
+impl Asset {
+    /// Makes a new [`Asset`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::interstitial::Asset;
+    /// use std::time::Duration;
+    ///
+    /// let asset = Asset::new("https://example.com/ad.m3u8", Duration::from_secs(15));
+    /// ```
+    #[must_use]
+    pub fn new<T: Into<String>>(uri: T, duration: Duration) -> Self {
+        Self {
+            uri: uri.into(),
+            duration,
+        }
+    }
+}
+
+/// The JSON object, the `X-ASSET-LIST` attribute of an [`Interstitial`]
+/// points to.
+///
+/// If the `serde` feature is enabled, this type can be deserialized from the
+/// JSON, for example with `serde_json`:
+///
+/// ```json
+/// { "ASSETS": [{ "URI": "https://example.com/ad.m3u8", "DURATION": 15.0 }] }
+/// ```
+#[derive(ShortHand, Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[shorthand(enable(must_use, into))]
+pub struct AssetList {
+    /// The assets, that should be played in order.
+    #[cfg_attr(feature = "serde", serde(rename = "ASSETS"))]
+    assets: Vec<Asset>,
+}
+
+impl AssetList {
+    /// Makes a new [`AssetList`].
+    #[must_use]
+    pub fn new<T: Into<Vec<Asset>>>(assets: T) -> Self {
+        Self {
+            assets: assets.into(),
+        }
+    }
+}
+
+/// A value of the `X-SNAP` attribute of an [`Interstitial`], which allows
+/// the client to move the interstitial to the nearest segment boundary.
+#[non_exhaustive]
+#[derive(Ord, PartialOrd, Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString)]
+#[strum(serialize_all = "SCREAMING-KEBAB-CASE")]
+pub enum Snap {
+    /// The interstitial should start at the nearest segment boundary.
+    Out,
+    /// The primary asset should resume at the nearest segment boundary.
+    In,
+}
+
+/// A value of the `X-RESTRICT` attribute of an [`Interstitial`], which
+/// restricts the navigation of the user during the interstitial.
+#[non_exhaustive]
+#[derive(Ord, PartialOrd, Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString)]
+#[strum(serialize_all = "SCREAMING-KEBAB-CASE")]
+pub enum Restriction {
+    /// The user must not skip the interstitial.
+    Skip,
+    /// The user must not jump over the interstitial, by seeking past it.
+    Jump,
+}
+
+/// An [`ExtXDateRange`] with the class [`CLASS`].
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::interstitial::{Asset, AssetList, Interstitial};
+/// use hls_m3u8::tags::ExtXDateRange;
+/// use std::convert::TryFrom;
+/// use std::time::Duration;
+///
+/// let date_range = ExtXDateRange::try_from(concat!(
+///     "#EXT-X-DATERANGE:ID=\"ad1\",CLASS=\"com.apple.hls.interstitial\",",
+///     "START-DATE=\"2010-02-19T14:54:23.031+08:00\",X-ASSET-LIST=\"ads.json\""
+/// ))?;
+///
+/// let interstitial = Interstitial::try_from(date_range)?;
+///
+/// let assets = interstitial.resolve_assets(|uri| {
+///     assert_eq!(uri, "ads.json");
+///     // download and deserialize the asset list here
+///     Ok::<_, hls_m3u8::Error>(AssetList::new(vec![Asset::new(
+///         "ad.m3u8",
+///         Duration::from_secs(15),
+///     )]))
+/// })?;
+///
+/// assert_eq!(assets, vec![Asset::new("ad.m3u8", Duration::from_secs(15))]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(AsRef, Deref, Debug, Clone, PartialEq, Eq)]
+pub struct Interstitial<'a>(ExtXDateRange<'a>);
+
+impl<'a> Interstitial<'a> {
+    /// Returns the uri of the primary playlist of the asset (`X-ASSET-URI`).
+    #[must_use]
+    pub fn asset_uri(&self) -> Option<&str> { self.attribute("X-ASSET-URI") }
+
+    /// Returns the uri of the [`AssetList`] (`X-ASSET-LIST`).
+    #[must_use]
+    pub fn asset_list(&self) -> Option<&str> { self.attribute("X-ASSET-LIST") }
+
+    /// Returns the offset from the start of the interstitial, where the
+    /// playback of the primary asset should resume (`X-RESUME-OFFSET`).
+    ///
+    /// If it is missing, the playback resumes after the duration of the
+    /// interstitial.
+    #[must_use]
+    pub fn resume_offset(&self) -> Option<Duration> { self.duration_attribute("X-RESUME-OFFSET") }
+
+    /// Returns the maximum duration of the interstitial (`X-PLAYOUT-LIMIT`).
+    #[must_use]
+    pub fn playout_limit(&self) -> Option<Duration> { self.duration_attribute("X-PLAYOUT-LIMIT") }
+
+    /// Returns the known values of the `X-SNAP` attribute.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::interstitial::{Interstitial, Snap};
+    /// use hls_m3u8::tags::ExtXDateRange;
+    /// use std::convert::TryFrom;
+    ///
+    /// let interstitial = Interstitial::try_from(ExtXDateRange::try_from(concat!(
+    ///     "#EXT-X-DATERANGE:ID=\"ad1\",CLASS=\"com.apple.hls.interstitial\",",
+    ///     "X-ASSET-URI=\"ad.m3u8\",X-SNAP=\"OUT,IN\""
+    /// ))?)?;
+    ///
+    /// assert_eq!(
+    ///     interstitial.snaps().collect::<Vec<_>>(),
+    ///     vec![Snap::Out, Snap::In]
+    /// );
+    /// assert!(interstitial.has_snap(Snap::In));
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    pub fn snaps(&self) -> impl Iterator<Item = Snap> + '_ { self.list_attribute("X-SNAP") }
+
+    /// Returns `true`, if the `X-SNAP` attribute contains the [`Snap`].
+    #[must_use]
+    pub fn has_snap(&self, snap: Snap) -> bool { self.snaps().any(|value| value == snap) }
+
+    /// Returns the known values of the `X-RESTRICT` attribute.
+    pub fn restrictions(&self) -> impl Iterator<Item = Restriction> + '_ {
+        self.list_attribute("X-RESTRICT")
+    }
+
+    /// Returns `true`, if the `X-RESTRICT` attribute contains the
+    /// [`Restriction`].
+    #[must_use]
+    pub fn has_restriction(&self, restriction: Restriction) -> bool {
+        self.restrictions().any(|value| value == restriction)
+    }
+
+    /// Returns the assets, that should be played.
+    ///
+    /// If the interstitial has an [`Interstitial::asset_uri`], it is returned
+    /// as the only asset with the [`ExtXDateRange::duration`] (or
+    /// [`ExtXDateRange::planned_duration`]) of the interstitial. Otherwise
+    /// `fetch` is called with the [`Interstitial::asset_list`] uri, which
+    /// should download and deserialize the [`AssetList`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `fetch`.
+    pub fn resolve_assets<F, E>(&self, fetch: F) -> Result<Vec<Asset>, E>
+    where
+        F: FnOnce(&str) -> Result<AssetList, E>,
+    {
+        if let Some(uri) = self.asset_uri() {
+            let duration = self.0.duration.or(self.0.planned_duration);
+
+            Ok(vec![Asset::new(uri, duration.unwrap_or_default())])
+        } else if let Some(uri) = self.asset_list() {
+            Ok(fetch(uri)?.assets)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    /// Returns the underlying [`ExtXDateRange`].
+    #[must_use]
+    pub fn into_inner(self) -> ExtXDateRange<'a> { self.0 }
+
+    /// Returns a builder for an [`Interstitial`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::interstitial::{Interstitial, Restriction};
+    /// use hls_m3u8::types::Cue;
+    /// use std::time::Duration;
+    ///
+    /// let interstitial = Interstitial::builder()
+    ///     .id("preroll")
+    ///     .cue(Cue::Pre | Cue::Once)
+    ///     .asset_uri("https://example.com/ad.m3u8")
+    ///     .resume_offset(Duration::from_secs(0))
+    ///     .restrict(vec![Restriction::Skip, Restriction::Jump])
+    ///     .build()?;
+    ///
+    /// assert_eq!(
+    ///     interstitial.to_string(),
+    ///     concat!(
+    ///         "#EXT-X-DATERANGE:ID=\"preroll\",CLASS=\"com.apple.hls.interstitial\",",
+    ///         "CUE=\"PRE,ONCE\",X-ASSET-URI=\"https://example.com/ad.m3u8\",",
+    ///         "X-RESTRICT=\"SKIP,JUMP\",X-RESUME-OFFSET=0"
+    ///     )
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub fn builder() -> InterstitialBuilder<'a> { InterstitialBuilder::default() }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.0
+            .client_attributes
+            .get(name)
+            .and_then(|value| value.as_str())
+    }
+
+    fn duration_attribute(&self, name: &str) -> Option<Duration> {
+        let value = self.0.client_attributes.get(name)?.as_float()?;
+
+        Duration::try_from_secs_f32(value.as_f32()).ok()
+    }
+
+    fn list_attribute<T: std::str::FromStr>(&self, name: &str) -> impl Iterator<Item = T> + '_ {
+        self.attribute(name)
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|value| value.trim().parse().ok())
+    }
+}
+
+/// A builder for an [`Interstitial`], see [`Interstitial::builder`].
+///
+/// Exactly one of [`InterstitialBuilder::asset_uri`] and
+/// [`InterstitialBuilder::asset_list`] has to be set.
+#[derive(Default, Clone)]
+pub struct InterstitialBuilder<'a> {
+    date_range: ExtXDateRangeBuilder<'a>,
+}
+
+impl<'a> InterstitialBuilder<'a> {
+    /// Sets the [`ExtXDateRange::id`].
+    pub fn id<T: Into<Cow<'a, str>>>(&mut self, value: T) -> &mut Self {
+        self.date_range.id(value);
+        self
+    }
+
+    /// Sets the [`ExtXDateRange::start_date`].
+    #[cfg(feature = "chrono")]
+    pub fn start_date(&mut self, value: DateTime<FixedOffset>) -> &mut Self {
+        self.date_range.start_date(value);
+        self
+    }
+
+    /// Sets the [`ExtXDateRange::start_date`].
+    #[cfg(not(feature = "chrono"))]
+    pub fn start_date<T: Into<Cow<'a, str>>>(&mut self, value: T) -> &mut Self {
+        self.date_range.start_date(value);
+        self
+    }
+
+    /// Sets the [`ExtXDateRange::cue`].
+    pub fn cue<T: Into<CueHint>>(&mut self, value: T) -> &mut Self {
+        self.date_range.cue(value);
+        self
+    }
+
+    /// Sets the [`ExtXDateRange::duration`].
+    pub fn duration(&mut self, value: Duration) -> &mut Self {
+        self.date_range.duration(value);
+        self
+    }
+
+    /// Sets the [`ExtXDateRange::planned_duration`].
+    pub fn planned_duration(&mut self, value: Duration) -> &mut Self {
+        self.date_range.planned_duration(value);
+        self
+    }
+
+    /// Sets the uri of the primary playlist of the asset (`X-ASSET-URI`).
+    pub fn asset_uri<T: Into<Cow<'a, str>>>(&mut self, value: T) -> &mut Self {
+        self.insert_client_attribute("X-ASSET-URI", Value::String(value.into()))
+    }
+
+    /// Sets the uri of the [`AssetList`] (`X-ASSET-LIST`).
+    pub fn asset_list<T: Into<Cow<'a, str>>>(&mut self, value: T) -> &mut Self {
+        self.insert_client_attribute("X-ASSET-LIST", Value::String(value.into()))
+    }
+
+    /// Sets the [`Interstitial::resume_offset`].
+    pub fn resume_offset(&mut self, value: Duration) -> &mut Self {
+        self.insert_client_attribute(
+            "X-RESUME-OFFSET",
+            Value::Float(Float::new(value.as_secs_f32())),
+        )
+    }
+
+    /// Sets the [`Interstitial::playout_limit`].
+    pub fn playout_limit(&mut self, value: Duration) -> &mut Self {
+        self.insert_client_attribute(
+            "X-PLAYOUT-LIMIT",
+            Value::Float(Float::new(value.as_secs_f32())),
+        )
+    }
+
+    /// Sets the `X-SNAP` attribute.
+    pub fn snap<I: IntoIterator<Item = Snap>>(&mut self, values: I) -> &mut Self {
+        self.insert_client_attribute("X-SNAP", Value::String(join(values).into()))
+    }
+
+    /// Sets the `X-RESTRICT` attribute.
+    pub fn restrict<I: IntoIterator<Item = Restriction>>(&mut self, values: I) -> &mut Self {
+        self.insert_client_attribute("X-RESTRICT", Value::String(join(values).into()))
+    }
+
+    /// Inserts any other client attribute (for example `X-COM-EXAMPLE-ID`).
+    pub fn insert_client_attribute<K: Into<Cow<'a, str>>, V: Into<Value<'a>>>(
+        &mut self,
+        key: K,
+        value: V,
+    ) -> &mut Self {
+        self.date_range.insert_client_attribute(key, value);
+        self
+    }
+
+    /// Builds the [`Interstitial`].
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the [`ExtXDateRange`] can not be built or if not exactly
+    /// one of `X-ASSET-URI` and `X-ASSET-LIST` is set.
+    pub fn build(&self) -> crate::Result<Interstitial<'a>> {
+        let date_range = self
+            .date_range
+            .clone()
+            .class(CLASS)
+            .build()
+            .map_err(Error::builder)?;
+
+        let result = Interstitial(date_range);
+
+        match (result.asset_uri(), result.asset_list()) {
+            (Some(_), Some(_)) => {
+                Err(Error::builder(
+                    "`X-ASSET-URI` and `X-ASSET-LIST` are mutually exclusive",
+                ))
+            }
+            (None, None) => Err(Error::builder("`X-ASSET-URI` or `X-ASSET-LIST` is missing")),
+            _ => Ok(result),
+        }
+    }
+}
+
+// the derived builder of the `ExtXDateRange` does not implement `Debug`:
+impl<'a> fmt::Debug for InterstitialBuilder<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InterstitialBuilder")
+            .finish_non_exhaustive()
+    }
+}
+
+fn join<I: IntoIterator<Item = T>, T: ToString>(values: I) -> String {
+    values
+        .into_iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Fails, if the [`ExtXDateRange::class`] is not [`CLASS`].
+impl<'a> TryFrom<ExtXDateRange<'a>> for Interstitial<'a> {
+    type Error = Error;
+
+    fn try_from(value: ExtXDateRange<'a>) -> Result<Self, Self::Error> {
+        if value.class().map(AsRef::as_ref) == Some(CLASS) {
+            Ok(Self(value))
+        } else {
+            Err(Error::custom(format!(
+                "expected the class {:?} for an interstitial, but got {:?}",
+                CLASS,
+                value.class()
+            )))
+        }
+    }
+}
+
+impl<'a> From<Interstitial<'a>> for ExtXDateRange<'a> {
+    fn from(value: Interstitial<'a>) -> Self { value.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn date_range(class: &'static str) -> ExtXDateRange<'static> {
+        ExtXDateRange::try_from(
+            format!(
+                concat!(
+                    "#EXT-X-DATERANGE:ID=\"ad1\",CLASS=\"{}\",",
+                    "START-DATE=\"2010-02-19T14:54:23.031+08:00\",",
+                    "DURATION=15.5"
+                ),
+                class
+            )
+            .as_str(),
+        )
+        .unwrap()
+        .into_owned()
+    }
+
+    #[test]
+    fn test_try_from() {
+        assert!(Interstitial::try_from(date_range(CLASS)).is_ok());
+        assert!(Interstitial::try_from(date_range("com.example.class")).is_err());
+    }
+
+    #[test]
+    fn test_attributes() {
+        let interstitial = Interstitial::try_from(
+            ExtXDateRange::try_from(concat!(
+                "#EXT-X-DATERANGE:ID=\"ad1\",CLASS=\"com.apple.hls.interstitial\",",
+                "X-ASSET-LIST=\"ads.json\",X-RESUME-OFFSET=12.5,X-PLAYOUT-LIMIT=30,",
+                "X-SNAP=\"IN\",X-RESTRICT=\"JUMP,UNKNOWN\""
+            ))
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(interstitial.asset_uri(), None);
+        assert_eq!(interstitial.asset_list(), Some("ads.json"));
+        assert_eq!(
+            interstitial.resume_offset(),
+            Some(Duration::from_secs_f64(12.5))
+        );
+        assert_eq!(interstitial.playout_limit(), Some(Duration::from_secs(30)));
+        assert_eq!(interstitial.snaps().collect::<Vec<_>>(), vec![Snap::In]);
+        assert!(!interstitial.has_snap(Snap::Out));
+        assert_eq!(
+            interstitial.restrictions().collect::<Vec<_>>(),
+            vec![Restriction::Jump]
+        );
+        assert!(interstitial.has_restriction(Restriction::Jump));
+        assert!(!interstitial.has_restriction(Restriction::Skip));
+
+        let interstitial = Interstitial::try_from(date_range(CLASS)).unwrap();
+
+        assert_eq!(interstitial.resume_offset(), None);
+        assert_eq!(interstitial.playout_limit(), None);
+        assert_eq!(interstitial.snaps().count(), 0);
+        assert_eq!(interstitial.restrictions().count(), 0);
+    }
+
+    #[test]
+    fn test_builder() {
+        let interstitial = Interstitial::builder()
+            .id("ad1")
+            .start_date({
+                #[cfg(feature = "chrono")]
+                {
+                    "2010-02-19T14:54:23.031+08:00".parse().unwrap()
+                }
+                #[cfg(not(feature = "chrono"))]
+                {
+                    "2010-02-19T14:54:23.031+08:00"
+                }
+            })
+            .duration(Duration::from_secs(15))
+            .asset_list("ads.json")
+            .playout_limit(Duration::from_secs(30))
+            .snap(vec![Snap::Out, Snap::In])
+            .insert_client_attribute("X-COM-EXAMPLE-ID", Value::string("xyz"))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            interstitial.to_string(),
+            concat!(
+                "#EXT-X-DATERANGE:ID=\"ad1\",CLASS=\"com.apple.hls.interstitial\",",
+                "START-DATE=\"2010-02-19T14:54:23.031+08:00\",DURATION=15,",
+                "X-ASSET-LIST=\"ads.json\",X-COM-EXAMPLE-ID=\"xyz\",",
+                "X-PLAYOUT-LIMIT=30,X-SNAP=\"OUT,IN\""
+            )
+        );
+
+        let input = interstitial.to_string();
+        let parsed = Interstitial::try_from(ExtXDateRange::try_from(input.as_str()).unwrap());
+        assert_eq!(parsed.unwrap(), interstitial);
+
+        // the asset is missing:
+        assert!(Interstitial::builder().id("ad1").build().is_err());
+        // the asset uri and the asset list are mutually exclusive:
+        assert!(Interstitial::builder()
+            .id("ad1")
+            .asset_uri("ad.m3u8")
+            .asset_list("ads.json")
+            .build()
+            .is_err());
+        // the id is missing:
+        assert!(Interstitial::builder()
+            .asset_uri("ad.m3u8")
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_resolve_assets() {
+        let mut value = date_range(CLASS);
+        let interstitial = Interstitial::try_from(value.clone()).unwrap();
+
+        assert_eq!(
+            interstitial.resolve_assets(|_| -> Result<_, ()> { unreachable!() }),
+            Ok(vec![])
+        );
+
+        value
+            .client_attributes
+            .insert("X-ASSET-URI".into(), Value::string("ad.m3u8"));
+        let interstitial = Interstitial::try_from(value).unwrap();
+
+        assert_eq!(interstitial.asset_uri(), Some("ad.m3u8"));
+        assert_eq!(
+            interstitial.resolve_assets(|_| -> Result<_, ()> { unreachable!() }),
+            Ok(vec![Asset::new("ad.m3u8", Duration::from_secs_f64(15.5))])
+        );
+    }
+}
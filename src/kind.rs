@@ -0,0 +1,171 @@
+use crate::tags::ExtM3u;
+use crate::utils::tag_name;
+
+/// The kind of a playlist, as returned by [`detect_kind`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PlaylistKind {
+    /// The input looks like a [`MasterPlaylist`].
+    ///
+    /// [`MasterPlaylist`]: crate::MasterPlaylist
+    Master,
+    /// The input looks like a [`MediaPlaylist`].
+    ///
+    /// [`MediaPlaylist`]: crate::MediaPlaylist
+    Media,
+    /// The input does not start with `#EXTM3U` or contains no tag, that only
+    /// appears in one kind of playlist.
+    Unknown,
+}
+
+/// The tags, that must only appear in a [`MasterPlaylist`].
+///
+/// [`MasterPlaylist`]: crate::MasterPlaylist
+const MASTER_TAGS: &[&str] = &[
+    "EXT-X-STREAM-INF",
+    "EXT-X-I-FRAME-STREAM-INF",
+    "EXT-X-MEDIA",
+    "EXT-X-SESSION-DATA",
+    "EXT-X-SESSION-KEY",
+    "EXT-X-CONTENT-STEERING",
+];
+
+/// The tags, that must only appear in a [`MediaPlaylist`].
+///
+/// [`MediaPlaylist`]: crate::MediaPlaylist
+const MEDIA_TAGS: &[&str] = &[
+    "EXTINF",
+    "EXT-X-BYTERANGE",
+    "EXT-X-BITRATE",
+    "EXT-X-DISCONTINUITY",
+    "EXT-X-GAP",
+    "EXT-X-KEY",
+    "EXT-X-MAP",
+    "EXT-X-PART",
+    "EXT-X-PART-INF",
+    "EXT-X-PROGRAM-DATE-TIME",
+    "EXT-X-DATERANGE",
+    "EXT-X-TARGETDURATION",
+    "EXT-X-MEDIA-SEQUENCE",
+    "EXT-X-DISCONTINUITY-SEQUENCE",
+    "EXT-X-ENDLIST",
+    "EXT-X-PLAYLIST-TYPE",
+    "EXT-X-I-FRAMES-ONLY",
+    "EXT-X-SERVER-CONTROL",
+    "EXT-X-SKIP",
+    "EXT-X-PRELOAD-HINT",
+    "EXT-X-RENDITION-REPORT",
+];
+
+/// Returns the kind of the playlist, without parsing it.
+///
+/// The lines are scanned until the first tag, that only appears in one kind
+/// of playlist, is found. A URI, that is not preceded by an
+/// `#EXT-X-STREAM-INF` tag, indicates a [`MediaPlaylist`] as well.
+///
+/// This is much cheaper than trying to parse the input as a
+/// [`MasterPlaylist`] and then as a [`MediaPlaylist`], but the input is not
+/// validated, so parsing it can still fail.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::{detect_kind, PlaylistKind};
+///
+/// assert_eq!(
+///     detect_kind(concat!(
+///         "#EXTM3U\n",
+///         "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+///         "low/index.m3u8\n",
+///     )),
+///     PlaylistKind::Master
+/// );
+///
+/// assert_eq!(
+///     detect_kind(concat!(
+///         "#EXTM3U\n",
+///         "#EXT-X-TARGETDURATION:10\n",
+///         "#EXTINF:9.009,\n",
+///         "segment.ts\n",
+///     )),
+///     PlaylistKind::Media
+/// );
+///
+/// assert_eq!(detect_kind("segment.ts"), PlaylistKind::Unknown);
+/// ```
+///
+/// [`MasterPlaylist`]: crate::MasterPlaylist
+/// [`MediaPlaylist`]: crate::MediaPlaylist
+#[must_use]
+pub fn detect_kind(input: &str) -> PlaylistKind {
+    let mut lines = input.lines().map(str::trim).filter(|line| !line.is_empty());
+
+    if lines.next() != Some(ExtM3u::PREFIX) {
+        return PlaylistKind::Unknown;
+    }
+
+    for line in lines {
+        if !line.starts_with('#') {
+            return PlaylistKind::Media;
+        } else if !line.starts_with("#EXT") {
+            // a comment
+            continue;
+        }
+
+        let name = tag_name(line);
+
+        if MASTER_TAGS.contains(&name) {
+            return PlaylistKind::Master;
+        } else if MEDIA_TAGS.contains(&name) {
+            return PlaylistKind::Media;
+        }
+    }
+
+    PlaylistKind::Unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_detect_kind() {
+        assert_eq!(
+            detect_kind(concat!(
+                "\n",
+                "#EXTM3U\n",
+                "#EXT-X-VERSION:4\n",
+                "#EXT-X-INDEPENDENT-SEGMENTS\n",
+                "# a comment\n",
+                "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"audio\",NAME=\"English\"\n",
+            )),
+            PlaylistKind::Master
+        );
+
+        assert_eq!(
+            detect_kind(concat!(
+                "#EXTM3U\n",
+                "#EXT-X-VERSION:3\n",
+                "#EXT-X-MEDIA-SEQUENCE:10\n",
+            )),
+            PlaylistKind::Media
+        );
+
+        // a legacy playlist without EXTINF tags:
+        assert_eq!(
+            detect_kind("#EXTM3U\nsegment-1.ts\nsegment-2.ts\n"),
+            PlaylistKind::Media
+        );
+
+        assert_eq!(
+            detect_kind("#EXTM3U\n#EXT-X-VERSION:3\n"),
+            PlaylistKind::Unknown
+        );
+        assert_eq!(
+            detect_kind("#EXTINF:10,\nsegment.ts\n"),
+            PlaylistKind::Unknown
+        );
+        assert_eq!(detect_kind(""), PlaylistKind::Unknown);
+    }
+}
@@ -76,6 +76,41 @@
 //!       `DateTime<FixedOffset>`
 //!     - [`ExtXDateRange::end_date`] will change from [`String`] to
 //!       `DateTime<FixedOffset>`
+//! - [`flate2`] (optional)
+//!   - Enables `Compression::Gzip`, so [`MediaPlaylist::to_bytes`] can write
+//!     pre-compressed playlist bodies.
+//!   - This feature depends on the following dependencies:
+//!     - [`flate2`]
+//! - [`serde`] (optional)
+//!   - Implements `Serialize` and `Deserialize` for the JSON types of this
+//!     crate (like [`AssetList`], [`Chapter`] and [`SegmentIndex`]).
+//!   - This feature depends on the following dependencies:
+//!     - [`serde`]
+//! - [`language-tags`] (optional)
+//!   - Adds a warning to the [`Validator`] for malformed or not normalized
+//!     `LANGUAGE` and `ASSOC-LANGUAGE` attributes and enables
+//!     `ExtXMedia::normalize_languages`.
+//!   - This feature depends on the following dependencies:
+//!     - [`language-tags`]
+//! - `m3u8-rs-compat` (optional)
+//!   - Implements `TryFrom` conversions between the [`MediaPlaylist`] and
+//!     [`MasterPlaylist`] of this crate and the playlists of the [`m3u8-rs`]
+//!     crate, so both crates can be used side by side.
+//!   - This feature depends on the following dependencies:
+//!     - [`m3u8-rs`]
+//! - `scte35` (optional)
+//!   - Decodes the hex encoded SCTE-35 splice information of the `SCTE35-CMD`,
+//!     `SCTE35-OUT` and `SCTE35-IN` attributes of an [`ExtXDateRange`] (see
+//!     `ExtXDateRange::splice_info_out`).
+//!   - This feature has no additional dependencies.
+//! - `intern` (optional)
+//!   - Interns strings, that repeat across many playlists (codecs, group ids
+//!     and `KEYFORMAT`s), when a playlist is made independent of its lifetime
+//!     with `into_owned`. Equal strings will then share a single allocation,
+//!     which reduces memory usage of long-running playlist caches.
+//!   - Interned strings are never freed, so the interner is bounded: at most
+//!     4096 strings of up to 256 bytes are interned, every other string gets
+//!     its own allocation as without the feature.
 //!
 //! They are configured in your `Cargo.toml` and can be enabled like this
 //!
@@ -89,14 +124,34 @@
 //! crate::tags::ExtXDateRange::start_date
 //! [`ExtXDateRange::end_date`]:
 //! crate::tags::ExtXDateRange::end_date
+//! [`ExtXDateRange`]: crate::tags::ExtXDateRange
 //! [`chrono`]: https://github.com/chronotope/chrono
 //! [`backtrace`]: https://github.com/rust-lang/backtrace-rs
+//! [`flate2`]: https://github.com/rust-lang/flate2-rs
+//! [`language-tags`]: https://github.com/pyfisch/rust-language-tags
+//! [`m3u8-rs`]: https://github.com/rutgersc/m3u8-rs
+//! [`serde`]: https://github.com/serde-rs/serde
+//! [`AssetList`]: crate::interstitial::AssetList
+//! [`Chapter`]: crate::chapters::Chapter
+//! [`SegmentIndex`]: crate::index::SegmentIndex
 //! [HLS]: https://tools.ietf.org/html/rfc8216
 
+pub use coverage::{capabilities, AttributeCoverage, Capabilities, ProtocolCoverage, TagCoverage};
 pub use error::Error;
-pub use master_playlist::MasterPlaylist;
-pub use media_playlist::MediaPlaylist;
-pub use media_segment::MediaSegment;
+pub use kind::{detect_kind, PlaylistKind};
+pub use master_playlist::{MasterPlaylist, MasterPlaylistChange};
+pub use master_playlist_parser::{MasterPlaylistEvent, MasterPlaylistParser};
+pub use media_playlist::{MediaPlaylist, PlaylistHeader};
+pub use media_segment::{MediaSegment, PendingSegment};
+pub use parse_options::{Ignored, IgnoredHandler, ParseOptions, Strictness};
+pub use validation::{
+    Clock, Diagnostic, Severity, SystemClock, UpdateViolation, ValidationReport, Validator,
+};
+pub use visitor::{TagVisitor, TagVisitorMut};
+pub use writer::{
+    Compression, DefaultUriNormalizer, DurationPrecision, KeyWritePolicy, UriKind, UriNormalizer,
+    UriPolicy, UtcFormat, Writer,
+};
 
 /// Builder structs
 pub mod builder {
@@ -122,18 +177,67 @@ pub mod builder {
         pub use crate::types::stream_data::StreamDataBuilder;
     }
 }
+/// The most commonly used types and traits.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::prelude::*;
+/// use std::convert::TryFrom;
+///
+/// let playlist = MediaPlaylist::try_from(concat!(
+///     "#EXTM3U\n",
+///     "#EXT-X-TARGETDURATION:10\n",
+///     "#EXTINF:9.5,\n",
+///     "1.ts\n",
+/// ))?;
+///
+/// assert_eq!(playlist.required_version(), ProtocolVersion::V3);
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+pub mod prelude {
+    pub use crate::tags::{
+        ExtInf, ExtXByteRange, ExtXDateRange, ExtXKey, ExtXMap, ExtXMedia, ExtXProgramDateTime,
+        ExtXSessionData, ExtXSessionKey, ExtXStart, VariantStream,
+    };
+    pub use crate::types::{
+        ByteRange, DecryptionKey, EncryptionMethod, MediaType, ProtocolVersion,
+    };
+    pub use crate::{
+        Decryptable, Error, MasterPlaylist, MediaPlaylist, MediaSegment, ParseOptions,
+        RequiredVersion, Writer,
+    };
+}
+pub mod ads;
+pub mod attribute;
+pub mod bundle;
+pub mod chapters;
+pub mod generator;
+pub mod index;
+pub mod interstitial;
+pub mod profiles;
+#[cfg(feature = "scte35")]
+pub mod scte35;
 pub mod tags;
 pub mod types;
 
 #[macro_use]
 mod utils;
-mod attribute;
+#[cfg(feature = "m3u8-rs-compat")]
+mod compat;
+mod coverage;
 mod error;
+mod kind;
 mod line;
 mod master_playlist;
+mod master_playlist_parser;
 mod media_playlist;
 mod media_segment;
+mod parse_options;
 mod traits;
+mod validation;
+mod visitor;
+mod writer;
 
 pub use error::Result;
 pub use stable_vec;
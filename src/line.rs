@@ -5,21 +5,60 @@ use derive_more::Display;
 
 use crate::tags;
 use crate::types::PlaylistType;
+use crate::utils;
 use crate::Error;
 
 #[derive(Debug, Clone)]
 pub(crate) struct Lines<'a> {
-    lines: ::core::iter::FilterMap<::core::str::Lines<'a>, fn(&'a str) -> Option<&'a str>>,
+    lines: ::core::iter::Enumerate<::core::str::Lines<'a>>,
+    line: usize,
+    current: &'a str,
+    comments: Vec<&'a str>,
+}
+
+impl<'a> Lines<'a> {
+    /// Returns the number of the line (starting at 1), that has been returned
+    /// last.
+    pub(crate) const fn line(&self) -> usize { self.line }
+
+    /// Returns the trimmed line, that has been returned last. For a variant
+    /// stream this is the line of the `#EXT-X-STREAM-INF` tag.
+    pub(crate) const fn current(&self) -> &'a str { self.current }
+
+    /// Returns the comments, that appeared between the `#EXT-X-STREAM-INF`
+    /// tag and its uri, if the line returned last was a variant stream.
+    pub(crate) fn comments(&self) -> &[&'a str] { &self.comments }
+
+    fn next_line(&mut self) -> Option<(usize, &'a str)> {
+        self.lines
+            .by_ref()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .find(|(_, line)| !line.is_empty())
+    }
 }
 
 impl<'a> Iterator for Lines<'a> {
     type Item = crate::Result<Line<'a>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let line = self.lines.next()?;
+        let (number, line) = self.next_line()?;
+        self.line = number;
+        self.current = line;
+        self.comments.clear();
 
         if line.starts_with(tags::VariantStream::PREFIX_EXTXSTREAMINF) {
-            let uri = self.lines.next()?;
+            // comments might appear between the tag and its uri:
+            let uri = loop {
+                match self.next_line() {
+                    Some((_, next)) if next.starts_with('#') && !next.starts_with("#EXT") => {
+                        self.comments.push(next);
+                    }
+                    Some((_, next)) => break next,
+                    None => {
+                        return Some(Err(Error::custom(format!("missing URI for {:?}", line))));
+                    }
+                }
+            };
 
             Some(
                 tags::VariantStream::try_from(format!("{}\n{}", line, uri).as_str())
@@ -41,13 +80,15 @@ impl<'a> FusedIterator for Lines<'a> {}
 impl<'a> From<&'a str> for Lines<'a> {
     fn from(buffer: &'a str) -> Self {
         Self {
-            lines: buffer
-                .lines()
-                .filter_map(|line| Some(line.trim()).filter(|v| !v.is_empty())),
+            lines: buffer.lines().enumerate(),
+            line: 0,
+            current: "",
+            comments: vec![],
         }
     }
 }
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) enum Line<'a> {
     Tag(Tag<'a>),
@@ -62,9 +103,12 @@ pub(crate) enum Tag<'a> {
     ExtXVersion(tags::ExtXVersion),
     ExtInf(tags::ExtInf<'a>),
     ExtXByteRange(tags::ExtXByteRange),
+    ExtXBitrate(tags::ExtXBitrate),
     ExtXDiscontinuity(tags::ExtXDiscontinuity),
+    ExtXGap(tags::ExtXGap),
     ExtXKey(tags::ExtXKey<'a>),
     ExtXMap(tags::ExtXMap<'a>),
+    ExtXPart(tags::ExtXPart<'a>),
     ExtXProgramDateTime(tags::ExtXProgramDateTime<'a>),
     ExtXDateRange(tags::ExtXDateRange<'a>),
     ExtXTargetDuration(tags::ExtXTargetDuration),
@@ -73,15 +117,29 @@ pub(crate) enum Tag<'a> {
     ExtXEndList(tags::ExtXEndList),
     PlaylistType(PlaylistType),
     ExtXIFramesOnly(tags::ExtXIFramesOnly),
+    ExtXAllowCache(tags::ExtXAllowCache),
     ExtXMedia(tags::ExtXMedia<'a>),
     ExtXSessionData(tags::ExtXSessionData<'a>),
     ExtXSessionKey(tags::ExtXSessionKey<'a>),
     ExtXIndependentSegments(tags::ExtXIndependentSegments),
     ExtXStart(tags::ExtXStart),
+    ExtXContentSteering(tags::ExtXContentSteering<'a>),
+    ExtXServerControl(tags::ExtXServerControl),
+    ExtXPartInf(tags::ExtXPartInf),
+    ExtXSkip(tags::ExtXSkip<'a>),
+    ExtXPreloadHint(tags::ExtXPreloadHint<'a>),
+    ExtXRenditionReport(tags::ExtXRenditionReport<'a>),
     VariantStream(tags::VariantStream<'a>),
     Unknown(&'a str),
 }
 
+impl<'a> Tag<'a> {
+    /// Returns an error, that the tag in the `line` violates the `rule`.
+    pub(crate) fn misplaced(&self, line: usize, rule: &'static str) -> Error {
+        Error::misplaced_tag(utils::tag_name(&self.to_string()), line, rule)
+    }
+}
+
 impl<'a> TryFrom<&'a str> for Tag<'a> {
     type Error = Error;
 
@@ -92,14 +150,20 @@ impl<'a> TryFrom<&'a str> for Tag<'a> {
             TryFrom::try_from(input).map(Self::ExtInf)
         } else if input.starts_with(tags::ExtXByteRange::PREFIX) {
             TryFrom::try_from(input).map(Self::ExtXByteRange)
+        } else if input.starts_with(tags::ExtXBitrate::PREFIX) {
+            TryFrom::try_from(input).map(Self::ExtXBitrate)
         } else if input.starts_with(tags::ExtXDiscontinuitySequence::PREFIX) {
             TryFrom::try_from(input).map(Self::ExtXDiscontinuitySequence)
         } else if input.starts_with(tags::ExtXDiscontinuity::PREFIX) {
             TryFrom::try_from(input).map(Self::ExtXDiscontinuity)
+        } else if input.starts_with(tags::ExtXGap::PREFIX) {
+            TryFrom::try_from(input).map(Self::ExtXGap)
         } else if input.starts_with(tags::ExtXKey::PREFIX) {
             TryFrom::try_from(input).map(Self::ExtXKey)
         } else if input.starts_with(tags::ExtXMap::PREFIX) {
             TryFrom::try_from(input).map(Self::ExtXMap)
+        } else if input.starts_with(tags::ExtXPart::PREFIX) {
+            TryFrom::try_from(input).map(Self::ExtXPart)
         } else if input.starts_with(tags::ExtXProgramDateTime::PREFIX) {
             TryFrom::try_from(input).map(Self::ExtXProgramDateTime)
         } else if input.starts_with(tags::ExtXTargetDuration::PREFIX) {
@@ -114,6 +178,8 @@ impl<'a> TryFrom<&'a str> for Tag<'a> {
             TryFrom::try_from(input).map(Self::PlaylistType)
         } else if input.starts_with(tags::ExtXIFramesOnly::PREFIX) {
             TryFrom::try_from(input).map(Self::ExtXIFramesOnly)
+        } else if input.starts_with(tags::ExtXAllowCache::PREFIX) {
+            TryFrom::try_from(input).map(Self::ExtXAllowCache)
         } else if input.starts_with(tags::ExtXMedia::PREFIX) {
             TryFrom::try_from(input).map(Self::ExtXMedia)
         } else if input.starts_with(tags::VariantStream::PREFIX_EXTXIFRAME)
@@ -128,8 +194,56 @@ impl<'a> TryFrom<&'a str> for Tag<'a> {
             TryFrom::try_from(input).map(Self::ExtXIndependentSegments)
         } else if input.starts_with(tags::ExtXStart::PREFIX) {
             TryFrom::try_from(input).map(Self::ExtXStart)
+        } else if input.starts_with(tags::ExtXContentSteering::PREFIX) {
+            TryFrom::try_from(input).map(Self::ExtXContentSteering)
+        } else if input.starts_with(tags::ExtXServerControl::PREFIX) {
+            TryFrom::try_from(input).map(Self::ExtXServerControl)
+        } else if input.starts_with(tags::ExtXPartInf::PREFIX) {
+            TryFrom::try_from(input).map(Self::ExtXPartInf)
+        } else if input.starts_with(tags::ExtXSkip::PREFIX) {
+            TryFrom::try_from(input).map(Self::ExtXSkip)
+        } else if input.starts_with(tags::ExtXPreloadHint::PREFIX) {
+            TryFrom::try_from(input).map(Self::ExtXPreloadHint)
+        } else if input.starts_with(tags::ExtXRenditionReport::PREFIX) {
+            TryFrom::try_from(input).map(Self::ExtXRenditionReport)
         } else {
             Ok(Self::Unknown(input))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_comments_in_variant_stream() {
+        let mut lines = Lines::from(concat!(
+            "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+            "# a comment\n",
+            "\n",
+            "# another comment\n",
+            "http://example.com/low/index.m3u8\n",
+            "# trailing comment\n",
+        ));
+
+        assert!(matches!(
+            lines.next(),
+            Some(Ok(Line::Tag(Tag::VariantStream(_))))
+        ));
+        assert_eq!(lines.line(), 1);
+        assert_eq!(lines.comments(), ["# a comment", "# another comment"]);
+        assert_eq!(lines.next(), Some(Ok(Line::Comment("# trailing comment"))));
+        assert_eq!(lines.line(), 6);
+        assert!(lines.comments().is_empty());
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn test_missing_variant_stream_uri() {
+        let mut lines = Lines::from("#EXT-X-STREAM-INF:BANDWIDTH=150000\n# comment\n");
+
+        assert!(matches!(lines.next(), Some(Err(_))));
+    }
+}
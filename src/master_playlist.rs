@@ -2,17 +2,21 @@ use std::borrow::Cow;
 use std::collections::HashSet;
 use std::convert::TryFrom;
 use std::fmt;
+use std::mem;
 
 use derive_builder::Builder;
 
 use crate::line::{Line, Lines, Tag};
 use crate::tags::{
-    ExtM3u, ExtXIndependentSegments, ExtXMedia, ExtXSessionData, ExtXSessionKey, ExtXStart,
-    ExtXVersion, VariantStream,
+    ExtM3u, ExtXContentSteering, ExtXIndependentSegments, ExtXMedia, ExtXSessionData,
+    ExtXSessionKey, ExtXStart, ExtXVersion, SessionData, VariantStream,
 };
 use crate::types::{ClosedCaptions, MediaType, ProtocolVersion};
-use crate::utils::{tag, BoolExt};
-use crate::{Error, RequiredVersion};
+use crate::utils::{self, BoolExt};
+use crate::{
+    CustomTag, Error, ParseOptions, RequiredVersion, UriKind, ValidationReport, VersionReason,
+    Writer,
+};
 
 /// The master playlist describes all of the available variants for your
 /// content.
@@ -120,6 +124,14 @@ pub struct MasterPlaylist<'a> {
     /// the start.
     #[builder(default)]
     pub start: Option<ExtXStart>,
+    /// Allows the server to steer the client between different pathways
+    /// (for example content delivery networks).
+    ///
+    /// ### Note
+    ///
+    /// This field is optional.
+    #[builder(default)]
+    pub content_steering: Option<ExtXContentSteering<'a>>,
     /// A list of all [`ExtXMedia`] tags, which describe an alternative
     /// rendition.
     ///
@@ -134,14 +146,14 @@ pub struct MasterPlaylist<'a> {
     /// This field is optional.
     ///
     /// [`MediaPlaylist`]: crate::MediaPlaylist
-    #[builder(default)]
+    #[builder(default, setter(custom))]
     pub media: Vec<ExtXMedia<'a>>,
     /// A list of all streams of this [`MasterPlaylist`].
     ///
     /// ### Note
     ///
     /// This field is optional.
-    #[builder(default)]
+    #[builder(default, setter(custom))]
     pub variant_streams: Vec<VariantStream<'a>>,
     /// The [`ExtXSessionData`] tag allows arbitrary session data to be
     /// carried in a [`MasterPlaylist`].
@@ -149,7 +161,7 @@ pub struct MasterPlaylist<'a> {
     /// ### Note
     ///
     /// This field is optional.
-    #[builder(default)]
+    #[builder(default, setter(custom))]
     pub session_data: Vec<ExtXSessionData<'a>>,
     /// A list of [`ExtXSessionKey`]s, that allows the client to preload
     /// these keys without having to read the [`MediaPlaylist`]s first.
@@ -159,15 +171,38 @@ pub struct MasterPlaylist<'a> {
     /// This field is optional.
     ///
     /// [`MediaPlaylist`]: crate::MediaPlaylist
-    #[builder(default)]
+    #[builder(default, setter(custom))]
     pub session_keys: Vec<ExtXSessionKey<'a>>,
     /// A list of all tags that could not be identified while parsing the input.
     ///
     /// ### Note
     ///
     /// This field is optional.
-    #[builder(default)]
+    #[builder(default, setter(custom))]
     pub unknown_tags: Vec<Cow<'a, str>>,
+    /// Unknown tags, that are written before the [`VariantStream`] with the
+    /// given index in [`MasterPlaylist::variant_streams`]. Tags with a
+    /// position after the last variant stream are written after all variant
+    /// streams.
+    ///
+    /// Comments between an `#EXT-X-STREAM-INF` tag and its uri are kept here
+    /// as well and are written before that variant stream.
+    ///
+    /// ### Note
+    ///
+    /// This field is optional.
+    #[builder(default, setter(custom))]
+    pub positioned_tags: Vec<(usize, Cow<'a, str>)>,
+    /// Everything, that follows the `#EXTM3U` tag on the same line.
+    ///
+    /// Some tools write parameters after the tag, which are kept, so they can
+    /// be written again.
+    ///
+    /// ### Note
+    ///
+    /// This field is optional.
+    #[builder(default)]
+    pub header_attributes: Option<Cow<'a, str>>,
 }
 
 impl<'a> MasterPlaylist<'a> {
@@ -258,6 +293,58 @@ impl<'a> MasterPlaylist<'a> {
         })
     }
 
+    /// Returns the [`VariantStream::ExtXStreamInf`]s ordered by preference.
+    ///
+    /// A variant with a higher [`StreamData::score`] is preferred and variants
+    /// without a score come last. Variants with the same score are ordered by
+    /// their [`StreamData::bandwidth`] (the highest first) and otherwise keep
+    /// the order of the playlist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MasterPlaylist;
+    /// use std::convert::TryFrom;
+    ///
+    /// let playlist = MasterPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-STREAM-INF:BANDWIDTH=150000,SCORE=1.0\n",
+    ///     "low.m3u8\n",
+    ///     "#EXT-X-STREAM-INF:BANDWIDTH=640000,SCORE=1.0\n",
+    ///     "high-avc.m3u8\n",
+    ///     "#EXT-X-STREAM-INF:BANDWIDTH=540000,SCORE=2.0\n",
+    ///     "high-hevc.m3u8\n",
+    /// ))?;
+    ///
+    /// assert_eq!(
+    ///     playlist
+    ///         .variants_by_preference()
+    ///         .map(|variant| variant.bandwidth())
+    ///         .collect::<Vec<_>>(),
+    ///     vec![540_000, 640_000, 150_000]
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    ///
+    /// [`StreamData::score`]: crate::types::StreamData::score
+    /// [`StreamData::bandwidth`]: crate::types::StreamData::bandwidth
+    pub fn variants_by_preference(&self) -> impl Iterator<Item = &VariantStream<'a>> {
+        let mut result = self
+            .variant_streams
+            .iter()
+            .filter(|stream| matches!(stream, VariantStream::ExtXStreamInf { .. }))
+            .collect::<Vec<_>>();
+
+        // the sort is stable, so equal variants keep their order:
+        result.sort_by(|a, b| {
+            b.score()
+                .cmp(&a.score())
+                .then_with(|| b.bandwidth().cmp(&a.bandwidth()))
+        });
+
+        result.into_iter()
+    }
+
     /// Returns all `ExtXMedia` tags, associated with the provided stream.
     pub fn associated_with<'b>(
         &'b self,
@@ -268,6 +355,499 @@ impl<'a> MasterPlaylist<'a> {
             .filter(move |media| stream.is_associated(media))
     }
 
+    /// Removes all closed captions from the playlist.
+    ///
+    /// All [`ExtXMedia`] tags with [`MediaType::ClosedCaptions`] are removed
+    /// and every [`VariantStream::ExtXStreamInf`] is set to
+    /// [`ClosedCaptions::None`], which signals that there are no closed
+    /// captions in any variant stream.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MasterPlaylist;
+    /// use hls_m3u8::tags::{ExtXMedia, VariantStream};
+    /// use hls_m3u8::types::{ClosedCaptions, InStreamId, MediaType, StreamData};
+    ///
+    /// let mut playlist = MasterPlaylist::builder()
+    ///     .media(vec![ExtXMedia::builder()
+    ///         .media_type(MediaType::ClosedCaptions)
+    ///         .group_id("cc")
+    ///         .name("English")
+    ///         .instream_id(InStreamId::Cc1)
+    ///         .build()?])
+    ///     .variant_streams(vec![VariantStream::ExtXStreamInf {
+    ///         uri: "low/index.m3u8".into(),
+    ///         frame_rate: None,
+    ///         audio: None,
+    ///         subtitles: None,
+    ///         closed_captions: Some(ClosedCaptions::group_id("cc")),
+    ///         stream_data: StreamData::new(150_000),
+    ///     }])
+    ///     .build()?;
+    ///
+    /// playlist.strip_closed_captions();
+    ///
+    /// assert!(playlist.media.is_empty());
+    /// assert!(matches!(
+    ///     &playlist.variant_streams[0],
+    ///     VariantStream::ExtXStreamInf {
+    ///         closed_captions: Some(ClosedCaptions::None),
+    ///         ..
+    ///     }
+    /// ));
+    /// # Ok::<(), Box<dyn ::std::error::Error>>(())
+    /// ```
+    pub fn strip_closed_captions(&mut self) -> &mut Self {
+        self.media
+            .retain(|media| media.media_type != MediaType::ClosedCaptions);
+
+        for stream in &mut self.variant_streams {
+            if let VariantStream::ExtXStreamInf {
+                closed_captions, ..
+            } = stream
+            {
+                *closed_captions = Some(ClosedCaptions::None);
+            }
+        }
+
+        self
+    }
+
+    /// Retains only the [`VariantStream`]s, for which the predicate returns
+    /// `true`, and removes everything, that is only referenced by the removed
+    /// variant streams:
+    ///
+    /// - [`ExtXMedia`] tags, whose group is no longer referenced by any variant
+    ///   stream.
+    /// - [`VariantStream::ExtXIFrame`] streams, which have the same `VIDEO`
+    ///   group or `RESOLUTION` as a removed [`VariantStream::ExtXStreamInf`],
+    ///   but no longer match any of the remaining ones.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MasterPlaylist;
+    /// use std::convert::TryFrom;
+    ///
+    /// let mut playlist = MasterPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"low\",NAME=\"English\",URI=\"low/a.m3u8\"\n",
+    ///     "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"high\",NAME=\"English\",URI=\"high/a.m3u8\"\n",
+    ///     "#EXT-X-STREAM-INF:BANDWIDTH=500000,RESOLUTION=640x360,AUDIO=\"low\"\n",
+    ///     "low/v.m3u8\n",
+    ///     "#EXT-X-STREAM-INF:BANDWIDTH=5000000,RESOLUTION=1920x1080,AUDIO=\"high\"\n",
+    ///     "high/v.m3u8\n",
+    ///     "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=50000,RESOLUTION=640x360,URI=\"low/i.m3u8\"\n",
+    ///     "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=500000,RESOLUTION=1920x1080,URI=\"high/i.m3u8\"\n",
+    /// ))?;
+    ///
+    /// playlist.retain_variants(|stream| stream.bandwidth() < 1_000_000);
+    ///
+    /// assert_eq!(playlist.variant_streams.len(), 2);
+    /// assert_eq!(playlist.media.len(), 1);
+    /// assert_eq!(playlist.media[0].group_id(), "low");
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    pub fn retain_variants<F>(&mut self, predicate: F) -> &mut Self
+    where
+        F: FnMut(&VariantStream<'a>) -> bool,
+    {
+        let streams = mem::take(&mut self.variant_streams);
+        let mut is_kept = streams.iter().map(predicate).collect::<Vec<_>>();
+
+        let references = |is_kept: &[bool], kept: bool, f: &dyn Fn(&VariantStream<'a>) -> bool| {
+            streams
+                .iter()
+                .zip(is_kept)
+                .any(|(stream, is_kept)| *is_kept == kept && f(stream))
+        };
+
+        let is_orphaned_i_frame = streams
+            .iter()
+            .map(|i_frame| {
+                references(&is_kept, false, &|stream| is_i_frame_of(i_frame, stream))
+                    && !references(&is_kept, true, &|stream| is_i_frame_of(i_frame, stream))
+            })
+            .collect::<Vec<_>>();
+
+        for (is_kept, is_orphaned) in is_kept.iter_mut().zip(is_orphaned_i_frame) {
+            *is_kept &= !is_orphaned;
+        }
+
+        self.media.retain(|media| {
+            !references(&is_kept, false, &|stream| stream.is_associated(media))
+                || references(&is_kept, true, &|stream| stream.is_associated(media))
+        });
+
+        // the tags are written before the next remaining variant stream:
+        for (position, _) in &mut self.positioned_tags {
+            *position = is_kept.iter().take(*position).filter(|v| **v).count();
+        }
+
+        self.variant_streams = streams
+            .into_iter()
+            .zip(is_kept)
+            .filter(|(_, is_kept)| *is_kept)
+            .map(|(stream, _)| stream)
+            .collect();
+
+        self
+    }
+
+    /// Selects the [`ExtXMedia`] tag of the given [`MediaType`], that should
+    /// be played.
+    ///
+    /// The `language_preferences` are tried in order and the first rendition
+    /// with a matching language is returned. An exact (case-insensitive) match
+    /// is preferred over a match of the language prefix (`en` matches `en-US`
+    /// and the other way around). If multiple renditions match equally well,
+    /// the one with `DEFAULT=YES`, then the one with `AUTOSELECT=YES` and
+    /// then the first one is selected.
+    ///
+    /// If no language matches, the rendition with `DEFAULT=YES` or else the
+    /// first one with `AUTOSELECT=YES` is returned. Renditions with
+    /// `FORCED=YES` are only considered, if `allow_forced` is `true`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MasterPlaylist;
+    /// use hls_m3u8::tags::ExtXMedia;
+    /// use hls_m3u8::types::MediaType;
+    ///
+    /// let playlist = MasterPlaylist::builder()
+    ///     .media(vec![
+    ///         ExtXMedia::builder()
+    ///             .media_type(MediaType::Audio)
+    ///             .group_id("aac")
+    ///             .name("English")
+    ///             .language("en")
+    ///             .is_default(true)
+    ///             .is_autoselect(true)
+    ///             .build()?,
+    ///         ExtXMedia::builder()
+    ///             .media_type(MediaType::Audio)
+    ///             .group_id("aac")
+    ///             .name("Deutsch")
+    ///             .language("de")
+    ///             .is_autoselect(true)
+    ///             .build()?,
+    ///     ])
+    ///     .build()?;
+    ///
+    /// let selected = playlist.select_rendition(MediaType::Audio, &["de-AT", "en"], false);
+    /// assert_eq!(selected.map(|media| media.name().as_ref()), Some("Deutsch"));
+    ///
+    /// let selected = playlist.select_rendition(MediaType::Audio, &["fr"], false);
+    /// assert_eq!(selected.map(|media| media.name().as_ref()), Some("English"));
+    /// # Ok::<(), Box<dyn ::std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn select_rendition(
+        &self,
+        media_type: MediaType,
+        language_preferences: &[&str],
+        allow_forced: bool,
+    ) -> Option<&ExtXMedia<'a>> {
+        let candidates = || {
+            self.media
+                .iter()
+                .filter(move |media| media.media_type == media_type)
+                .filter(move |media| allow_forced || !media.is_forced)
+        };
+
+        // lower ranks are better
+        let rank = |media: &ExtXMedia<'_>| {
+            match (media.is_default, media.is_autoselect) {
+                (true, _) => 0,
+                (false, true) => 1,
+                (false, false) => 2,
+            }
+        };
+
+        for preference in language_preferences {
+            let selected = candidates()
+                .filter_map(|media| {
+                    let quality = language_match(media.language()?, preference)?;
+                    Some((quality, rank(media), media))
+                })
+                // `min_by_key` returns the first of equal elements
+                .min_by_key(|(quality, rank, _)| (*quality, *rank));
+
+            if let Some((_, _, media)) = selected {
+                return Some(media);
+            }
+        }
+
+        candidates()
+            .filter(|media| rank(media) < 2)
+            .min_by_key(|media| rank(media))
+    }
+
+    /// Returns the [`ExtXSessionData`] tags with the
+    /// [`ExtXSessionData::CHAPTERS`] data id, that point to the chapters of
+    /// the presentation (one for each language).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MasterPlaylist;
+    /// use hls_m3u8::tags::SessionData;
+    /// use std::convert::TryFrom;
+    ///
+    /// let playlist = MasterPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-SESSION-DATA:DATA-ID=\"com.apple.hls.title\",VALUE=\"Title\"\n",
+    ///     "#EXT-X-SESSION-DATA:DATA-ID=\"com.apple.hls.chapters\",URI=\"en.json\",LANGUAGE=\"en\"\n",
+    ///     "#EXT-X-SESSION-DATA:DATA-ID=\"com.apple.hls.chapters\",URI=\"de.json\",LANGUAGE=\"de\"\n",
+    ///     "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+    ///     "low.m3u8\n",
+    /// ))?;
+    ///
+    /// assert_eq!(
+    ///     playlist
+    ///         .chapters_session_data()
+    ///         .map(|data| (data.language().map(AsRef::as_ref), &data.data))
+    ///         .collect::<Vec<_>>(),
+    ///     vec![
+    ///         (Some("en"), &SessionData::Uri("en.json".into())),
+    ///         (Some("de"), &SessionData::Uri("de.json".into())),
+    ///     ]
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    pub fn chapters_session_data(&self) -> impl Iterator<Item = &ExtXSessionData<'a>> {
+        self.session_data
+            .iter()
+            .filter(|data| data.data_id() == ExtXSessionData::CHAPTERS)
+    }
+
+    /// Returns the differences between this playlist and the `next` version
+    /// of it.
+    ///
+    /// The variants are identified by their uri, the renditions by their
+    /// media type, group id and name. This can be used to detect, that a
+    /// packager changed the bitrate ladder.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MasterPlaylist;
+    /// use hls_m3u8::MasterPlaylistChange;
+    /// use std::convert::TryFrom;
+    ///
+    /// let previous = MasterPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+    ///     "low.m3u8\n",
+    ///     "#EXT-X-STREAM-INF:BANDWIDTH=640000\n",
+    ///     "high.m3u8\n",
+    /// ))?;
+    ///
+    /// let next = MasterPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-STREAM-INF:BANDWIDTH=200000\n",
+    ///     "low.m3u8\n",
+    /// ))?;
+    ///
+    /// assert_eq!(
+    ///     previous.diff(&next),
+    ///     vec![
+    ///         MasterPlaylistChange::VariantChanged {
+    ///             uri: "low.m3u8".into()
+    ///         },
+    ///         MasterPlaylistChange::VariantRemoved {
+    ///             uri: "high.m3u8".into()
+    ///         },
+    ///     ]
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub fn diff(&self, next: &Self) -> Vec<MasterPlaylistChange> {
+        let mut result = Vec::new();
+
+        let variant_key = |variant: &VariantStream<'_>| -> (bool, String) {
+            match variant {
+                VariantStream::ExtXIFrame { uri, .. } => (true, uri.to_string()),
+                VariantStream::ExtXStreamInf { uri, .. } => (false, uri.to_string()),
+            }
+        };
+
+        for change in diff_by_key(&self.variant_streams, &next.variant_streams, variant_key) {
+            result.push(match change {
+                Difference::Changed((_, uri)) => MasterPlaylistChange::VariantChanged { uri },
+                Difference::Removed((_, uri)) => MasterPlaylistChange::VariantRemoved { uri },
+                Difference::Added((_, uri)) => MasterPlaylistChange::VariantAdded { uri },
+            });
+        }
+
+        let media_key = |media: &ExtXMedia<'_>| {
+            (
+                media.media_type,
+                media.group_id().to_string(),
+                media.name().to_string(),
+            )
+        };
+
+        for change in diff_by_key(&self.media, &next.media, media_key) {
+            result.push(match change {
+                Difference::Changed((media_type, group_id, name)) => {
+                    MasterPlaylistChange::RenditionChanged {
+                        media_type,
+                        group_id,
+                        name,
+                    }
+                }
+                Difference::Removed((media_type, group_id, name)) => {
+                    MasterPlaylistChange::RenditionRemoved {
+                        media_type,
+                        group_id,
+                        name,
+                    }
+                }
+                Difference::Added((media_type, group_id, name)) => {
+                    MasterPlaylistChange::RenditionAdded {
+                        media_type,
+                        group_id,
+                        name,
+                    }
+                }
+            });
+        }
+
+        // session keys have no identity, so they can only be added or removed:
+        let key_uri = |key: &ExtXSessionKey<'_>| key.0.uri().to_string();
+
+        for key in &self.session_keys {
+            if !next.session_keys.contains(key) {
+                result.push(MasterPlaylistChange::SessionKeyRemoved { uri: key_uri(key) });
+            }
+        }
+
+        for key in &next.session_keys {
+            if !self.session_keys.contains(key) {
+                result.push(MasterPlaylistChange::SessionKeyAdded { uri: key_uri(key) });
+            }
+        }
+
+        if self.content_steering != next.content_steering {
+            result.push(MasterPlaylistChange::ContentSteeringChanged);
+        }
+
+        result
+    }
+
+    /// Checks the playlist for problems, that are not covered by the
+    /// specification, but are known to break some players.
+    ///
+    /// This is a shorthand for [`Validator::validate_master_playlist`] with the
+    /// default [`Validator`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MasterPlaylist;
+    /// use std::convert::TryFrom;
+    ///
+    /// let playlist = MasterPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-SESSION-DATA:DATA-ID=\"title\",VALUE=\"Title\"\n",
+    ///     "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+    ///     "low.m3u8\n",
+    /// ))?;
+    ///
+    /// assert_eq!(playlist.validation_report().warnings().count(), 1);
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    ///
+    /// [`Validator::validate_master_playlist`]:
+    /// crate::Validator::validate_master_playlist
+    /// [`Validator`]: crate::Validator
+    #[must_use]
+    pub fn validation_report(&self) -> ValidationReport {
+        crate::Validator::new().validate_master_playlist(self)
+    }
+
+    /// Returns the first unknown tag with the given name. The leading `#` of
+    /// the name is optional.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MasterPlaylist;
+    /// use std::convert::TryFrom;
+    ///
+    /// let playlist = MasterPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-VENDOR-ID:1\n",
+    ///     "#EXT-X-VENDOR-NAME:a\n",
+    ///     "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+    ///     "low.m3u8\n",
+    /// ))?;
+    ///
+    /// assert_eq!(
+    ///     playlist.unknown_tag("#EXT-X-VENDOR-ID"),
+    ///     Some("#EXT-X-VENDOR-ID:1")
+    /// );
+    /// assert_eq!(playlist.unknown_tag("EXT-X-VENDOR"), None);
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub fn unknown_tag(&self, name: &str) -> Option<&str> {
+        let name = name.strip_prefix('#').unwrap_or(name);
+
+        self.unknown_tags
+            .iter()
+            .map(AsRef::as_ref)
+            .find(|line| utils::tag_name(line) == name)
+    }
+
+    /// Returns all unknown tags, whose name starts with the given prefix. The
+    /// leading `#` of the prefix is optional.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MasterPlaylist;
+    /// use std::convert::TryFrom;
+    ///
+    /// let playlist = MasterPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-VENDOR-ID:1\n",
+    ///     "#EXT-X-VENDOR-NAME:a\n",
+    ///     "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+    ///     "low.m3u8\n",
+    /// ))?;
+    ///
+    /// assert_eq!(
+    ///     playlist
+    ///         .unknown_tags_with_prefix("EXT-X-VENDOR-")
+    ///         .collect::<Vec<_>>(),
+    ///     vec!["#EXT-X-VENDOR-ID:1", "#EXT-X-VENDOR-NAME:a"]
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    pub fn unknown_tags_with_prefix<'b>(
+        &'b self,
+        prefix: &'b str,
+    ) -> impl Iterator<Item = &'b str> + 'b {
+        utils::tags_with_prefix(&self.unknown_tags, prefix)
+    }
+
+    /// Removes all unknown tags with the given name and returns them. The
+    /// leading `#` of the name is optional.
+    pub fn remove_unknown_tags(&mut self, name: &str) -> Vec<Cow<'a, str>> {
+        utils::remove_tags(&mut self.unknown_tags, name)
+    }
+
+    /// Removes duplicate unknown tags. The first occurrence of a tag is kept.
+    pub fn dedup_unknown_tags(&mut self) -> &mut Self {
+        utils::dedup_tags(&mut self.unknown_tags);
+        self
+    }
+
     /// Makes the struct independent of its lifetime, by taking ownership of all
     /// internal [`Cow`]s.
     ///
@@ -280,6 +860,7 @@ impl<'a> MasterPlaylist<'a> {
         MasterPlaylist {
             has_independent_segments: self.has_independent_segments,
             start: self.start,
+            content_steering: self.content_steering.map(|v| v.into_owned()),
             media: self.media.into_iter().map(|v| v.into_owned()).collect(),
             variant_streams: self
                 .variant_streams
@@ -301,6 +882,14 @@ impl<'a> MasterPlaylist<'a> {
                 .into_iter()
                 .map(|v| Cow::Owned(v.into_owned()))
                 .collect(),
+            header_attributes: self
+                .header_attributes
+                .map(|value| Cow::Owned(value.into_owned())),
+            positioned_tags: self
+                .positioned_tags
+                .into_iter()
+                .map(|(position, v)| (position, Cow::Owned(v.into_owned())))
+                .collect(),
         }
     }
 }
@@ -311,15 +900,98 @@ impl<'a> RequiredVersion for MasterPlaylist<'a> {
             self.has_independent_segments
                 .athen_some(ExtXIndependentSegments),
             self.start,
+            self.content_steering,
             self.media,
             self.variant_streams,
             self.session_data,
             self.session_keys
         ]
     }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        let mut result: Vec<_> =
+            required_version_reasons![self.media, self.variant_streams, self.session_keys];
+
+        result.sort();
+        result.dedup();
+        result
+    }
 }
 
 impl<'a> MasterPlaylistBuilder<'a> {
+    /// A list of all [`ExtXMedia`] tags, which describe an alternative
+    /// rendition.
+    pub fn media<I>(&mut self, value: I) -> &mut Self
+    where
+        I: IntoIterator<Item = ExtXMedia<'a>>,
+    {
+        self.media = Some(value.into_iter().collect());
+        self
+    }
+
+    /// A list of all streams of the [`MasterPlaylist`].
+    pub fn variant_streams<I>(&mut self, value: I) -> &mut Self
+    where
+        I: IntoIterator<Item = VariantStream<'a>>,
+    {
+        self.variant_streams = Some(value.into_iter().collect());
+        self
+    }
+
+    /// A list of [`ExtXSessionData`] tags, which carry arbitrary session data.
+    pub fn session_data<I>(&mut self, value: I) -> &mut Self
+    where
+        I: IntoIterator<Item = ExtXSessionData<'a>>,
+    {
+        self.session_data = Some(value.into_iter().collect());
+        self
+    }
+
+    /// A list of [`ExtXSessionKey`]s, that allows the client to preload keys.
+    pub fn session_keys<I>(&mut self, value: I) -> &mut Self
+    where
+        I: IntoIterator<Item = ExtXSessionKey<'a>>,
+    {
+        self.session_keys = Some(value.into_iter().collect());
+        self
+    }
+
+    /// A list of all tags that could not be identified while parsing the input.
+    pub fn unknown_tags<I>(&mut self, value: I) -> &mut Self
+    where
+        I: IntoIterator<Item = Cow<'a, str>>,
+    {
+        self.unknown_tags = Some(value.into_iter().collect());
+        self
+    }
+
+    /// Adds an unknown tag, that is written before the [`VariantStream`] with
+    /// the index `position`, see [`MasterPlaylist::positioned_tags`].
+    pub fn push_unknown_tag<T: Into<Cow<'a, str>>>(
+        &mut self,
+        position: usize,
+        value: T,
+    ) -> &mut Self {
+        self.positioned_tags
+            .get_or_insert_with(Vec::new)
+            .push((position, value.into()));
+        self
+    }
+
+    /// Adds a [`CustomTag`] at its [`CustomTag::position`] or to the
+    /// [`MasterPlaylist::unknown_tags`], if it has no position.
+    pub fn push_custom_tag<T: CustomTag>(&mut self, value: T) -> &mut Self {
+        let tag = Cow::Owned(value.to_string());
+
+        match value.position() {
+            Some(position) => self.push_unknown_tag(position, tag),
+            None => {
+                self.unknown_tags.get_or_insert_with(Vec::new).push(tag);
+                self
+            }
+        }
+    }
+
     fn validate(&self) -> Result<(), String> {
         if let Some(variant_streams) = &self.variant_streams {
             self.validate_variants(variant_streams)
@@ -334,6 +1006,7 @@ impl<'a> MasterPlaylistBuilder<'a> {
 
     fn validate_variants(&self, variant_streams: &[VariantStream<'_>]) -> crate::Result<()> {
         let mut closed_captions_none = false;
+        let mut has_closed_captions_group = false;
 
         for variant in variant_streams {
             match &variant {
@@ -365,19 +1038,19 @@ impl<'a> MasterPlaylistBuilder<'a> {
                     if let Some(closed_captions) = &closed_captions {
                         match &closed_captions {
                             ClosedCaptions::GroupId(group_id) => {
-                                if closed_captions_none {
-                                    return Err(Error::custom("ClosedCaptions has to be `None`"));
-                                }
+                                has_closed_captions_group = true;
 
                                 if !self.check_media_group(MediaType::ClosedCaptions, group_id) {
                                     return Err(Error::unmatched_group(group_id));
                                 }
                             }
-                            _ => {
-                                if !closed_captions_none {
-                                    closed_captions_none = true;
-                                }
-                            }
+                            _ => closed_captions_none = true,
+                        }
+
+                        // multiple groups are allowed, but a `NONE` in one variant stream
+                        // requires `NONE` in all of them (independent of the order):
+                        if closed_captions_none && has_closed_captions_group {
+                            return Err(Error::custom("ClosedCaptions has to be `None`"));
                         }
                     }
                 }
@@ -411,6 +1084,7 @@ impl<'a> MasterPlaylistBuilder<'a> {
         Ok(())
     }
 
+    #[allow(clippy::unnecessary_map_or)]
     fn check_media_group<T: AsRef<str>>(&self, media_type: MediaType, group_id: T) -> bool {
         self.media.as_ref().map_or(false, |value| {
             value.iter().any(|media| {
@@ -431,6 +1105,7 @@ impl<'a> RequiredVersion for MasterPlaylistBuilder<'a> {
                 .unwrap_or(false)
                 .athen_some(ExtXIndependentSegments),
             self.start.flatten(),
+            self.content_steering,
             self.media,
             self.variant_streams,
             self.session_data,
@@ -439,28 +1114,92 @@ impl<'a> RequiredVersion for MasterPlaylistBuilder<'a> {
     }
 }
 
-impl<'a> fmt::Display for MasterPlaylist<'a> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "{}", ExtM3u)?;
+impl<'a> MasterPlaylist<'a> {
+    pub(crate) fn write_to<W: fmt::Write>(&self, f: &mut W, writer: &Writer) -> fmt::Result {
+        if let Some(value) = &self.header_attributes {
+            writeln!(f, "{} {}", ExtM3u, value)?;
+        } else {
+            writeln!(f, "{}", ExtM3u)?;
+        }
+
+        let version = writer.version(self.required_version());
 
-        if self.required_version() != ProtocolVersion::V1 {
-            writeln!(f, "{}", ExtXVersion::new(self.required_version()))?;
+        if version != ProtocolVersion::V1 {
+            writeln!(f, "{}", ExtXVersion::new(version))?;
         }
 
         for value in &self.media {
-            writeln!(f, "{}", value)?;
+            match value
+                .uri()
+                .map(|uri| (uri, writer.uri(UriKind::Media, uri)))
+            {
+                Some((uri, new_uri)) if *uri != new_uri => {
+                    let mut value = value.clone();
+                    value.set_uri(Some(new_uri.into_owned()));
+                    writeln!(f, "{}", value)?;
+                }
+                _ => writeln!(f, "{}", value)?,
+            }
         }
 
-        for value in &self.variant_streams {
+        let mut positioned_tags = self.positioned_tags.iter().collect::<Vec<_>>();
+        positioned_tags.sort_by_key(|(position, _)| *position);
+        let mut positioned_tags = positioned_tags.into_iter().peekable();
+
+        for (i, value) in self.variant_streams.iter().enumerate() {
+            while let Some((_, tag)) = positioned_tags.next_if(|(position, _)| *position <= i) {
+                writeln!(f, "{}", tag)?;
+            }
+
+            let (uri, new_uri) = match value {
+                VariantStream::ExtXIFrame { uri, .. } => (uri, writer.uri(UriKind::IFrame, uri)),
+                VariantStream::ExtXStreamInf { uri, .. } => {
+                    (uri, writer.uri(UriKind::StreamInf, uri))
+                }
+            };
+
+            if *uri == new_uri {
+                writeln!(f, "{}", value)?;
+            } else {
+                let mut value = value.clone();
+                let (VariantStream::ExtXIFrame { uri, .. }
+                | VariantStream::ExtXStreamInf { uri, .. }) = &mut value;
+                *uri = Cow::Owned(new_uri.into_owned());
+                writeln!(f, "{}", value)?;
+            }
+        }
+
+        for (_, value) in positioned_tags {
             writeln!(f, "{}", value)?;
         }
 
         for value in &self.session_data {
-            writeln!(f, "{}", value)?;
+            match &value.data {
+                SessionData::Uri(uri) => {
+                    let new_uri = writer.uri(UriKind::SessionData, uri);
+
+                    if *uri == new_uri {
+                        writeln!(f, "{}", value)?;
+                    } else {
+                        let mut value = value.clone();
+                        value.data = SessionData::Uri(Cow::Owned(new_uri.into_owned()));
+                        writeln!(f, "{}", value)?;
+                    }
+                }
+                SessionData::Value(_) => writeln!(f, "{}", value)?,
+            }
         }
 
         for value in &self.session_keys {
-            writeln!(f, "{}", value)?;
+            let new_uri = writer.uri(UriKind::SessionKey, value.0.uri());
+
+            if new_uri == value.0.uri().as_ref() {
+                writeln!(f, "{}", value)?;
+            } else {
+                let mut value = value.clone();
+                value.0.set_uri(new_uri.into_owned());
+                writeln!(f, "{}", value)?;
+            }
         }
 
         if self.has_independent_segments {
@@ -471,6 +1210,10 @@ impl<'a> fmt::Display for MasterPlaylist<'a> {
             writeln!(f, "{}", value)?;
         }
 
+        if let Some(value) = &self.content_steering {
+            writeln!(f, "{}", value)?;
+        }
+
         for value in &self.unknown_tags {
             writeln!(f, "{}", value)?;
         }
@@ -479,81 +1222,340 @@ impl<'a> fmt::Display for MasterPlaylist<'a> {
     }
 }
 
+impl<'a> fmt::Display for MasterPlaylist<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_to(f, &Writer::default())
+    }
+}
+
 impl<'a> TryFrom<&'a str> for MasterPlaylist<'a> {
     type Error = Error;
 
     fn try_from(input: &'a str) -> Result<Self, Self::Error> {
-        let input = tag(input, ExtM3u::PREFIX)?;
-        let mut builder = Self::builder();
-
-        let mut media = vec![];
-        let mut variant_streams = vec![];
-        let mut session_data = vec![];
-        let mut session_keys = vec![];
-        let mut unknown_tags = vec![];
-
-        for line in Lines::from(input) {
-            match line? {
-                Line::Tag(tag) => {
-                    match tag {
-                        Tag::ExtXVersion(_) => {
-                            // This tag can be ignored, because the
-                            // MasterPlaylist will automatically set the
-                            // ExtXVersion tag to the minimum required version
-                            // TODO: this might be verified?
-                        }
-                        Tag::ExtInf(_)
-                        | Tag::ExtXByteRange(_)
-                        | Tag::ExtXDiscontinuity(_)
-                        | Tag::ExtXKey(_)
-                        | Tag::ExtXMap(_)
-                        | Tag::ExtXProgramDateTime(_)
-                        | Tag::ExtXDateRange(_)
-                        | Tag::ExtXTargetDuration(_)
-                        | Tag::ExtXMediaSequence(_)
-                        | Tag::ExtXDiscontinuitySequence(_)
-                        | Tag::ExtXEndList(_)
-                        | Tag::PlaylistType(_)
-                        | Tag::ExtXIFramesOnly(_) => {
-                            return Err(Error::unexpected_tag(tag));
-                        }
-                        Tag::ExtXMedia(t) => {
-                            media.push(t);
-                        }
-                        Tag::VariantStream(t) => {
-                            variant_streams.push(t);
-                        }
-                        Tag::ExtXSessionData(t) => {
-                            session_data.push(t);
-                        }
-                        Tag::ExtXSessionKey(t) => {
-                            session_keys.push(t);
-                        }
-                        Tag::ExtXIndependentSegments(_) => {
-                            builder.has_independent_segments(true);
-                        }
-                        Tag::ExtXStart(t) => {
-                            builder.start(t);
-                        }
-                        Tag::Unknown(value) => {
-                            // [6.3.1. General Client Responsibilities]
-                            // > ignore any unrecognized tags.
-                            unknown_tags.push(Cow::Borrowed(value));
-                        }
-                    }
+        Ok(ParseOptions::new().parse_master_playlist(input)?.0)
+    }
+}
+
+/// Returns `true`, if `i_frame` is an [`VariantStream::ExtXIFrame`], that
+/// has the same video group or resolution as the
+/// [`VariantStream::ExtXStreamInf`] `stream`.
+/// A difference between two versions of a [`MasterPlaylist`], found by
+/// [`MasterPlaylist::diff`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, thiserror::Error)]
+pub enum MasterPlaylistChange {
+    /// A [`VariantStream`] has been added.
+    #[error("the variant {uri:?} has been added")]
+    VariantAdded {
+        /// The uri of the variant.
+        uri: String,
+    },
+    /// A [`VariantStream`] has been removed.
+    #[error("the variant {uri:?} has been removed")]
+    VariantRemoved {
+        /// The uri of the variant.
+        uri: String,
+    },
+    /// The attributes of a [`VariantStream`] have been changed.
+    #[error("the variant {uri:?} has been changed")]
+    VariantChanged {
+        /// The uri of the variant.
+        uri: String,
+    },
+    /// An [`ExtXMedia`] tag has been added.
+    #[error("the {media_type} rendition {name:?} in the group {group_id:?} has been added")]
+    RenditionAdded {
+        /// The [`ExtXMedia::media_type`] of the rendition.
+        media_type: MediaType,
+        /// The [`ExtXMedia::group_id`] of the rendition.
+        group_id: String,
+        /// The [`ExtXMedia::name`] of the rendition.
+        name: String,
+    },
+    /// An [`ExtXMedia`] tag has been removed.
+    #[error("the {media_type} rendition {name:?} in the group {group_id:?} has been removed")]
+    RenditionRemoved {
+        /// The [`ExtXMedia::media_type`] of the rendition.
+        media_type: MediaType,
+        /// The [`ExtXMedia::group_id`] of the rendition.
+        group_id: String,
+        /// The [`ExtXMedia::name`] of the rendition.
+        name: String,
+    },
+    /// The attributes of an [`ExtXMedia`] tag have been changed.
+    #[error("the {media_type} rendition {name:?} in the group {group_id:?} has been changed")]
+    RenditionChanged {
+        /// The [`ExtXMedia::media_type`] of the rendition.
+        media_type: MediaType,
+        /// The [`ExtXMedia::group_id`] of the rendition.
+        group_id: String,
+        /// The [`ExtXMedia::name`] of the rendition.
+        name: String,
+    },
+    /// An [`ExtXSessionKey`] has been added.
+    #[error("the session key {uri:?} has been added")]
+    SessionKeyAdded {
+        /// The uri of the key.
+        uri: String,
+    },
+    /// An [`ExtXSessionKey`] has been removed.
+    #[error("the session key {uri:?} has been removed")]
+    SessionKeyRemoved {
+        /// The uri of the key.
+        uri: String,
+    },
+    /// The [`MasterPlaylist::content_steering`] has been added, removed or
+    /// changed.
+    #[error("the content steering has been changed")]
+    ContentSteeringChanged,
+}
+
+enum Difference<K> {
+    Added(K),
+    Removed(K),
+    Changed(K),
+}
+
+/// Compares the items, that have the same key. If multiple items have the
+/// same key, they are compared in their order.
+fn diff_by_key<T, K, F>(previous: &[T], next: &[T], key: F) -> Vec<Difference<K>>
+where
+    T: PartialEq,
+    K: PartialEq,
+    F: Fn(&T) -> K,
+{
+    let mut result = Vec::new();
+    let next_keys = next.iter().map(&key).collect::<Vec<_>>();
+    let mut is_matched = vec![false; next.len()];
+
+    for item in previous {
+        let item_key = key(item);
+        let position = next_keys
+            .iter()
+            .zip(&is_matched)
+            .position(|(key, is_matched)| !is_matched && *key == item_key);
+
+        match position {
+            Some(i) => {
+                is_matched[i] = true;
+
+                if next[i] != *item {
+                    result.push(Difference::Changed(item_key));
+                }
+            }
+            None => result.push(Difference::Removed(item_key)),
+        }
+    }
+
+    for (key, is_matched) in next_keys.into_iter().zip(is_matched) {
+        if !is_matched {
+            result.push(Difference::Added(key));
+        }
+    }
+
+    result
+}
+
+fn is_i_frame_of(i_frame: &VariantStream<'_>, stream: &VariantStream<'_>) -> bool {
+    match (i_frame, stream) {
+        (
+            VariantStream::ExtXIFrame {
+                stream_data: i_frame,
+                ..
+            },
+            VariantStream::ExtXStreamInf { stream_data, .. },
+        ) => {
+            if let (Some(a), Some(b)) = (i_frame.video(), stream_data.video()) {
+                return a == b;
+            }
+
+            i_frame.resolution().is_some() && i_frame.resolution() == stream_data.resolution()
+        }
+        _ => false,
+    }
+}
+
+/// Returns `Some(0)`, if the language tags are equal, `Some(1)`, if one is a
+/// prefix of the other (`en` and `en-US`) and `None` otherwise.
+fn language_match(language: &str, preference: &str) -> Option<u8> {
+    let is_prefix = |long: &str, short: &str| {
+        long.len() > short.len()
+            && long.as_bytes()[short.len()] == b'-'
+            && long[..short.len()].eq_ignore_ascii_case(short)
+    };
+
+    if language.eq_ignore_ascii_case(preference) {
+        Some(0)
+    } else if is_prefix(language, preference) || is_prefix(preference, language) {
+        Some(1)
+    } else {
+        None
+    }
+}
+
+pub(crate) fn parse_master_playlist<'a>(
+    input: &'a str,
+    options: &ParseOptions,
+    report: &mut ValidationReport,
+) -> crate::Result<MasterPlaylist<'a>> {
+    let first_line = utils::leading_lines(input);
+    let (header_attributes, input) = ExtM3u::parse_header(input)?;
+    let mut state = MasterPlaylistState::default();
+
+    state.header(header_attributes.map(Cow::Borrowed), options, report)?;
+
+    let mut lines = Lines::from(input);
+
+    while let Some(line) = lines.next() {
+        match line? {
+            Line::Tag(tag) => {
+                let number = first_line + lines.line();
+
+                if let Tag::Unknown(value) = tag {
+                    options.report_ignored_tag(number, value);
+                } else {
+                    options.report_ignored_attributes(number, lines.current());
                 }
-                Line::Uri(uri) => {
-                    return Err(Error::custom(format!("unexpected uri: {:?}", uri)));
+
+                for comment in lines.comments() {
+                    state.push_comment(Cow::Borrowed(*comment));
                 }
-                Line::Comment(_) => {}
+
+                state.push_tag(tag, number, options, report)?;
+            }
+            Line::Uri(uri) => {
+                return Err(Error::custom(format!("unexpected uri: {:?}", uri)));
+            }
+            Line::Comment(_) => {}
+        }
+    }
+
+    state.build()
+}
+
+/// Everything, that has been parsed of a [`MasterPlaylist`] so far.
+#[derive(Default, Clone)]
+pub(crate) struct MasterPlaylistState<'a> {
+    builder: MasterPlaylistBuilder<'a>,
+    media: Vec<ExtXMedia<'a>>,
+    variant_streams: Vec<VariantStream<'a>>,
+    session_data: Vec<ExtXSessionData<'a>>,
+    session_keys: Vec<ExtXSessionKey<'a>>,
+    unknown_tags: Vec<Cow<'a, str>>,
+    seen: HashSet<&'static str>,
+}
+
+impl<'a> MasterPlaylistState<'a> {
+    pub(crate) fn header(
+        &mut self,
+        header_attributes: Option<Cow<'a, str>>,
+        options: &ParseOptions,
+        report: &mut ValidationReport,
+    ) -> crate::Result<()> {
+        if let Some(value) = header_attributes {
+            options.violation(
+                report,
+                Error::custom(format!("unexpected parameters after #EXTM3U: {:?}", value)),
+            )?;
+            self.builder.header_attributes(value);
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn push_tag(
+        &mut self,
+        tag: Tag<'a>,
+        line: usize,
+        options: &ParseOptions,
+        report: &mut ValidationReport,
+    ) -> crate::Result<()> {
+        match tag {
+            Tag::ExtXVersion(_) => {
+                options.check_once(report, &mut self.seen, "EXT-X-VERSION")?;
+
+                // This tag can be ignored, because the
+                // MasterPlaylist will automatically set the
+                // ExtXVersion tag to the minimum required version
+                // TODO: this might be verified?
+            }
+            Tag::ExtInf(_)
+            | Tag::ExtXByteRange(_)
+            | Tag::ExtXBitrate(_)
+            | Tag::ExtXDiscontinuity(_)
+            | Tag::ExtXGap(_)
+            | Tag::ExtXKey(_)
+            | Tag::ExtXMap(_)
+            | Tag::ExtXPart(_)
+            | Tag::ExtXProgramDateTime(_)
+            | Tag::ExtXDateRange(_)
+            | Tag::ExtXTargetDuration(_)
+            | Tag::ExtXMediaSequence(_)
+            | Tag::ExtXDiscontinuitySequence(_)
+            | Tag::ExtXEndList(_)
+            | Tag::PlaylistType(_)
+            | Tag::ExtXIFramesOnly(_)
+            | Tag::ExtXAllowCache(_)
+            | Tag::ExtXServerControl(_)
+            | Tag::ExtXPartInf(_)
+            | Tag::ExtXSkip(_)
+            | Tag::ExtXPreloadHint(_)
+            | Tag::ExtXRenditionReport(_) => {
+                return Err(tag.misplaced(line, crate::error::NOT_IN_MASTER_PLAYLIST));
+            }
+            Tag::ExtXMedia(t) => {
+                self.media.push(t);
+            }
+            Tag::VariantStream(t) => {
+                self.variant_streams.push(t);
+            }
+            Tag::ExtXSessionData(t) => {
+                self.session_data.push(t);
+            }
+            Tag::ExtXSessionKey(t) => {
+                self.session_keys.push(t);
+            }
+            Tag::ExtXIndependentSegments(_) => {
+                options.check_once(report, &mut self.seen, "EXT-X-INDEPENDENT-SEGMENTS")?;
+                self.builder.has_independent_segments(true);
+            }
+            Tag::ExtXStart(t) => {
+                options.check_once(report, &mut self.seen, "EXT-X-START")?;
+                self.builder.start(t);
+            }
+            Tag::ExtXContentSteering(t) => {
+                options.check_once(report, &mut self.seen, "EXT-X-CONTENT-STEERING")?;
+                self.builder.content_steering(t);
+            }
+            Tag::Unknown(value) => {
+                self.push_unknown(Cow::Borrowed(value));
             }
         }
 
-        builder.media(media);
-        builder.variant_streams(variant_streams);
-        builder.session_data(session_data);
-        builder.session_keys(session_keys);
-        builder.unknown_tags(unknown_tags);
+        Ok(())
+    }
+
+    pub(crate) fn push_unknown(&mut self, value: Cow<'a, str>) {
+        // [6.3.1. General Client Responsibilities]
+        // > ignore any unrecognized tags.
+        self.unknown_tags.push(value);
+    }
+
+    /// Keeps a comment, that appeared between an `#EXT-X-STREAM-INF` tag and
+    /// its uri, by writing it before the variant stream.
+    pub(crate) fn push_comment(&mut self, value: Cow<'a, str>) {
+        self.builder
+            .push_unknown_tag(self.variant_streams.len(), value);
+    }
+
+    pub(crate) fn build(self) -> crate::Result<MasterPlaylist<'a>> {
+        let mut builder = self.builder;
+
+        builder.media(self.media);
+        builder.variant_streams(self.variant_streams);
+        builder.session_data(self.session_data);
+        builder.session_keys(self.session_keys);
+        builder.unknown_tags(self.unknown_tags);
 
         builder.build().map_err(Error::builder)
     }
@@ -562,9 +1564,396 @@ impl<'a> TryFrom<&'a str> for MasterPlaylist<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::StreamData;
+    use crate::types::{InStreamId, StreamData};
     use pretty_assertions::assert_eq;
 
+    fn closed_captions_stream(closed_captions: ClosedCaptions<'static>) -> VariantStream<'static> {
+        VariantStream::ExtXStreamInf {
+            uri: "http://example.com/low/index.m3u8".into(),
+            frame_rate: None,
+            audio: None,
+            subtitles: None,
+            closed_captions: Some(closed_captions),
+            stream_data: StreamData::new(150_000),
+        }
+    }
+
+    fn closed_captions_media(group_id: &'static str) -> ExtXMedia<'static> {
+        ExtXMedia::builder()
+            .media_type(MediaType::ClosedCaptions)
+            .group_id(group_id)
+            .name(group_id)
+            .instream_id(InStreamId::Cc1)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_content_steering() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-CONTENT-STEERING:SERVER-URI=\"steering.json\",PATHWAY-ID=\"CDN-A\"\n",
+        );
+
+        let playlist = MasterPlaylist::try_from(input).unwrap();
+
+        let mut content_steering = ExtXContentSteering::new("steering.json");
+        content_steering.set_pathway_id(Some("CDN-A"));
+
+        assert_eq!(playlist.content_steering, Some(content_steering));
+        assert_eq!(playlist.to_string(), input);
+        assert_eq!(playlist.required_version(), ProtocolVersion::V1);
+
+        let mut options = ParseOptions::new();
+        options.set_strictness(crate::Strictness::Strict);
+
+        assert!(options
+            .parse_master_playlist(concat!(
+                "#EXTM3U\n",
+                "#EXT-X-CONTENT-STEERING:SERVER-URI=\"a.json\"\n",
+                "#EXT-X-CONTENT-STEERING:SERVER-URI=\"b.json\"\n",
+            ))
+            .is_err());
+
+        assert!(crate::MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-CONTENT-STEERING:SERVER-URI=\"a.json\"\n",
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn test_req_video_layout() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-VERSION:12\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=150000,REQ-VIDEO-LAYOUT=\"CH-STEREO,CH-MONO\"\n",
+            "http://example.com/spatial/index.m3u8\n",
+        );
+
+        let playlist = MasterPlaylist::try_from(input).unwrap();
+
+        assert_eq!(playlist.to_string(), input);
+        assert_eq!(playlist.required_version(), ProtocolVersion::V12);
+        assert_eq!(
+            playlist.required_version_reasons(),
+            vec![VersionReason::from(crate::types::Feature::ReqVideoLayout)]
+        );
+    }
+
+    #[test]
+    fn test_legacy_stream_inf_attributes() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-STREAM-INF:PROGRAM-ID=1,BANDWIDTH=1280000,RESOLUTION=1280x720,",
+            "NAME=\"720p\"\n",
+            "http://example.com/720p.m3u8\n",
+        );
+
+        let playlist = MasterPlaylist::try_from(input).unwrap();
+
+        assert_eq!(playlist.variant_streams[0].program_id(), Some(1));
+        assert_eq!(
+            playlist.variant_streams[0].name().map(|name| name.as_ref()),
+            Some("720p")
+        );
+        assert_eq!(playlist.to_string(), input);
+    }
+
+    #[test]
+    fn test_blank_lines_in_variant_stream() {
+        let playlist = MasterPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+            "\n",
+            "low/index.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=240000\r\n",
+            "\r\n",
+            "# a comment\r\n",
+            "\r\n",
+            "mid/index.m3u8\r\n",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            playlist.to_string(),
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+                "low/index.m3u8\n",
+                "# a comment\n",
+                "#EXT-X-STREAM-INF:BANDWIDTH=240000\n",
+                "mid/index.m3u8\n",
+            )
+        );
+        assert_eq!(playlist.positioned_tags, vec![(1, "# a comment".into())]);
+
+        assert!(
+            MasterPlaylist::try_from("#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=150000\n\n").is_err()
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let previous = MasterPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-CONTENT-STEERING:SERVER-URI=\"steering.json\"\n",
+            "#EXT-X-SESSION-KEY:METHOD=AES-128,URI=\"key-1\"\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"audio\",NAME=\"English\",LANGUAGE=\"en\"\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"audio\",NAME=\"German\",LANGUAGE=\"de\"\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=150000,AUDIO=\"audio\"\n",
+            "low.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=640000,AUDIO=\"audio\"\n",
+            "high.m3u8\n",
+            "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=65000,URI=\"low-iframes.m3u8\"\n",
+        ))
+        .unwrap();
+
+        assert_eq!(previous.diff(&previous), vec![]);
+
+        let next = MasterPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-SESSION-KEY:METHOD=AES-128,URI=\"key-2\"\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"audio\",NAME=\"English\",LANGUAGE=\"en-US\"\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"audio\",NAME=\"French\",LANGUAGE=\"fr\"\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=150000,AUDIO=\"audio\"\n",
+            "low.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1280000,AUDIO=\"audio\"\n",
+            "high.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=2560000,AUDIO=\"audio\"\n",
+            "highest.m3u8\n",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            previous
+                .diff(&next)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "the variant \"high.m3u8\" has been changed",
+                "the variant \"low-iframes.m3u8\" has been removed",
+                "the variant \"highest.m3u8\" has been added",
+                "the AUDIO rendition \"English\" in the group \"audio\" has been changed",
+                "the AUDIO rendition \"German\" in the group \"audio\" has been removed",
+                "the AUDIO rendition \"French\" in the group \"audio\" has been added",
+                "the session key \"key-1\" has been removed",
+                "the session key \"key-2\" has been added",
+                "the content steering has been changed",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_variants_by_preference() {
+        let playlist = MasterPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=300000\n",
+            "unscored.m3u8\n",
+            "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=90000,SCORE=9.0,URI=\"iframe.m3u8\"\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=150000,SCORE=1.5\n",
+            "low.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=640000,SCORE=1.5\n",
+            "high.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=150000,SCORE=1.5\n",
+            "low-backup.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=100000,SCORE=2.0\n",
+            "best.m3u8\n",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            playlist
+                .variants_by_preference()
+                .map(|variant| {
+                    match variant {
+                        VariantStream::ExtXStreamInf { uri, .. } => uri.as_ref(),
+                        VariantStream::ExtXIFrame { uri, .. } => uri.as_ref(),
+                    }
+                })
+                .collect::<Vec<_>>(),
+            vec![
+                "best.m3u8",
+                "high.m3u8",
+                "low.m3u8",
+                "low-backup.m3u8",
+                "unscored.m3u8"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_retain_variants() {
+        let mut playlist = MasterPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MEDIA:TYPE=VIDEO,GROUP-ID=\"sd\",NAME=\"a\"\n",
+            "#EXT-X-MEDIA:TYPE=VIDEO,GROUP-ID=\"hd\",NAME=\"a\"\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"a\"\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"unused\",NAME=\"a\"\n",
+            "#EXT-X-MEDIA:TYPE=CLOSED-CAPTIONS,GROUP-ID=\"cc\",NAME=\"a\",INSTREAM-ID=\"CC1\"\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1,VIDEO=\"sd\",AUDIO=\"aac\"\n",
+            "sd.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=2,VIDEO=\"hd\",AUDIO=\"aac\",CLOSED-CAPTIONS=\"cc\"\n",
+            "hd.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=3,RESOLUTION=1920x1080\n",
+            "fhd.m3u8\n",
+            "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=1,VIDEO=\"sd\",URI=\"sd-i.m3u8\"\n",
+            "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=2,VIDEO=\"hd\",URI=\"hd-i.m3u8\"\n",
+            "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=3,RESOLUTION=1920x1080,URI=\"fhd-i.m3u8\"\n",
+            "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=4,URI=\"other-i.m3u8\"\n",
+        ))
+        .unwrap();
+
+        playlist.positioned_tags = vec![(1, "#X-HD".into()), (2, "#X-FHD".into())];
+        playlist.retain_variants(|stream| {
+            !matches!(stream, VariantStream::ExtXStreamInf { .. }) || stream.bandwidth() == 1
+        });
+
+        assert_eq!(
+            playlist
+                .variant_streams
+                .iter()
+                .map(|stream| stream.bandwidth())
+                .collect::<Vec<_>>(),
+            vec![1, 1, 4]
+        );
+        assert_eq!(
+            playlist
+                .media
+                .iter()
+                .map(|media| media.group_id().as_ref())
+                .collect::<Vec<_>>(),
+            vec!["sd", "aac", "unused"]
+        );
+        assert_eq!(
+            playlist.positioned_tags,
+            vec![(1, "#X-HD".into()), (1, "#X-FHD".into())]
+        );
+    }
+
+    #[test]
+    fn test_positioned_tags() {
+        struct Vendor;
+
+        impl fmt::Display for Vendor {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "#X-VENDOR") }
+        }
+
+        impl CustomTag for Vendor {}
+
+        let stream = |uri: &'static str| {
+            VariantStream::ExtXStreamInf {
+                uri: uri.into(),
+                frame_rate: None,
+                audio: None,
+                subtitles: None,
+                closed_captions: None,
+                stream_data: StreamData::new(150_000),
+            }
+        };
+
+        let playlist = MasterPlaylist::builder()
+            .variant_streams(vec![stream("low.m3u8"), stream("high.m3u8")])
+            .push_unknown_tag(5, "#X-END")
+            .push_unknown_tag(1, "#X-HIGH")
+            .push_unknown_tag(0, "#X-LOW")
+            .push_custom_tag(Vendor)
+            .build()
+            .unwrap();
+
+        assert_eq!(playlist.unknown_tags, vec!["#X-VENDOR"]);
+        assert_eq!(
+            playlist.to_string(),
+            concat!(
+                "#EXTM3U\n",
+                "#X-LOW\n",
+                "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+                "low.m3u8\n",
+                "#X-HIGH\n",
+                "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+                "high.m3u8\n",
+                "#X-END\n",
+                "#X-VENDOR\n",
+            )
+        );
+        assert_eq!(playlist.clone().into_owned(), playlist);
+    }
+
+    #[test]
+    fn test_unknown_tags() {
+        let mut playlist = MasterPlaylist::builder()
+            .unknown_tags(vec![
+                "#X-VENDOR-ID:1".into(),
+                "#X-VENDOR-ID:2".into(),
+                "#X-OTHER".into(),
+                "#X-VENDOR-ID:1".into(),
+            ])
+            .build()
+            .unwrap();
+
+        assert_eq!(playlist.unknown_tag("X-OTHER"), Some("#X-OTHER"));
+        assert_eq!(playlist.unknown_tags_with_prefix("#X-").count(), 4);
+
+        playlist.dedup_unknown_tags();
+        assert_eq!(
+            playlist.unknown_tags,
+            vec!["#X-VENDOR-ID:1", "#X-VENDOR-ID:2", "#X-OTHER"]
+        );
+
+        assert_eq!(
+            playlist.remove_unknown_tags("X-VENDOR-ID"),
+            vec!["#X-VENDOR-ID:1", "#X-VENDOR-ID:2"]
+        );
+        assert_eq!(playlist.unknown_tags, vec!["#X-OTHER"]);
+        assert_eq!(playlist.unknown_tag("X-VENDOR-ID"), None);
+    }
+
+    #[test]
+    fn test_closed_captions() {
+        let mut playlist = MasterPlaylist::builder()
+            .media(vec![
+                closed_captions_media("cc1"),
+                closed_captions_media("cc2"),
+            ])
+            .variant_streams(vec![
+                closed_captions_stream(ClosedCaptions::group_id("cc1")),
+                closed_captions_stream(ClosedCaptions::group_id("cc2")),
+            ])
+            .build()
+            .unwrap();
+
+        playlist.strip_closed_captions();
+
+        assert_eq!(playlist.media, vec![]);
+        assert_eq!(
+            playlist.variant_streams,
+            vec![
+                closed_captions_stream(ClosedCaptions::None),
+                closed_captions_stream(ClosedCaptions::None),
+            ]
+        );
+
+        // `NONE` in one variant stream requires `NONE` in all of them:
+        for streams in &[
+            vec![
+                closed_captions_stream(ClosedCaptions::None),
+                closed_captions_stream(ClosedCaptions::group_id("cc1")),
+            ],
+            vec![
+                closed_captions_stream(ClosedCaptions::group_id("cc1")),
+                closed_captions_stream(ClosedCaptions::None),
+            ],
+        ] {
+            assert!(MasterPlaylist::builder()
+                .media(vec![closed_captions_media("cc1")])
+                .variant_streams(streams.clone())
+                .build()
+                .is_err());
+        }
+    }
+
     #[test]
     fn test_audio_streams() {
         let astreams = vec![
@@ -822,4 +2211,54 @@ mod tests {
             .to_string()
         );
     }
+
+    #[test]
+    fn test_select_rendition() {
+        let media = |name: &'static str, language: &'static str, flags: (bool, bool, bool)| {
+            ExtXMedia::builder()
+                .media_type(MediaType::Subtitles)
+                .group_id("subs")
+                .name(name)
+                .language(language)
+                .uri(format!("{}.m3u8", name))
+                .is_default(flags.0)
+                .is_autoselect(flags.1)
+                .is_forced(flags.2)
+                .build()
+                .unwrap()
+        };
+
+        let playlist = MasterPlaylist::builder()
+            .media(vec![
+                media("en", "en", (false, true, false)),
+                media("en-forced", "en", (false, true, true)),
+                media("en-us", "en-US", (true, true, false)),
+                media("de", "de", (false, false, false)),
+                media("fr", "fr", (false, false, true)),
+            ])
+            .build()
+            .unwrap();
+
+        let select = |preferences: &[&str], allow_forced| {
+            playlist
+                .select_rendition(MediaType::Subtitles, preferences, allow_forced)
+                .map(|media| media.name().to_string())
+        };
+
+        assert_eq!(select(&["EN"], false), Some("en".to_string()));
+        assert_eq!(select(&["en-GB"], false), Some("en".to_string()));
+        assert_eq!(select(&["en-us"], false), Some("en-us".to_string()));
+        assert_eq!(select(&["it", "de-CH"], false), Some("de".to_string()));
+        // forced renditions are skipped
+        assert_eq!(select(&["fr"], false), Some("en-us".to_string()));
+        assert_eq!(select(&["fr"], true), Some("fr".to_string()));
+        assert_eq!(select(&[], false), Some("en-us".to_string()));
+        // `e` is not a prefix of `en`
+        assert_eq!(select(&["e"], false), Some("en-us".to_string()));
+
+        assert_eq!(
+            playlist.select_rendition(MediaType::Audio, &["en"], true),
+            None
+        );
+    }
 }
@@ -0,0 +1,287 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::line::Tag;
+use crate::master_playlist::MasterPlaylistState;
+use crate::tags::{ExtM3u, ExtXMedia, ExtXSessionData, ExtXSessionKey, VariantStream};
+use crate::{Error, MasterPlaylist, ParseOptions, ValidationReport};
+
+/// A tag of a [`MasterPlaylist`], that has been parsed by a
+/// [`MasterPlaylistParser`].
+#[non_exhaustive]
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum MasterPlaylistEvent {
+    /// An `#EXT-X-MEDIA` tag.
+    Media(ExtXMedia<'static>),
+    /// An `#EXT-X-STREAM-INF` or `#EXT-X-I-FRAME-STREAM-INF` tag.
+    VariantStream(VariantStream<'static>),
+    /// An `#EXT-X-SESSION-DATA` tag.
+    SessionData(ExtXSessionData<'static>),
+    /// An `#EXT-X-SESSION-KEY` tag.
+    SessionKey(ExtXSessionKey<'static>),
+}
+
+/// Parses a [`MasterPlaylist`] from chunks of the input, as they arrive (for
+/// example from the body of an HTTP response).
+///
+/// Every call of [`MasterPlaylistParser::feed`] returns the tags, that have
+/// been completed by the chunk, so the selection of a variant can start before
+/// the whole playlist has been received. The chunks may split the input at
+/// any position.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::{MasterPlaylistEvent, MasterPlaylistParser};
+///
+/// let mut parser = MasterPlaylistParser::new();
+///
+/// assert!(parser
+///     .feed("#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=150000\n")?
+///     .is_empty());
+///
+/// let events = parser.feed("low.m3u8\n#EXT-X-STREAM-INF:BANDWI")?;
+/// assert_eq!(events.len(), 1);
+/// assert!(matches!(events[0], MasterPlaylistEvent::VariantStream(_)));
+///
+/// parser.feed("DTH=240000\nmid.m3u8")?;
+///
+/// let (playlist, report) = parser.finish()?;
+/// assert_eq!(playlist.variant_streams.len(), 2);
+/// assert!(report.is_empty());
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+#[derive(Default, Clone)]
+pub struct MasterPlaylistParser {
+    options: ParseOptions,
+    report: ValidationReport,
+    state: MasterPlaylistState<'static>,
+    /// The input, that does not end with a newline yet.
+    buffer: String,
+    /// An `#EXT-X-STREAM-INF` tag, that is waiting for its uri.
+    stream_inf: Option<String>,
+    /// The number of the last line, that has been parsed.
+    line: usize,
+    has_header: bool,
+}
+
+impl fmt::Debug for MasterPlaylistParser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MasterPlaylistParser")
+            .field("options", &self.options)
+            .field("report", &self.report)
+            .field("buffer", &self.buffer)
+            .field("stream_inf", &self.stream_inf)
+            .field("line", &self.line)
+            .field("has_header", &self.has_header)
+            .finish()
+    }
+}
+
+impl MasterPlaylistParser {
+    /// Makes a new [`MasterPlaylistParser`] with the default
+    /// [`ParseOptions`].
+    #[must_use]
+    pub fn new() -> Self { Self::default() }
+
+    /// Makes a new [`MasterPlaylistParser`] with the given [`ParseOptions`].
+    #[must_use]
+    pub fn with_options(options: ParseOptions) -> Self {
+        Self {
+            options,
+            ..Self::default()
+        }
+    }
+
+    /// Parses all lines, that are completed by the `chunk`, and returns the
+    /// tags, that have been found.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the input is not a valid [`MasterPlaylist`]. The parser
+    /// should not be used after an error.
+    pub fn feed(&mut self, chunk: &str) -> crate::Result<Vec<MasterPlaylistEvent>> {
+        self.buffer.push_str(chunk);
+
+        let end = match self.buffer.rfind('\n') {
+            Some(i) => i + 1,
+            None => return Ok(Vec::new()),
+        };
+
+        let lines = self.buffer.drain(..end).collect::<String>();
+        let mut events = Vec::new();
+
+        for line in lines.lines() {
+            if let Some(event) = self.push_line(line)? {
+                events.push(event);
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Parses the rest of the input and returns the [`MasterPlaylist`]
+    /// together with the warnings, that have been found in lenient mode.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the input is not a valid [`MasterPlaylist`].
+    pub fn finish(mut self) -> crate::Result<(MasterPlaylist<'static>, ValidationReport)> {
+        let rest = std::mem::take(&mut self.buffer);
+        self.push_line(&rest)?;
+
+        if let Some(line) = self.stream_inf {
+            return Err(Error::custom(format!("missing URI for {:?}", line)));
+        }
+
+        if !self.has_header {
+            return Err(Error::missing_tag(ExtM3u::PREFIX, rest));
+        }
+
+        Ok((self.state.build()?, self.report))
+    }
+
+    fn push_line(&mut self, line: &str) -> crate::Result<Option<MasterPlaylistEvent>> {
+        let line = line.trim();
+        self.line += 1;
+
+        if line.is_empty() {
+            return Ok(None);
+        }
+
+        if !self.has_header {
+            let (header_attributes, _) = ExtM3u::parse_header(line)?;
+            let header_attributes = header_attributes.map(|value| Cow::Owned(value.to_string()));
+
+            self.has_header = true;
+            self.state
+                .header(header_attributes, &self.options, &mut self.report)?;
+
+            return Ok(None);
+        }
+
+        let tag = if let Some(stream_inf) = self.stream_inf.take() {
+            // comments might appear between the tag and its uri:
+            if line.starts_with('#') && !line.starts_with("#EXT") {
+                self.state.push_comment(Cow::Owned(line.to_string()));
+                self.stream_inf = Some(stream_inf);
+                return Ok(None);
+            }
+
+            let input = format!("{}\n{}", stream_inf, line);
+            Tag::VariantStream(VariantStream::try_from(input.as_str())?.into_owned())
+        } else if line.starts_with(VariantStream::PREFIX_EXTXSTREAMINF) {
+            self.options.report_ignored_attributes(self.line, line);
+            self.stream_inf = Some(line.to_string());
+            return Ok(None);
+        } else if line.starts_with("#EXT") {
+            let tag = Tag::try_from(line)?;
+
+            if !matches!(tag, Tag::Unknown(_)) {
+                self.options.report_ignored_attributes(self.line, line);
+            }
+
+            match tag {
+                Tag::ExtXMedia(t) => Tag::ExtXMedia(t.into_owned()),
+                Tag::VariantStream(t) => Tag::VariantStream(t.into_owned()),
+                Tag::ExtXSessionData(t) => Tag::ExtXSessionData(t.into_owned()),
+                Tag::ExtXSessionKey(t) => Tag::ExtXSessionKey(t.into_owned()),
+                Tag::ExtXVersion(t) => Tag::ExtXVersion(t),
+                Tag::ExtXIndependentSegments(t) => Tag::ExtXIndependentSegments(t),
+                Tag::ExtXStart(t) => Tag::ExtXStart(t),
+                Tag::ExtXContentSteering(t) => Tag::ExtXContentSteering(t.into_owned()),
+                Tag::Unknown(value) => {
+                    self.options.report_ignored_tag(self.line, value);
+                    self.state.push_unknown(Cow::Owned(value.to_string()));
+                    return Ok(None);
+                }
+                tag => {
+                    return Err(tag.misplaced(self.line, crate::error::NOT_IN_MASTER_PLAYLIST));
+                }
+            }
+        } else if line.starts_with('#') {
+            return Ok(None);
+        } else {
+            return Err(Error::custom(format!("unexpected uri: {:?}", line)));
+        };
+
+        let event = match &tag {
+            Tag::ExtXMedia(t) => Some(MasterPlaylistEvent::Media(t.clone())),
+            Tag::VariantStream(t) => Some(MasterPlaylistEvent::VariantStream(t.clone())),
+            Tag::ExtXSessionData(t) => Some(MasterPlaylistEvent::SessionData(t.clone())),
+            Tag::ExtXSessionKey(t) => Some(MasterPlaylistEvent::SessionKey(t.clone())),
+            _ => None,
+        };
+
+        self.state
+            .push_tag(tag, self.line, &self.options, &mut self.report)?;
+
+        Ok(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const PLAYLIST: &str = concat!(
+        "#EXTM3U\r\n",
+        "#EXT-X-INDEPENDENT-SEGMENTS\n",
+        "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"English\",URI=\"en.m3u8\"\n",
+        "#EXT-X-STREAM-INF:BANDWIDTH=150000,AUDIO=\"aac\"\n",
+        "# a comment\n",
+        "\n",
+        "low.m3u8\n",
+        "#EXT-X-UNKNOWN-TAG\n",
+        "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=86000,URI=\"low/iframe.m3u8\"\n",
+        "#EXT-X-SESSION-DATA:DATA-ID=\"com.example.title\",VALUE=\"Example\"\n",
+        "#EXT-X-STREAM-INF:BANDWIDTH=240000,AUDIO=\"aac\"\n",
+        "mid.m3u8",
+    );
+
+    #[test]
+    fn test_chunks() {
+        let expected = MasterPlaylist::try_from(PLAYLIST).unwrap();
+
+        for size in 1..PLAYLIST.len() {
+            let mut parser = MasterPlaylistParser::new();
+            let mut events = Vec::new();
+
+            for chunk in PLAYLIST.as_bytes().chunks(size) {
+                events.extend(parser.feed(std::str::from_utf8(chunk).unwrap()).unwrap());
+            }
+
+            let (playlist, report) = parser.finish().unwrap();
+
+            assert_eq!(playlist, expected);
+            assert!(report.is_empty());
+            // the last variant stream is only completed by `finish`
+            assert_eq!(events.len(), 4);
+            assert_eq!(
+                events[0],
+                MasterPlaylistEvent::Media(expected.media[0].clone().into_owned())
+            );
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut parser = MasterPlaylistParser::new();
+        assert!(parser.feed("#EXT-X-VERSION:1\n").is_err());
+
+        let mut parser = MasterPlaylistParser::new();
+        let error = parser.feed("\n#EXTM3U\n#EXTINF:10,\n").unwrap_err();
+        assert_eq!(error.line(), Some(3));
+
+        let mut parser = MasterPlaylistParser::new();
+        parser
+            .feed("#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=1")
+            .unwrap();
+        assert!(parser.finish().is_err());
+
+        assert!(MasterPlaylistParser::new().finish().is_err());
+    }
+}
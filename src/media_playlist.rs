@@ -2,6 +2,8 @@ use std::borrow::Cow;
 use std::collections::HashSet;
 use std::convert::TryFrom;
 use std::fmt;
+use std::iter::FromIterator;
+use std::ops::Range;
 use std::str::FromStr;
 use std::time::Duration;
 
@@ -10,19 +12,53 @@ use stable_vec::StableVec;
 
 use crate::line::{Line, Lines, Tag};
 use crate::media_segment::MediaSegment;
+use crate::media_segment::PendingSegment;
+use crate::profiles::Profile;
 use crate::tags::{
-    ExtM3u, ExtXByteRange, ExtXDiscontinuitySequence, ExtXEndList, ExtXIFramesOnly,
-    ExtXIndependentSegments, ExtXKey, ExtXMediaSequence, ExtXStart, ExtXTargetDuration,
-    ExtXVersion,
+    CueMarker, ExtM3u, ExtXAllowCache, ExtXBitrate, ExtXByteRange, ExtXDiscontinuitySequence,
+    ExtXEndList, ExtXIFramesOnly, ExtXIndependentSegments, ExtXKey, ExtXMap, ExtXMediaSequence,
+    ExtXPartInf, ExtXPreloadHint, ExtXRenditionReport, ExtXServerControl, ExtXSkip, ExtXStart,
+    ExtXTargetDuration, ExtXVersion,
 };
 use crate::types::{
-    DecryptionKey, EncryptionMethod, InitializationVector, KeyFormat, PlaylistType, ProtocolVersion,
+    ByteRange, ContentKind, DecryptionKey, EncryptionMethod, InitializationVector, KeyFormat,
+    PlaylistType, ProtocolVersion,
 };
-use crate::utils::{tag, BoolExt};
-use crate::{Error, RequiredVersion};
+use crate::utils::{self, BoolExt};
+use crate::{
+    Compression, CustomTag, DurationPrecision, Error, KeyWritePolicy, ParseOptions,
+    RequiredVersion, Severity, TagVisitor, TagVisitorMut, UpdateViolation, UriKind,
+    ValidationReport, Validator, VersionReason, Writer,
+};
+
+/// The tags of a [`MediaPlaylist`], that appear before the first
+/// [`MediaSegment`], see [`MediaPlaylist::parse_header_only`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct PlaylistHeader {
+    /// The [`ProtocolVersion`] of the `#EXT-X-VERSION` tag, if present.
+    pub version: Option<ProtocolVersion>,
+    /// See [`MediaPlaylist::target_duration`].
+    pub target_duration: Duration,
+    /// See [`MediaPlaylist::media_sequence`].
+    pub media_sequence: usize,
+    /// See [`MediaPlaylist::discontinuity_sequence`].
+    pub discontinuity_sequence: usize,
+    /// See [`MediaPlaylist::playlist_type`].
+    pub playlist_type: Option<PlaylistType>,
+    /// See [`MediaPlaylist::allow_cache`].
+    pub allow_cache: Option<bool>,
+    /// See [`MediaPlaylist::has_i_frames_only`].
+    pub has_i_frames_only: bool,
+    /// See [`MediaPlaylist::has_independent_segments`].
+    pub has_independent_segments: bool,
+}
+
+/// A run of [`MediaSegment`]s, that are encrypted in the same way.
+type EncryptionRun<'a> = (Range<usize>, Vec<(EncryptionMethod, KeyFormat<'a>)>);
 
 /// Media playlist.
-#[derive(Builder, Debug, Clone, PartialEq, Eq)]
+#[derive(Builder, Debug, Clone)]
 #[builder(build_fn(skip), setter(strip_option))]
 #[non_exhaustive]
 pub struct MediaPlaylist<'a> {
@@ -63,6 +99,18 @@ pub struct MediaPlaylist<'a> {
     /// This field is optional.
     #[builder(default, setter(into))]
     pub playlist_type: Option<PlaylistType>,
+    /// Indicates whether the client may cache the downloaded
+    /// [`MediaSegment`]s for later replay (the legacy `EXT-X-ALLOW-CACHE`
+    /// tag).
+    ///
+    /// The tag has been removed in [`ProtocolVersion::V7`], so it is only
+    /// written, if the playlist is written with an older version.
+    ///
+    /// ### Note
+    ///
+    /// This field is optional.
+    #[builder(default, setter(into))]
+    pub allow_cache: Option<bool>,
     /// Indicates that each [`MediaSegment`] in the playlist describes a single
     /// I-frame. I-frames are encoded video frames, whose decoding does not
     /// depend on any other frame. I-frame Playlists can be used for trick
@@ -91,6 +139,38 @@ pub struct MediaPlaylist<'a> {
     /// This field is optional.
     #[builder(default, setter(into))]
     pub start: Option<ExtXStart>,
+    /// Indicates, which delivery directives of Low-Latency HLS are supported
+    /// by the server.
+    ///
+    /// ### Note
+    ///
+    /// This field is optional.
+    #[builder(default, setter(into))]
+    pub server_control: Option<ExtXServerControl>,
+    /// The `PART-TARGET` of the `#EXT-X-PART-INF` tag, which is the maximum
+    /// duration of the partial segments of a Low-Latency HLS playlist.
+    ///
+    /// Unlike the [`MediaPlaylist::target_duration`], the part target may be a
+    /// fraction of a second.
+    ///
+    /// ### Note
+    ///
+    /// This field is optional, but it is required, if the playlist contains
+    /// partial segments.
+    #[builder(default, setter(into))]
+    pub part_target: Option<Duration>,
+    /// The [`MediaSegment`]s at the start of a playlist delta update, that have
+    /// been skipped by the server.
+    ///
+    /// The skipped segments are not part of [`MediaPlaylist::segments`], the
+    /// first segment has the [`MediaSegment::number`]
+    /// `media_sequence + skipped_segments`.
+    ///
+    /// ### Note
+    ///
+    /// This field is optional.
+    #[builder(default, setter(into))]
+    pub skip: Option<ExtXSkip<'a>>,
     /// Indicates that no more [`MediaSegment`]s will be added to the
     /// [`MediaPlaylist`] file.
     ///
@@ -109,6 +189,43 @@ pub struct MediaPlaylist<'a> {
     /// This field is required.
     #[builder(setter(custom))]
     pub segments: StableVec<MediaSegment<'a>>,
+    /// The tags after the last [`MediaSegment`], that do not form a complete
+    /// segment yet (see [`PendingSegment`]).
+    ///
+    /// ### Note
+    ///
+    /// This field is optional.
+    #[builder(default)]
+    pub pending_segment: Option<PendingSegment<'a>>,
+    /// The resources (like the next partial segment), that will be added to
+    /// the playlist soon and can already be requested by a client.
+    ///
+    /// ### Note
+    ///
+    /// This field is optional.
+    #[builder(default, setter(into))]
+    pub preload_hints: Vec<ExtXPreloadHint<'a>>,
+    /// The most recent segments and partial segments of the other renditions
+    /// (see [`MediaPlaylistBuilder::push_rendition_report`]).
+    ///
+    /// ### Note
+    ///
+    /// This field is optional.
+    #[builder(default, setter(into))]
+    pub rendition_reports: Vec<ExtXRenditionReport<'a>>,
+    /// How the durations of the `#EXTINF` tags are written (unless it is
+    /// overridden by the [`Writer::duration_precision`]).
+    ///
+    /// When a playlist is parsed, the precision of the input is used, so the
+    /// durations are written like they have been read (for example `4.000`
+    /// instead of `4`).
+    ///
+    /// ### Note
+    ///
+    /// This field is optional and by default [`DurationPrecision::Shortest`].
+    /// It is not compared by [`PartialEq`].
+    #[builder(default)]
+    pub duration_precision: DurationPrecision,
     /// The allowable excess duration of each media segment in the
     /// associated playlist.
     ///
@@ -125,13 +242,41 @@ pub struct MediaPlaylist<'a> {
     /// `Duration::from_secs(0)`.
     #[builder(default = "Duration::from_secs(0)")]
     pub allowable_excess_duration: Duration,
+    /// The kind of media in the [`MediaSegment`]s, which enables additional
+    /// checks in [`MediaPlaylist::validation_report`].
+    ///
+    /// ### Note
+    ///
+    /// This field is optional and will not be written to or parsed from a
+    /// playlist file. It is not compared by [`PartialEq`].
+    #[builder(default, setter(into))]
+    pub content_kind: Option<ContentKind>,
+    /// Everything, that follows the `#EXTM3U` tag on the same line.
+    ///
+    /// Some tools write parameters after the tag, which are kept, so they can
+    /// be written again.
+    ///
+    /// ### Note
+    ///
+    /// This field is optional and not compared by [`PartialEq`].
+    #[builder(default, setter(into))]
+    pub header_attributes: Option<Cow<'a, str>>,
     /// A list of unknown tags.
     ///
     /// ### Note
     ///
     /// This field is optional.
-    #[builder(default, setter(into))]
+    #[builder(default, setter(custom))]
     pub unknown: Vec<Cow<'a, str>>,
+    /// Unknown tags, that are written before the [`MediaSegment`] with the
+    /// given [`MediaSegment::number`]. Tags with a position after the last
+    /// segment are written after all segments.
+    ///
+    /// ### Note
+    ///
+    /// This field is optional.
+    #[builder(default, setter(custom))]
+    pub positioned_tags: Vec<(usize, Cow<'a, str>)>,
 }
 
 impl<'a> MediaPlaylistBuilder<'a> {
@@ -146,6 +291,7 @@ impl<'a> MediaPlaylistBuilder<'a> {
 
     fn validate_media_segments(&self, target_duration: Duration) -> crate::Result<()> {
         let mut last_range_uri = None;
+        let mut is_gap_range = false;
 
         if let Some(segments) = &self.segments {
             // verify the independent segments
@@ -188,8 +334,7 @@ impl<'a> MediaPlaylistBuilder<'a> {
                 let segment_duration = segment.duration.duration();
 
                 // round the duration if it is .5s
-                let rounded_segment_duration =
-                    Duration::from_secs(segment_duration.as_secs_f64().round() as u64);
+                let rounded_segment_duration = rounded_duration(segment_duration);
 
                 let max_segment_duration = self
                     .allowable_excess_duration
@@ -213,11 +358,23 @@ impl<'a> MediaPlaylistBuilder<'a> {
                         if last_range_uri.ok_or_else(Error::invalid_input)? != segment.uri() {
                             return Err(Error::invalid_input());
                         }
+
+                        // a gap segment must not be the first segment of a byte
+                        // range chain, because the offsets of the following
+                        // segments are derived from it:
+                        if is_gap_range {
+                            return Err(Error::custom(format!(
+                                "the byte range of {:?} continues the byte range of a gap segment",
+                                segment.uri()
+                            )));
+                        }
                     } else {
                         last_range_uri = Some(segment.uri());
+                        is_gap_range = segment.has_gap;
                     }
                 } else {
                     last_range_uri = None;
+                    is_gap_range = false;
                 }
             }
         }
@@ -225,6 +382,32 @@ impl<'a> MediaPlaylistBuilder<'a> {
         Ok(())
     }
 
+    /// Adds an [`ExtXRenditionReport`] to the resulting playlist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::tags::ExtXRenditionReport;
+    /// use std::time::Duration;
+    ///
+    /// let playlist = MediaPlaylist::builder()
+    ///     .target_duration(Duration::from_secs(4))
+    ///     .segments(vec![])
+    ///     .push_rendition_report(ExtXRenditionReport::new("../1M/index.m3u8"))
+    ///     .push_rendition_report(ExtXRenditionReport::new("../4M/index.m3u8"))
+    ///     .build()?;
+    ///
+    /// assert_eq!(playlist.rendition_reports.len(), 2);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn push_rendition_report(&mut self, value: ExtXRenditionReport<'a>) -> &mut Self {
+        self.rendition_reports
+            .get_or_insert_with(Vec::new)
+            .push(value);
+        self
+    }
+
     /// Adds a media segment to the resulting playlist and assigns the next free
     /// [`MediaSegment::number`] to the segment.
     pub fn push_segment(&mut self, segment: MediaSegment<'a>) -> &mut Self {
@@ -240,9 +423,85 @@ impl<'a> MediaPlaylistBuilder<'a> {
         self
     }
 
+    /// A list of unknown tags.
+    pub fn unknown<I>(&mut self, value: I) -> &mut Self
+    where
+        I: IntoIterator<Item = Cow<'a, str>>,
+    {
+        self.unknown = Some(value.into_iter().collect());
+        self
+    }
+
+    /// Adds an unknown tag, that is written before the [`MediaSegment`] with
+    /// the [`MediaSegment::number`] `position`, see
+    /// [`MediaPlaylist::positioned_tags`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::MediaSegment;
+    /// use std::time::Duration;
+    ///
+    /// let playlist = MediaPlaylist::builder()
+    ///     .target_duration(Duration::from_secs(10))
+    ///     .segments((0..2).map(|i| {
+    ///         MediaSegment::builder()
+    ///             .duration(Duration::from_secs(10))
+    ///             .uri(format!("{}.ts", i))
+    ///             .build()
+    ///             .unwrap()
+    ///     }))
+    ///     .push_unknown_tag(1, "#EXT-X-CUE-OUT:10")
+    ///     .build()?;
+    ///
+    /// assert_eq!(
+    ///     playlist.to_string(),
+    ///     concat!(
+    ///         "#EXTM3U\n",
+    ///         "#EXT-X-TARGETDURATION:10\n",
+    ///         "#EXTINF:10,\n",
+    ///         "0.ts\n",
+    ///         "#EXT-X-CUE-OUT:10\n",
+    ///         "#EXTINF:10,\n",
+    ///         "1.ts\n",
+    ///     )
+    /// );
+    /// # Ok::<(), String>(())
+    /// ```
+    pub fn push_unknown_tag<T: Into<Cow<'a, str>>>(
+        &mut self,
+        position: usize,
+        value: T,
+    ) -> &mut Self {
+        self.positioned_tags
+            .get_or_insert_with(Vec::new)
+            .push((position, value.into()));
+        self
+    }
+
+    /// Adds a [`CustomTag`] at its [`CustomTag::position`] or to the
+    /// [`MediaPlaylist::unknown`] tags, if it has no position.
+    pub fn push_custom_tag<T: CustomTag>(&mut self, value: T) -> &mut Self {
+        let tag = Cow::Owned(value.to_string());
+
+        match value.position() {
+            Some(position) => self.push_unknown_tag(position, tag),
+            None => {
+                self.unknown.get_or_insert_with(Vec::new).push(tag);
+                self
+            }
+        }
+    }
+
     /// Parse the rest of the [`MediaPlaylist`] from an m3u8 file.
     pub fn parse(&mut self, input: &'a str) -> crate::Result<MediaPlaylist<'a>> {
-        parse_media_playlist(input, self)
+        parse_media_playlist(
+            input,
+            self,
+            &ParseOptions::new(),
+            &mut ValidationReport::default(),
+        )
     }
 
     /// Adds segments to the resulting playlist and assigns a
@@ -255,12 +514,19 @@ impl<'a> MediaPlaylistBuilder<'a> {
     /// number has been set explicitly. This function assumes, that all segments
     /// will be present in the final media playlist and the following is only
     /// possible if the segment is marked with `ExtXDiscontinuity`.
-    pub fn segments(&mut self, segments: Vec<MediaSegment<'a>>) -> &mut Self {
-        let mut vec = StableVec::<MediaSegment<'a>>::with_capacity(segments.len());
-        let mut remaining = Vec::with_capacity(segments.len());
+    pub fn segments<I>(&mut self, segments: I) -> &mut Self
+    where
+        I: IntoIterator<Item = MediaSegment<'a>>,
+    {
+        let segments = segments.into_iter();
+        let (capacity, _) = segments.size_hint();
+
+        let mut vec = StableVec::<MediaSegment<'a>>::with_capacity(capacity);
+        let mut remaining = Vec::with_capacity(capacity);
 
         for segment in segments {
             if segment.explicit_number {
+                vec.reserve_for(segment.number);
                 vec.insert(segment.number, segment);
             } else {
                 remaining.push(segment);
@@ -275,6 +541,132 @@ impl<'a> MediaPlaylistBuilder<'a> {
         self
     }
 
+    /// Returns the [`MediaPlaylist::target_duration`], if it has been set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use std::time::Duration;
+    ///
+    /// let mut builder = MediaPlaylist::builder();
+    /// assert_eq!(builder.get_target_duration(), None);
+    ///
+    /// builder.target_duration(Duration::from_secs(10));
+    /// assert_eq!(builder.get_target_duration(), Some(Duration::from_secs(10)));
+    /// ```
+    #[must_use]
+    pub fn get_target_duration(&self) -> Option<Duration> { self.target_duration }
+
+    /// Returns the [`MediaPlaylist::media_sequence`], if it has been set.
+    #[must_use]
+    pub fn get_media_sequence(&self) -> Option<usize> { self.media_sequence }
+
+    /// Returns the [`MediaPlaylist::discontinuity_sequence`], if it has been
+    /// set.
+    #[must_use]
+    pub fn get_discontinuity_sequence(&self) -> Option<usize> { self.discontinuity_sequence }
+
+    /// Returns the [`MediaPlaylist::playlist_type`], if it has been set.
+    #[must_use]
+    pub fn get_playlist_type(&self) -> Option<PlaylistType> { self.playlist_type.flatten() }
+
+    /// Returns the [`MediaPlaylist::allow_cache`] flag, if it has been set.
+    #[must_use]
+    pub fn get_allow_cache(&self) -> Option<bool> { self.allow_cache.flatten() }
+
+    /// Returns the [`MediaPlaylist::has_i_frames_only`] flag, if it has been
+    /// set.
+    #[must_use]
+    pub fn get_has_i_frames_only(&self) -> Option<bool> { self.has_i_frames_only }
+
+    /// Returns the [`MediaPlaylist::has_independent_segments`] flag, if it has
+    /// been set.
+    #[must_use]
+    pub fn get_has_independent_segments(&self) -> Option<bool> { self.has_independent_segments }
+
+    /// Returns the [`MediaPlaylist::start`], if it has been set.
+    #[must_use]
+    pub fn get_start(&self) -> Option<ExtXStart> { self.start.flatten() }
+
+    /// Returns the [`MediaPlaylist::server_control`], if it has been set.
+    #[must_use]
+    pub fn get_server_control(&self) -> Option<ExtXServerControl> { self.server_control.flatten() }
+
+    /// Returns the [`MediaPlaylist::part_target`], if it has been set.
+    #[must_use]
+    pub fn get_part_target(&self) -> Option<Duration> { self.part_target.flatten() }
+
+    /// Returns the [`MediaPlaylist::skip`], if it has been set.
+    #[must_use]
+    pub fn get_skip(&self) -> Option<&ExtXSkip<'a>> { self.skip.as_ref()?.as_ref() }
+
+    /// Returns the [`MediaPlaylist::preload_hints`], if they have been set.
+    #[must_use]
+    pub fn get_preload_hints(&self) -> Option<&[ExtXPreloadHint<'a>]> {
+        self.preload_hints.as_deref()
+    }
+
+    /// Returns the [`MediaPlaylist::rendition_reports`], if they have been
+    /// set.
+    #[must_use]
+    pub fn get_rendition_reports(&self) -> Option<&[ExtXRenditionReport<'a>]> {
+        self.rendition_reports.as_deref()
+    }
+
+    /// Returns the [`MediaPlaylist::has_end_list`] flag, if it has been set.
+    #[must_use]
+    pub fn get_has_end_list(&self) -> Option<bool> { self.has_end_list }
+
+    /// Returns the [`MediaSegment`]s, that have been added so far.
+    ///
+    /// The [`MediaSegment::number`]s of implicitly numbered segments are only
+    /// assigned by [`MediaPlaylistBuilder::build`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::MediaSegment;
+    /// use std::time::Duration;
+    ///
+    /// let mut builder = MediaPlaylist::builder();
+    /// assert!(builder.get_segments().is_none());
+    ///
+    /// builder.push_segment(
+    ///     MediaSegment::builder()
+    ///         .duration(Duration::from_secs(10))
+    ///         .uri("1.ts")
+    ///         .build()?,
+    /// );
+    /// assert_eq!(builder.get_segments().map(|s| s.num_elements()), Some(1));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn get_segments(&self) -> Option<&StableVec<MediaSegment<'a>>> { self.segments.as_ref() }
+
+    /// Returns the [`MediaPlaylist::allowable_excess_duration`], if it has
+    /// been set.
+    #[must_use]
+    pub fn get_allowable_excess_duration(&self) -> Option<Duration> {
+        self.allowable_excess_duration
+    }
+
+    /// Returns the [`MediaPlaylist::content_kind`], if it has been set.
+    #[must_use]
+    pub fn get_content_kind(&self) -> Option<ContentKind> { self.content_kind.flatten() }
+
+    /// Returns the [`MediaPlaylist::header_attributes`], if they have been
+    /// set.
+    #[must_use]
+    pub fn get_header_attributes(&self) -> Option<&str> {
+        self.header_attributes.as_ref()?.as_deref()
+    }
+
+    /// Returns the unknown tags, if they have been set.
+    #[must_use]
+    pub fn get_unknown(&self) -> Option<&[Cow<'a, str>]> { self.unknown.as_deref() }
+
     /// Builds a new `MediaPlaylist`.
     ///
     /// # Errors
@@ -284,7 +676,13 @@ impl<'a> MediaPlaylistBuilder<'a> {
         // validate builder
         self.validate()?;
 
-        let sequence_number = self.media_sequence.unwrap_or(0);
+        // the skipped segments of a delta update are not part of the segments:
+        let sequence_number = self.media_sequence.unwrap_or(0)
+            + self
+                .skip
+                .as_ref()
+                .and_then(Option::as_ref)
+                .map_or(0, ExtXSkip::skipped_segments);
 
         let mut segments = self
             .segments
@@ -341,6 +739,8 @@ impl<'a> MediaPlaylistBuilder<'a> {
             }
         }
 
+        update_map_changed(segments.values_mut());
+
         // TODO: can segments be missing?
         if !segments.is_compact() {
             // find the missing segment by iterating through all segments:
@@ -359,19 +759,93 @@ impl<'a> MediaPlaylistBuilder<'a> {
             media_sequence: self.media_sequence.unwrap_or(0),
             discontinuity_sequence: self.discontinuity_sequence.unwrap_or(0),
             playlist_type: self.playlist_type.unwrap_or(None),
+            allow_cache: self.allow_cache.unwrap_or(None),
             has_i_frames_only: self.has_i_frames_only.unwrap_or(false),
             has_independent_segments: self.has_independent_segments.unwrap_or(false),
             start: self.start.unwrap_or(None),
+            server_control: self.server_control.unwrap_or(None),
+            part_target: self.part_target.unwrap_or(None),
+            skip: self.skip.clone().unwrap_or(None),
             has_end_list: self.has_end_list.unwrap_or(false),
             segments,
+            pending_segment: self.pending_segment.clone().unwrap_or(None),
+            preload_hints: self.preload_hints.clone().unwrap_or_default(),
+            rendition_reports: self.rendition_reports.clone().unwrap_or_default(),
+            duration_precision: self.duration_precision.unwrap_or_default(),
             allowable_excess_duration: self
                 .allowable_excess_duration
                 .unwrap_or_else(|| Duration::from_secs(0)),
+            content_kind: self.content_kind.unwrap_or(None),
+            header_attributes: self.header_attributes.clone().unwrap_or(None),
             unknown: self.unknown.clone().unwrap_or_default(),
+            positioned_tags: self.positioned_tags.clone().unwrap_or_default(),
         })
     }
 }
 
+/// Adds the segments like [`MediaPlaylistBuilder::push_segment`].
+///
+/// # Example
+///
+/// ```
+/// # use hls_m3u8::MediaPlaylist;
+/// use hls_m3u8::MediaSegment;
+/// use std::time::Duration;
+///
+/// let mut builder = MediaPlaylist::builder();
+/// builder.target_duration(Duration::from_secs(10));
+///
+/// builder.extend((0..3).map(|i| {
+///     MediaSegment::builder()
+///         .duration(Duration::from_secs(10))
+///         .uri(format!("{}.ts", i))
+///         .build()
+///         .unwrap()
+/// }));
+///
+/// assert_eq!(builder.build()?.segments.num_elements(), 3);
+/// # Ok::<(), String>(())
+/// ```
+impl<'a> Extend<MediaSegment<'a>> for MediaPlaylistBuilder<'a> {
+    fn extend<I: IntoIterator<Item = MediaSegment<'a>>>(&mut self, iter: I) {
+        for segment in iter {
+            self.push_segment(segment);
+        }
+    }
+}
+
+/// Makes a builder with the segments (see [`MediaPlaylistBuilder::segments`]).
+///
+/// # Example
+///
+/// ```
+/// # use hls_m3u8::MediaPlaylist;
+/// use hls_m3u8::builder::MediaPlaylistBuilder;
+/// use hls_m3u8::MediaSegment;
+/// use std::time::Duration;
+///
+/// let mut builder = (0..3)
+///     .map(|i| {
+///         MediaSegment::builder()
+///             .duration(Duration::from_secs(10))
+///             .uri(format!("{}.ts", i))
+///             .build()
+///             .unwrap()
+///     })
+///     .collect::<MediaPlaylistBuilder<'_>>();
+///
+/// let playlist = builder.target_duration(Duration::from_secs(10)).build()?;
+/// assert_eq!(playlist.segments[2].uri(), "2.ts");
+/// # Ok::<(), String>(())
+/// ```
+impl<'a> FromIterator<MediaSegment<'a>> for MediaPlaylistBuilder<'a> {
+    fn from_iter<I: IntoIterator<Item = MediaSegment<'a>>>(iter: I) -> Self {
+        let mut builder = Self::default();
+        builder.segments(iter);
+        builder
+    }
+}
+
 impl<'a> RequiredVersion for MediaPlaylistBuilder<'a> {
     fn required_version(&self) -> ProtocolVersion {
         required_version![
@@ -388,8 +862,13 @@ impl<'a> RequiredVersion for MediaPlaylistBuilder<'a> {
                 .unwrap_or(false)
                 .athen_some(ExtXIndependentSegments),
             self.start,
+            self.server_control,
+            self.part_target.flatten().map(ExtXPartInf),
+            self.skip,
             self.has_end_list.unwrap_or(false).athen_some(ExtXEndList),
-            self.segments
+            self.segments,
+            self.preload_hints,
+            self.rendition_reports
         ]
     }
 }
@@ -400,6 +879,102 @@ impl<'a> MediaPlaylist<'a> {
     #[inline]
     pub fn builder() -> MediaPlaylistBuilder<'a> { MediaPlaylistBuilder::default() }
 
+    /// Parses only the tags, that appear before the first [`MediaSegment`],
+    /// which is much cheaper than parsing the whole playlist, if only the
+    /// header is needed (for example to route a request).
+    ///
+    /// Parsing stops at the first tag, that belongs to a segment, so errors in
+    /// the rest of the input are not detected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::types::PlaylistType;
+    ///
+    /// let header = MediaPlaylist::parse_header_only(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-VERSION:3\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXT-X-MEDIA-SEQUENCE:42\n",
+    ///     "#EXT-X-PLAYLIST-TYPE:EVENT\n",
+    ///     "#EXTINF:10,\n",
+    ///     "42.ts\n",
+    /// ))?;
+    ///
+    /// assert_eq!(header.target_duration.as_secs(), 10);
+    /// assert_eq!(header.media_sequence, 42);
+    /// assert_eq!(header.playlist_type, Some(PlaylistType::Event));
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the input does not start with `#EXTM3U`, if a tag in the
+    /// header is invalid or if the `#EXT-X-TARGETDURATION` tag is missing.
+    pub fn parse_header_only(input: &str) -> crate::Result<PlaylistHeader> {
+        let first_line = utils::leading_lines(input);
+        let (_, input) = ExtM3u::parse_header(input)?;
+
+        let mut version = None;
+        let mut target_duration = None;
+        let mut media_sequence = 0;
+        let mut discontinuity_sequence = 0;
+        let mut playlist_type = None;
+        let mut allow_cache = None;
+        let mut has_i_frames_only = false;
+        let mut has_independent_segments = false;
+
+        let mut lines = Lines::from(input);
+
+        while let Some(line) = lines.next() {
+            match line? {
+                Line::Tag(tag) => {
+                    match tag {
+                        Tag::ExtXVersion(t) => version = Some(t.version()),
+                        Tag::ExtXTargetDuration(t) => target_duration = Some(t.0),
+                        Tag::ExtXMediaSequence(t) => media_sequence = t.0,
+                        Tag::ExtXDiscontinuitySequence(t) => discontinuity_sequence = t.0,
+                        Tag::PlaylistType(t) => playlist_type = Some(t),
+                        Tag::ExtXAllowCache(t) => allow_cache = Some(t.0),
+                        Tag::ExtXIFramesOnly(_) => has_i_frames_only = true,
+                        Tag::ExtXIndependentSegments(_) => has_independent_segments = true,
+                        Tag::ExtXStart(_)
+                        | Tag::ExtXServerControl(_)
+                        | Tag::ExtXPartInf(_)
+                        | Tag::Unknown(_) => {}
+                        Tag::ExtXMedia(_)
+                        | Tag::VariantStream(_)
+                        | Tag::ExtXSessionData(_)
+                        | Tag::ExtXSessionKey(_)
+                        | Tag::ExtXContentSteering(_) => {
+                            return Err(tag.misplaced(
+                                first_line + lines.line(),
+                                crate::error::NOT_IN_MEDIA_PLAYLIST,
+                            ));
+                        }
+                        // the first segment (or the end of the playlist) has been reached
+                        _ => break,
+                    }
+                }
+                Line::Comment(_) => {}
+                Line::Uri(_) => break,
+            }
+        }
+
+        Ok(PlaylistHeader {
+            version,
+            target_duration: target_duration
+                .ok_or_else(|| Error::missing_tag("#EXT-X-TARGETDURATION", ""))?,
+            media_sequence,
+            discontinuity_sequence,
+            playlist_type,
+            allow_cache,
+            has_i_frames_only,
+            has_independent_segments,
+        })
+    }
+
     /// Computes the `Duration` of the [`MediaPlaylist`], by adding each segment
     /// duration together.
     #[must_use]
@@ -407,459 +982,3979 @@ impl<'a> MediaPlaylist<'a> {
         self.segments.values().map(|s| s.duration.duration()).sum()
     }
 
-    /// Makes the struct independent of its lifetime, by taking ownership of all
-    /// internal [`Cow`]s.
+    /// Returns the exact number of bytes of all [`MediaSegment`]s, if every
+    /// segment has a [`MediaSegment::byte_range`] (like a playlist, where all
+    /// segments are stored in a single file).
     ///
-    /// # Note
+    /// The Media Initialization Sections of the [`MediaSegment::map`]s are not
+    /// included.
     ///
-    /// This is a relatively expensive operation.
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use std::convert::TryFrom;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXT-X-VERSION:4\n",
+    ///     "#EXTINF:10,\n",
+    ///     "#EXT-X-BYTERANGE:75232@0\n",
+    ///     "video.ts\n",
+    ///     "#EXTINF:10,\n",
+    ///     "#EXT-X-BYTERANGE:82112\n",
+    ///     "video.ts\n",
+    /// ))?;
+    ///
+    /// assert_eq!(playlist.total_bytes(), Some(75232 + 82112));
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
     #[must_use]
-    pub fn into_owned(self) -> MediaPlaylist<'static> {
-        MediaPlaylist {
-            target_duration: self.target_duration,
-            media_sequence: self.media_sequence,
-            discontinuity_sequence: self.discontinuity_sequence,
-            playlist_type: self.playlist_type,
-            has_i_frames_only: self.has_i_frames_only,
-            has_independent_segments: self.has_independent_segments,
-            start: self.start,
-            has_end_list: self.has_end_list,
-            segments: {
+    pub fn total_bytes(&self) -> Option<usize> {
+        self.segments.values().map(MediaSegment::total_bytes).sum()
+    }
+
+    /// Returns the segment with the [`MediaSegment::number`], which is its
+    /// media sequence number (for example the `_HLS_msn` of a blocking
+    /// playlist reload).
+    ///
+    /// The number does not depend on how the segments are stored, so it is
+    /// the same for a parsed playlist, a built playlist and a playlist with
+    /// an [`ExtXSkip`] tag.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use std::convert::TryFrom;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:4\n",
+    ///     "#EXT-X-MEDIA-SEQUENCE:10\n",
+    ///     "#EXTINF:4,\n",
+    ///     "10.ts\n",
+    ///     "#EXTINF:4,\n",
+    ///     "11.ts\n",
+    /// ))?;
+    ///
+    /// assert_eq!(
+    ///     playlist.segment(11).map(|s| s.uri().as_str()),
+    ///     Some("11.ts")
+    /// );
+    /// assert!(playlist.segment(9).is_none());
+    /// assert!(playlist.segment(12).is_none());
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub fn segment(&self, number: usize) -> Option<&MediaSegment<'a>> {
+        let first = self.segments.find_first_index()?;
+        let offset = number.checked_sub(self.segments[first].number)?;
+
+        // segments with an explicit number might not be numbered consecutively:
+        match self.segments.get(first + offset) {
+            Some(segment) if segment.number == number => Some(segment),
+            _ => {
                 self.segments
-                    .into_iter()
-                    .map(|(_, s)| s.into_owned())
-                    .collect()
-            },
-            allowable_excess_duration: self.allowable_excess_duration,
-            unknown: {
-                self.unknown
-                    .into_iter()
-                    .map(|v| Cow::Owned(v.into_owned()))
-                    .collect()
-            },
+                    .values()
+                    .find(|segment| segment.number == number)
+            }
         }
     }
-}
 
-impl<'a> RequiredVersion for MediaPlaylist<'a> {
-    fn required_version(&self) -> ProtocolVersion {
-        required_version![
-            ExtXTargetDuration(self.target_duration),
-            (self.media_sequence != 0).athen(|| ExtXMediaSequence(self.media_sequence)),
-            (self.discontinuity_sequence != 0)
-                .athen(|| ExtXDiscontinuitySequence(self.discontinuity_sequence)),
-            self.playlist_type,
-            self.has_i_frames_only.athen_some(ExtXIFramesOnly),
-            self.has_independent_segments
-                .athen_some(ExtXIndependentSegments),
-            self.start,
-            self.has_end_list.athen_some(ExtXEndList),
-            self.segments
-        ]
+    /// Writes the playlist to bytes with the given [`Compression`], which
+    /// can be used as the body of a response.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::{Compression, MediaPlaylist};
+    /// use std::convert::TryFrom;
+    ///
+    /// let input = concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXTINF:10,\n",
+    ///     "segment.ts\n",
+    /// );
+    /// let playlist = MediaPlaylist::try_from(input)?;
+    ///
+    /// assert_eq!(playlist.to_bytes(Compression::None)?, input.as_bytes());
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function fails, if the compression fails (for example, because
+    /// the gzip level is larger than `9`).
+    pub fn to_bytes(&self, compression: Compression) -> crate::Result<Vec<u8>> {
+        compression.compress(self.to_string().into_bytes())
     }
-}
 
-impl<'a> fmt::Display for MediaPlaylist<'a> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "{}", ExtM3u)?;
-
-        if self.required_version() != ProtocolVersion::V1 {
-            writeln!(f, "{}", ExtXVersion::new(self.required_version()))?;
-        }
+    /// Checks the playlist for problems, that are not covered by the
+    /// specification, but are known to break some players.
+    ///
+    /// This is a shorthand for [`Validator::validate_media_playlist`] with the
+    /// default [`Validator`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::types::ContentKind;
+    /// use hls_m3u8::MediaSegment;
+    /// use std::time::Duration;
+    ///
+    /// let playlist = MediaPlaylist::builder()
+    ///     .target_duration(Duration::from_secs(120))
+    ///     .content_kind(ContentKind::Subtitles)
+    ///     .segments(vec![MediaSegment::builder()
+    ///         .duration(Duration::from_secs(120))
+    ///         .uri("subtitles.vtt")
+    ///         .build()?])
+    ///     .build()?;
+    ///
+    /// assert_eq!(playlist.validation_report().warnings().count(), 1);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn validation_report(&self) -> ValidationReport {
+        Validator::new().validate_media_playlist(self)
+    }
 
-        writeln!(f, "{}", ExtXTargetDuration(self.target_duration))?;
+    /// Checks, if the playlist can be played by a device with the given
+    /// [`Profile`]. Every problem is reported with [`Severity::Error`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::profiles;
+    /// use std::convert::TryFrom;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXTINF:10,\n",
+    ///     "1.ts\n",
+    /// ))?;
+    ///
+    /// assert!(playlist
+    ///     .check_profile(profiles::CHROMECAST_1ST_GEN)
+    ///     .is_empty());
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    ///
+    /// [`Profile`]: crate::profiles::Profile
+    #[must_use]
+    pub fn check_profile(&self, profile: Profile) -> ValidationReport {
+        profile.check_media_playlist(self)
+    }
 
-        if self.media_sequence != 0 {
-            writeln!(f, "{}", ExtXMediaSequence(self.media_sequence))?;
+    /// Returns the byte ranges of the [`MediaSegment`]s, where the ranges of
+    /// consecutive segments with the same uri are merged, if they follow each
+    /// other.
+    ///
+    /// This is useful for an origin shield, that has to translate the
+    /// requests for the individual segments of a single file into upstream
+    /// range requests. Segments without a byte range are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::types::ByteRange;
+    /// use std::convert::TryFrom;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-VERSION:4\n",
+    ///     "#EXT-X-TARGETDURATION:1\n",
+    ///     "#EXTINF:1,\n",
+    ///     "#EXT-X-BYTERANGE:100@0\n",
+    ///     "video.mp4\n",
+    ///     "#EXTINF:1,\n",
+    ///     "#EXT-X-BYTERANGE:100\n",
+    ///     "video.mp4\n",
+    ///     "#EXTINF:1,\n",
+    ///     "#EXT-X-BYTERANGE:50@300\n",
+    ///     "video.mp4\n",
+    /// ))?;
+    ///
+    /// assert_eq!(
+    ///     playlist.parts_as_single_file_ranges(),
+    ///     vec![
+    ///         ("video.mp4", ByteRange::from(0..200)),
+    ///         ("video.mp4", ByteRange::from(300..350)),
+    ///     ]
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub fn parts_as_single_file_ranges(&self) -> Vec<(&str, ByteRange)> {
+        let mut result: Vec<(&str, ByteRange)> = Vec::new();
+        let mut is_previous_range = false;
+
+        for segment in self.segments.values() {
+            let range = match segment.byte_range {
+                Some(range) => *range,
+                None => {
+                    is_previous_range = false;
+                    continue;
+                }
+            };
+
+            let start = range.start().unwrap_or(0);
+            let uri = segment.uri().as_str();
+
+            match result.last_mut() {
+                Some((last_uri, last))
+                    if is_previous_range && *last_uri == uri && last.end() == start =>
+                {
+                    last.set_len(last.len() + range.len());
+                }
+                _ => result.push((uri, ByteRange::from(start..range.end()))),
+            }
+
+            is_previous_range = true;
+        }
+
+        result
+    }
+
+    /// Returns the runs of consecutive [`MediaSegment`]s, that are marked with
+    /// [`MediaSegment::has_gap`], as a range of [`MediaSegment::number`]s
+    /// together with the duration of the run.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::MediaSegment;
+    /// use std::time::Duration;
+    ///
+    /// let mut playlist = MediaPlaylist::builder()
+    ///     .target_duration(Duration::from_secs(4))
+    ///     .segments((0..10).map(|i| {
+    ///         MediaSegment::builder()
+    ///             .duration(Duration::from_secs(4))
+    ///             .has_gap((3..5).contains(&i))
+    ///             .uri(format!("{}.ts", i))
+    ///             .build()
+    ///             .unwrap()
+    ///     }))
+    ///     .build()?;
+    ///
+    /// assert_eq!(playlist.gap_runs(), vec![(3..5, Duration::from_secs(8))]);
+    /// assert_eq!(playlist.availability_ratio(), 0.8);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn gap_runs(&self) -> Vec<(Range<usize>, Duration)> {
+        let mut result: Vec<(Range<usize>, Duration)> = Vec::new();
+
+        for segment in self.segments.values().filter(|segment| segment.has_gap) {
+            let duration = segment.duration.duration();
+
+            match result.last_mut() {
+                Some((range, total)) if range.end == segment.number => {
+                    range.end += 1;
+                    *total += duration;
+                }
+                _ => result.push((segment.number..segment.number + 1, duration)),
+            }
+        }
+
+        result
+    }
+
+    /// Returns the runs of consecutive [`MediaSegment`]s, that are encrypted
+    /// in the same way, as a range of [`MediaSegment::number`]s together with
+    /// the [`EncryptionMethod`] and [`KeyFormat`] of every key, that applies
+    /// to the segments.
+    ///
+    /// Unencrypted segments (`METHOD=NONE`) have no keys. A key without a
+    /// `KEYFORMAT` has the [`KeyFormat::Identity`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::types::{EncryptionMethod, KeyFormat};
+    /// use std::convert::TryFrom;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXTINF:10,\n",
+    ///     "0.ts\n",
+    ///     "#EXT-X-KEY:METHOD=AES-128,URI=\"key\"\n",
+    ///     "#EXTINF:10,\n",
+    ///     "1.ts\n",
+    ///     "#EXTINF:10,\n",
+    ///     "2.ts\n",
+    ///     "#EXT-X-KEY:METHOD=NONE\n",
+    ///     "#EXTINF:10,\n",
+    ///     "3.ts\n",
+    /// ))?;
+    ///
+    /// assert_eq!(
+    ///     playlist.encryption_map(),
+    ///     vec![
+    ///         (0..1, vec![]),
+    ///         (1..3, vec![(EncryptionMethod::Aes128, KeyFormat::Identity)]),
+    ///         (3..4, vec![]),
+    ///     ]
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    ///
+    /// [`EncryptionMethod`]: crate::types::EncryptionMethod
+    /// [`KeyFormat`]: crate::types::KeyFormat
+    /// [`KeyFormat::Identity`]: crate::types::KeyFormat::Identity
+    #[must_use]
+    pub fn encryption_map(&self) -> Vec<EncryptionRun<'a>> {
+        let mut result: Vec<EncryptionRun<'a>> = Vec::new();
+
+        for segment in self.segments.values() {
+            let mut keys = segment
+                .keys
+                .iter()
+                .filter_map(ExtXKey::as_ref)
+                .map(|key| (key.method, key.key_format()))
+                .collect::<Vec<_>>();
+
+            keys.sort();
+            keys.dedup();
+
+            match result.last_mut() {
+                Some((range, value)) if range.end == segment.number && *value == keys => {
+                    range.end += 1;
+                }
+                _ => result.push((segment.number..segment.number + 1, keys)),
+            }
+        }
+
+        result
+    }
+
+    /// Returns the share of the [`MediaPlaylist::duration`], that is not
+    /// marked with [`MediaSegment::has_gap`], as a number between `0.0` and
+    /// `1.0`.
+    ///
+    /// A playlist without any segments is fully available.
+    #[must_use]
+    pub fn availability_ratio(&self) -> f64 {
+        let total = self.duration();
+
+        if total == Duration::ZERO {
+            return 1.0;
+        }
+
+        let gaps = self
+            .gap_runs()
+            .into_iter()
+            .map(|(_, duration)| duration)
+            .sum::<Duration>();
+
+        1.0 - gaps.as_secs_f64() / total.as_secs_f64()
+    }
+
+    /// Returns the keys, that are in effect for the first segment.
+    ///
+    /// Playlists often have a single `#EXT-X-KEY` tag in front of the first
+    /// segment, that covers the whole playlist, so a client can start the
+    /// license request with these keys, before the segments are examined.
+    /// Empty keys (`METHOD=NONE`) are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use std::convert::TryFrom;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"skd://key\",KEYFORMAT=\"identity\"\n",
+    ///     "#EXTINF:10,\n",
+    ///     "1.ts\n",
+    /// ))?;
+    ///
+    /// let keys = playlist.initial_keys().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(keys.len(), 1);
+    /// assert_eq!(keys[0].uri(), "skd://key");
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    pub fn initial_keys(&self) -> impl Iterator<Item = &DecryptionKey<'a>> {
+        self.segments
+            .find_first()
+            .into_iter()
+            .flat_map(|segment| &segment.keys)
+            .filter_map(ExtXKey::as_ref)
+    }
+
+    /// Calls the methods of the [`TagVisitor`] for every tag of the playlist
+    /// in the order in which they are written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::TagVisitor;
+    /// use std::convert::TryFrom;
+    ///
+    /// #[derive(Default)]
+    /// struct CountDiscontinuities(usize);
+    ///
+    /// impl<'a> TagVisitor<'a> for CountDiscontinuities {
+    ///     fn visit_discontinuity(&mut self) { self.0 += 1; }
+    /// }
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXTINF:10,\n",
+    ///     "1.ts\n",
+    ///     "#EXT-X-DISCONTINUITY\n",
+    ///     "#EXTINF:10,\n",
+    ///     "2.ts\n",
+    /// ))?;
+    ///
+    /// let mut visitor = CountDiscontinuities::default();
+    /// playlist.visit(&mut visitor);
+    ///
+    /// assert_eq!(visitor.0, 1);
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    pub fn visit(&self, visitor: &mut impl TagVisitor<'a>) {
+        visitor.visit_target_duration(self.target_duration);
+
+        if self.media_sequence != 0 {
+            visitor.visit_media_sequence(self.media_sequence);
         }
 
         if self.discontinuity_sequence != 0 {
-            writeln!(
-                f,
-                "{}",
-                ExtXDiscontinuitySequence(self.discontinuity_sequence)
-            )?;
+            visitor.visit_discontinuity_sequence(self.discontinuity_sequence);
         }
 
-        if let Some(value) = &self.playlist_type {
-            writeln!(f, "{}", value)?;
+        if let Some(value) = self.playlist_type {
+            visitor.visit_playlist_type(value);
+        }
+
+        if let Some(value) = self.allow_cache {
+            visitor.visit_allow_cache(value);
         }
 
         if self.has_i_frames_only {
-            writeln!(f, "{}", ExtXIFramesOnly)?;
+            visitor.visit_i_frames_only();
         }
 
         if self.has_independent_segments {
-            writeln!(f, "{}", ExtXIndependentSegments)?;
+            visitor.visit_independent_segments();
         }
 
         if let Some(value) = &self.start {
-            writeln!(f, "{}", value)?;
+            visitor.visit_start(value);
         }
 
-        let mut available_keys = HashSet::<ExtXKey<'_>>::new();
+        if let Some(value) = &self.server_control {
+            visitor.visit_server_control(value);
+        }
 
-        for segment in self.segments.values() {
-            for key in &segment.keys {
-                if let ExtXKey(Some(decryption_key)) = key {
-                    // next segment will be encrypted, so the segment can not have an empty key
-                    available_keys.remove(&ExtXKey::empty());
-
-                    let mut decryption_key = decryption_key.clone();
-                    let key = {
-                        if let InitializationVector::Number(_) = decryption_key.iv {
-                            // set the iv from a segment number to missing
-                            // this does reduce the output size and the correct iv
-                            // is automatically set, when parsing.
-                            decryption_key.iv = InitializationVector::Missing;
-                        }
+        if let Some(value) = self.part_target {
+            visitor.visit_part_inf(value);
+        }
 
-                        ExtXKey(Some(decryption_key.clone()))
-                    };
+        if let Some(value) = &self.skip {
+            visitor.visit_skip(value);
+        }
 
-                    // only do something if a key has been overwritten
-                    if available_keys.insert(key.clone()) {
-                        let mut remove_key = None;
+        for segment in self.segments.values() {
+            segment.visit(visitor);
+        }
 
-                        // an old key might be removed:
-                        for k in &available_keys {
-                            if let ExtXKey(Some(dk)) = k {
-                                if dk.format == decryption_key.format && key != *k {
-                                    remove_key = Some(k.clone());
-                                    break;
-                                }
-                            } else {
-                                unreachable!("empty keys should not exist in `available_keys`");
-                            }
-                        }
+        if let Some(segment) = &self.pending_segment {
+            segment.visit(visitor);
+        }
 
-                        if let Some(k) = remove_key {
-                            // this should always be true:
-                            let res = available_keys.remove(&k);
-                            debug_assert!(res);
-                        }
+        for value in &self.preload_hints {
+            visitor.visit_preload_hint(value);
+        }
 
-                        writeln!(f, "{}", key)?;
-                    }
-                } else {
-                    // the next segment is not encrypted, so remove all available keys
-                    available_keys.clear();
-                    available_keys.insert(ExtXKey::empty());
-                    writeln!(f, "{}", key)?;
-                }
-            }
+        for value in &self.rendition_reports {
+            visitor.visit_rendition_report(value);
+        }
 
-            write!(f, "{}", segment)?;
+        for (_, value) in &self.positioned_tags {
+            visitor.visit_unknown(value);
         }
 
         for value in &self.unknown {
-            writeln!(f, "{}", value)?;
+            visitor.visit_unknown(value);
         }
 
         if self.has_end_list {
-            writeln!(f, "{}", ExtXEndList)?;
+            visitor.visit_end_list();
         }
-
-        Ok(())
     }
-}
 
-fn parse_media_playlist<'a>(
-    input: &'a str,
-    builder: &mut MediaPlaylistBuilder<'a>,
-) -> crate::Result<MediaPlaylist<'a>> {
-    let input = tag(input, "#EXTM3U")?;
+    /// Like [`MediaPlaylist::visit`], but the [`TagVisitorMut`] can modify the
+    /// tags.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::tags::ExtInf;
+    /// use hls_m3u8::TagVisitorMut;
+    /// use std::convert::TryFrom;
+    ///
+    /// struct StripTitles;
+    ///
+    /// impl<'a> TagVisitorMut<'a> for StripTitles {
+    ///     fn visit_inf(&mut self, inf: &mut ExtInf<'a>) { inf.set_title(None::<String>); }
+    /// }
+    ///
+    /// let mut playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXTINF:10,title\n",
+    ///     "1.ts\n",
+    /// ))?;
+    ///
+    /// playlist.visit_mut(&mut StripTitles);
+    ///
+    /// assert_eq!(playlist.segments[0].duration.title(), &None);
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    pub fn visit_mut(&mut self, visitor: &mut impl TagVisitorMut<'a>) {
+        visitor.visit_target_duration(&mut self.target_duration);
 
-    let mut segment = MediaSegment::builder();
-    let mut segments = vec![];
+        if self.media_sequence != 0 {
+            visitor.visit_media_sequence(&mut self.media_sequence);
+        }
 
-    let mut has_partial_segment = false;
-    let mut has_discontinuity_tag = false;
-    let mut unknown = vec![];
-    let mut available_keys = HashSet::new();
+        if self.discontinuity_sequence != 0 {
+            visitor.visit_discontinuity_sequence(&mut self.discontinuity_sequence);
+        }
 
-    for line in Lines::from(input) {
-        match line? {
-            Line::Tag(tag) => {
-                match tag {
-                    Tag::ExtInf(t) => {
-                        has_partial_segment = true;
-                        segment.duration(t);
-                    }
-                    Tag::ExtXByteRange(t) => {
-                        has_partial_segment = true;
-                        segment.byte_range(t);
-                    }
-                    Tag::ExtXDiscontinuity(_) => {
-                        has_discontinuity_tag = true;
-                        has_partial_segment = true;
-                        segment.has_discontinuity(true);
-                    }
-                    Tag::ExtXKey(key) => {
-                        has_partial_segment = true;
+        if let Some(value) = &mut self.playlist_type {
+            visitor.visit_playlist_type(value);
+        }
 
-                        // An ExtXKey applies to every MediaSegment and to every Media
-                        // Initialization Section declared by an ExtXMap tag, that appears
-                        // between it and the next ExtXKey tag in the Playlist file with the
-                        // same KEYFORMAT attribute (or the end of the Playlist file).
+        if let Some(value) = &mut self.allow_cache {
+            visitor.visit_allow_cache(value);
+        }
 
-                        let mut is_new_key = true;
-                        let mut remove = None;
+        if self.has_i_frames_only {
+            visitor.visit_i_frames_only();
+        }
 
-                        if let ExtXKey(Some(decryption_key)) = &key {
-                            for old_key in &available_keys {
-                                if let ExtXKey(Some(old_decryption_key)) = &old_key {
-                                    if old_decryption_key.format == decryption_key.format {
-                                        // remove the old key
-                                        remove = Some(old_key.clone());
+        if self.has_independent_segments {
+            visitor.visit_independent_segments();
+        }
 
-                                        // there are no keys with the same format in
-                                        // available_keys so the loop can stop here:
-                                        break;
-                                    }
-                                } else {
-                                    // remove an empty key
-                                    remove = Some(ExtXKey::empty());
-                                    break;
-                                }
-                            }
-                        } else {
-                            available_keys.clear();
-                            available_keys.insert(ExtXKey::empty());
-                            is_new_key = false;
-                        }
+        if let Some(value) = &mut self.start {
+            visitor.visit_start(value);
+        }
 
-                        if let Some(key) = &remove {
-                            available_keys.remove(key);
-                        }
+        if let Some(value) = &mut self.server_control {
+            visitor.visit_server_control(value);
+        }
 
-                        if is_new_key {
-                            available_keys.insert(key);
-                        }
-                    }
-                    Tag::ExtXMap(mut t) => {
-                        has_partial_segment = true;
+        if let Some(value) = &mut self.part_target {
+            visitor.visit_part_inf(value);
+        }
 
-                        t.keys = available_keys.iter().cloned().collect();
-                        segment.map(t);
-                    }
-                    Tag::ExtXProgramDateTime(t) => {
-                        has_partial_segment = true;
-                        segment.program_date_time(t);
-                    }
-                    Tag::ExtXDateRange(t) => {
-                        has_partial_segment = true;
-                        segment.date_range(t);
-                    }
-                    Tag::ExtXTargetDuration(t) => {
-                        builder.target_duration(t.0);
-                    }
-                    Tag::ExtXMediaSequence(t) => {
-                        builder.media_sequence(t.0);
-                    }
-                    Tag::ExtXDiscontinuitySequence(t) => {
-                        // this tag must appear before the first MediaSegment in the playlist
-                        // https://tools.ietf.org/html/rfc8216#section-4.3.3.3
-                        if !segments.is_empty() {
-                            return Err(Error::custom("discontinuity sequence tag must appear before the first media segment in the playlist"));
-                        }
+        if let Some(value) = &mut self.skip {
+            visitor.visit_skip(value);
+        }
 
-                        // this tag must appear before any ExtXDiscontinuity tag
-                        // https://tools.ietf.org/html/rfc8216#section-4.3.3.3
-                        if has_discontinuity_tag {
-                            return Err(Error::custom("discontinuity sequence tag must appear before any `ExtXDiscontinuity` tag"));
-                        }
+        for segment in self.segments.values_mut() {
+            segment.visit_mut(visitor);
+        }
 
-                        builder.discontinuity_sequence(t.0);
-                    }
-                    Tag::ExtXEndList(_) => {
-                        builder.has_end_list(true);
-                    }
-                    Tag::PlaylistType(t) => {
-                        builder.playlist_type(t);
-                    }
-                    Tag::ExtXIFramesOnly(_) => {
-                        builder.has_i_frames_only(true);
-                    }
-                    Tag::ExtXMedia(_)
-                    | Tag::VariantStream(_)
-                    | Tag::ExtXSessionData(_)
-                    | Tag::ExtXSessionKey(_) => {
-                        return Err(Error::unexpected_tag(tag));
-                    }
-                    Tag::ExtXIndependentSegments(_) => {
-                        builder.has_independent_segments(true);
-                    }
-                    Tag::ExtXStart(t) => {
-                        builder.start(t);
-                    }
-                    Tag::ExtXVersion(_) => {}
-                    Tag::Unknown(s) => {
-                        // [6.3.1. General Client Responsibilities]
-                        // > ignore any unrecognized tags.
-                        unknown.push(Cow::Borrowed(s));
-                    }
-                }
-            }
-            Line::Uri(uri) => {
-                segment.uri(uri);
-                segment.keys(available_keys.iter().cloned().collect::<Vec<_>>());
-                segments.push(segment.build().map_err(Error::builder)?);
+        if let Some(segment) = &mut self.pending_segment {
+            segment.visit_mut(visitor);
+        }
 
-                segment = MediaSegment::builder();
-                has_partial_segment = false;
-            }
-            Line::Comment(_) => {}
+        for value in &mut self.preload_hints {
+            visitor.visit_preload_hint(value);
         }
+
+        for value in &mut self.rendition_reports {
+            visitor.visit_rendition_report(value);
+        }
+
+        for (_, value) in &mut self.positioned_tags {
+            visitor.visit_unknown(value);
+        }
+
+        for value in &mut self.unknown {
+            visitor.visit_unknown(value);
+        }
+
+        if self.has_end_list {
+            visitor.visit_end_list();
+        }
+    }
+
+    /// Merges segments, that might arrive out of order (for example in
+    /// peer-to-peer delivery), into the playlist.
+    ///
+    /// Segments with an explicit [`MediaSegment::number`] are inserted at the
+    /// position of their number and replace an existing segment with the same
+    /// number. Segments without an explicit number are appended after the
+    /// segment with the highest number. Segments with a number lower than the
+    /// [`MediaPlaylist::media_sequence`] are ignored, because they have
+    /// already been removed from the playlist.
+    ///
+    /// # Note
+    ///
+    /// The segments are sorted by their number, but the numbers might have
+    /// gaps, until the missing segments have been merged (for example, if the
+    /// segment 4 arrives before the segment 3). While there is a gap, the
+    /// segments after it are not at the index `number - media_sequence` and
+    /// [`MediaPlaylist::segment`] has to search for them.
+    ///
+    /// A [`Writer`] refuses to write the playlist, while there is a gap.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::MediaSegment;
+    /// use std::time::Duration;
+    ///
+    /// let segment = |number: usize| {
+    ///     MediaSegment::builder()
+    ///         .number(Some(number))
+    ///         .duration(Duration::from_secs(4))
+    ///         .uri(format!("{}.ts", number))
+    ///         .build()
+    ///         .unwrap()
+    /// };
+    ///
+    /// let mut playlist = MediaPlaylist::builder()
+    ///     .target_duration(Duration::from_secs(4))
+    ///     .segments(vec![segment(0), segment(1)])
+    ///     .build()?;
+    ///
+    /// playlist.merge_updates(vec![segment(4), segment(2), segment(3)]);
+    ///
+    /// assert_eq!(
+    ///     playlist
+    ///         .segments
+    ///         .values()
+    ///         .map(|s| s.number())
+    ///         .collect::<Vec<_>>(),
+    ///     vec![0, 1, 2, 3, 4]
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn merge_updates<I>(&mut self, segments: I)
+    where
+        I: IntoIterator<Item = MediaSegment<'a>>,
+    {
+        let mut next_number = self
+            .segments
+            .values()
+            .map(|segment| segment.number + 1)
+            .max()
+            .unwrap_or(self.media_sequence);
+
+        let mut merged = std::mem::take(&mut self.segments)
+            .into_iter()
+            .map(|(_, segment)| segment)
+            .collect::<Vec<_>>();
+
+        for mut segment in segments {
+            if !segment.explicit_number {
+                segment.number = next_number;
+            }
+
+            if segment.number < self.media_sequence {
+                continue;
+            }
+
+            next_number = next_number.max(segment.number + 1);
+
+            match merged.binary_search_by_key(&segment.number, |s| s.number) {
+                Ok(i) => merged[i] = segment,
+                Err(i) => merged.insert(i, segment),
+            }
+        }
+
+        update_map_changed(&mut merged);
+        self.segments = merged.into_iter().collect();
+    }
+
+    /// Checks, if `next` is a valid update of this playlist, that a client
+    /// might receive, when it reloads the playlist.
+    ///
+    /// A valid update
+    /// - has the same [`MediaPlaylist::target_duration`],
+    /// - does not decrease the [`MediaPlaylist::media_sequence`] and the
+    ///   [`MediaPlaylist::discontinuity_sequence`],
+    /// - only removes segments from the start of the playlist (and never from a
+    ///   playlist with the [`PlaylistType::Event`]) and does not change the uri
+    ///   or the duration of the remaining segments,
+    /// - does not change a playlist, that has ended.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`UpdateViolation`], that has been found.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::UpdateViolation;
+    /// use std::convert::TryFrom;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXT-X-MEDIA-SEQUENCE:1\n",
+    ///     "#EXTINF:10,\n",
+    ///     "1.ts\n",
+    ///     "#EXTINF:10,\n",
+    ///     "2.ts\n",
+    /// ))?;
+    ///
+    /// let next = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXT-X-MEDIA-SEQUENCE:2\n",
+    ///     "#EXTINF:10,\n",
+    ///     "2.ts\n",
+    ///     "#EXTINF:10,\n",
+    ///     "3.ts\n",
+    /// ))?;
+    ///
+    /// assert_eq!(playlist.compatible_update(&next), Ok(()));
+    /// assert_eq!(
+    ///     next.compatible_update(&playlist),
+    ///     Err(UpdateViolation::MediaSequenceDecreased {
+    ///         previous: 2,
+    ///         next: 1
+    ///     })
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    ///
+    /// [`PlaylistType::Event`]: crate::types::PlaylistType::Event
+    pub fn compatible_update(&self, next: &Self) -> Result<(), UpdateViolation> {
+        if self.has_end_list || self.playlist_type == Some(PlaylistType::Vod) {
+            if self != next {
+                return Err(UpdateViolation::ChangedAfterEnd);
+            }
+
+            return Ok(());
+        }
+
+        if self.target_duration != next.target_duration {
+            return Err(UpdateViolation::TargetDurationChanged {
+                previous: self.target_duration,
+                next: next.target_duration,
+            });
+        }
+
+        if next.media_sequence < self.media_sequence {
+            return Err(UpdateViolation::MediaSequenceDecreased {
+                previous: self.media_sequence,
+                next: next.media_sequence,
+            });
+        }
+
+        if next.discontinuity_sequence < self.discontinuity_sequence {
+            return Err(UpdateViolation::DiscontinuitySequenceDecreased {
+                previous: self.discontinuity_sequence,
+                next: next.discontinuity_sequence,
+            });
+        }
+
+        let is_event = self.playlist_type == Some(PlaylistType::Event);
+
+        for segment in self.segments.values() {
+            if segment.number < next.media_sequence && !is_event {
+                continue;
+            }
+
+            match next.segment(segment.number) {
+                Some(other) => {
+                    if other.uri() != segment.uri() || other.duration != segment.duration {
+                        return Err(UpdateViolation::SegmentChanged {
+                            number: segment.number,
+                        });
+                    }
+                }
+                None => {
+                    return Err(UpdateViolation::SegmentRemoved {
+                        number: segment.number,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reconstructs the full playlist from a playlist delta update, by
+    /// replacing the [`ExtXSkip`] tag of the `delta` with the skipped
+    /// [`MediaSegment`]s of this playlist.
+    ///
+    /// The `#EXT-X-DATERANGE` tags listed in
+    /// [`ExtXSkip::recently_removed_dateranges`] are removed from the skipped
+    /// segments. If the `delta` does not have an [`ExtXSkip`] tag, it is
+    /// already a full playlist and is returned unchanged.
+    ///
+    /// # Errors
+    ///
+    /// This function fails, if the [`MediaPlaylist::media_sequence`] of the
+    /// `delta` is smaller than the one of this playlist or if one of the
+    /// skipped segments is not part of this playlist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use std::convert::TryFrom;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:4\n",
+    ///     "#EXT-X-MEDIA-SEQUENCE:10\n",
+    ///     "#EXTINF:4,\n",
+    ///     "10.ts\n",
+    ///     "#EXTINF:4,\n",
+    ///     "11.ts\n",
+    ///     "#EXTINF:4,\n",
+    ///     "12.ts\n",
+    /// ))?;
+    ///
+    /// let delta = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:4\n",
+    ///     "#EXT-X-MEDIA-SEQUENCE:11\n",
+    ///     "#EXT-X-SKIP:SKIPPED-SEGMENTS=1\n",
+    ///     "#EXTINF:4,\n",
+    ///     "12.ts\n",
+    ///     "#EXTINF:4,\n",
+    ///     "13.ts\n",
+    /// ))?;
+    ///
+    /// let playlist = playlist.apply_delta(&delta)?;
+    ///
+    /// assert_eq!(playlist.skip, None);
+    /// assert_eq!(
+    ///     playlist
+    ///         .segments
+    ///         .values()
+    ///         .map(|s| s.uri().as_ref())
+    ///         .collect::<Vec<_>>(),
+    ///     vec!["11.ts", "12.ts", "13.ts"]
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    pub fn apply_delta(&self, delta: &Self) -> crate::Result<Self> {
+        let skip = {
+            if let Some(skip) = &delta.skip {
+                skip
+            } else {
+                return Ok(delta.clone());
+            }
+        };
+
+        if delta.media_sequence < self.media_sequence {
+            return Err(Error::custom(format!(
+                "the media sequence of the delta update ({}) is smaller than the previous one ({})",
+                delta.media_sequence, self.media_sequence
+            )));
+        }
+
+        let skipped = delta.media_sequence..delta.media_sequence + skip.skipped_segments();
+        let mut segments =
+            Vec::with_capacity(skip.skipped_segments() + delta.segments.num_elements());
+
+        for number in skipped {
+            let mut segment = self.segment(number).cloned().ok_or_else(|| {
+                Error::custom(format!(
+                    "the skipped segment {} is missing in the previous playlist",
+                    number
+                ))
+            })?;
+
+            if segment.date_range.as_ref().is_some_and(|date_range| {
+                skip.recently_removed_dateranges()
+                    .iter()
+                    .any(|id| id == date_range.id())
+            }) {
+                segment.date_range = None;
+            }
+
+            segments.push(segment);
+        }
+
+        segments.extend(delta.segments.values().cloned());
+        update_map_changed(&mut segments);
+
+        let mut result = delta.clone();
+        result.skip = None;
+        result.segments = segments.into_iter().collect();
+
+        Ok(result)
+    }
+
+    /// Makes a playlist delta update, by replacing the [`MediaSegment`]s at
+    /// the start of the playlist with an [`ExtXSkip`] tag.
+    ///
+    /// Only segments, that end at least `can_skip_until` before the end of
+    /// the playlist, are skipped. The skipped segments end before the first
+    /// segment with an `#EXT-X-DATERANGE` tag, so that all date ranges are
+    /// kept. The `#EXT-X-MAP` of the skipped segments is moved to the first
+    /// remaining segment and the keys are kept, because they are stored for
+    /// every segment. If no segment can be skipped, the playlist is returned
+    /// unchanged.
+    ///
+    /// [`MediaPlaylist::apply_delta`] reverses this operation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::tags::ExtXSkip;
+    /// use std::convert::TryFrom;
+    /// use std::time::Duration;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:4\n",
+    ///     "#EXTINF:4,\n",
+    ///     "0.ts\n",
+    ///     "#EXTINF:4,\n",
+    ///     "1.ts\n",
+    ///     "#EXTINF:4,\n",
+    ///     "2.ts\n",
+    /// ))?;
+    ///
+    /// let delta = playlist.to_delta(Duration::from_secs(8));
+    ///
+    /// assert_eq!(delta.skip, Some(ExtXSkip::new(1)));
+    /// assert_eq!(delta.segments.num_elements(), 2);
+    /// assert_eq!(playlist.apply_delta(&delta)?, playlist);
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub fn to_delta(&self, can_skip_until: Duration) -> Self {
+        let boundary = self.duration().saturating_sub(can_skip_until);
+
+        let mut end = Duration::from_secs(0);
+        let mut map = None;
+        let mut skipped_segments = 0;
+
+        for segment in self.segments.values() {
+            end += segment.duration.duration();
+
+            if end > boundary || segment.date_range.is_some() {
+                break;
+            }
+
+            if segment.map.is_some() {
+                map = segment.map.clone();
+            }
+
+            skipped_segments += 1;
+        }
+
+        if skipped_segments == 0 {
+            return self.clone();
+        }
+
+        let mut segments = self
+            .segments
+            .values()
+            .skip(skipped_segments)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if let Some(segment) = segments.first_mut() {
+            if segment.map.is_none() {
+                segment.map = map;
+            }
+        }
+
+        update_map_changed(&mut segments);
+
+        let mut result = self.clone();
+        result.skip = Some(ExtXSkip::new(skipped_segments));
+        result.segments = segments.into_iter().collect();
+
+        result
+    }
+
+    /// Splits the playlist at every `#EXT-X-DISCONTINUITY` tag into
+    /// independent playlists, that can be stored as separate VOD assets.
+    ///
+    /// Every playlist
+    /// - has the [`PlaylistType::Vod`] and an `#EXT-X-ENDLIST` tag,
+    /// - starts with the [`MediaSegment::number`] `0` and the discontinuity
+    ///   sequence `0`,
+    /// - has the smallest valid [`MediaPlaylist::target_duration`] and
+    /// - starts with the `#EXT-X-MAP`, that is in effect for its first segment.
+    ///
+    /// The keys of the segments are kept and the tags, that only make sense
+    /// for a live playlist (like [`MediaPlaylist::server_control`] and
+    /// [`MediaPlaylist::pending_segment`]), are removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use std::convert::TryFrom;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXTINF:10,\n",
+    ///     "program1/1.ts\n",
+    ///     "#EXT-X-DISCONTINUITY\n",
+    ///     "#EXTINF:6,\n",
+    ///     "program2/1.ts\n",
+    ///     "#EXTINF:6,\n",
+    ///     "program2/2.ts\n",
+    /// ))?;
+    ///
+    /// let playlists = playlist.split_at_discontinuities();
+    ///
+    /// assert_eq!(playlists.len(), 2);
+    /// assert_eq!(playlists[1].segments.num_elements(), 2);
+    /// assert_eq!(playlists[1].target_duration.as_secs(), 6);
+    /// assert!(playlists[1].has_end_list);
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub fn split_at_discontinuities(&self) -> Vec<Self> {
+        let mut runs: Vec<Vec<MediaSegment<'a>>> = vec![];
+        let mut run_tags: Vec<Vec<(usize, Cow<'a, str>)>> = vec![];
+        let mut map = None;
+
+        let mut positioned_tags = self.positioned_tags.iter().collect::<Vec<_>>();
+        positioned_tags.sort_by_key(|(position, _)| *position);
+        let mut positioned_tags = positioned_tags.into_iter().peekable();
+
+        for segment in self.segments.values() {
+            let mut segment = segment.clone();
+
+            if segment.map.is_some() {
+                map = segment.map.clone();
+            }
+
+            if segment.has_discontinuity || runs.is_empty() {
+                segment.has_discontinuity = false;
+
+                if segment.map.is_none() {
+                    segment.map = map.clone();
+                }
+
+                runs.push(vec![]);
+                run_tags.push(vec![]);
+            }
+
+            if let (Some(run), Some(tags)) = (runs.last_mut(), run_tags.last_mut()) {
+                while let Some((_, value)) =
+                    positioned_tags.next_if(|(position, _)| *position <= segment.number)
+                {
+                    tags.push((run.len(), value.clone()));
+                }
+
+                segment.number = run.len();
+                segment.explicit_number = false;
+                run.push(segment);
+            }
+        }
+
+        if let (Some(run), Some(tags)) = (runs.last(), run_tags.last_mut()) {
+            tags.extend(positioned_tags.map(|(_, value)| (run.len(), value.clone())));
+        }
+
+        runs.into_iter()
+            .zip(run_tags)
+            .map(|(mut segments, positioned_tags)| {
+                update_map_changed(&mut segments);
+
+                let mut playlist = self.clone();
+
+                playlist.media_sequence = 0;
+                playlist.discontinuity_sequence = 0;
+                playlist.playlist_type = Some(PlaylistType::Vod);
+                playlist.start = None;
+                playlist.server_control = None;
+                playlist.skip = None;
+                playlist.has_end_list = true;
+                playlist.segments = segments.into_iter().collect();
+                playlist.pending_segment = None;
+                playlist.preload_hints = Vec::new();
+                playlist.rendition_reports = Vec::new();
+                playlist.positioned_tags = positioned_tags;
+                playlist.target_duration = playlist.min_target_duration();
+
+                playlist
+            })
+            .collect()
+    }
+
+    /// Returns the smallest target duration, that is valid for all
+    /// [`MediaSegment`]s of this playlist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::MediaSegment;
+    /// use std::time::Duration;
+    ///
+    /// let playlist = MediaPlaylist::builder()
+    ///     .target_duration(Duration::from_secs(10))
+    ///     .segments(vec![MediaSegment::builder()
+    ///         .duration(Duration::from_secs_f64(5.5))
+    ///         .uri("1.ts")
+    ///         .build()?])
+    ///     .build()?;
+    ///
+    /// assert_eq!(playlist.min_target_duration(), Duration::from_secs(6));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn min_target_duration(&self) -> Duration {
+        self.segments
+            .values()
+            .map(|segment| rounded_duration(segment.duration.duration()))
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Changes the [`MediaPlaylist::target_duration`] and checks all
+    /// [`MediaSegment`]s against it.
+    ///
+    /// Every segment, that is longer than the new target duration (plus the
+    /// [`MediaPlaylist::allowable_excess_duration`]), is reported as an error.
+    /// The segments are not changed, because a segment can only be shortened
+    /// by re-encoding the media.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::MediaSegment;
+    /// use std::time::Duration;
+    ///
+    /// let mut playlist = MediaPlaylist::builder()
+    ///     .target_duration(Duration::from_secs(20))
+    ///     .segments(vec![MediaSegment::builder()
+    ///         .duration(Duration::from_secs(20))
+    ///         .uri("1.ts")
+    ///         .build()?])
+    ///     .build()?;
+    ///
+    /// assert!(playlist.retarget(Duration::from_secs(20)).is_ok());
+    /// assert!(!playlist.retarget(Duration::from_secs(10)).is_ok());
+    /// assert_eq!(playlist.target_duration, Duration::from_secs(10));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn retarget(&mut self, target_duration: Duration) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        let max_duration = target_duration + self.allowable_excess_duration;
+
+        self.target_duration = target_duration;
+
+        for segment in self.segments.values() {
+            let duration = segment.duration.duration();
+
+            if rounded_duration(duration) > max_duration {
+                report.push(
+                    Severity::Error,
+                    Some(segment.number),
+                    format!(
+                        "segment duration {:?} exceeds the target duration {:?}",
+                        duration, target_duration
+                    ),
+                );
+            }
+        }
+
+        report
+    }
+
+    /// Writes the [`MediaSegment`]s of this playlist as a compact,
+    /// newline-delimited log, which can be appended to as new segments become
+    /// available.
+    ///
+    /// Each segment is written as a single record of the form
+    /// `<duration>\t<uri>\t<flags>`, where `flags` is `D` for a segment
+    /// with [`MediaSegment::has_discontinuity`] or `-` if no flag is set.
+    ///
+    /// Backslashes, tabs and line breaks in the uri are escaped as `\\`,
+    /// `\t`, `\n` and `\r`, so that every segment stays on a single line.
+    ///
+    /// The playlist can be restored with [`MediaPlaylist::from_segment_log`].
+    ///
+    /// # Note
+    ///
+    /// Only the duration, the uri and the flags of a segment are written,
+    /// everything else (for example keys or byte ranges) is lost.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::MediaSegment;
+    /// use std::time::Duration;
+    ///
+    /// let playlist = MediaPlaylist::builder()
+    ///     .target_duration(Duration::from_secs(10))
+    ///     .segments(vec![
+    ///         MediaSegment::builder()
+    ///             .duration(Duration::from_secs_f64(9.009))
+    ///             .uri("first.ts")
+    ///             .build()?,
+    ///         MediaSegment::builder()
+    ///             .duration(Duration::from_secs(10))
+    ///             .has_discontinuity(true)
+    ///             .uri("second.ts")
+    ///             .build()?,
+    ///     ])
+    ///     .build()?;
+    ///
+    /// assert_eq!(
+    ///     playlist.to_segment_log(),
+    ///     "9.009\tfirst.ts\t-\n10\tsecond.ts\tD\n"
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn to_segment_log(&self) -> String {
+        let mut result = String::new();
+
+        for segment in self.segments.values() {
+            result.push_str(&segment.duration.duration().as_secs_f64().to_string());
+            result.push('\t');
+            escape_log_field(&mut result, segment.uri());
+            result.push('\t');
+
+            if segment.has_discontinuity {
+                result.push('D');
+            } else {
+                result.push('-');
+            }
+
+            result.push('\n');
+        }
+
+        result
+    }
+
+    /// Reconstructs a [`MediaPlaylist`] from a log, that has been created with
+    /// [`MediaPlaylist::to_segment_log`].
+    ///
+    /// The `header` provides all playlist-level fields (like the
+    /// [`MediaPlaylist::target_duration`]), the segments are taken from the
+    /// `log`. Empty lines in the log are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use std::time::Duration;
+    ///
+    /// let mut header = MediaPlaylist::builder();
+    /// header.target_duration(Duration::from_secs(10));
+    ///
+    /// let playlist =
+    ///     MediaPlaylist::from_segment_log(header, "9.009\tfirst.ts\t-\n10\tsecond.ts\tD\n")?;
+    ///
+    /// assert_eq!(playlist.segments.num_elements(), 2);
+    /// assert_eq!(playlist.segments[1].has_discontinuity, true);
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function fails, if a record is malformed or if the resulting
+    /// playlist is invalid.
+    pub fn from_segment_log(
+        mut header: MediaPlaylistBuilder<'a>,
+        log: &'a str,
+    ) -> crate::Result<Self> {
+        let mut segments = vec![];
+
+        for record in log.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let mut fields = record.split('\t');
+
+            let (duration, uri, flags) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(duration), Some(uri), Some(flags)) if fields.next().is_none() => {
+                    (duration, uri, flags)
+                }
+                _ => return Err(Error::custom(format!("invalid log record: {:?}", record))),
+            };
+
+            let mut has_discontinuity = false;
+
+            for flag in flags.chars() {
+                match flag {
+                    'D' => has_discontinuity = true,
+                    '-' => {}
+                    _ => {
+                        return Err(Error::custom(format!(
+                            "unknown flag {:?} in log record: {:?}",
+                            flag, record
+                        )));
+                    }
+                }
+            }
+
+            let duration = duration
+                .parse()
+                .map_err(|e| Error::parse_float(duration, e))
+                .and_then(|secs| {
+                    Duration::try_from_secs_f64(secs).map_err(|_| {
+                        Error::custom(format!(
+                            "invalid duration {:?} in log record: {:?}",
+                            duration, record
+                        ))
+                    })
+                })?;
+
+            let uri = unescape_log_field(uri).ok_or_else(|| {
+                Error::custom(format!("invalid escape in log record: {:?}", record))
+            })?;
+
+            segments.push(
+                MediaSegment::builder()
+                    .duration(duration)
+                    .has_discontinuity(has_discontinuity)
+                    .uri(uri)
+                    .build()
+                    .map_err(Error::builder)?,
+            );
+        }
+
+        header.segments(segments);
+        header.build().map_err(Error::builder)
+    }
+
+    /// Returns the first unknown tag with the given name. The leading `#` of
+    /// the name is optional.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use std::convert::TryFrom;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXT-X-VENDOR-ID:1\n",
+    ///     "#EXT-X-VENDOR-NAME:a\n",
+    /// ))?;
+    ///
+    /// assert_eq!(
+    ///     playlist.unknown_tag("#EXT-X-VENDOR-ID"),
+    ///     Some("#EXT-X-VENDOR-ID:1")
+    /// );
+    /// assert_eq!(playlist.unknown_tag("EXT-X-VENDOR"), None);
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub fn unknown_tag(&self, name: &str) -> Option<&str> {
+        let name = name.strip_prefix('#').unwrap_or(name);
+
+        self.unknown
+            .iter()
+            .map(AsRef::as_ref)
+            .find(|line| utils::tag_name(line) == name)
+    }
+
+    /// Returns all unknown tags, whose name starts with the given prefix. The
+    /// leading `#` of the prefix is optional.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use std::convert::TryFrom;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXT-X-VENDOR-ID:1\n",
+    ///     "#EXT-X-VENDOR-NAME:a\n",
+    /// ))?;
+    ///
+    /// assert_eq!(
+    ///     playlist
+    ///         .unknown_tags_with_prefix("EXT-X-VENDOR-")
+    ///         .collect::<Vec<_>>(),
+    ///     vec!["#EXT-X-VENDOR-ID:1", "#EXT-X-VENDOR-NAME:a"]
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    pub fn unknown_tags_with_prefix<'b>(
+        &'b self,
+        prefix: &'b str,
+    ) -> impl Iterator<Item = &'b str> + 'b {
+        utils::tags_with_prefix(&self.unknown, prefix)
+    }
+
+    /// Removes all unknown tags with the given name and returns them. The
+    /// leading `#` of the name is optional.
+    pub fn remove_unknown_tags(&mut self, name: &str) -> Vec<Cow<'a, str>> {
+        utils::remove_tags(&mut self.unknown, name)
+    }
+
+    /// Removes duplicate unknown tags. The first occurrence of a tag is kept.
+    pub fn dedup_unknown_tags(&mut self) -> &mut Self {
+        utils::dedup_tags(&mut self.unknown);
+        self
+    }
+
+    /// Makes the struct independent of its lifetime, by taking ownership of all
+    /// internal [`Cow`]s.
+    ///
+    /// # Note
+    ///
+    /// This is a relatively expensive operation.
+    #[must_use]
+    pub fn into_owned(self) -> MediaPlaylist<'static> {
+        MediaPlaylist {
+            target_duration: self.target_duration,
+            media_sequence: self.media_sequence,
+            discontinuity_sequence: self.discontinuity_sequence,
+            playlist_type: self.playlist_type,
+            allow_cache: self.allow_cache,
+            has_i_frames_only: self.has_i_frames_only,
+            has_independent_segments: self.has_independent_segments,
+            start: self.start,
+            server_control: self.server_control,
+            part_target: self.part_target,
+            skip: self.skip.map(|v| v.into_owned()),
+            has_end_list: self.has_end_list,
+            segments: {
+                self.segments
+                    .into_iter()
+                    .map(|(_, s)| s.into_owned())
+                    .collect()
+            },
+            pending_segment: self.pending_segment.map(|v| v.into_owned()),
+            preload_hints: self
+                .preload_hints
+                .into_iter()
+                .map(|v| v.into_owned())
+                .collect(),
+            rendition_reports: self
+                .rendition_reports
+                .into_iter()
+                .map(|v| v.into_owned())
+                .collect(),
+            duration_precision: self.duration_precision,
+            allowable_excess_duration: self.allowable_excess_duration,
+            content_kind: self.content_kind,
+            header_attributes: self
+                .header_attributes
+                .map(|value| Cow::Owned(value.into_owned())),
+            unknown: {
+                self.unknown
+                    .into_iter()
+                    .map(|v| Cow::Owned(v.into_owned()))
+                    .collect()
+            },
+            positioned_tags: self
+                .positioned_tags
+                .into_iter()
+                .map(|(position, v)| (position, Cow::Owned(v.into_owned())))
+                .collect(),
+        }
+    }
+}
+
+/// Two playlists are equal, if they contain the same tags and segments. The
+/// following fields only affect how a playlist is written or checked and are
+/// not compared:
+/// - [`MediaPlaylist::duration_precision`]
+/// - [`MediaPlaylist::header_attributes`]
+/// - [`MediaPlaylist::content_kind`]
+impl<'a> PartialEq for MediaPlaylist<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.target_duration == other.target_duration
+            && self.media_sequence == other.media_sequence
+            && self.discontinuity_sequence == other.discontinuity_sequence
+            && self.playlist_type == other.playlist_type
+            && self.allow_cache == other.allow_cache
+            && self.has_i_frames_only == other.has_i_frames_only
+            && self.has_independent_segments == other.has_independent_segments
+            && self.start == other.start
+            && self.server_control == other.server_control
+            && self.part_target == other.part_target
+            && self.skip == other.skip
+            && self.has_end_list == other.has_end_list
+            && self.segments == other.segments
+            && self.pending_segment == other.pending_segment
+            && self.preload_hints == other.preload_hints
+            && self.rendition_reports == other.rendition_reports
+            && self.allowable_excess_duration == other.allowable_excess_duration
+            && self.unknown == other.unknown
+            && self.positioned_tags == other.positioned_tags
+    }
+}
+
+impl<'a> Eq for MediaPlaylist<'a> {}
+
+impl<'a> RequiredVersion for MediaPlaylist<'a> {
+    fn required_version(&self) -> ProtocolVersion {
+        required_version![
+            ExtXTargetDuration(self.target_duration),
+            (self.media_sequence != 0).athen(|| ExtXMediaSequence(self.media_sequence)),
+            (self.discontinuity_sequence != 0)
+                .athen(|| ExtXDiscontinuitySequence(self.discontinuity_sequence)),
+            self.playlist_type,
+            self.has_i_frames_only.athen_some(ExtXIFramesOnly),
+            self.has_independent_segments
+                .athen_some(ExtXIndependentSegments),
+            self.start,
+            self.server_control,
+            self.part_target.map(ExtXPartInf),
+            self.skip,
+            self.has_end_list.athen_some(ExtXEndList),
+            self.segments,
+            self.pending_segment,
+            self.preload_hints,
+            self.rendition_reports,
+            (!self.segments.is_empty()).athen_some(self.duration_precision)
+        ]
+    }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        let mut result: Vec<_> = required_version_reasons![
+            self.has_i_frames_only.athen_some(ExtXIFramesOnly),
+            self.segments,
+            self.pending_segment,
+            (!self.segments.is_empty()).athen_some(self.duration_precision)
+        ];
+
+        result.sort();
+        result.dedup();
+        result
+    }
+}
+
+impl<'a> MediaPlaylist<'a> {
+    /// Returns an error, if the numbers of the segments have a gap (see
+    /// [`MediaPlaylist::merge_updates`]), because the segments after the gap
+    /// would be numbered differently, when the written playlist is parsed
+    /// again.
+    pub(crate) fn check_numbering(&self) -> crate::Result<()> {
+        let mut numbers = self.segments.values().map(|segment| segment.number);
+
+        if let Some(mut previous) = numbers.next() {
+            for number in numbers {
+                if number != previous + 1 {
+                    return Err(Error::custom(format!(
+                        "missing the segment {} (the segments are not numbered consecutively)",
+                        previous + 1
+                    )));
+                }
+
+                previous = number;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn write_to<W: fmt::Write>(&self, f: &mut W, writer: &Writer) -> fmt::Result {
+        if let Some(value) = &self.header_attributes {
+            writeln!(f, "{} {}", ExtM3u, value)?;
+        } else {
+            writeln!(f, "{}", ExtM3u)?;
+        }
+
+        let precision = writer
+            .duration_precision()
+            .unwrap_or(self.duration_precision);
+        let mut required = self.required_version();
+
+        if !self.segments.is_empty() {
+            required = required.max(precision.required_version());
+        }
+
+        let version = writer.version(required);
+
+        if version != ProtocolVersion::V1 {
+            writeln!(f, "{}", ExtXVersion::new(version))?;
+        }
+
+        writeln!(f, "{}", ExtXTargetDuration(self.target_duration))?;
+
+        if self.media_sequence != 0 {
+            writeln!(f, "{}", ExtXMediaSequence(self.media_sequence))?;
+        }
+
+        if self.discontinuity_sequence != 0 {
+            writeln!(
+                f,
+                "{}",
+                ExtXDiscontinuitySequence(self.discontinuity_sequence)
+            )?;
+        }
+
+        if let Some(value) = &self.playlist_type {
+            writeln!(f, "{}", value)?;
+        }
+
+        // the tag has been removed in version 7:
+        if let Some(value) = self.allow_cache {
+            if version < ProtocolVersion::V7 {
+                writeln!(f, "{}", ExtXAllowCache(value))?;
+            }
+        }
+
+        if self.has_i_frames_only {
+            writeln!(f, "{}", ExtXIFramesOnly)?;
+        }
+
+        if self.has_independent_segments {
+            writeln!(f, "{}", ExtXIndependentSegments)?;
+        }
+
+        if let Some(value) = &self.start {
+            writeln!(f, "{}", value)?;
+        }
+
+        if let Some(value) = &self.server_control {
+            writeln!(f, "{}", value)?;
+        }
+
+        if let Some(value) = self.part_target {
+            writeln!(f, "{}", ExtXPartInf(value))?;
+        }
+
+        if let Some(value) = &self.skip {
+            writeln!(f, "{}", value)?;
+        }
+
+        let mut available_keys = HashSet::<ExtXKey<'_>>::new();
+        let mut bitrate = None;
+
+        let mut positioned_tags = self.positioned_tags.iter().collect::<Vec<_>>();
+        positioned_tags.sort_by_key(|(position, _)| *position);
+        let mut positioned_tags = positioned_tags.into_iter().peekable();
+
+        for segment in self.segments.values() {
+            while let Some((_, value)) =
+                positioned_tags.next_if(|(position, _)| *position <= segment.number)
+            {
+                writeln!(f, "{}", value)?;
+            }
+
+            write_keys(f, writer, &mut available_keys, &segment.keys)?;
+
+            if let Some(value) = segment.bitrate.filter(|value| bitrate != Some(*value)) {
+                writeln!(f, "{}", ExtXBitrate(value))?;
+                bitrate = Some(value);
+            }
+
+            segment.write_to(f, writer, precision)?;
+        }
+
+        for (_, value) in positioned_tags {
+            writeln!(f, "{}", value)?;
+        }
+
+        if let Some(segment) = &self.pending_segment {
+            write_keys(f, writer, &mut available_keys, &segment.keys)?;
+            segment.write_to(f, writer)?;
+        }
+
+        for value in &self.preload_hints {
+            writeln!(f, "{}", value)?;
+        }
+
+        for value in &self.rendition_reports {
+            writeln!(f, "{}", value)?;
+        }
+
+        for value in &self.unknown {
+            writeln!(f, "{}", value)?;
+        }
+
+        if self.has_end_list {
+            writeln!(f, "{}", ExtXEndList)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the key, as it should be written.
+///
+/// An iv from a segment number is set to missing. This does reduce the output
+/// size and the correct iv is automatically set, when parsing.
+fn prepare_key<'a>(key: &ExtXKey<'a>, writer: &Writer) -> ExtXKey<'a> {
+    let mut key = key.clone();
+
+    if let ExtXKey(Some(decryption_key)) = &mut key {
+        if let InitializationVector::Number(_) = decryption_key.iv {
+            decryption_key.iv = InitializationVector::Missing;
+        }
+
+        let uri = writer.uri(UriKind::Key, decryption_key.uri());
+
+        if uri != decryption_key.uri().as_ref() {
+            decryption_key.set_uri(uri.into_owned());
+        }
+    }
+
+    key
+}
+
+/// Writes the keys of a segment according to the [`Writer::key_policy`].
+fn write_keys<'a, W: fmt::Write>(
+    f: &mut W,
+    writer: &Writer,
+    available_keys: &mut HashSet<ExtXKey<'a>>,
+    keys: &[ExtXKey<'a>],
+) -> fmt::Result {
+    match writer.key_policy() {
+        KeyWritePolicy::Changed => write_changed_keys(f, writer, available_keys, keys, false)?,
+        KeyWritePolicy::Dedup => write_changed_keys(f, writer, available_keys, keys, true)?,
+        KeyWritePolicy::PerSegment => {
+            if keys.iter().any(ExtXKey::is_some) {
+                available_keys.clear();
+
+                for key in keys.iter().filter(|k| k.is_some()) {
+                    writeln!(f, "{}", prepare_key(key, writer))?;
+                }
+            } else if keys.iter().any(ExtXKey::is_none)
+                && !available_keys.contains(&ExtXKey::empty())
+            {
+                available_keys.insert(ExtXKey::empty());
+                writeln!(f, "{}", ExtXKey::empty())?;
+            }
+        }
+        KeyWritePolicy::Always => {
+            for key in keys {
+                writeln!(f, "{}", prepare_key(key, writer))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes only those keys, that are not already in effect (see
+/// [`KeyWritePolicy::Changed`]). Repeated empty keys are skipped, if
+/// `dedup_empty` is `true` (see [`KeyWritePolicy::Dedup`]).
+fn write_changed_keys<'a, W: fmt::Write>(
+    f: &mut W,
+    writer: &Writer,
+    available_keys: &mut HashSet<ExtXKey<'a>>,
+    keys: &[ExtXKey<'a>],
+    dedup_empty: bool,
+) -> fmt::Result {
+    for key in keys {
+        if let ExtXKey(Some(decryption_key)) = key {
+            // next segment will be encrypted, so the segment can not have an empty key
+            available_keys.remove(&ExtXKey::empty());
+
+            let key = prepare_key(key, writer);
+            // keys, that only differ in their default attributes are the same:
+            let normalized = ExtXKey(key.as_ref().map(DecryptionKey::normalized));
+
+            // only do something if a key has been overwritten
+            if available_keys.insert(normalized.clone()) {
+                let mut remove_key = None;
+
+                // an old key might be removed:
+                for k in available_keys.iter() {
+                    if let ExtXKey(Some(dk)) = k {
+                        if dk.key_format() == decryption_key.key_format() && normalized != *k {
+                            remove_key = Some(k.clone());
+                            break;
+                        }
+                    } else {
+                        unreachable!("empty keys should not exist in `available_keys`");
+                    }
+                }
+
+                if let Some(k) = remove_key {
+                    // this should always be true:
+                    let res = available_keys.remove(&k);
+                    debug_assert!(res);
+                }
+
+                writeln!(f, "{}", key)?;
+            }
+        } else if !dedup_empty || !available_keys.contains(&ExtXKey::empty()) {
+            // the next segment is not encrypted, so remove all available keys
+            available_keys.clear();
+            available_keys.insert(ExtXKey::empty());
+            writeln!(f, "{}", key)?;
+        }
+    }
+
+    Ok(())
+}
+
+impl<'a> fmt::Display for MediaPlaylist<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_to(f, &Writer::default())
+    }
+}
+
+/// Assigns [`MediaSegment::map_changed`] to the segments, which have to be in
+/// playback order.
+fn update_map_changed<'a: 'b, 'b, I>(segments: I)
+where
+    I: IntoIterator<Item = &'b mut MediaSegment<'a>>,
+{
+    // the offset of a byte range without one is the start of the resource:
+    let section = |map: &ExtXMap<'a>| {
+        let range = map.range().map(|mut range| {
+            if range.start().is_none() {
+                range.set_start(Some(0));
+            }
+
+            range
+        });
+
+        (map.uri().clone(), range)
+    };
+
+    let mut current = None;
+
+    for segment in segments {
+        segment.map_changed = false;
+
+        if let Some(map) = &segment.map {
+            let next = section(map);
+            segment.map_changed = current.as_ref() != Some(&next);
+            current = Some(next);
+        }
+    }
+}
+
+/// Rounds the duration to full seconds (a duration of .5s is rounded up), like
+/// it is done for the `#EXT-X-TARGETDURATION` check.
+pub(crate) fn rounded_duration(duration: Duration) -> Duration {
+    Duration::from_secs(duration.as_secs_f64().round() as u64)
+}
+
+pub(crate) fn parse_media_playlist<'a>(
+    input: &'a str,
+    builder: &mut MediaPlaylistBuilder<'a>,
+    options: &ParseOptions,
+    report: &mut ValidationReport,
+) -> crate::Result<MediaPlaylist<'a>> {
+    let first_line = utils::leading_lines(input);
+    let (header_attributes, input) = ExtM3u::parse_header(input)?;
+
+    if let Some(value) = header_attributes {
+        options.violation(
+            report,
+            Error::custom(format!("unexpected parameters after #EXTM3U: {:?}", value)),
+        )?;
+        builder.header_attributes(value);
+    }
+
+    let mut segment = MediaSegment::builder();
+    let mut segments = vec![];
+
+    let mut has_partial_segment = false;
+    let mut has_discontinuity_tag = false;
+    let mut bitrate = None;
+    let mut unknown = vec![];
+    let mut cue_markers = vec![];
+    let mut preload_hints = vec![];
+    let mut rendition_reports = vec![];
+    let mut available_keys = HashSet::new();
+    let mut seen = HashSet::new();
+    // the first line of the segment, that has not been completed by an uri:
+    let mut segment_line = None;
+    let mut duration_line = None;
+    let mut byte_range_line = None;
+    let mut has_end_list = false;
+
+    let mut lines = Lines::from(input);
+
+    while let Some(line) = lines.next() {
+        let number = first_line + lines.line();
+
+        match line? {
+            Line::Tag(tag) => {
+                if !matches!(tag, Tag::Unknown(_)) {
+                    options.report_ignored_attributes(number, lines.current());
+                }
+
+                if !segments.is_empty()
+                    && !options.allow_late_playlist_tags()
+                    && matches!(
+                        tag,
+                        Tag::ExtXTargetDuration(_)
+                            | Tag::ExtXMediaSequence(_)
+                            | Tag::PlaylistType(_)
+                            | Tag::ExtXIFramesOnly(_)
+                            | Tag::ExtXIndependentSegments(_)
+                            | Tag::ExtXStart(_)
+                            | Tag::ExtXServerControl(_)
+                            | Tag::ExtXPartInf(_)
+                            | Tag::ExtXVersion(_)
+                    )
+                {
+                    options.violation(
+                        report,
+                        tag.misplaced(number, "must appear before the first media segment"),
+                    )?;
+                }
+
+                if matches!(
+                    tag,
+                    Tag::ExtInf(_)
+                        | Tag::ExtXByteRange(_)
+                        | Tag::ExtXDiscontinuity(_)
+                        | Tag::ExtXGap(_)
+                        | Tag::ExtXKey(_)
+                        | Tag::ExtXMap(_)
+                        | Tag::ExtXProgramDateTime(_)
+                        | Tag::ExtXDateRange(_)
+                        | Tag::ExtXPart(_)
+                ) {
+                    segment_line.get_or_insert((number, lines.line()));
+                }
+
+                match tag {
+                    Tag::ExtInf(t) => {
+                        // the previous `#EXTINF` tag has been overwritten
+                        if let Some(line) = duration_line.replace(number) {
+                            options.violation(
+                                report,
+                                Error::misplaced_tag(
+                                    "EXTINF",
+                                    line,
+                                    "must be followed by a media segment",
+                                ),
+                            )?;
+                        }
+
+                        has_partial_segment = true;
+                        segment.duration(t);
+                    }
+                    Tag::ExtXByteRange(t) => {
+                        if let Some(line) = byte_range_line.replace(number) {
+                            options.violation(
+                                report,
+                                Error::misplaced_tag(
+                                    "EXT-X-BYTERANGE",
+                                    line,
+                                    "must be followed by a media segment",
+                                ),
+                            )?;
+                        }
+
+                        has_partial_segment = true;
+                        segment.byte_range(t);
+                    }
+                    Tag::ExtXDiscontinuity(_) => {
+                        has_discontinuity_tag = true;
+                        has_partial_segment = true;
+                        segment.has_discontinuity(true);
+                    }
+                    Tag::ExtXGap(_) => {
+                        has_partial_segment = true;
+                        segment.has_gap(true);
+                    }
+                    Tag::ExtXBitrate(t) => {
+                        // applies to every segment until the next `#EXT-X-BITRATE` tag
+                        bitrate = Some(t.0);
+                    }
+                    Tag::ExtXKey(key) => {
+                        has_partial_segment = true;
+
+                        // An ExtXKey applies to every MediaSegment and to every Media
+                        // Initialization Section declared by an ExtXMap tag, that appears
+                        // between it and the next ExtXKey tag in the Playlist file with the
+                        // same KEYFORMAT attribute (or the end of the Playlist file).
+
+                        let mut is_new_key = true;
+                        let mut remove = None;
+
+                        if let ExtXKey(Some(decryption_key)) = &key {
+                            for old_key in &available_keys {
+                                if let ExtXKey(Some(old_decryption_key)) = &old_key {
+                                    if old_decryption_key.key_format()
+                                        == decryption_key.key_format()
+                                    {
+                                        // remove the old key
+                                        remove = Some(old_key.clone());
+
+                                        // there are no keys with the same format in
+                                        // available_keys so the loop can stop here:
+                                        break;
+                                    }
+                                } else {
+                                    // remove an empty key
+                                    remove = Some(ExtXKey::empty());
+                                    break;
+                                }
+                            }
+                        } else {
+                            available_keys.clear();
+                            available_keys.insert(ExtXKey::empty());
+                            is_new_key = false;
+                        }
+
+                        if let Some(key) = &remove {
+                            available_keys.remove(key);
+                        }
+
+                        if is_new_key {
+                            available_keys.insert(key);
+                        }
+                    }
+                    Tag::ExtXMap(mut t) => {
+                        has_partial_segment = true;
+
+                        t.keys = available_keys.iter().cloned().collect();
+                        segment.map(t);
+                    }
+                    Tag::ExtXProgramDateTime(t) => {
+                        has_partial_segment = true;
+                        segment.program_date_time(t);
+                    }
+                    Tag::ExtXDateRange(t) => {
+                        has_partial_segment = true;
+                        segment.date_range(t);
+                    }
+                    Tag::ExtXPart(t) => {
+                        has_partial_segment = true;
+                        segment.push_part(t);
+                    }
+                    Tag::ExtXTargetDuration(t) => {
+                        options.check_once(report, &mut seen, "EXT-X-TARGETDURATION")?;
+                        builder.target_duration(t.0);
+                    }
+                    Tag::ExtXMediaSequence(t) => {
+                        options.check_once(report, &mut seen, "EXT-X-MEDIA-SEQUENCE")?;
+                        builder.media_sequence(t.0);
+                    }
+                    Tag::ExtXDiscontinuitySequence(t) => {
+                        // this tag must appear before the first MediaSegment in the playlist
+                        // https://tools.ietf.org/html/rfc8216#section-4.3.3.3
+                        if !segments.is_empty() {
+                            return Err(Error::misplaced_tag(
+                                "EXT-X-DISCONTINUITY-SEQUENCE",
+                                number,
+                                "must appear before the first media segment",
+                            ));
+                        }
+
+                        // this tag must appear before any ExtXDiscontinuity tag
+                        // https://tools.ietf.org/html/rfc8216#section-4.3.3.3
+                        if has_discontinuity_tag {
+                            return Err(Error::misplaced_tag(
+                                "EXT-X-DISCONTINUITY-SEQUENCE",
+                                number,
+                                "must appear before any EXT-X-DISCONTINUITY tag",
+                            ));
+                        }
+
+                        options.check_once(report, &mut seen, "EXT-X-DISCONTINUITY-SEQUENCE")?;
+                        builder.discontinuity_sequence(t.0);
+                    }
+                    Tag::ExtXEndList(_) => {
+                        options.check_once(report, &mut seen, "EXT-X-ENDLIST")?;
+                        has_end_list = true;
+                        builder.has_end_list(true);
+                    }
+                    Tag::PlaylistType(t) => {
+                        options.check_once(report, &mut seen, "EXT-X-PLAYLIST-TYPE")?;
+                        builder.playlist_type(t);
+                    }
+                    Tag::ExtXAllowCache(t) => {
+                        options.check_once(report, &mut seen, "EXT-X-ALLOW-CACHE")?;
+                        builder.allow_cache(t.0);
+                    }
+                    Tag::ExtXIFramesOnly(_) => {
+                        options.check_once(report, &mut seen, "EXT-X-I-FRAMES-ONLY")?;
+                        builder.has_i_frames_only(true);
+                    }
+                    Tag::ExtXMedia(_)
+                    | Tag::VariantStream(_)
+                    | Tag::ExtXSessionData(_)
+                    | Tag::ExtXSessionKey(_)
+                    | Tag::ExtXContentSteering(_) => {
+                        return Err(tag.misplaced(number, crate::error::NOT_IN_MEDIA_PLAYLIST));
+                    }
+                    Tag::ExtXIndependentSegments(_) => {
+                        options.check_once(report, &mut seen, "EXT-X-INDEPENDENT-SEGMENTS")?;
+                        builder.has_independent_segments(true);
+                    }
+                    Tag::ExtXStart(t) => {
+                        options.check_once(report, &mut seen, "EXT-X-START")?;
+                        builder.start(t);
+                    }
+                    Tag::ExtXServerControl(t) => {
+                        options.check_once(report, &mut seen, "EXT-X-SERVER-CONTROL")?;
+                        builder.server_control(t);
+                    }
+                    Tag::ExtXPartInf(t) => {
+                        options.check_once(report, &mut seen, "EXT-X-PART-INF")?;
+                        builder.part_target(t.0);
+                    }
+                    Tag::ExtXSkip(t) => {
+                        // the skipped segments are the first segments of the playlist
+                        if !segments.is_empty() || has_partial_segment {
+                            return Err(Error::misplaced_tag(
+                                "EXT-X-SKIP",
+                                number,
+                                "must appear before the first media segment",
+                            ));
+                        }
+
+                        options.check_once(report, &mut seen, "EXT-X-SKIP")?;
+                        builder.skip(t);
+                    }
+                    Tag::ExtXPreloadHint(t) => {
+                        preload_hints.push(t);
+                    }
+                    Tag::ExtXRenditionReport(t) => {
+                        rendition_reports.push(t);
+                    }
+                    Tag::ExtXVersion(_) => {
+                        options.check_once(report, &mut seen, "EXT-X-VERSION")?;
+                    }
+                    Tag::Unknown(s) => {
+                        match CueMarker::try_from(s) {
+                            Ok(marker) if options.parse_cue_markers() => cue_markers.push(marker),
+                            // [6.3.1. General Client Responsibilities]
+                            // > ignore any unrecognized tags.
+                            _ => {
+                                options.report_ignored_tag(number, s);
+                                unknown.push(Cow::Borrowed(s));
+                            }
+                        }
+                    }
+                }
+            }
+            Line::Uri(uri) => {
+                segment.uri(uri);
+                segment.cue_markers(std::mem::take(&mut cue_markers));
+                segment.keys(available_keys.iter().cloned().collect::<Vec<_>>());
+
+                if let Some(value) = bitrate {
+                    segment.bitrate(value);
+                }
+                segments.push(segment.build().map_err(Error::builder)?);
+
+                segment = MediaSegment::builder();
+                has_partial_segment = false;
+                segment_line = None;
+                duration_line = None;
+                byte_range_line = None;
+            }
+            // comments between the `#EXTINF` tag and the uri belong to the segment:
+            Line::Comment(comment) if duration_line.is_some() => {
+                segment.push_comment(comment);
+            }
+            Line::Comment(_) => {}
+        }
+    }
+
+    // a playlist, that has ended, can not be continued by a pending segment:
+    if let Some((number, line)) = segment_line.filter(|_| has_end_list) {
+        let name = input
+            .lines()
+            .nth(line - 1)
+            .map_or("", |raw| utils::tag_name(raw.trim()));
+
+        options.violation(
+            report,
+            Error::misplaced_tag(name, number, "must be followed by a media segment"),
+        )?;
+    }
+
+    if has_partial_segment {
+        // the `#EXTINF` tag of a segment at the live edge might not be published yet
+        let keys = available_keys.into_iter().collect();
+        let pending = segment
+            .to_pending(keys)
+            .ok_or_else(|| Error::custom("Missing URI for the last `MediaSegment`"))?;
+
+        builder.pending_segment(pending);
+    }
+
+    // markers after the last segment can not be assigned to a segment:
+    unknown.extend(
+        cue_markers
+            .into_iter()
+            .map(|marker| Cow::Owned(marker.to_string())),
+    );
+
+    if builder.duration_precision.is_none() {
+        builder.duration_precision(DurationPrecision::infer(input));
+    }
+
+    builder.preload_hints(preload_hints);
+    builder.rendition_reports(rendition_reports);
+    builder.unknown(unknown);
+    builder.segments(segments);
+    builder.build().map_err(Error::builder)
+}
+
+impl FromStr for MediaPlaylist<'static> {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Ok(ParseOptions::new()
+            .parse_media_playlist(input)?
+            .0
+            .into_owned())
+    }
+}
+
+impl<'a> TryFrom<&'a str> for MediaPlaylist<'a> {
+    type Error = Error;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        Ok(ParseOptions::new().parse_media_playlist(input)?.0)
+    }
+}
+
+/// Writes `value` into a segment log, escaping the characters that would
+/// otherwise end the field or the record.
+fn escape_log_field(result: &mut String, value: &str) {
+    for c in value.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '\t' => result.push_str("\\t"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            _ => result.push(c),
+        }
+    }
+}
+
+/// Reverses [`escape_log_field`]. Returns `None` for an unknown or an
+/// incomplete escape sequence.
+fn unescape_log_field(value: &str) -> Option<Cow<'_, str>> {
+    if !value.contains('\\') {
+        return Some(Cow::Borrowed(value));
+    }
+
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        result.push(match chars.next()? {
+            '\\' => '\\',
+            't' => '\t',
+            'n' => '\n',
+            'r' => '\r',
+            _ => return None,
+        });
+    }
+
+    Some(Cow::Owned(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PreloadHintType;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn too_large_segment_duration_test() {
+        let playlist = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:8\n",
+            "#EXT-X-VERSION:3\n",
+            "#EXTINF:9.009,\n",
+            "http://media.example.com/first.ts\n",
+            "#EXTINF:9.509,\n",
+            "http://media.example.com/second.ts\n",
+            "#EXTINF:3.003,\n",
+            "http://media.example.com/third.ts\n",
+            "#EXT-X-ENDLIST\n"
+        );
+
+        // Error (allowable segment duration = target duration = 8)
+        assert!(MediaPlaylist::try_from(playlist).is_err());
+
+        // Error (allowable segment duration = 9)
+        assert!(MediaPlaylist::builder()
+            .allowable_excess_duration(Duration::from_secs(1))
+            .parse(playlist)
+            .is_err());
+
+        // Ok (allowable segment duration = 10)
+        assert_eq!(
+            MediaPlaylist::builder()
+                .allowable_excess_duration(Duration::from_secs(2))
+                .parse(playlist)
+                .unwrap(),
+            MediaPlaylist::builder()
+                .allowable_excess_duration(Duration::from_secs(2))
+                .target_duration(Duration::from_secs(8))
+                .segments(vec![
+                    MediaSegment::builder()
+                        .duration(Duration::from_secs_f64(9.009))
+                        .uri("http://media.example.com/first.ts")
+                        .build()
+                        .unwrap(),
+                    MediaSegment::builder()
+                        .duration(Duration::from_secs_f64(9.509))
+                        .uri("http://media.example.com/second.ts")
+                        .build()
+                        .unwrap(),
+                    MediaSegment::builder()
+                        .duration(Duration::from_secs_f64(3.003))
+                        .uri("http://media.example.com/third.ts")
+                        .build()
+                        .unwrap(),
+                ])
+                .has_end_list(true)
+                .build()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_segment_number_simple() {
+        let playlist = MediaPlaylist::builder()
+            .allowable_excess_duration(Duration::from_secs(2))
+            .target_duration(Duration::from_secs(8))
+            .segments(vec![
+                MediaSegment::builder()
+                    .duration(Duration::from_secs_f64(9.009))
+                    .uri("http://media.example.com/first.ts")
+                    .build()
+                    .unwrap(),
+                MediaSegment::builder()
+                    .duration(Duration::from_secs_f64(9.509))
+                    .uri("http://media.example.com/second.ts")
+                    .build()
+                    .unwrap(),
+                MediaSegment::builder()
+                    .duration(Duration::from_secs_f64(3.003))
+                    .uri("http://media.example.com/third.ts")
+                    .build()
+                    .unwrap(),
+            ])
+            .build()
+            .unwrap();
+
+        let mut segments = playlist.segments.into_iter().map(|(k, v)| (k, v.number));
+        assert_eq!(segments.next(), Some((0, 0)));
+        assert_eq!(segments.next(), Some((1, 1)));
+        assert_eq!(segments.next(), Some((2, 2)));
+        assert_eq!(segments.next(), None);
+    }
+
+    #[test]
+    fn test_segment_number_sequence() {
+        let playlist = MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(8))
+            .media_sequence(2680)
+            .segments(vec![
+                MediaSegment::builder()
+                    .duration(Duration::from_secs_f64(7.975))
+                    .uri("https://priv.example.com/fileSequence2680.ts")
+                    .build()
+                    .unwrap(),
+                MediaSegment::builder()
+                    .duration(Duration::from_secs_f64(7.941))
+                    .uri("https://priv.example.com/fileSequence2681.ts")
+                    .build()
+                    .unwrap(),
+                MediaSegment::builder()
+                    .duration(Duration::from_secs_f64(7.975))
+                    .uri("https://priv.example.com/fileSequence2682.ts")
+                    .build()
+                    .unwrap(),
+            ])
+            .build()
+            .unwrap();
+        let mut segments = playlist.segments.into_iter().map(|(k, v)| (k, v.number));
+        assert_eq!(segments.next(), Some((0, 2680)));
+        assert_eq!(segments.next(), Some((1, 2681)));
+        assert_eq!(segments.next(), Some((2, 2682)));
+        assert_eq!(segments.next(), None);
+    }
+
+    #[test]
+    fn test_retarget() {
+        let mut playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:25\n",
+            "#EXT-X-MEDIA-SEQUENCE:3\n",
+            "#EXTINF:8,\n",
+            "1.ts\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:25,\n",
+            "2.ts\n",
+            "#EXT-X-BYTERANGE:100@0\n",
+            "#EXTINF:12,\n",
+            "3.ts\n",
+        ))
+        .unwrap();
+
+        assert_eq!(playlist.min_target_duration(), Duration::from_secs(25));
+
+        let report = playlist.retarget(Duration::from_secs(10));
+        assert_eq!(
+            report
+                .errors()
+                .map(|diagnostic| diagnostic.segment())
+                .collect::<Vec<_>>(),
+            vec![Some(4), Some(5)]
+        );
+
+        // the segments are not changed:
+        assert_eq!(playlist.target_duration, Duration::from_secs(10));
+        assert_eq!(
+            playlist
+                .segments
+                .values()
+                .map(|s| (s.number(), s.uri().as_ref(), s.duration.duration()))
+                .collect::<Vec<_>>(),
+            vec![
+                (3, "1.ts", Duration::from_secs(8)),
+                (4, "2.ts", Duration::from_secs(25)),
+                (5, "3.ts", Duration::from_secs(12)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_segments_from_iterator() {
+        let segment = |number| {
+            MediaSegment::builder()
+                .duration(Duration::from_secs(10))
+                .uri(format!("{}.ts", number))
+                .number(Some(number))
+                .build()
+                .unwrap()
+        };
+
+        // the size hint of a filter is 0, so the capacity has to grow:
+        let playlist = MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(10))
+            .segments((0..4).map(segment).filter(|_| true))
+            .unknown(Some(Cow::Borrowed("#EXT-X-UNKNOWN")))
+            .build()
+            .unwrap();
+
+        assert_eq!(playlist.segments.num_elements(), 4);
+        assert_eq!(playlist.segments[3].uri(), "3.ts");
+        assert_eq!(playlist.unknown, vec![Cow::Borrowed("#EXT-X-UNKNOWN")]);
+    }
+
+    #[test]
+    fn test_segment_log() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-MEDIA-SEQUENCE:5\n",
+            "#EXTINF:9.009,\n",
+            "http://media.example.com/first.ts\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:3.003,\n",
+            "http://media.example.com/second.ts\n",
+        ))
+        .unwrap();
+
+        let log = playlist.to_segment_log();
+
+        assert_eq!(
+            log,
+            concat!(
+                "9.009\thttp://media.example.com/first.ts\t-\n",
+                "3.003\thttp://media.example.com/second.ts\tD\n",
+            )
+        );
+
+        let mut header = MediaPlaylist::builder();
+        header
+            .target_duration(Duration::from_secs(10))
+            .media_sequence(5);
+
+        assert_eq!(
+            MediaPlaylist::from_segment_log(header.clone(), &log).unwrap(),
+            playlist
+        );
+
+        assert!(MediaPlaylist::from_segment_log(header.clone(), "9.009\tfirst.ts").is_err());
+        assert!(MediaPlaylist::from_segment_log(header.clone(), "9.009\tfirst.ts\tX").is_err());
+        assert!(MediaPlaylist::from_segment_log(header.clone(), "abc\tfirst.ts\t-").is_err());
+    }
+
+    #[test]
+    fn test_segment_log_invalid_duration() {
+        let mut header = MediaPlaylist::builder();
+        header.target_duration(Duration::from_secs(10));
+
+        for duration in &["NaN", "inf", "-inf", "-1", "-0.5", "1e400"] {
+            assert!(
+                MediaPlaylist::from_segment_log(
+                    header.clone(),
+                    &format!("{}\tfirst.ts\t-", duration)
+                )
+                .is_err(),
+                "{}",
+                duration
+            );
+        }
+    }
+
+    #[test]
+    fn test_segment_log_escaped_uri() {
+        let playlist = MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(10))
+            .segments(vec![MediaSegment::builder()
+                .duration(Duration::from_secs(10))
+                .uri("a\tb\nc\rd\\e.ts")
+                .build()
+                .unwrap()])
+            .build()
+            .unwrap();
+
+        let log = playlist.to_segment_log();
+        assert_eq!(log, "10\ta\\tb\\nc\\rd\\\\e.ts\t-\n");
+
+        let mut header = MediaPlaylist::builder();
+        header.target_duration(Duration::from_secs(10));
+
+        assert_eq!(
+            MediaPlaylist::from_segment_log(header.clone(), &log).unwrap(),
+            playlist
+        );
+
+        assert!(MediaPlaylist::from_segment_log(header.clone(), "10\ta\\x.ts\t-").is_err());
+        assert!(MediaPlaylist::from_segment_log(header, "10\ta.ts\\\t-").is_err());
+    }
+
+    #[test]
+    fn test_comments_inside_segment() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "# comment before the segment\n",
+            "#EXTINF:9.009,\n",
+            "# comment between the tags\n",
+            "\n",
+            "#EXT-X-BYTERANGE:75232@0\n",
+            "  # indented comment before the uri\n",
+            "http://media.example.com/first.ts\n",
+            "# comment after the segment\n",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            playlist,
+            MediaPlaylist::builder()
+                .target_duration(Duration::from_secs(10))
+                .segments(vec![MediaSegment::builder()
+                    .duration(Duration::from_secs_f64(9.009))
+                    .byte_range(0..75232)
+                    .comments(vec![
+                        "# comment between the tags",
+                        "# indented comment before the uri"
+                    ])
+                    .uri("http://media.example.com/first.ts")
+                    .build()
+                    .unwrap()])
+                .build()
+                .unwrap()
+        );
+
+        assert!(playlist.to_string().contains(concat!(
+            "#EXTINF:9.009,\n",
+            "# comment between the tags\n",
+            "# indented comment before the uri\n",
+            "http://media.example.com/first.ts\n",
+        )));
+    }
+
+    #[test]
+    fn test_bitrate() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXTINF:10,\n",
+            "0.ts\n",
+            "#EXT-X-BITRATE:4500\n",
+            "#EXTINF:10,\n",
+            "1.ts\n",
+            "#EXTINF:10,\n",
+            "2.ts\n",
+            "#EXT-X-BITRATE:3000\n",
+            "#EXTINF:10,\n",
+            "3.ts\n",
+        );
+
+        let playlist = MediaPlaylist::try_from(input).unwrap();
+
+        assert_eq!(
+            playlist
+                .segments
+                .values()
+                .map(|segment| segment.bitrate)
+                .collect::<Vec<_>>(),
+            vec![None, Some(4500), Some(4500), Some(3000)]
+        );
+        assert_eq!(playlist.to_string(), input);
+
+        // the tag is only written, if the bitrate changes:
+        let playlist = MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(10))
+            .segments(
+                [Some(4500), Some(4500), None, Some(4500)]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, bitrate)| {
+                        let mut segment = MediaSegment::builder();
+                        segment
+                            .duration(Duration::from_secs(10))
+                            .uri(format!("{}.ts", i));
+
+                        if let Some(bitrate) = bitrate {
+                            segment.bitrate(*bitrate);
+                        }
+
+                        segment.build().unwrap()
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            playlist.to_string(),
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-TARGETDURATION:10\n",
+                "#EXT-X-BITRATE:4500\n",
+                "#EXTINF:10,\n",
+                "0.ts\n",
+                "#EXTINF:10,\n",
+                "1.ts\n",
+                "#EXTINF:10,\n",
+                "2.ts\n",
+                "#EXTINF:10,\n",
+                "3.ts\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_gap() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXTINF:10,\n",
+            "0.ts\n",
+            "#EXT-X-GAP\n",
+            "#EXTINF:10,\n",
+            "1.ts\n",
+            "#EXTINF:10,\n",
+            "2.ts\n",
+        );
+
+        let playlist = MediaPlaylist::try_from(input).unwrap();
+
+        assert_eq!(
+            playlist
+                .segments
+                .values()
+                .map(|segment| segment.has_gap)
+                .collect::<Vec<_>>(),
+            vec![false, true, false]
+        );
+        assert_eq!(playlist.to_string(), input);
+
+        // a gap segment must not start a byte range chain:
+        assert!(MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-VERSION:4\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-GAP\n",
+            "#EXT-X-BYTERANGE:100@0\n",
+            "#EXTINF:10,\n",
+            "all.ts\n",
+            "#EXT-X-BYTERANGE:100\n",
+            "#EXTINF:10,\n",
+            "all.ts\n",
+        ))
+        .is_err());
+
+        assert!(MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-VERSION:4\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-BYTERANGE:100@0\n",
+            "#EXTINF:10,\n",
+            "all.ts\n",
+            "#EXT-X-GAP\n",
+            "#EXT-X-BYTERANGE:100\n",
+            "#EXTINF:10,\n",
+            "all.ts\n",
+        ))
+        .is_ok());
+    }
+
+    #[test]
+    fn test_gap_runs() {
+        let playlist = MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(4))
+            .media_sequence(10)
+            .segments(
+                [false, true, true, false, true, false]
+                    .iter()
+                    .map(|has_gap| {
+                        MediaSegment::builder()
+                            .duration(Duration::from_secs(2))
+                            .has_gap(*has_gap)
+                            .uri("segment.ts")
+                            .build()
+                            .unwrap()
+                    }),
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            playlist.gap_runs(),
+            vec![
+                (11..13, Duration::from_secs(4)),
+                (14..15, Duration::from_secs(2))
+            ]
+        );
+        assert!((playlist.availability_ratio() - 0.5).abs() < f64::EPSILON);
+
+        let playlist = MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(4))
+            .segments(vec![])
+            .build()
+            .unwrap();
+
+        assert_eq!(playlist.gap_runs(), vec![]);
+        assert!((playlist.availability_ratio() - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_total_bytes() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-VERSION:4\n",
+            "#EXT-X-MAP:URI=\"main.mp4\",BYTERANGE=\"720@0\"\n",
+            "#EXTINF:10,\n",
+            "#EXT-X-BYTERANGE:1000@720\n",
+            "main.mp4\n",
+            "#EXTINF:10,\n",
+            "#EXT-X-BYTERANGE:2000\n",
+            "main.mp4\n",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            playlist
+                .segments
+                .values()
+                .map(MediaSegment::total_bytes)
+                .collect::<Vec<_>>(),
+            vec![Some(1000), Some(2000)]
+        );
+        assert_eq!(playlist.total_bytes(), Some(3000));
+
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-VERSION:4\n",
+            "#EXTINF:10,\n",
+            "#EXT-X-BYTERANGE:1000@0\n",
+            "main.ts\n",
+            "#EXTINF:10,\n",
+            "next.ts\n",
+        ))
+        .unwrap();
+
+        assert_eq!(playlist.total_bytes(), None);
+    }
+
+    #[test]
+    fn test_merge_updates() {
+        let segment = |number: Option<usize>, uri: &'static str| {
+            MediaSegment::builder()
+                .number(number)
+                .duration(Duration::from_secs(2))
+                .uri(uri)
+                .build()
+                .unwrap()
+        };
+
+        let mut playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:2\n",
+            "#EXT-X-MEDIA-SEQUENCE:10\n",
+            "#EXTINF:2,\n",
+            "10.ts\n",
+            "#EXTINF:2,\n",
+            "11.ts\n",
+        ))
+        .unwrap();
+
+        playlist.merge_updates(vec![
+            segment(Some(14), "14.ts"),
+            segment(Some(9), "9.ts"),
+            segment(Some(12), "12.ts"),
+            segment(None, "15.ts"),
+            segment(Some(11), "11-new.ts"),
+            segment(Some(13), "13.ts"),
+        ]);
+
+        assert_eq!(
+            playlist
+                .segments
+                .values()
+                .map(|s| (s.number(), s.uri().as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (10, "10.ts"),
+                (11, "11-new.ts"),
+                (12, "12.ts"),
+                (13, "13.ts"),
+                (14, "14.ts"),
+                (15, "15.ts"),
+            ]
+        );
+        assert!(playlist.segments.is_compact());
+
+        // the segment 16 is missing, so the numbers have a gap:
+        playlist.merge_updates(vec![segment(Some(18), "18.ts"), segment(Some(17), "17.ts")]);
+
+        let uri = |number| playlist.segment(number).map(|s| s.uri().as_str());
+
+        assert_eq!(uri(15), Some("15.ts"));
+        assert_eq!(uri(16), None);
+        assert_eq!(uri(17), Some("17.ts"));
+        assert_eq!(uri(18), Some("18.ts"));
+        assert_eq!(uri(19), None);
+
+        assert_eq!(
+            Writer::new()
+                .media_playlist_to_string(&playlist)
+                .unwrap_err()
+                .to_string(),
+            "missing the segment 16 (the segments are not numbered consecutively)"
+        );
+
+        // the gap has been filled:
+        playlist.merge_updates(vec![segment(Some(16), "16.ts")]);
+
+        assert!(Writer::new().media_playlist_to_string(&playlist).is_ok());
+
+        for number in 10..=18 {
+            assert_eq!(playlist.segment(number).map(|s| s.number()), Some(number));
+            assert_eq!(
+                playlist
+                    .segments
+                    .values()
+                    .nth(number - 10)
+                    .map(|s| s.number()),
+                Some(number)
+            );
+        }
+    }
+
+    #[test]
+    fn test_compatible_update() {
+        let playlist = |header: &str, segments: &[(usize, &str)]| {
+            let mut input = format!("#EXTM3U\n{}", header);
+
+            for (duration, uri) in segments {
+                input.push_str(&format!("#EXTINF:{},\n{}\n", duration, uri));
+            }
+
+            MediaPlaylist::try_from(input.as_str())
+                .unwrap()
+                .into_owned()
+        };
+
+        let previous = playlist(
+            "#EXT-X-TARGETDURATION:10\n#EXT-X-MEDIA-SEQUENCE:1\n",
+            &[(10, "1.ts"), (10, "2.ts")],
+        );
+
+        assert_eq!(previous.compatible_update(&previous), Ok(()));
+        assert_eq!(
+            previous.compatible_update(&playlist(
+                "#EXT-X-TARGETDURATION:6\n#EXT-X-MEDIA-SEQUENCE:1\n",
+                &[(6, "1.ts")]
+            )),
+            Err(UpdateViolation::TargetDurationChanged {
+                previous: Duration::from_secs(10),
+                next: Duration::from_secs(6)
+            })
+        );
+        assert_eq!(
+            previous.compatible_update(&playlist(
+                "#EXT-X-TARGETDURATION:10\n#EXT-X-MEDIA-SEQUENCE:1\n",
+                &[(10, "1.ts")]
+            )),
+            Err(UpdateViolation::SegmentRemoved { number: 2 })
+        );
+        assert_eq!(
+            previous.compatible_update(&playlist(
+                "#EXT-X-TARGETDURATION:10\n#EXT-X-MEDIA-SEQUENCE:2\n",
+                &[(9, "2.ts"), (10, "3.ts")]
+            )),
+            Err(UpdateViolation::SegmentChanged { number: 2 })
+        );
+        assert_eq!(
+            previous.compatible_update(&playlist(
+                "#EXT-X-TARGETDURATION:10\n#EXT-X-MEDIA-SEQUENCE:1\n#EXT-X-DISCONTINUITY-SEQUENCE:0\n",
+                &[(10, "1.ts"), (10, "2.ts"), (10, "3.ts")]
+            )),
+            Ok(())
+        );
+
+        let event = playlist(
+            "#EXT-X-TARGETDURATION:10\n#EXT-X-PLAYLIST-TYPE:EVENT\n",
+            &[(10, "1.ts"), (10, "2.ts")],
+        );
+
+        assert_eq!(
+            event.compatible_update(&playlist(
+                "#EXT-X-TARGETDURATION:10\n#EXT-X-PLAYLIST-TYPE:EVENT\n#EXT-X-MEDIA-SEQUENCE:1\n",
+                &[(10, "2.ts"), (10, "3.ts")]
+            )),
+            Err(UpdateViolation::SegmentRemoved { number: 0 })
+        );
+
+        let mut ended = previous.clone();
+        ended.has_end_list = true;
+
+        assert_eq!(ended.compatible_update(&ended), Ok(()));
+        assert_eq!(
+            ended.compatible_update(&previous),
+            Err(UpdateViolation::ChangedAfterEnd)
+        );
+    }
+
+    #[test]
+    fn test_builder_getters() {
+        let mut builder = MediaPlaylist::builder();
+
+        assert_eq!(builder.get_target_duration(), None);
+        assert_eq!(builder.get_media_sequence(), None);
+        assert_eq!(builder.get_playlist_type(), None);
+        assert!(builder.get_segments().is_none());
+
+        builder
+            .target_duration(Duration::from_secs(6))
+            .media_sequence(5)
+            .playlist_type(PlaylistType::Vod)
+            .has_end_list(false)
+            .header_attributes("TOOL=packager")
+            .unknown(vec![Cow::Borrowed("#EXT-X-UNKNOWN")]);
+
+        assert_eq!(builder.get_target_duration(), Some(Duration::from_secs(6)));
+        assert_eq!(builder.get_media_sequence(), Some(5));
+        assert_eq!(builder.get_discontinuity_sequence(), None);
+        assert_eq!(builder.get_playlist_type(), Some(PlaylistType::Vod));
+        assert_eq!(builder.get_has_end_list(), Some(false));
+        assert_eq!(builder.get_has_i_frames_only(), None);
+        assert_eq!(builder.get_header_attributes(), Some("TOOL=packager"));
+        assert_eq!(
+            builder.get_unknown(),
+            Some(&[Cow::Borrowed("#EXT-X-UNKNOWN")][..])
+        );
+    }
+
+    #[test]
+    fn test_header_attributes() {
+        let input = concat!(
+            "#EXTM3U  TOOL=\"packager\" \n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXTINF:10,\n",
+            "1.ts\n",
+        );
+
+        let playlist = MediaPlaylist::try_from(input).unwrap();
+
+        assert_eq!(
+            playlist.header_attributes,
+            Some(Cow::Borrowed("TOOL=\"packager\""))
+        );
+        assert!(playlist
+            .to_string()
+            .starts_with("#EXTM3U TOOL=\"packager\"\n#EXT-X-TARGETDURATION:10\n"));
+
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U \n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXTINF:10,\n",
+            "1.ts\n",
+        ))
+        .unwrap();
+
+        assert_eq!(playlist.header_attributes, None);
+        assert!(playlist.to_string().starts_with("#EXTM3U\n"));
+
+        assert!(
+            MediaPlaylist::try_from(concat!("#EXTM3UX\n", "#EXT-X-TARGETDURATION:10\n",)).is_err()
+        );
+    }
+
+    #[test]
+    fn test_eq() {
+        let playlist = |duration: &str| {
+            MediaPlaylist::try_from(
+                format!(
+                    "#EXTM3U\n#EXT-X-TARGETDURATION:4\n#EXTINF:{},\n1.ts\n#EXT-X-ENDLIST\n",
+                    duration
+                )
+                .as_str(),
+            )
+            .unwrap()
+            .into_owned()
+        };
+
+        // the duration precision only affects the output:
+        let (a, b) = (playlist("4.000"), playlist("4"));
+        assert_ne!(a.duration_precision, b.duration_precision);
+        assert_eq!(a, b);
+        assert_eq!(a.compatible_update(&b), Ok(()));
+
+        // the header attributes are only kept for the output:
+        let mut b = a.clone();
+        b.header_attributes = Some("TOOL=packager".into());
+        assert_eq!(a, b);
+        assert_eq!(a.compatible_update(&b), Ok(()));
+
+        // the content kind only enables additional checks:
+        b.content_kind = Some(ContentKind::Subtitles);
+        assert_eq!(a, b);
+        assert_eq!(a.compatible_update(&b), Ok(()));
+    }
+
+    #[test]
+    fn test_equivalent_keys() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"key.bin\"\n",
+            "#EXTINF:10,\n",
+            "1.ts\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"key.bin\",KEYFORMAT=\"identity\",",
+            "KEYFORMATVERSIONS=\"1\"\n",
+            "#EXTINF:10,\n",
+            "2.ts\n",
+        ))
+        .unwrap();
+
+        // the second key replaces the first one, because both have the same format
+        assert_eq!(playlist.segments[1].keys.len(), 1);
+        assert_eq!(playlist.to_string().matches("#EXT-X-KEY").count(), 1);
+    }
+
+    #[test]
+    fn test_initial_keys() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXTINF:10,\n",
+            "1.ts\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"key.bin\"\n",
+            "#EXTINF:10,\n",
+            "2.ts\n",
+        ))
+        .unwrap();
+
+        assert_eq!(playlist.initial_keys().count(), 0);
+
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-KEY:METHOD=NONE\n",
+            "#EXTINF:10,\n",
+            "1.ts\n",
+        ))
+        .unwrap();
+
+        assert_eq!(playlist.initial_keys().count(), 0);
+    }
+
+    #[test]
+    fn test_visit() {
+        #[derive(Default)]
+        struct Recorder(Vec<&'static str>);
+
+        impl<'a> TagVisitor<'a> for Recorder {
+            fn visit_target_duration(&mut self, _: Duration) { self.0.push("target_duration"); }
+
+            fn visit_media_sequence(&mut self, _: usize) { self.0.push("media_sequence"); }
+
+            fn visit_playlist_type(&mut self, _: PlaylistType) { self.0.push("playlist_type"); }
+
+            fn visit_segment(&mut self, _: &MediaSegment<'a>) { self.0.push("segment"); }
+
+            fn visit_key(&mut self, _: &ExtXKey<'a>) { self.0.push("key"); }
+
+            fn visit_discontinuity(&mut self) { self.0.push("discontinuity"); }
+
+            fn visit_inf(&mut self, _: &crate::tags::ExtInf<'a>) { self.0.push("inf"); }
+
+            fn visit_uri(&mut self, _: &crate::types::UriRef<'a>) { self.0.push("uri"); }
+
+            fn visit_pending_segment(&mut self, _: &PendingSegment<'a>) {
+                self.0.push("pending_segment");
+            }
+
+            fn visit_unknown(&mut self, _: &str) { self.0.push("unknown"); }
+
+            fn visit_end_list(&mut self) { self.0.push("end_list"); }
+        }
+
+        struct Rewrite;
+
+        impl<'a> TagVisitorMut<'a> for Rewrite {
+            fn visit_media_sequence(&mut self, media_sequence: &mut usize) { *media_sequence += 1; }
+
+            fn visit_uri(&mut self, uri: &mut crate::types::UriRef<'a>) {
+                *uri = format!("https://cdn.example.com/{}", uri).into();
+            }
+
+            fn visit_pending_segment(&mut self, segment: &mut PendingSegment<'a>) {
+                segment.has_discontinuity = false;
+            }
+        }
+
+        let mut playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-MEDIA-SEQUENCE:5\n",
+            "#EXT-X-PLAYLIST-TYPE:EVENT\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"key.bin\"\n",
+            "#EXTINF:10,\n",
+            "1.ts\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:10,\n",
+            "2.ts\n",
+            "#EXT-X-UNKNOWN\n",
+            "#EXT-X-DISCONTINUITY\n",
+        ))
+        .unwrap();
+
+        let mut recorder = Recorder::default();
+        playlist.visit(&mut recorder);
+
+        assert_eq!(
+            recorder.0,
+            vec![
+                "target_duration",
+                "media_sequence",
+                "playlist_type",
+                "segment",
+                "key",
+                "inf",
+                "uri",
+                "segment",
+                "key",
+                "discontinuity",
+                "inf",
+                "uri",
+                "pending_segment",
+                "key",
+                "discontinuity",
+                "unknown",
+            ]
+        );
+
+        playlist.visit_mut(&mut Rewrite);
+
+        assert_eq!(playlist.media_sequence, 6);
+        assert_eq!(
+            playlist.segments[1].uri(),
+            &crate::types::UriRef::from("https://cdn.example.com/2.ts")
+        );
+        assert_eq!(
+            playlist.pending_segment.map(|s| s.has_discontinuity),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_extend_builder() {
+        let segment = |number: Option<usize>| {
+            MediaSegment::builder()
+                .duration(Duration::from_secs(10))
+                .uri("x.ts")
+                .number(number)
+                .build()
+                .unwrap()
+        };
+
+        let mut builder = vec![segment(None), segment(None)]
+            .into_iter()
+            .collect::<MediaPlaylistBuilder<'_>>();
+        builder.extend(vec![segment(None), segment(Some(3)), segment(None)]);
+
+        let playlist = builder
+            .target_duration(Duration::from_secs(10))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            playlist
+                .segments
+                .values()
+                .map(|segment| segment.number)
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn test_pending_segment() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXTINF:4,\n",
+            "1.ts\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"key.bin\"\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2010-02-19T14:54:23.031+08:00\n",
+        );
+
+        let playlist = MediaPlaylist::try_from(input).unwrap();
+        let pending = playlist.pending_segment.as_ref().unwrap();
+
+        assert_eq!(playlist.segments.num_elements(), 1);
+        assert_eq!(pending.keys.len(), 1);
+        assert!(pending.program_date_time.is_some());
+        assert_eq!(playlist.to_string(), input);
+
+        // an `#EXTINF` tag must be followed by an uri:
+        assert!(MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2010-02-19T14:54:23.031+08:00\n",
+            "#EXTINF:4,\n",
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn test_parts() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-VERSION:6\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-MAP:URI=\"init.mp4\"\n",
+            "#EXT-X-PART:DURATION=2,URI=\"1.0.mp4\",INDEPENDENT=YES\n",
+            "#EXT-X-PART:DURATION=2,URI=\"1.1.mp4\"\n",
+            "#EXTINF:4,\n",
+            "1.mp4\n",
+            "#EXT-X-PART:DURATION=2,URI=\"2.0.mp4\",INDEPENDENT=YES\n",
+        );
+
+        let playlist = MediaPlaylist::try_from(input).unwrap();
+
+        assert_eq!(playlist.segments[0].parts.len(), 2);
+        assert!(playlist.segments[0].parts[0].is_independent());
+        assert_eq!(playlist.segments[0].parts[1].uri(), "1.1.mp4");
+        assert_eq!(
+            playlist.pending_segment.as_ref().unwrap().parts,
+            vec![{
+                let mut part = crate::tags::ExtXPart::new(Duration::from_secs(2), "2.0.mp4");
+                part.set_is_independent(true);
+                part
+            }]
+        );
+        assert!(playlist.unknown.is_empty());
+        assert_eq!(playlist.to_string(), input);
+    }
+
+    #[test]
+    fn test_server_control() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-SERVER-CONTROL:CAN-SKIP-UNTIL=24,PART-HOLD-BACK=1.5,CAN-BLOCK-RELOAD=YES\n",
+            "#EXTINF:4,\n",
+            "1.ts\n",
+        );
+
+        let playlist = MediaPlaylist::try_from(input).unwrap();
+        let server_control = playlist.server_control.unwrap();
+
+        assert_eq!(
+            server_control.can_skip_until(),
+            Some(Duration::from_secs(24))
+        );
+        assert_eq!(
+            server_control.part_hold_back(),
+            Some(Duration::from_millis(1500))
+        );
+        assert!(server_control.can_block_reload());
+        assert_eq!(playlist.to_string(), input);
+
+        let playlist = MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(4))
+            .server_control(ExtXServerControl::new())
+            .segments(vec![])
+            .build()
+            .unwrap();
+
+        assert_eq!(playlist.server_control, Some(ExtXServerControl::new()));
     }
 
-    if has_partial_segment {
-        return Err(Error::custom("Missing URI for the last `MediaSegment`"));
+    #[test]
+    fn test_part_inf() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-VERSION:3\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-PART-INF:PART-TARGET=0.33334\n",
+            "#EXT-X-PART:DURATION=0.33334,URI=\"1.0.mp4\"\n",
+            "#EXTINF:0.33334,\n",
+            "1.mp4\n",
+        );
+
+        let playlist = MediaPlaylist::try_from(input).unwrap();
+
+        assert_eq!(playlist.part_target, Some(Duration::from_secs_f64(0.33334)));
+        assert!(playlist.unknown.is_empty());
+        assert_eq!(playlist.to_string(), input);
+
+        assert!(MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-PART-INF:PART-TARGET=-1\n",
+        ))
+        .is_err());
     }
 
-    builder.unknown(unknown);
-    builder.segments(segments);
-    builder.build().map_err(Error::builder)
-}
+    #[test]
+    fn test_skip() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-MEDIA-SEQUENCE:10\n",
+            "#EXT-X-SERVER-CONTROL:CAN-SKIP-UNTIL=24\n",
+            "#EXT-X-SKIP:SKIPPED-SEGMENTS=3\n",
+            "#EXTINF:4,\n",
+            "13.ts\n",
+            "#EXTINF:4,\n",
+            "14.ts\n",
+        );
 
-impl FromStr for MediaPlaylist<'static> {
-    type Err = Error;
+        let playlist = MediaPlaylist::try_from(input).unwrap();
 
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        Ok(parse_media_playlist(input, &mut MediaPlaylist::builder())?.into_owned())
+        assert_eq!(playlist.skip, Some(ExtXSkip::new(3)));
+        assert_eq!(playlist.media_sequence, 10);
+        assert_eq!(playlist.segments[0].number(), 13);
+        assert_eq!(playlist.segments[1].number(), 14);
+        assert!(playlist.unknown.is_empty());
+        assert_eq!(playlist.to_string(), input);
+
+        assert!(MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXTINF:4,\n",
+            "1.ts\n",
+            "#EXT-X-SKIP:SKIPPED-SEGMENTS=3\n",
+        ))
+        .is_err());
     }
-}
 
-impl<'a> TryFrom<&'a str> for MediaPlaylist<'a> {
-    type Error = Error;
+    #[test]
+    fn test_apply_delta() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-MEDIA-SEQUENCE:10\n",
+            "#EXT-X-DATERANGE:ID=\"ad1\",START-DATE=\"2010-02-19T14:54:23.031+08:00\"\n",
+            "#EXTINF:4,\n",
+            "10.ts\n",
+            "#EXT-X-DATERANGE:ID=\"ad2\",START-DATE=\"2010-02-19T14:54:27.031+08:00\"\n",
+            "#EXTINF:4,\n",
+            "11.ts\n",
+            "#EXT-X-DATERANGE:ID=\"ad3\",START-DATE=\"2010-02-19T14:54:31.031+08:00\"\n",
+            "#EXTINF:4,\n",
+            "12.ts\n",
+        ))
+        .unwrap();
 
-    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
-        parse_media_playlist(input, &mut Self::builder())
+        let delta = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-MEDIA-SEQUENCE:11\n",
+            "#EXT-X-SKIP:SKIPPED-SEGMENTS=2,RECENTLY-REMOVED-DATERANGES=\"ad2\"\n",
+            "#EXTINF:4,\n",
+            "13.ts\n",
+        ))
+        .unwrap();
+
+        let result = playlist.apply_delta(&delta).unwrap();
+
+        assert_eq!(result.skip, None);
+        assert_eq!(result.media_sequence, 11);
+        assert_eq!(
+            result
+                .segments
+                .values()
+                .map(|s| (s.number(), s.uri().to_string()))
+                .collect::<Vec<_>>(),
+            vec![
+                (11, "11.ts".to_string()),
+                (12, "12.ts".to_string()),
+                (13, "13.ts".to_string())
+            ]
+        );
+        assert_eq!(result.segments[0].date_range, None);
+        assert_eq!(
+            result.segments[1]
+                .date_range
+                .as_ref()
+                .map(|d| d.id().as_ref()),
+            Some("ad3")
+        );
+
+        // a full playlist is returned unchanged
+        assert_eq!(playlist.apply_delta(&playlist).unwrap(), playlist);
+
+        // the skipped segments must be part of the previous playlist
+        let mut delta = delta;
+        delta.skip = Some(ExtXSkip::new(4));
+        assert!(playlist.apply_delta(&delta).is_err());
+
+        delta.media_sequence = 9;
+        delta.skip = Some(ExtXSkip::new(1));
+        assert!(playlist.apply_delta(&delta).is_err());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use pretty_assertions::assert_eq;
+    #[test]
+    fn test_to_delta() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-MEDIA-SEQUENCE:10\n",
+            "#EXT-X-SERVER-CONTROL:CAN-SKIP-UNTIL=8\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"key\"\n",
+            "#EXT-X-MAP:URI=\"init.mp4\"\n",
+            "#EXTINF:4,\n",
+            "10.mp4\n",
+            "#EXTINF:4,\n",
+            "11.mp4\n",
+            "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2010-02-19T14:54:23.031+08:00\"\n",
+            "#EXTINF:4,\n",
+            "12.mp4\n",
+            "#EXTINF:4,\n",
+            "13.mp4\n",
+            "#EXTINF:4,\n",
+            "14.mp4\n",
+        ))
+        .unwrap();
+
+        let delta = playlist.to_delta(Duration::from_secs(8));
+
+        assert_eq!(
+            delta.to_string(),
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-VERSION:6\n",
+                "#EXT-X-TARGETDURATION:4\n",
+                "#EXT-X-MEDIA-SEQUENCE:10\n",
+                "#EXT-X-SERVER-CONTROL:CAN-SKIP-UNTIL=8\n",
+                "#EXT-X-SKIP:SKIPPED-SEGMENTS=2\n",
+                "#EXT-X-KEY:METHOD=AES-128,URI=\"key\"\n",
+                "#EXT-X-MAP:URI=\"init.mp4\"\n",
+                "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2010-02-19T14:54:23.031+08:00\"\n",
+                "#EXTINF:4,\n",
+                "12.mp4\n",
+                "#EXTINF:4,\n",
+                "13.mp4\n",
+                "#EXTINF:4,\n",
+                "14.mp4\n",
+            )
+        );
+        assert_eq!(
+            MediaPlaylist::try_from(delta.to_string().as_str()).unwrap(),
+            delta
+        );
+        assert_eq!(
+            playlist.apply_delta(&delta).unwrap().segments[0],
+            playlist.segments[0]
+        );
+
+        assert_eq!(playlist.to_delta(Duration::from_secs(20)), playlist);
+    }
 
     #[test]
-    fn too_large_segment_duration_test() {
-        let playlist = concat!(
+    fn test_preload_hints() {
+        let input = concat!(
             "#EXTM3U\n",
-            "#EXT-X-TARGETDURATION:8\n",
-            "#EXT-X-VERSION:3\n",
-            "#EXTINF:9.009,\n",
-            "http://media.example.com/first.ts\n",
-            "#EXTINF:9.509,\n",
-            "http://media.example.com/second.ts\n",
-            "#EXTINF:3.003,\n",
-            "http://media.example.com/third.ts\n",
-            "#EXT-X-ENDLIST\n"
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-PART:DURATION=1,URI=\"part1.mp4\"\n",
+            "#EXTINF:4,\n",
+            "1.mp4\n",
+            "#EXT-X-PART:DURATION=1,URI=\"part2.mp4\"\n",
+            "#EXT-X-PRELOAD-HINT:TYPE=PART,URI=\"part3.mp4\"\n",
+            "#EXT-X-PRELOAD-HINT:TYPE=MAP,URI=\"init.mp4\",BYTERANGE-START=0,BYTERANGE-LENGTH=100\n",
         );
 
-        // Error (allowable segment duration = target duration = 8)
-        assert!(MediaPlaylist::try_from(playlist).is_err());
+        let playlist = MediaPlaylist::try_from(input).unwrap();
+
+        assert_eq!(playlist.segments.num_elements(), 1);
+        assert_eq!(playlist.pending_segment.as_ref().unwrap().parts.len(), 1);
+        let mut map_hint = ExtXPreloadHint::new(PreloadHintType::Map, "init.mp4");
+        map_hint
+            .set_byte_range_start(Some(0))
+            .set_byte_range_length(Some(100));
+
+        assert_eq!(
+            playlist.preload_hints,
+            vec![
+                ExtXPreloadHint::new(PreloadHintType::Part, "part3.mp4"),
+                map_hint
+            ]
+        );
+        assert!(playlist.unknown.is_empty());
+        assert_eq!(playlist.to_string(), input);
+    }
+
+    #[test]
+    fn test_parse_header_only() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:6\n",
+            "#EXT-X-DISCONTINUITY-SEQUENCE:2\n",
+            "#EXT-X-ALLOW-CACHE:NO\n",
+            "#EXT-X-I-FRAMES-ONLY\n",
+            "# a comment\n",
+            "#EXT-X-INDEPENDENT-SEGMENTS\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"key\"\n",
+            "#EXT-X-MEDIA-SEQUENCE:5\n",
+            "#EXTINF:6,\n",
+            "invalid\n",
+            "#EXT-X-BYTERANGE:invalid\n",
+        );
+
+        assert_eq!(
+            MediaPlaylist::parse_header_only(input).unwrap(),
+            PlaylistHeader {
+                version: None,
+                target_duration: Duration::from_secs(6),
+                media_sequence: 0,
+                discontinuity_sequence: 2,
+                playlist_type: None,
+                allow_cache: Some(false),
+                has_i_frames_only: true,
+                has_independent_segments: true,
+            }
+        );
+
+        assert!(MediaPlaylist::parse_header_only("#EXTM3U\n#EXTINF:6,\n1.ts\n").is_err());
+        assert!(MediaPlaylist::parse_header_only("#EXT-X-TARGETDURATION:6\n").is_err());
+        assert!(MediaPlaylist::parse_header_only(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:6\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1\n",
+            "a.m3u8\n",
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn test_allow_cache() {
+        // the tag may appear anywhere in the playlist:
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXTINF:10,\n",
+            "1.ts\n",
+            "#EXT-X-ALLOW-CACHE:NO\n",
+            "#EXT-X-ENDLIST\n",
+        ))
+        .unwrap();
+
+        assert_eq!(playlist.allow_cache, Some(false));
+        assert!(playlist.unknown.is_empty());
+        assert_eq!(
+            playlist.to_string(),
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-TARGETDURATION:10\n",
+                "#EXT-X-ALLOW-CACHE:NO\n",
+                "#EXTINF:10,\n",
+                "1.ts\n",
+                "#EXT-X-ENDLIST\n",
+            )
+        );
+
+        // the tag has been removed in version 7:
+        let mut writer = Writer::new();
+        writer.set_version_override(Some(ProtocolVersion::V7));
+
+        assert!(!writer
+            .media_playlist_to_string(&playlist)
+            .unwrap()
+            .contains("EXT-X-ALLOW-CACHE"));
+    }
+
+    #[test]
+    fn test_positioned_tags() {
+        struct CueIn;
+
+        impl fmt::Display for CueIn {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "#EXT-X-CUE-IN") }
+        }
+
+        impl CustomTag for CueIn {
+            fn position(&self) -> Option<usize> { Some(12) }
+        }
+
+        let segment = |discontinuity: bool, uri: &'static str| {
+            MediaSegment::builder()
+                .duration(Duration::from_secs(10))
+                .has_discontinuity(discontinuity)
+                .uri(uri)
+                .build()
+                .unwrap()
+        };
+
+        let playlist = MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(10))
+            .media_sequence(10)
+            .segments(vec![
+                segment(false, "1.ts"),
+                segment(true, "ad.ts"),
+                segment(true, "2.ts"),
+            ])
+            .push_unknown_tag(20, "#X-LAST")
+            .push_unknown_tag(11, "#EXT-X-CUE-OUT:10")
+            .push_custom_tag(CueIn)
+            .unknown(vec![Cow::Borrowed("#X-UNKNOWN")])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            playlist.to_string(),
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-TARGETDURATION:10\n",
+                "#EXT-X-MEDIA-SEQUENCE:10\n",
+                "#EXTINF:10,\n",
+                "1.ts\n",
+                "#EXT-X-CUE-OUT:10\n",
+                "#EXT-X-DISCONTINUITY\n",
+                "#EXTINF:10,\n",
+                "ad.ts\n",
+                "#EXT-X-CUE-IN\n",
+                "#EXT-X-DISCONTINUITY\n",
+                "#EXTINF:10,\n",
+                "2.ts\n",
+                "#X-LAST\n",
+                "#X-UNKNOWN\n",
+            )
+        );
+        assert_eq!(playlist.clone().into_owned(), playlist);
+
+        let playlists = playlist.split_at_discontinuities();
+
+        assert_eq!(
+            playlists[1].positioned_tags,
+            vec![(0, Cow::Borrowed("#EXT-X-CUE-OUT:10"))]
+        );
+        assert_eq!(
+            playlists[2].positioned_tags,
+            vec![
+                (0, Cow::Borrowed("#EXT-X-CUE-IN")),
+                (1, Cow::Borrowed("#X-LAST"))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_at_discontinuities() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-MEDIA-SEQUENCE:20\n",
+            "#EXT-X-DISCONTINUITY-SEQUENCE:3\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"key\"\n",
+            "#EXT-X-MAP:URI=\"init1.mp4\"\n",
+            "#EXTINF:10,\n",
+            "a/1.mp4\n",
+            "#EXTINF:10,\n",
+            "a/2.mp4\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:4,\n",
+            "b/1.mp4\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXT-X-MAP:URI=\"init2.mp4\"\n",
+            "#EXTINF:6,\n",
+            "c/1.mp4\n",
+            "#EXT-X-PART:DURATION=1,URI=\"c/2.1.mp4\"\n",
+        ))
+        .unwrap();
+
+        let playlists = playlist.split_at_discontinuities();
+
+        assert_eq!(playlists.len(), 3);
+        assert_eq!(
+            playlists[1].to_string(),
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-VERSION:6\n",
+                "#EXT-X-TARGETDURATION:4\n",
+                "#EXT-X-PLAYLIST-TYPE:VOD\n",
+                "#EXT-X-KEY:METHOD=AES-128,URI=\"key\"\n",
+                "#EXT-X-MAP:URI=\"init1.mp4\"\n",
+                "#EXTINF:4,\n",
+                "b/1.mp4\n",
+                "#EXT-X-ENDLIST\n",
+            )
+        );
+        assert_eq!(
+            playlists[0]
+                .segments
+                .values()
+                .map(MediaSegment::number)
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        assert_eq!(
+            playlists[2].segments[0].map.as_ref().unwrap().uri(),
+            "init2.mp4"
+        );
+        assert_eq!(playlists[2].pending_segment, None);
 
-        // Error (allowable segment duration = 9)
         assert!(MediaPlaylist::builder()
-            .allowable_excess_duration(Duration::from_secs(1))
-            .parse(playlist)
-            .is_err());
+            .target_duration(Duration::from_secs(10))
+            .segments(vec![])
+            .build()
+            .unwrap()
+            .split_at_discontinuities()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_rendition_reports() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXTINF:4,\n",
+            "1.mp4\n",
+            "#EXT-X-PRELOAD-HINT:TYPE=PART,URI=\"part.mp4\"\n",
+            "#EXT-X-RENDITION-REPORT:URI=\"../1M/index.m3u8\",LAST-MSN=1,LAST-PART=3\n",
+            "#EXT-X-RENDITION-REPORT:URI=\"../4M/index.m3u8\",LAST-MSN=1\n",
+        );
+
+        let playlist = MediaPlaylist::try_from(input).unwrap();
+
+        assert_eq!(playlist.rendition_reports.len(), 2);
+        assert_eq!(playlist.rendition_reports[0].last_part(), Some(3));
+        assert_eq!(playlist.rendition_reports[1].uri(), "../4M/index.m3u8");
+        assert_eq!(playlist.to_string(), input);
+    }
+
+    #[test]
+    fn test_encryption_map() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-MEDIA-SEQUENCE:5\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"https://example.com/a.key\"\n",
+            "#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"https://example.com/b.key\",KEYFORMAT=\"identity\"\n",
+            "#EXTINF:10,\n",
+            "5.ts\n",
+            "#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"https://example.com/c.key\"\n",
+            "#EXTINF:10,\n",
+            "6.ts\n",
+            "#EXT-X-KEY:METHOD=NONE\n",
+            "#EXTINF:10,\n",
+            "7.ts\n",
+            "#EXTINF:10,\n",
+            "8.ts\n",
+        ))
+        .unwrap();
+
+        let map = playlist.encryption_map();
+
+        assert_eq!(
+            map.iter()
+                .map(|(range, _)| range.clone())
+                .collect::<Vec<_>>(),
+            vec![5..7, 7..9]
+        );
+        // the second key replaces the first one, because both have the same
+        // `KeyFormat`:
+        assert_eq!(
+            map[0].1,
+            vec![(EncryptionMethod::SampleAes, KeyFormat::Identity)]
+        );
+        assert_eq!(map[1].1, vec![]);
 
-        // Ok (allowable segment duration = 10)
         assert_eq!(
             MediaPlaylist::builder()
-                .allowable_excess_duration(Duration::from_secs(2))
-                .parse(playlist)
-                .unwrap(),
-            MediaPlaylist::builder()
-                .allowable_excess_duration(Duration::from_secs(2))
-                .target_duration(Duration::from_secs(8))
-                .segments(vec![
-                    MediaSegment::builder()
-                        .duration(Duration::from_secs_f64(9.009))
-                        .uri("http://media.example.com/first.ts")
-                        .build()
-                        .unwrap(),
-                    MediaSegment::builder()
-                        .duration(Duration::from_secs_f64(9.509))
-                        .uri("http://media.example.com/second.ts")
-                        .build()
-                        .unwrap(),
-                    MediaSegment::builder()
-                        .duration(Duration::from_secs_f64(3.003))
-                        .uri("http://media.example.com/third.ts")
-                        .build()
-                        .unwrap(),
-                ])
-                .has_end_list(true)
+                .target_duration(Duration::from_secs(10))
+                .segments(vec![])
                 .build()
                 .unwrap()
+                .encryption_map(),
+            vec![]
         );
     }
 
     #[test]
-    fn test_segment_number_simple() {
+    fn test_to_bytes() {
         let playlist = MediaPlaylist::builder()
-            .allowable_excess_duration(Duration::from_secs(2))
-            .target_duration(Duration::from_secs(8))
-            .segments(vec![
-                MediaSegment::builder()
-                    .duration(Duration::from_secs_f64(9.009))
-                    .uri("http://media.example.com/first.ts")
-                    .build()
-                    .unwrap(),
-                MediaSegment::builder()
-                    .duration(Duration::from_secs_f64(9.509))
-                    .uri("http://media.example.com/second.ts")
-                    .build()
-                    .unwrap(),
-                MediaSegment::builder()
-                    .duration(Duration::from_secs_f64(3.003))
-                    .uri("http://media.example.com/third.ts")
-                    .build()
-                    .unwrap(),
-            ])
+            .target_duration(Duration::from_secs(10))
+            .segments(vec![MediaSegment::builder()
+                .duration(Duration::from_secs(10))
+                .uri("segment.ts")
+                .build()
+                .unwrap()])
             .build()
             .unwrap();
 
-        let mut segments = playlist.segments.into_iter().map(|(k, v)| (k, v.number));
-        assert_eq!(segments.next(), Some((0, 0)));
-        assert_eq!(segments.next(), Some((1, 1)));
-        assert_eq!(segments.next(), Some((2, 2)));
-        assert_eq!(segments.next(), None);
+        assert_eq!(
+            playlist.to_bytes(Compression::default()).unwrap(),
+            playlist.to_string().into_bytes()
+        );
     }
 
     #[test]
-    fn test_segment_number_sequence() {
+    #[cfg(feature = "flate2")]
+    fn test_to_bytes_gzip() {
+        use std::io::Read;
+
         let playlist = MediaPlaylist::builder()
-            .target_duration(Duration::from_secs(8))
-            .media_sequence(2680)
-            .segments(vec![
-                MediaSegment::builder()
-                    .duration(Duration::from_secs_f64(7.975))
-                    .uri("https://priv.example.com/fileSequence2680.ts")
-                    .build()
-                    .unwrap(),
-                MediaSegment::builder()
-                    .duration(Duration::from_secs_f64(7.941))
-                    .uri("https://priv.example.com/fileSequence2681.ts")
-                    .build()
-                    .unwrap(),
+            .target_duration(Duration::from_secs(10))
+            .segments(
+                (0..100)
+                    .map(|i| {
+                        MediaSegment::builder()
+                            .duration(Duration::from_secs(10))
+                            .uri(format!("segment-{}.ts", i))
+                            .build()
+                            .unwrap()
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .build()
+            .unwrap();
+
+        let bytes = playlist.to_bytes(Compression::Gzip(6)).unwrap();
+        assert!(bytes.len() < playlist.to_string().len());
+
+        let mut output = String::new();
+        flate2::read::GzDecoder::new(bytes.as_slice())
+            .read_to_string(&mut output)
+            .unwrap();
+        assert_eq!(output, playlist.to_string());
+
+        assert!(playlist.to_bytes(Compression::Gzip(10)).is_err());
+    }
+
+    #[test]
+    fn test_segment() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-VERSION:9\n",
+            "#EXT-X-MEDIA-SEQUENCE:10\n",
+            "#EXT-X-SERVER-CONTROL:CAN-SKIP-UNTIL=8\n",
+            "#EXT-X-SKIP:SKIPPED-SEGMENTS=2\n",
+            "#EXTINF:4,\n",
+            "12.ts\n",
+            "#EXTINF:4,\n",
+            "13.ts\n",
+        ))
+        .unwrap();
+
+        assert_eq!(playlist.segment(12).unwrap().uri(), "12.ts");
+        assert_eq!(playlist.segment(13).unwrap().uri(), "13.ts");
+        assert_eq!(playlist.segment(10), None);
+        assert_eq!(playlist.segment(14), None);
+
+        let playlist = MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(4))
+            .media_sequence(5)
+            .segments((5..7).map(|number| {
                 MediaSegment::builder()
-                    .duration(Duration::from_secs_f64(7.975))
-                    .uri("https://priv.example.com/fileSequence2682.ts")
+                    .duration(Duration::from_secs(4))
+                    .uri(format!("{}.ts", number))
                     .build()
-                    .unwrap(),
-            ])
+                    .unwrap()
+            }))
             .build()
             .unwrap();
-        let mut segments = playlist.segments.into_iter().map(|(k, v)| (k, v.number));
-        assert_eq!(segments.next(), Some((0, 2680)));
-        assert_eq!(segments.next(), Some((1, 2681)));
-        assert_eq!(segments.next(), Some((2, 2682)));
-        assert_eq!(segments.next(), None);
+
+        assert_eq!(playlist.segment(6).unwrap().uri(), "6.ts");
+        assert_eq!(playlist.segment(4), None);
+    }
+
+    #[test]
+    fn test_map_changed() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-VERSION:6\n",
+            "#EXTINF:4,\n",
+            "0.mp4\n",
+            "#EXT-X-MAP:URI=\"init.mp4\",BYTERANGE=\"100\"\n",
+            "#EXTINF:4,\n",
+            "1.mp4\n",
+            "#EXTINF:4,\n",
+            "2.mp4\n",
+            "#EXT-X-MAP:URI=\"init.mp4\",BYTERANGE=\"100@0\"\n",
+            "#EXTINF:4,\n",
+            "3.mp4\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXT-X-MAP:URI=\"init.mp4\",BYTERANGE=\"100@100\"\n",
+            "#EXTINF:4,\n",
+            "4.mp4\n",
+            "#EXT-X-MAP:URI=\"init.mp4\",BYTERANGE=\"100\"\n",
+            "#EXTINF:4,\n",
+            "5.mp4\n",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            playlist
+                .segments
+                .values()
+                .map(MediaSegment::map_changed)
+                .collect::<Vec<_>>(),
+            vec![false, true, false, false, true, true]
+        );
+
+        let playlists = playlist.split_at_discontinuities();
+
+        assert!(playlists[1].segments[0].map_changed());
     }
 
     #[test]
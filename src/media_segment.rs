@@ -5,10 +5,14 @@ use derive_builder::Builder;
 use shorthand::ShortHand;
 
 use crate::tags::{
-    ExtInf, ExtXByteRange, ExtXDateRange, ExtXDiscontinuity, ExtXKey, ExtXMap, ExtXProgramDateTime,
+    CueMarker, ExtInf, ExtXByteRange, ExtXDateRange, ExtXDiscontinuity, ExtXGap, ExtXKey, ExtXMap,
+    ExtXPart, ExtXProgramDateTime,
+};
+use crate::types::{DecryptionKey, ProtocolVersion, UriRef};
+use crate::{
+    Decryptable, DurationPrecision, RequiredVersion, TagVisitor, TagVisitorMut, UriKind,
+    VersionReason, Writer,
 };
-use crate::types::{DecryptionKey, ProtocolVersion};
-use crate::{Decryptable, RequiredVersion};
 
 /// A video is split into smaller chunks called [`MediaSegment`]s, which are
 /// specified by a uri and optionally a byte range.
@@ -31,6 +35,9 @@ use crate::{Decryptable, RequiredVersion};
 /// contain an Instantaneous Decoding Refresh (IDR); frames prior to the first
 /// IDR will be downloaded but possibly discarded.
 ///
+/// Segments are ordered by their [`MediaSegment::number`] first, so sorting a
+/// list of segments puts them into playback order.
+///
 /// [`MediaPlaylist`]: crate::MediaPlaylist
 #[derive(ShortHand, Debug, Clone, Builder, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[builder(setter(strip_option))]
@@ -80,7 +87,7 @@ pub struct MediaSegment<'a> {
     /// [`ExtXMap`]: crate::tags::ExtXMap
     /// [`KeyFormat`]: crate::types::KeyFormat
     /// [`EncryptionMethod`]: crate::types::EncryptionMethod
-    #[builder(default, setter(into))]
+    #[builder(default, setter(custom))]
     pub keys: Vec<ExtXKey<'a>>,
     /// This field specifies how to obtain the Media Initialization Section
     /// required to parse the applicable `MediaSegment`s.
@@ -96,6 +103,22 @@ pub struct MediaSegment<'a> {
     /// [`ExtXIFramesOnly`]: crate::tags::ExtXIFramesOnly
     #[builder(default)]
     pub map: Option<ExtXMap<'a>>,
+    /// Whether the [`MediaSegment::map`] declares a different Media
+    /// Initialization Section than the one of the previous segments, so a
+    /// client has to fetch the new initialization section before it can play
+    /// this segment.
+    ///
+    /// ## Note
+    ///
+    /// This field is assigned automatically by [`MediaPlaylistBuilder::build`].
+    /// A byte range without an offset starts at the beginning of the resource,
+    /// so `BYTERANGE="100"` and `BYTERANGE="100@0"` declare the same section.
+    ///
+    /// [`MediaPlaylistBuilder::build`]:
+    /// crate::builder::MediaPlaylistBuilder::build
+    #[builder(default, setter(skip))]
+    #[shorthand(disable(set, skip))]
+    pub(crate) map_changed: bool,
     /// This field indicates that a `MediaSegment` is a sub-range of the
     /// resource identified by its URI.
     ///
@@ -112,6 +135,19 @@ pub struct MediaSegment<'a> {
     /// This field is optional.
     #[builder(default)]
     pub date_range: Option<ExtXDateRange<'a>>,
+    /// The non-standard ad markers, that appear before the segment.
+    ///
+    /// They are only parsed, if [`ParseOptions::parse_cue_markers`] is
+    /// enabled.
+    ///
+    /// ## Note
+    ///
+    /// This field is optional.
+    ///
+    /// [`ParseOptions::parse_cue_markers`]:
+    /// crate::ParseOptions::parse_cue_markers
+    #[builder(default, setter(custom))]
+    pub cue_markers: Vec<CueMarker<'a>>,
     /// This field indicates a discontinuity between the `MediaSegment` that
     /// follows it and the one that preceded it.
     ///
@@ -128,6 +164,34 @@ pub struct MediaSegment<'a> {
     /// - encoding sequence
     #[builder(default)]
     pub has_discontinuity: bool,
+    /// This field indicates that the resource of the `MediaSegment` is not
+    /// available (for example because of an outage of the origin server) and
+    /// should not be loaded by clients.
+    ///
+    /// See [`MediaPlaylist::gap_runs`] and
+    /// [`MediaPlaylist::availability_ratio`].
+    ///
+    /// ## Note
+    ///
+    /// This field is optional and by default `false`.
+    ///
+    /// [`MediaPlaylist::gap_runs`]: crate::MediaPlaylist::gap_runs
+    /// [`MediaPlaylist::availability_ratio`]:
+    /// crate::MediaPlaylist::availability_ratio
+    #[builder(default)]
+    pub has_gap: bool,
+    /// The approximate bitrate of the `MediaSegment` in kbit/s.
+    ///
+    /// ## Note
+    ///
+    /// This field is optional. An `#EXT-X-BITRATE` tag applies to every
+    /// `MediaSegment` until the next `#EXT-X-BITRATE` tag, so it is only
+    /// written, if the bitrate differs from the one of the previous segment.
+    /// A segment without a bitrate, that follows a segment with a bitrate,
+    /// will have the bitrate of the previous segment, after the playlist has
+    /// been written and parsed again.
+    #[builder(default)]
+    pub bitrate: Option<u64>,
     /// This field associates the first sample of a media segment with an
     /// absolute date and/or time.
     ///
@@ -136,6 +200,14 @@ pub struct MediaSegment<'a> {
     /// This field is optional.
     #[builder(default)]
     pub program_date_time: Option<ExtXProgramDateTime<'a>>,
+    /// The partial segments of a Low-Latency HLS playlist, that contain the
+    /// media of this segment.
+    ///
+    /// ## Note
+    ///
+    /// This field is optional.
+    #[builder(default, setter(custom))]
+    pub parts: Vec<ExtXPart<'a>>,
     /// This field indicates the duration of a media segment.
     ///
     /// ## Note
@@ -143,6 +215,14 @@ pub struct MediaSegment<'a> {
     /// This field is required.
     #[builder(setter(into))]
     pub duration: ExtInf<'a>,
+    /// The comment lines, that appear between the `#EXTINF` tag and the uri
+    /// of the segment. They are written in the same place again.
+    ///
+    /// ## Note
+    ///
+    /// This field is optional.
+    #[builder(default, setter(custom))]
+    pub comments: Vec<Cow<'a, str>>,
     /// The URI of a media segment.
     ///
     /// ## Note
@@ -150,7 +230,7 @@ pub struct MediaSegment<'a> {
     /// This field is required.
     #[builder(setter(into))]
     #[shorthand(enable(into), disable(skip))]
-    uri: Cow<'a, str>,
+    uri: UriRef<'a>,
 }
 
 impl<'a> MediaSegment<'a> {
@@ -176,6 +256,30 @@ impl<'a> MediaSegment<'a> {
     #[inline]
     pub fn builder() -> MediaSegmentBuilder<'static> { MediaSegmentBuilder::default() }
 
+    /// Returns the exact number of bytes of the [`MediaSegment`], which is the
+    /// length of its [`MediaSegment::byte_range`].
+    ///
+    /// Without a byte range the segment is the whole resource, whose size is
+    /// not known, so `None` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaSegment;
+    /// use std::time::Duration;
+    ///
+    /// let segment = MediaSegment::builder()
+    ///     .byte_range(100..1100)
+    ///     .duration(Duration::from_secs(4))
+    ///     .uri("movie.mp4")
+    ///     .build()?;
+    ///
+    /// assert_eq!(segment.total_bytes(), Some(1000));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn total_bytes(&self) -> Option<usize> { self.byte_range.map(|range| range.len()) }
+
     /// Makes the struct independent of its lifetime, by taking ownership of all
     /// internal [`Cow`]s.
     ///
@@ -190,17 +294,41 @@ impl<'a> MediaSegment<'a> {
             explicit_number: self.explicit_number,
             keys: self.keys.into_iter().map(|k| k.into_owned()).collect(),
             map: self.map.map(|v| v.into_owned()),
+            map_changed: self.map_changed,
             byte_range: self.byte_range,
             date_range: self.date_range.map(|v| v.into_owned()),
+            cue_markers: self
+                .cue_markers
+                .into_iter()
+                .map(|v| v.into_owned())
+                .collect(),
             has_discontinuity: self.has_discontinuity,
+            has_gap: self.has_gap,
+            bitrate: self.bitrate,
             program_date_time: self.program_date_time.map(|v| v.into_owned()),
+            parts: self.parts.into_iter().map(|v| v.into_owned()).collect(),
+            comments: self
+                .comments
+                .into_iter()
+                .map(|v| Cow::Owned(v.into_owned()))
+                .collect(),
             duration: self.duration.into_owned(),
-            uri: Cow::Owned(self.uri.into_owned()),
+            uri: self.uri.into_owned(),
         }
     }
 }
 
 impl<'a> MediaSegmentBuilder<'a> {
+    /// Sets the [`ExtXKey`] tags, which specify how to decrypt the
+    /// [`MediaSegment`].
+    pub fn keys<I>(&mut self, value: I) -> &mut Self
+    where
+        I: IntoIterator<Item = ExtXKey<'a>>,
+    {
+        self.keys = Some(value.into_iter().collect());
+        self
+    }
+
     /// Pushes an [`ExtXKey`] tag.
     pub fn push_key<VALUE: Into<ExtXKey<'a>>>(&mut self, value: VALUE) -> &mut Self {
         if let Some(keys) = &mut self.keys {
@@ -212,6 +340,57 @@ impl<'a> MediaSegmentBuilder<'a> {
         self
     }
 
+    /// Sets the [`ExtXPart`] tags, which are the partial segments of the
+    /// [`MediaSegment`].
+    pub fn parts<I>(&mut self, value: I) -> &mut Self
+    where
+        I: IntoIterator<Item = ExtXPart<'a>>,
+    {
+        self.parts = Some(value.into_iter().collect());
+        self
+    }
+
+    /// Pushes an [`ExtXPart`] tag.
+    pub fn push_part(&mut self, value: ExtXPart<'a>) -> &mut Self {
+        self.parts.get_or_insert_with(Vec::new).push(value);
+        self
+    }
+
+    /// Sets the comment lines, that appear between the `#EXTINF` tag and the
+    /// uri of the [`MediaSegment`].
+    pub fn comments<I, T>(&mut self, value: I) -> &mut Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Cow<'a, str>>,
+    {
+        self.comments = Some(value.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Pushes a comment line, that appears between the `#EXTINF` tag and the
+    /// uri of the [`MediaSegment`].
+    pub fn push_comment<T: Into<Cow<'a, str>>>(&mut self, value: T) -> &mut Self {
+        self.comments
+            .get_or_insert_with(Vec::new)
+            .push(value.into());
+        self
+    }
+
+    /// Sets the [`CueMarker`]s, that appear before the [`MediaSegment`].
+    pub fn cue_markers<I>(&mut self, value: I) -> &mut Self
+    where
+        I: IntoIterator<Item = CueMarker<'a>>,
+    {
+        self.cue_markers = Some(value.into_iter().collect());
+        self
+    }
+
+    /// Pushes a [`CueMarker`].
+    pub fn push_cue_marker(&mut self, value: CueMarker<'a>) -> &mut Self {
+        self.cue_markers.get_or_insert_with(Vec::new).push(value);
+        self
+    }
+
     /// The number of a [`MediaSegment`]. Normally this should not be set
     /// explicitly, because the [`MediaPlaylist::builder`] will automatically
     /// apply the correct number.
@@ -225,14 +404,32 @@ impl<'a> MediaSegmentBuilder<'a> {
     }
 }
 
-impl<'a> fmt::Display for MediaSegment<'a> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // NOTE: self.keys will be printed by the `MediaPlaylist` to prevent redundance.
+impl<'a> MediaSegment<'a> {
+    pub(crate) fn write_to<W: fmt::Write>(
+        &self,
+        f: &mut W,
+        writer: &Writer,
+        precision: DurationPrecision,
+    ) -> fmt::Result {
+        // NOTE: self.keys and self.bitrate will be printed by the `MediaPlaylist` to
+        // prevent redundance.
 
-        if let Some(value) = &self.map {
+        for value in &self.cue_markers {
             writeln!(f, "{}", value)?;
         }
 
+        if let Some(value) = &self.map {
+            let uri = writer.uri(UriKind::Map, value.uri());
+
+            if uri == value.uri().as_ref() {
+                writeln!(f, "{}", value)?;
+            } else {
+                let mut value = value.clone();
+                value.set_uri(uri.into_owned());
+                writeln!(f, "{}", value)?;
+            }
+        }
+
         if let Some(value) = &self.byte_range {
             writeln!(f, "{}", value)?;
         }
@@ -245,14 +442,129 @@ impl<'a> fmt::Display for MediaSegment<'a> {
             writeln!(f, "{}", ExtXDiscontinuity)?;
         }
 
+        if self.has_gap {
+            writeln!(f, "{}", ExtXGap)?;
+        }
+
         if let Some(value) = &self.program_date_time {
+            writeln!(f, "{}", writer.program_date_time(value))?;
+        }
+
+        for value in &self.parts {
             writeln!(f, "{}", value)?;
         }
 
-        writeln!(f, "{}", self.duration)?;
-        writeln!(f, "{}", self.uri)?;
+        self.duration.write_to(f, precision)?;
+        writeln!(f)?;
+
+        for comment in &self.comments {
+            writeln!(f, "{}", comment)?;
+        }
+
+        writeln!(f, "{}", writer.uri(UriKind::Segment, &self.uri))?;
         Ok(())
     }
+
+    /// Visits the tags of the segment in the order in which they are written.
+    pub(crate) fn visit<V: TagVisitor<'a>>(&self, visitor: &mut V) {
+        visitor.visit_segment(self);
+
+        for key in &self.keys {
+            visitor.visit_key(key);
+        }
+
+        if let Some(value) = self.bitrate {
+            visitor.visit_bitrate(value);
+        }
+
+        for value in &self.cue_markers {
+            visitor.visit_cue_marker(value);
+        }
+
+        if let Some(value) = &self.map {
+            visitor.visit_map(value);
+        }
+
+        if let Some(value) = &self.byte_range {
+            visitor.visit_byte_range(value);
+        }
+
+        if let Some(value) = &self.date_range {
+            visitor.visit_date_range(value);
+        }
+
+        if self.has_discontinuity {
+            visitor.visit_discontinuity();
+        }
+
+        if self.has_gap {
+            visitor.visit_gap();
+        }
+
+        if let Some(value) = &self.program_date_time {
+            visitor.visit_program_date_time(value);
+        }
+
+        for value in &self.parts {
+            visitor.visit_part(value);
+        }
+
+        visitor.visit_inf(&self.duration);
+        visitor.visit_uri(&self.uri);
+    }
+
+    pub(crate) fn visit_mut<V: TagVisitorMut<'a>>(&mut self, visitor: &mut V) {
+        visitor.visit_segment(self);
+
+        for key in &mut self.keys {
+            visitor.visit_key(key);
+        }
+
+        if let Some(value) = &mut self.bitrate {
+            visitor.visit_bitrate(value);
+        }
+
+        for value in &mut self.cue_markers {
+            visitor.visit_cue_marker(value);
+        }
+
+        if let Some(value) = &mut self.map {
+            visitor.visit_map(value);
+        }
+
+        if let Some(value) = &mut self.byte_range {
+            visitor.visit_byte_range(value);
+        }
+
+        if let Some(value) = &mut self.date_range {
+            visitor.visit_date_range(value);
+        }
+
+        if self.has_discontinuity {
+            visitor.visit_discontinuity();
+        }
+
+        if self.has_gap {
+            visitor.visit_gap();
+        }
+
+        if let Some(value) = &mut self.program_date_time {
+            visitor.visit_program_date_time(value);
+        }
+
+        for value in &mut self.parts {
+            visitor.visit_part(value);
+        }
+
+        visitor.visit_inf(&mut self.duration);
+        visitor.visit_uri(&mut self.uri);
+    }
+}
+
+impl<'a> fmt::Display for MediaSegment<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_to(f, &Writer::default(), DurationPrecision::Shortest)
+    }
 }
 
 impl<'a> RequiredVersion for MediaSegment<'a> {
@@ -269,10 +581,22 @@ impl<'a> RequiredVersion for MediaSegment<'a> {
                     None
                 }
             },
+            {
+                if self.has_gap {
+                    Some(ExtXGap)
+                } else {
+                    None
+                }
+            },
             self.program_date_time,
+            self.parts,
             self.duration
         ]
     }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        required_version_reasons![self.keys, self.map, self.byte_range, self.duration]
+    }
 }
 
 impl<'a> Decryptable<'a> for MediaSegment<'a> {
@@ -282,6 +606,189 @@ impl<'a> Decryptable<'a> for MediaSegment<'a> {
     }
 }
 
+/// The tags of a [`MediaSegment`], that has not been completed yet (it has no
+/// `#EXTINF` tag and no uri).
+///
+/// At the live edge of a Low-Latency HLS playlist, the parts of the next
+/// segment are published before its `#EXTINF` tag. The tags between the last
+/// uri and the end of such a playlist are kept as the
+/// [`MediaPlaylist::pending_segment`].
+///
+/// [`MediaPlaylist::pending_segment`]: crate::MediaPlaylist::pending_segment
+#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub struct PendingSegment<'a> {
+    /// The keys, that apply to the segment (see [`MediaSegment::keys`]).
+    pub keys: Vec<ExtXKey<'a>>,
+    /// See [`MediaSegment::map`].
+    pub map: Option<ExtXMap<'a>>,
+    /// See [`MediaSegment::date_range`].
+    pub date_range: Option<ExtXDateRange<'a>>,
+    /// See [`MediaSegment::has_discontinuity`].
+    pub has_discontinuity: bool,
+    /// See [`MediaSegment::program_date_time`].
+    pub program_date_time: Option<ExtXProgramDateTime<'a>>,
+    /// The partial segments, that have already been published (see
+    /// [`MediaSegment::parts`]).
+    pub parts: Vec<ExtXPart<'a>>,
+}
+
+impl<'a> PendingSegment<'a> {
+    /// Makes a new, empty [`PendingSegment`].
+    #[must_use]
+    pub fn new() -> Self { Self::default() }
+
+    /// Makes the struct independent of its lifetime, by taking ownership of all
+    /// internal [`Cow`]s.
+    ///
+    /// # Note
+    ///
+    /// This is a relatively expensive operation.
+    ///
+    /// [`Cow`]: std::borrow::Cow
+    #[must_use]
+    #[allow(clippy::redundant_closure_for_method_calls)]
+    pub fn into_owned(self) -> PendingSegment<'static> {
+        PendingSegment {
+            keys: self.keys.into_iter().map(|k| k.into_owned()).collect(),
+            map: self.map.map(|v| v.into_owned()),
+            date_range: self.date_range.map(|v| v.into_owned()),
+            has_discontinuity: self.has_discontinuity,
+            program_date_time: self.program_date_time.map(|v| v.into_owned()),
+            parts: self.parts.into_iter().map(|v| v.into_owned()).collect(),
+        }
+    }
+
+    pub(crate) fn write_to<W: fmt::Write>(&self, f: &mut W, writer: &Writer) -> fmt::Result {
+        // NOTE: self.keys will be printed by the `MediaPlaylist` like the keys of the
+        // segments.
+
+        if let Some(value) = &self.map {
+            let mut value = value.clone();
+            let uri = writer.uri(UriKind::Map, value.uri()).into_owned();
+            value.set_uri(uri);
+            writeln!(f, "{}", value)?;
+        }
+
+        if let Some(value) = &self.date_range {
+            writeln!(f, "{}", value)?;
+        }
+
+        if self.has_discontinuity {
+            writeln!(f, "{}", ExtXDiscontinuity)?;
+        }
+
+        if let Some(value) = &self.program_date_time {
+            writeln!(f, "{}", writer.program_date_time(value))?;
+        }
+
+        for value in &self.parts {
+            writeln!(f, "{}", value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Visits the tags of the segment in the order in which they are written.
+    pub(crate) fn visit<V: TagVisitor<'a>>(&self, visitor: &mut V) {
+        visitor.visit_pending_segment(self);
+
+        for key in &self.keys {
+            visitor.visit_key(key);
+        }
+
+        if let Some(value) = &self.map {
+            visitor.visit_map(value);
+        }
+
+        if let Some(value) = &self.date_range {
+            visitor.visit_date_range(value);
+        }
+
+        if self.has_discontinuity {
+            visitor.visit_discontinuity();
+        }
+
+        if let Some(value) = &self.program_date_time {
+            visitor.visit_program_date_time(value);
+        }
+
+        for value in &self.parts {
+            visitor.visit_part(value);
+        }
+    }
+
+    pub(crate) fn visit_mut<V: TagVisitorMut<'a>>(&mut self, visitor: &mut V) {
+        visitor.visit_pending_segment(self);
+
+        for key in &mut self.keys {
+            visitor.visit_key(key);
+        }
+
+        if let Some(value) = &mut self.map {
+            visitor.visit_map(value);
+        }
+
+        if let Some(value) = &mut self.date_range {
+            visitor.visit_date_range(value);
+        }
+
+        if self.has_discontinuity {
+            visitor.visit_discontinuity();
+        }
+
+        if let Some(value) = &mut self.program_date_time {
+            visitor.visit_program_date_time(value);
+        }
+
+        for value in &mut self.parts {
+            visitor.visit_part(value);
+        }
+    }
+}
+
+impl<'a> RequiredVersion for PendingSegment<'a> {
+    fn required_version(&self) -> ProtocolVersion {
+        required_version![
+            self.keys,
+            self.map,
+            self.date_range,
+            {
+                if self.has_discontinuity {
+                    Some(ExtXDiscontinuity)
+                } else {
+                    None
+                }
+            },
+            self.program_date_time,
+            self.parts
+        ]
+    }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        required_version_reasons![self.keys, self.map]
+    }
+}
+
+impl<'a> MediaSegmentBuilder<'a> {
+    /// Returns the tags of a segment, that has neither an `#EXTINF` tag nor
+    /// an `#EXT-X-BYTERANGE` tag, nor an uri (see [`PendingSegment`]).
+    pub(crate) fn to_pending(&self, keys: Vec<ExtXKey<'a>>) -> Option<PendingSegment<'a>> {
+        if self.duration.is_some() || self.uri.is_some() || self.byte_range.flatten().is_some() {
+            return None;
+        }
+
+        Some(PendingSegment {
+            keys,
+            map: self.map.clone().flatten(),
+            date_range: self.date_range.clone().flatten(),
+            has_discontinuity: self.has_discontinuity.unwrap_or(false),
+            program_date_time: self.program_date_time.as_ref().cloned().flatten(),
+            parts: self.parts.clone().unwrap_or_default(),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -310,4 +817,27 @@ mod tests {
             .to_string()
         );
     }
+
+    #[test]
+    fn test_ord() {
+        let segment = |number: usize, uri: &'static str| {
+            MediaSegment::builder()
+                .number(Some(number))
+                .duration(Duration::from_secs(4))
+                .uri(uri)
+                .build()
+                .unwrap()
+        };
+
+        let mut segments = [segment(2, "a.ts"), segment(0, "c.ts"), segment(1, "b.ts")];
+        segments.sort();
+
+        assert_eq!(
+            segments
+                .iter()
+                .map(MediaSegment::number)
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+    }
 }
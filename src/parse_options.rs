@@ -0,0 +1,872 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::panic::RefUnwindSafe;
+use std::sync::Arc;
+
+use shorthand::ShortHand;
+
+use crate::attribute::AttributePairs;
+use crate::types::Value;
+use crate::utils::tag_name;
+use crate::{
+    master_playlist, media_playlist, Error, MasterPlaylist, MediaPlaylist, ProtocolCoverage,
+    ValidationReport,
+};
+
+/// Decides what happens, if the input violates a requirement of the
+/// specification, that the parser is able to recover from.
+#[non_exhaustive]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Strictness {
+    /// The violation is reported as a [`Severity::Warning`] and the parser
+    /// continues (for example the last of multiple `#EXT-X-TARGETDURATION`
+    /// tags is used).
+    ///
+    /// This is the default.
+    ///
+    /// [`Severity::Warning`]: crate::Severity::Warning
+    #[default]
+    Lenient,
+    /// Parsing fails with an [`Error`].
+    Strict,
+}
+
+/// Input, that has been ignored or changed by the parser.
+///
+/// It is passed to the handler of [`ParseOptions::on_ignored`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Ignored<'a> {
+    /// A tag, that is not supported by this crate.
+    ///
+    /// The tag is kept in the unknown tags of the playlist (like
+    /// [`MediaPlaylist::unknown`]), but it has no effect on the parsed
+    /// playlist. Tags, that are consumed by the parser (like the cue markers
+    /// with [`ParseOptions::parse_cue_markers`]), are not reported.
+    Tag {
+        /// The number of the line (starting at 1).
+        line: usize,
+        /// The whole tag (for example
+        /// `"#EXT-X-DEFINE:NAME=\"a\",VALUE=\"b\""`).
+        input: &'a str,
+    },
+    /// An attribute of a supported tag, that is not supported by this crate
+    /// and therefore discarded.
+    Attribute {
+        /// The number of the line (starting at 1).
+        line: usize,
+        /// The name of the tag without the leading `#`.
+        tag: &'a str,
+        /// The name of the attribute.
+        name: &'a str,
+        /// The value of the attribute. An enumerated-string (or any other
+        /// value, that is not a valid [`Value`]) is passed as a
+        /// [`Value::String`].
+        value: &'a Value<'a>,
+    },
+    /// A violation of the specification, that has been fixed in
+    /// [`Strictness::Lenient`] mode.
+    Violation(&'a Error),
+}
+
+/// Receives the [`Ignored`] input of the parser.
+///
+/// It is implemented for closures:
+///
+/// ```
+/// use hls_m3u8::{Ignored, ParseOptions};
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+///
+/// let count = Arc::new(AtomicUsize::new(0));
+/// let mut options = ParseOptions::new();
+///
+/// options.on_ignored({
+///     let count = Arc::clone(&count);
+///     move |ignored: &Ignored<'_>| {
+///         if let Ignored::Tag { .. } = ignored {
+///             count.fetch_add(1, Ordering::Relaxed);
+///         }
+///     }
+/// });
+///
+/// options.parse_media_playlist("#EXTM3U\n#EXT-X-TARGETDURATION:10\n#EXT-X-CUE-OUT:30\n")?;
+///
+/// assert_eq!(count.load(Ordering::Relaxed), 1);
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+pub trait IgnoredHandler: Send + Sync + RefUnwindSafe {
+    /// Is called for every [`Ignored`] input.
+    fn ignored(&self, ignored: &Ignored<'_>);
+}
+
+impl<F> IgnoredHandler for F
+where
+    F: Fn(&Ignored<'_>) + Send + Sync + RefUnwindSafe,
+{
+    fn ignored(&self, ignored: &Ignored<'_>) { self(ignored) }
+}
+
+/// Options for parsing a [`MediaPlaylist`] or a [`MasterPlaylist`].
+///
+/// The `FromStr` and `TryFrom<&str>` implementations of the playlists use the
+/// default options and discard the warnings.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::{ParseOptions, Strictness};
+///
+/// let input = concat!(
+///     "#EXTM3U\n",
+///     "#EXT-X-TARGETDURATION:10\n",
+///     "#EXT-X-TARGETDURATION:6\n",
+///     "#EXTINF:6,\n",
+///     "1.ts\n",
+/// );
+///
+/// let (playlist, report) = ParseOptions::new().parse_media_playlist(input)?;
+///
+/// assert_eq!(playlist.target_duration.as_secs(), 6);
+/// assert_eq!(
+///     report.to_string(),
+///     "warning: the tag EXT-X-TARGETDURATION must not appear more than once\n"
+/// );
+///
+/// let mut options = ParseOptions::new();
+/// options.set_strictness(Strictness::Strict);
+///
+/// assert!(options.parse_media_playlist(input).is_err());
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+#[derive(ShortHand, Default, Clone)]
+#[shorthand(enable(must_use, copy))]
+#[non_exhaustive]
+pub struct ParseOptions {
+    /// How violations of the specification are handled.
+    ///
+    /// By default this is [`Strictness::Lenient`].
+    strictness: Strictness,
+    /// Whether media playlist tags (like `#EXT-X-TARGETDURATION`) may appear
+    /// after the first media segment, without being reported as a violation.
+    ///
+    /// The `#EXT-X-DISCONTINUITY-SEQUENCE` and the `#EXT-X-SKIP` tag must
+    /// always appear before the first media segment, while the
+    /// `#EXT-X-ENDLIST` tag may appear anywhere.
+    ///
+    /// By default this is `false`.
+    allow_late_playlist_tags: bool,
+    /// Whether the non-standard `#EXT-X-CUE-OUT`, `#EXT-X-CUE-OUT-CONT` and
+    /// `#EXT-X-CUE-IN` tags are parsed into the [`MediaSegment::cue_markers`]
+    /// of the following segment, instead of being kept as unknown tags.
+    ///
+    /// By default this is `false`.
+    ///
+    /// [`MediaSegment::cue_markers`]: crate::MediaSegment::cue_markers
+    parse_cue_markers: bool,
+    #[shorthand(enable(skip))]
+    on_ignored: Option<Arc<dyn IgnoredHandler>>,
+}
+
+impl fmt::Debug for ParseOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParseOptions")
+            .field("strictness", &self.strictness)
+            .field("allow_late_playlist_tags", &self.allow_late_playlist_tags)
+            .field("parse_cue_markers", &self.parse_cue_markers)
+            .field("on_ignored", &self.on_ignored.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+impl PartialEq for ParseOptions {
+    fn eq(&self, other: &Self) -> bool {
+        let handler = |options: &Self| {
+            options
+                .on_ignored
+                .as_ref()
+                .map(|handler| Arc::as_ptr(handler).cast::<()>())
+        };
+
+        self.strictness == other.strictness
+            && self.allow_late_playlist_tags == other.allow_late_playlist_tags
+            && self.parse_cue_markers == other.parse_cue_markers
+            && handler(self) == handler(other)
+    }
+}
+
+impl Eq for ParseOptions {}
+
+impl Hash for ParseOptions {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.strictness.hash(state);
+        self.allow_late_playlist_tags.hash(state);
+        self.parse_cue_markers.hash(state);
+    }
+}
+
+impl ParseOptions {
+    /// Makes new [`ParseOptions`] with the default settings.
+    #[must_use]
+    pub fn new() -> Self { Self::default() }
+
+    /// Sets a handler, that is called with every line or attribute, that is
+    /// ignored by the parser, and with every violation, that is fixed in
+    /// [`Strictness::Lenient`] mode.
+    ///
+    /// This can be used to find out, how much of the parsed playlists is
+    /// not understood by this crate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::{Ignored, ParseOptions};
+    /// use std::sync::Mutex;
+    ///
+    /// let input = concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-DEFINE:NAME=\"a\",VALUE=\"b\"\n",
+    ///     "#EXT-X-STREAM-INF:BANDWIDTH=150000,PATHWAY-ID=\"CDN-A\"\n",
+    ///     "low.m3u8\n",
+    /// );
+    ///
+    /// let ignored = std::sync::Arc::new(Mutex::new(Vec::new()));
+    /// let mut options = ParseOptions::new();
+    ///
+    /// options.on_ignored({
+    ///     let ignored = ignored.clone();
+    ///     move |value: &Ignored<'_>| ignored.lock().unwrap().push(format!("{:?}", value))
+    /// });
+    ///
+    /// options.parse_master_playlist(input)?;
+    ///
+    /// assert_eq!(
+    ///     *ignored.lock().unwrap(),
+    ///     vec![
+    ///         "Tag { line: 2, input: \"#EXT-X-DEFINE:NAME=\\\"a\\\",VALUE=\\\"b\\\"\" }",
+    ///         concat!(
+    ///             "Attribute { line: 3, tag: \"EXT-X-STREAM-INF\", name: \"PATHWAY-ID\", ",
+    ///             "value: String(\"CDN-A\") }"
+    ///         ),
+    ///     ]
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    pub fn on_ignored<H: IgnoredHandler + 'static>(&mut self, handler: H) -> &mut Self {
+        self.on_ignored = Some(Arc::new(handler));
+        self
+    }
+
+    /// Parses a [`MediaPlaylist`] and returns it together with the warnings,
+    /// that have been found in lenient mode.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the input is not a valid [`MediaPlaylist`] or if it violates
+    /// the specification in [`Strictness::Strict`] mode.
+    pub fn parse_media_playlist<'a>(
+        &self,
+        input: &'a str,
+    ) -> crate::Result<(MediaPlaylist<'a>, ValidationReport)> {
+        let mut report = ValidationReport::default();
+
+        let playlist = match self.unfold(input, &mut report)? {
+            Cow::Borrowed(input) => {
+                media_playlist::parse_media_playlist(
+                    input,
+                    &mut MediaPlaylist::builder(),
+                    self,
+                    &mut report,
+                )?
+            }
+            Cow::Owned(input) => {
+                media_playlist::parse_media_playlist(
+                    &input,
+                    &mut MediaPlaylist::builder(),
+                    self,
+                    &mut report,
+                )?
+                .into_owned()
+            }
+        };
+
+        Ok((playlist, report))
+    }
+
+    /// Parses a [`MasterPlaylist`] and returns it together with the warnings,
+    /// that have been found in lenient mode.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the input is not a valid [`MasterPlaylist`] or if it violates
+    /// the specification in [`Strictness::Strict`] mode.
+    pub fn parse_master_playlist<'a>(
+        &self,
+        input: &'a str,
+    ) -> crate::Result<(MasterPlaylist<'a>, ValidationReport)> {
+        let mut report = ValidationReport::default();
+
+        let playlist = match self.unfold(input, &mut report)? {
+            Cow::Borrowed(input) => {
+                master_playlist::parse_master_playlist(input, self, &mut report)?
+            }
+            Cow::Owned(input) => {
+                master_playlist::parse_master_playlist(&input, self, &mut report)?.into_owned()
+            }
+        };
+
+        Ok((playlist, report))
+    }
+
+    /// Joins tags, that have been folded over multiple lines with a trailing
+    /// backslash, and removes carriage returns in the middle of a line.
+    ///
+    /// Comments and blank lines between the folded lines are skipped. Every
+    /// changed line is reported as a violation with its raw form. The input
+    /// is only copied, if it contains such a line.
+    ///
+    /// A folded tag is followed by one blank line for every line, that has
+    /// been joined, so the line numbers still refer to the raw input.
+    fn unfold<'a>(
+        &self,
+        input: &'a str,
+        report: &mut ValidationReport,
+    ) -> crate::Result<Cow<'a, str>> {
+        let is_folded = |raw: &str| {
+            let line = raw.trim();
+            line.starts_with("#EXT") && line.ends_with('\\')
+        };
+
+        if !input
+            .lines()
+            .any(|raw| raw.contains('\r') || is_folded(raw))
+        {
+            return Ok(Cow::Borrowed(input));
+        }
+
+        let mut result = String::with_capacity(input.len());
+        let mut lines = input.lines();
+
+        while let Some(raw) = lines.next() {
+            let mut line = raw.trim().to_string();
+
+            if line.contains('\r') {
+                self.violation(
+                    report,
+                    Error::custom(format!("the line {:?} contains a carriage return", raw)),
+                )?;
+
+                line.retain(|c| c != '\r');
+            }
+
+            let mut joined_lines = 0;
+
+            if is_folded(&line) {
+                let mut raw = raw.to_string();
+
+                while line.ends_with('\\') {
+                    line.pop();
+                    line.truncate(line.trim_end().len());
+
+                    let next = loop {
+                        match lines.next() {
+                            Some(next) => {
+                                joined_lines += 1;
+                                raw.push('\n');
+                                raw.push_str(next);
+
+                                let next = next.trim();
+
+                                if !next.is_empty()
+                                    && (!next.starts_with('#') || next.starts_with("#EXT"))
+                                {
+                                    break next;
+                                }
+                            }
+                            None => break "",
+                        }
+                    };
+
+                    line.push_str(next);
+                    line.retain(|c| c != '\r');
+                }
+
+                self.violation(
+                    report,
+                    Error::custom(format!("the tag {:?} is folded over multiple lines", raw)),
+                )?;
+            }
+
+            result.push_str(&line);
+            result.push('\n');
+
+            for _ in 0..joined_lines {
+                result.push('\n');
+            }
+        }
+
+        Ok(Cow::Owned(result))
+    }
+
+    /// Fails with the `error` in strict mode, otherwise it is added to the
+    /// `report` as a warning.
+    pub(crate) fn violation(
+        &self,
+        report: &mut ValidationReport,
+        error: Error,
+    ) -> crate::Result<()> {
+        match self.strictness {
+            Strictness::Strict => Err(error),
+            Strictness::Lenient => {
+                if let Some(handler) = &self.on_ignored {
+                    handler.ignored(&Ignored::Violation(&error));
+                }
+
+                report.push_violation(&error);
+                Ok(())
+            }
+        }
+    }
+
+    /// Passes an unknown tag, that is kept in the unknown tags of the
+    /// playlist, to the [`IgnoredHandler`].
+    pub(crate) fn report_ignored_tag(&self, number: usize, input: &str) {
+        if let Some(handler) = &self.on_ignored {
+            handler.ignored(&Ignored::Tag {
+                line: number,
+                input,
+            });
+        }
+    }
+
+    /// Passes the attributes of the supported tag in the `line`, that are not
+    /// supported, to the [`IgnoredHandler`].
+    pub(crate) fn report_ignored_attributes(&self, number: usize, line: &str) {
+        let handler = match &self.on_ignored {
+            Some(handler) => handler,
+            None => return,
+        };
+
+        let name = tag_name(line);
+        let coverage = ProtocolCoverage::report();
+
+        // tags without an attribute list have no attributes to ignore
+        match coverage.tag(name) {
+            Some(tag) if !tag.attributes().is_empty() => {}
+            _ => return,
+        }
+
+        let attributes = line.find(':').map_or("", |index| &line[index + 1..]);
+
+        for (key, value) in AttributePairs::new(attributes) {
+            // client attributes are kept by the `#EXT-X-DATERANGE` tag
+            let is_client_attribute = name == "EXT-X-DATERANGE" && key.starts_with("X-");
+
+            if !is_client_attribute && !coverage.supports_attribute(name, key) {
+                let value = Value::try_from(value).unwrap_or_else(|_| Value::string(value));
+
+                handler.ignored(&Ignored::Attribute {
+                    line: number,
+                    tag: name,
+                    name: key,
+                    value: &value,
+                });
+            }
+        }
+    }
+
+    /// Reports a violation, if the `tag` has already been `seen`.
+    pub(crate) fn check_once(
+        &self,
+        report: &mut ValidationReport,
+        seen: &mut HashSet<&'static str>,
+        tag: &'static str,
+    ) -> crate::Result<()> {
+        if seen.insert(tag) {
+            Ok(())
+        } else {
+            self.violation(report, Error::duplicate_tag(tag))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Diagnostic;
+    use pretty_assertions::assert_eq;
+    use std::sync::Mutex;
+
+    fn strict() -> ParseOptions {
+        let mut options = ParseOptions::new();
+        options.set_strictness(Strictness::Strict);
+        options
+    }
+
+    #[test]
+    fn test_duplicate_media_playlist_tags() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-VERSION:3\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-MEDIA-SEQUENCE:1\n",
+            "#EXT-X-VERSION:3\n",
+            "#EXT-X-MEDIA-SEQUENCE:2\n",
+            "#EXTINF:10,\n",
+            "1.ts\n",
+            "#EXT-X-ENDLIST\n",
+        );
+
+        let (playlist, report) = ParseOptions::new().parse_media_playlist(input).unwrap();
+
+        assert_eq!(playlist.media_sequence, 2);
+        assert_eq!(
+            report.to_string(),
+            concat!(
+                "warning: the tag EXT-X-VERSION must not appear more than once\n",
+                "warning: the tag EXT-X-MEDIA-SEQUENCE must not appear more than once\n",
+            )
+        );
+
+        assert_eq!(
+            strict().parse_media_playlist(input).unwrap_err(),
+            Error::duplicate_tag("EXT-X-VERSION")
+        );
+    }
+
+    #[test]
+    fn test_duplicate_master_playlist_tags() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-INDEPENDENT-SEGMENTS\n",
+            "#EXT-X-INDEPENDENT-SEGMENTS\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+            "low.m3u8\n",
+        );
+
+        let (_, report) = ParseOptions::new().parse_master_playlist(input).unwrap();
+
+        assert_eq!(report.warnings().count(), 1);
+        assert!(strict().parse_master_playlist(input).is_err());
+    }
+
+    #[test]
+    fn test_folded_lines() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=150000,\\\n",
+            "# a comment\n",
+            "\n",
+            "  CODECS=\"avc1.42e00a,mp4a.40.2\",\\\n",
+            "RESOLUTION=416x234\n",
+            "low.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=240000,\rRESOLUTION=416x234\n",
+            "mid.m3u8\n",
+        );
+
+        let (playlist, report) = ParseOptions::new().parse_master_playlist(input).unwrap();
+
+        assert_eq!(playlist.variant_streams.len(), 2);
+        assert_eq!(
+            playlist.variant_streams[0].to_string(),
+            concat!(
+                "#EXT-X-STREAM-INF:BANDWIDTH=150000,",
+                "CODECS=\"avc1.42e00a,mp4a.40.2\",",
+                "RESOLUTION=416x234\n",
+                "low.m3u8"
+            )
+        );
+        assert_eq!(
+            report.to_string(),
+            concat!(
+                "warning: the tag \"#EXT-X-STREAM-INF:BANDWIDTH=150000,\\\\\\n# a comment\\n\\n  ",
+                "CODECS=\\\"avc1.42e00a,mp4a.40.2\\\",\\\\\\nRESOLUTION=416x234\" is folded over ",
+                "multiple lines\n",
+                "warning: the line \"#EXT-X-STREAM-INF:BANDWIDTH=240000,\\rRESOLUTION=416x234\" ",
+                "contains a carriage return\n",
+            )
+        );
+        assert!(strict().parse_master_playlist(input).is_err());
+
+        let input = concat!(
+            "#EXTM3U\r\n",
+            "#EXT-X-TARGETDURATION:10\r\n",
+            "#EXT-X-KEY:METHOD=AES-128,\\\r\n",
+            "URI=\"key\"\r\n",
+            "#EXTINF:10,\r\n",
+            "1.ts\r\n",
+        );
+
+        let (playlist, report) = ParseOptions::new().parse_media_playlist(input).unwrap();
+
+        assert_eq!(playlist.segments[0].keys.len(), 1);
+        assert_eq!(report.warnings().count(), 1);
+
+        let (_, report) = strict()
+            .parse_media_playlist("#EXTM3U\r\n#EXT-X-TARGETDURATION:10\r\n")
+            .unwrap();
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn test_unfold() {
+        let mut report = ValidationReport::default();
+
+        // a backslash, that does not fold a tag, does not copy the input:
+        let input = "#EXTM3U\n#EXT-X-TARGETDURATION:10\n#EXTINF:10,\nC:\\1.ts\r\n";
+        assert!(matches!(
+            ParseOptions::new().unfold(input, &mut report).unwrap(),
+            Cow::Borrowed(_)
+        ));
+        assert!(report.is_empty());
+
+        // the line numbers refer to the raw input:
+        let error = ParseOptions::new()
+            .parse_media_playlist(concat!(
+                "#EXTM3U\n",
+                "#EXT-X-TARGETDURATION:10\n",
+                "#EXT-X-KEY:METHOD=AES-128,\\\n",
+                "# a comment\n",
+                "URI=\"key\"\n",
+                "#EXT-X-SESSION-DATA:DATA-ID=\"com.example.title\",VALUE=\"title\"\n",
+            ))
+            .unwrap_err();
+
+        assert_eq!(error.line(), Some(6));
+    }
+
+    #[test]
+    fn test_header_attributes() {
+        let input = "#EXTM3U TOOL=packager\n#EXT-X-TARGETDURATION:10\n";
+
+        let (playlist, report) = ParseOptions::new().parse_media_playlist(input).unwrap();
+
+        assert_eq!(playlist.header_attributes.as_deref(), Some("TOOL=packager"));
+        assert_eq!(report.warnings().count(), 1);
+        assert!(strict().parse_media_playlist(input).is_err());
+
+        assert!(strict()
+            .parse_media_playlist("#EXTM3U \n#EXT-X-TARGETDURATION:10\n")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_tag_order() {
+        let input = concat!(
+            "\n",
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXTINF:10,\n",
+            "#EXTINF:10,\n",
+            "1.ts\n",
+            "#EXT-X-MEDIA-SEQUENCE:1\n",
+            "#EXT-X-ENDLIST\n",
+            "#EXT-X-DISCONTINUITY\n",
+        );
+
+        let (playlist, report) = ParseOptions::new().parse_media_playlist(input).unwrap();
+
+        assert_eq!(playlist.media_sequence, 1);
+        assert_eq!(
+            report
+                .diagnostics()
+                .iter()
+                .map(Diagnostic::line)
+                .collect::<Vec<_>>(),
+            vec![Some(4), Some(7), Some(9)]
+        );
+        assert_eq!(
+            report.to_string(),
+            concat!(
+                "warning: the tag EXTINF in line 4 must be followed by a media segment\n",
+                "warning: the tag EXT-X-MEDIA-SEQUENCE in line 7 must appear before the first ",
+                "media segment\n",
+                "warning: the tag EXT-X-DISCONTINUITY in line 9 must be followed by a media ",
+                "segment\n",
+            )
+        );
+
+        let error = strict().parse_media_playlist(input).unwrap_err();
+        assert_eq!(error.line(), Some(4));
+
+        let mut options = ParseOptions::new();
+        options.set_allow_late_playlist_tags(true);
+
+        let (_, report) = options.parse_media_playlist(input).unwrap();
+        assert_eq!(report.diagnostics().len(), 2);
+    }
+
+    #[test]
+    fn test_misplaced_tags() {
+        let error = ParseOptions::new()
+            .parse_media_playlist(concat!(
+                "#EXTM3U\n",
+                "#EXT-X-TARGETDURATION:10\n",
+                "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+                "low.m3u8\n",
+            ))
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "the tag EXT-X-STREAM-INF in line 3 must not appear in a media playlist"
+        );
+
+        let error = ParseOptions::new()
+            .parse_master_playlist(concat!(
+                "#EXTM3U\n",
+                "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+                "low.m3u8\n",
+                "#EXTINF:10,\n",
+            ))
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "the tag EXTINF in line 4 must not appear in a master playlist"
+        );
+
+        let error = ParseOptions::new()
+            .parse_media_playlist(concat!(
+                "#EXTM3U\n",
+                "#EXT-X-TARGETDURATION:10\n",
+                "#EXTINF:10,\n",
+                "1.ts\n",
+                "#EXT-X-DISCONTINUITY-SEQUENCE:1\n",
+            ))
+            .unwrap_err();
+
+        assert_eq!(error.line(), Some(5));
+    }
+
+    #[test]
+    fn test_on_ignored() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-CUE-OUT:30\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"key\",UNKNOWN=1,OTHER=VALUE\n",
+            "#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2010-02-19T14:54:23.031+08:00\",X-COM-A=1\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXTINF:10,\n",
+            "1.ts\n",
+        );
+
+        let ignored = Arc::new(Mutex::new(Vec::new()));
+        let mut options = ParseOptions::new();
+
+        options.on_ignored({
+            let ignored = Arc::clone(&ignored);
+            move |value: &Ignored<'_>| {
+                let value = match value {
+                    Ignored::Tag { line, input } => format!("tag {} {}", line, input),
+                    Ignored::Attribute {
+                        line,
+                        tag,
+                        name,
+                        value,
+                    } => format!("attribute {} {} {}={}", line, tag, name, value),
+                    Ignored::Violation(error) => format!("violation {}", error),
+                };
+
+                ignored.lock().unwrap().push(value);
+            }
+        });
+
+        let (playlist, _) = options.parse_media_playlist(input).unwrap();
+
+        assert_eq!(playlist.segments[0].keys.len(), 1);
+        assert_eq!(
+            *ignored.lock().unwrap(),
+            vec![
+                "tag 3 #EXT-X-CUE-OUT:30".to_string(),
+                "attribute 4 EXT-X-KEY UNKNOWN=1".to_string(),
+                "attribute 4 EXT-X-KEY OTHER=\"VALUE\"".to_string(),
+                "violation the tag EXT-X-TARGETDURATION must not appear more than once".to_string(),
+            ]
+        );
+
+        // violations are not passed to the handler in strict mode:
+        ignored.lock().unwrap().clear();
+        options.set_strictness(Strictness::Strict);
+
+        assert!(options.parse_media_playlist(input).is_err());
+        assert_eq!(ignored.lock().unwrap().len(), 3);
+
+        // consumed cue markers are not reported:
+        ignored.lock().unwrap().clear();
+        options.set_strictness(Strictness::Lenient);
+        options.set_parse_cue_markers(true);
+
+        let (playlist, _) = options.parse_media_playlist(input).unwrap();
+
+        assert_eq!(playlist.segments[0].cue_markers.len(), 1);
+        assert!(!ignored
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|value| value.starts_with("tag")));
+        options.set_parse_cue_markers(false);
+
+        // the options are only equal, if they share the same handler:
+        assert_eq!(options.clone(), options);
+        assert_ne!(ParseOptions::new(), options);
+    }
+
+    #[test]
+    fn test_cue_markers() {
+        use crate::tags::CueMarker;
+        use std::borrow::Cow;
+        use std::time::Duration;
+
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXTINF:10,\n",
+            "content1.ts\n",
+            "#EXT-X-CUE-OUT:20\n",
+            "#EXTINF:10,\n",
+            "ad1.ts\n",
+            "#EXT-X-CUE-OUT-CONT:10/20\n",
+            "#EXTINF:10,\n",
+            "ad2.ts\n",
+            "#EXT-X-CUE-IN\n",
+            "#EXTINF:10,\n",
+            "content2.ts\n",
+            "#EXT-X-CUE-OUT:30\n",
+        );
+
+        let (playlist, _) = ParseOptions::new().parse_media_playlist(input).unwrap();
+
+        assert!(playlist.segments.values().all(|s| s.cue_markers.is_empty()));
+        assert_eq!(playlist.unknown.len(), 4);
+
+        let mut options = ParseOptions::new();
+        options.set_parse_cue_markers(true);
+
+        let (playlist, _) = options.parse_media_playlist(input).unwrap();
+
+        assert_eq!(
+            playlist.segments[1].cue_markers,
+            vec![CueMarker::Out {
+                duration: Some(Duration::from_secs(20))
+            }]
+        );
+        assert_eq!(
+            playlist.segments[2].cue_markers,
+            vec![CueMarker::Cont {
+                elapsed: Some(Duration::from_secs(10)),
+                duration: Some(Duration::from_secs(20)),
+                scte35: None,
+            }]
+        );
+        assert_eq!(playlist.segments[3].cue_markers, vec![CueMarker::In]);
+        // a marker after the last segment is kept as an unknown tag:
+        assert_eq!(playlist.unknown, vec![Cow::Borrowed("#EXT-X-CUE-OUT:30")]);
+
+        assert_eq!(playlist.to_string(), input);
+    }
+}
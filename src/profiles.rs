@@ -0,0 +1,321 @@
+//! Named device profiles, that describe which playlists a device is able to
+//! play.
+//!
+//! The predefined profiles are based on the documented capabilities of the
+//! devices and should be verified against the devices, that have to be
+//! supported. Custom profiles can be created with [`Profile::new`].
+//!
+//! # Example
+//!
+//! ```
+//! use hls_m3u8::profiles::{self, Profile};
+//! use hls_m3u8::types::ProtocolVersion;
+//! use hls_m3u8::MediaPlaylist;
+//! use std::convert::TryFrom;
+//!
+//! let playlist = MediaPlaylist::try_from(concat!(
+//!     "#EXTM3U\n",
+//!     "#EXT-X-VERSION:6\n",
+//!     "#EXT-X-TARGETDURATION:10\n",
+//!     "#EXT-X-MAP:URI=\"init.mp4\"\n",
+//!     "#EXTINF:10,\n",
+//!     "1.m4s\n",
+//! ))?;
+//!
+//! assert!(playlist.check_profile(profiles::APPLE_TV_TVOS_12).is_ok());
+//! assert!(!playlist.check_profile(profiles::CHROMECAST_1ST_GEN).is_ok());
+//!
+//! let profile = Profile::new("Set-top box", ProtocolVersion::V7, &["EXT-X-DATERANGE"]);
+//! assert!(playlist.check_profile(profile).is_ok());
+//! # Ok::<(), hls_m3u8::Error>(())
+//! ```
+use crate::types::ProtocolVersion;
+use crate::{MediaPlaylist, RequiredVersion, Severity, ValidationReport};
+
+/// The tags of the low-latency extension, that are not supported by older
+/// devices.
+const LOW_LATENCY_TAGS: &[&str] = &[
+    "EXT-X-PART-INF",
+    "EXT-X-SERVER-CONTROL",
+    "EXT-X-PART",
+    "EXT-X-SKIP",
+    "EXT-X-PRELOAD-HINT",
+    "EXT-X-RENDITION-REPORT",
+];
+
+/// The capabilities of a device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Profile {
+    name: &'static str,
+    max_version: ProtocolVersion,
+    forbidden_tags: &'static [&'static str],
+}
+
+impl Profile {
+    /// Makes a new [`Profile`].
+    ///
+    /// The `forbidden_tags` are the names of the tags (like `EXT-X-MAP`),
+    /// that the device is unable to play. The leading `#` is optional.
+    #[must_use]
+    pub const fn new(
+        name: &'static str,
+        max_version: ProtocolVersion,
+        forbidden_tags: &'static [&'static str],
+    ) -> Self {
+        Self {
+            name,
+            max_version,
+            forbidden_tags,
+        }
+    }
+
+    /// Returns the name of the device.
+    #[must_use]
+    pub const fn name(&self) -> &'static str { self.name }
+
+    /// Returns the highest [`ProtocolVersion`], the device supports.
+    #[must_use]
+    pub const fn max_version(&self) -> ProtocolVersion { self.max_version }
+
+    /// Returns the names of the tags, the device does not support.
+    #[must_use]
+    pub const fn forbidden_tags(&self) -> &'static [&'static str] { self.forbidden_tags }
+
+    /// Returns `true`, if the device does not support the tag with the given
+    /// name. The leading `#` is optional.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::profiles;
+    ///
+    /// assert!(profiles::CHROMECAST_1ST_GEN.is_forbidden("#EXT-X-MAP"));
+    /// assert!(!profiles::CHROMECAST_1ST_GEN.is_forbidden("EXTINF"));
+    /// ```
+    #[must_use]
+    pub fn is_forbidden(&self, tag: &str) -> bool {
+        let tag = tag.strip_prefix('#').unwrap_or(tag);
+
+        self.forbidden_tags
+            .iter()
+            .any(|forbidden| forbidden.strip_prefix('#').unwrap_or(forbidden) == tag)
+    }
+
+    pub(crate) fn check_media_playlist(&self, playlist: &MediaPlaylist<'_>) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        if playlist.required_version() > self.max_version {
+            let reasons = playlist
+                .required_version_reasons()
+                .into_iter()
+                .filter(|reason| reason.version() > self.max_version)
+                .map(|reason| reason.to_string())
+                .collect::<Vec<_>>();
+
+            report.push(
+                Severity::Error,
+                None,
+                format!(
+                    "the playlist requires V{}, but {} supports at most V{}: {}",
+                    playlist.required_version(),
+                    self.name,
+                    self.max_version,
+                    reasons.join(", ")
+                ),
+            );
+        }
+
+        let tags = [
+            ("EXT-X-MEDIA-SEQUENCE", playlist.media_sequence != 0),
+            (
+                "EXT-X-DISCONTINUITY-SEQUENCE",
+                playlist.discontinuity_sequence != 0,
+            ),
+            ("EXT-X-PLAYLIST-TYPE", playlist.playlist_type.is_some()),
+            ("EXT-X-I-FRAMES-ONLY", playlist.has_i_frames_only),
+            (
+                "EXT-X-INDEPENDENT-SEGMENTS",
+                playlist.has_independent_segments,
+            ),
+            ("EXT-X-START", playlist.start.is_some()),
+            ("EXT-X-SERVER-CONTROL", playlist.server_control.is_some()),
+            ("EXT-X-PART-INF", playlist.part_target.is_some()),
+            ("EXT-X-SKIP", playlist.skip.is_some()),
+            ("EXT-X-PRELOAD-HINT", !playlist.preload_hints.is_empty()),
+            (
+                "EXT-X-RENDITION-REPORT",
+                !playlist.rendition_reports.is_empty(),
+            ),
+            ("EXT-X-ENDLIST", playlist.has_end_list),
+        ];
+
+        for (tag, _) in tags.iter().filter(|(_, is_present)| *is_present) {
+            self.check_tag(&mut report, None, tag);
+        }
+
+        for segment in playlist.segments.values() {
+            let tags = [
+                ("EXT-X-KEY", !segment.keys.is_empty()),
+                ("EXT-X-MAP", segment.map.is_some()),
+                ("EXT-X-BYTERANGE", segment.byte_range.is_some()),
+                ("EXT-X-DATERANGE", segment.date_range.is_some()),
+                ("EXT-X-DISCONTINUITY", segment.has_discontinuity),
+                ("EXT-X-GAP", segment.has_gap),
+                ("EXT-X-BITRATE", segment.bitrate.is_some()),
+                (
+                    "EXT-X-PROGRAM-DATE-TIME",
+                    segment.program_date_time.is_some(),
+                ),
+            ];
+
+            for (tag, _) in tags.iter().filter(|(_, is_present)| *is_present) {
+                self.check_tag(&mut report, Some(segment.number), tag);
+            }
+        }
+
+        for (position, line) in &playlist.positioned_tags {
+            let tag = line.split(':').next().unwrap_or(line);
+
+            self.check_tag(&mut report, Some(*position), tag);
+        }
+
+        for line in &playlist.unknown {
+            let tag = line.split(':').next().unwrap_or(line);
+
+            self.check_tag(&mut report, None, tag);
+        }
+
+        report
+    }
+
+    fn check_tag(&self, report: &mut ValidationReport, segment: Option<usize>, tag: &str) {
+        if self.is_forbidden(tag) {
+            report.push(
+                Severity::Error,
+                segment,
+                format!(
+                    "{} is not supported by {}",
+                    tag.strip_prefix('#').unwrap_or(tag),
+                    self.name
+                ),
+            );
+        }
+    }
+}
+
+/// Apple TV with tvOS 12, which supports all versions, but not the
+/// low-latency extension.
+pub const APPLE_TV_TVOS_12: Profile =
+    Profile::new("Apple TV tvOS 12", ProtocolVersion::V7, LOW_LATENCY_TAGS);
+
+/// The first generation of the Chromecast, which does not support fragmented
+/// MPEG-4 segments.
+pub const CHROMECAST_1ST_GEN: Profile = Profile::new(
+    "Chromecast 1st gen",
+    ProtocolVersion::V5,
+    &[
+        "EXT-X-MAP",
+        "EXT-X-PART-INF",
+        "EXT-X-SERVER-CONTROL",
+        "EXT-X-PART",
+        "EXT-X-SKIP",
+        "EXT-X-PRELOAD-HINT",
+        "EXT-X-RENDITION-REPORT",
+    ],
+);
+
+/// All predefined profiles.
+pub const PROFILES: &[Profile] = &[APPLE_TV_TVOS_12, CHROMECAST_1ST_GEN];
+
+/// Returns the predefined [`Profile`] with the given name.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::profiles;
+///
+/// assert_eq!(
+///     profiles::find("Chromecast 1st gen"),
+///     Some(profiles::CHROMECAST_1ST_GEN)
+/// );
+/// assert_eq!(profiles::find("Unknown device"), None);
+/// ```
+#[must_use]
+pub fn find(name: &str) -> Option<Profile> {
+    PROFILES
+        .iter()
+        .copied()
+        .find(|profile| profile.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Diagnostic;
+    use pretty_assertions::assert_eq;
+    use std::convert::TryFrom;
+
+    fn playlist() -> MediaPlaylist<'static> {
+        MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-VERSION:6\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-PART-INF:PART-TARGET=1.0\n",
+            "#EXT-X-MAP:URI=\"init.mp4\"\n",
+            "#EXTINF:10,\n",
+            "1.m4s\n",
+            "#EXTINF:10,\n",
+            "2.m4s\n",
+        ))
+        .unwrap()
+        .into_owned()
+    }
+
+    #[test]
+    fn test_check_profile() {
+        let report = playlist().check_profile(CHROMECAST_1ST_GEN);
+
+        assert_eq!(
+            report
+                .diagnostics()
+                .iter()
+                .map(Diagnostic::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "error: the playlist requires V6, but Chromecast 1st gen supports at most V5: EXT-X-MAP requires V6".to_string(),
+                "error: EXT-X-PART-INF is not supported by Chromecast 1st gen".to_string(),
+                "error (segment 0): EXT-X-MAP is not supported by Chromecast 1st gen".to_string(),
+            ]
+        );
+
+        let report = playlist().check_profile(APPLE_TV_TVOS_12);
+
+        assert_eq!(report.errors().count(), 1);
+        assert_eq!(
+            report.diagnostics()[0].message(),
+            "EXT-X-PART-INF is not supported by Apple TV tvOS 12"
+        );
+
+        let profile = Profile::new("Custom", ProtocolVersion::V7, &["#EXT-X-DISCONTINUITY"]);
+        assert!(playlist().check_profile(profile).is_empty());
+    }
+
+    #[test]
+    fn test_is_forbidden() {
+        let profile = Profile::new("Custom", ProtocolVersion::V7, &["#EXT-X-MAP", "EXT-X-KEY"]);
+
+        assert!(profile.is_forbidden("EXT-X-MAP"));
+        assert!(profile.is_forbidden("#EXT-X-KEY"));
+        assert!(!profile.is_forbidden("EXT-X-BYTERANGE"));
+    }
+
+    #[test]
+    fn test_unique_names() {
+        for (i, profile) in PROFILES.iter().enumerate() {
+            assert_eq!(find(profile.name()), Some(*profile));
+            assert!(PROFILES[i + 1..]
+                .iter()
+                .all(|other| other.name() != profile.name()));
+        }
+    }
+}
@@ -0,0 +1,805 @@
+//! Decoding of the SCTE-35 splice information, that is carried in the
+//! `SCTE35-CMD`, `SCTE35-OUT` and `SCTE35-IN` attributes of an
+//! [`ExtXDateRange`].
+//!
+//! The attributes contain a hex encoded `splice_info_section` (see
+//! [SCTE-35]), which can be decoded into a [`SpliceInfo`]. The splice insert
+//! and time signal commands and the segmentation descriptors are decoded,
+//! all other commands and descriptors are preserved as raw bytes.
+//!
+//! Encrypted sections are not supported.
+//!
+//! # Example
+//!
+//! ```
+//! use hls_m3u8::scte35::{SpliceCommand, SpliceDescriptor};
+//! use hls_m3u8::tags::ExtXDateRange;
+//! use std::convert::TryFrom;
+//!
+//! let date_range = ExtXDateRange::try_from(concat!(
+//!     "#EXT-X-DATERANGE:ID=\"splice-6FFFFFF0\",START-DATE=\"2014-03-05T11:15:00Z\",",
+//!     "PLANNED-DURATION=307,SCTE35-OUT=0xFC3034000000000000FFFFF00506FE72BD0050001E021C43554549",
+//!     "4800008E7FCF0001A599B00808000000002CA0A18A3402009AC9D17E"
+//! ))?;
+//!
+//! let splice_info = date_range.splice_info_out().unwrap()?;
+//!
+//! assert_eq!(
+//!     splice_info.command(),
+//!     &SpliceCommand::TimeSignal {
+//!         pts_time: Some(0x0_72BD_0050)
+//!     }
+//! );
+//!
+//! if let SpliceDescriptor::Segmentation(descriptor) = &splice_info.descriptors()[0] {
+//!     assert_eq!(descriptor.event_id(), 0x4800_008E);
+//!     assert_eq!(descriptor.type_id(), 0x34);
+//! }
+//! # Ok::<(), hls_m3u8::Error>(())
+//! ```
+//!
+//! [`ExtXDateRange`]: crate::tags::ExtXDateRange
+//! [SCTE-35]: https://www.scte.org/standards/library/catalog/scte-35-digital-program-insertion-cueing-message/
+use std::str::FromStr;
+use std::time::Duration;
+
+use shorthand::ShortHand;
+
+use crate::Error;
+
+/// The frequency of the timestamps and durations in a [`SpliceInfo`].
+pub const TICKS_PER_SECOND: u64 = 90_000;
+
+/// Converts a timestamp or a duration in 90 kHz ticks into a [`Duration`].
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::scte35;
+/// use std::time::Duration;
+///
+/// assert_eq!(
+///     scte35::ticks_to_duration(27_630_000),
+///     Duration::from_secs(307)
+/// );
+/// ```
+#[must_use]
+pub fn ticks_to_duration(ticks: u64) -> Duration {
+    Duration::from_secs(ticks / TICKS_PER_SECOND)
+        + Duration::from_nanos((ticks % TICKS_PER_SECOND) * 1_000_000_000 / TICKS_PER_SECOND)
+}
+
+/// A decoded `splice_info_section`.
+#[derive(ShortHand, Debug, Clone, PartialEq, Eq, Hash)]
+#[shorthand(enable(must_use), disable(set))]
+pub struct SpliceInfo {
+    /// The version of the SCTE-35 protocol (currently always `0`).
+    #[shorthand(enable(copy))]
+    protocol_version: u8,
+    /// The offset in 90 kHz ticks, that has to be added to all timestamps in
+    /// the section.
+    #[shorthand(enable(copy))]
+    pts_adjustment: u64,
+    /// The authorization tier of the section (`0xFFF` if the tier is not
+    /// used).
+    #[shorthand(enable(copy))]
+    tier: u16,
+    /// The splice command.
+    command: SpliceCommand,
+    /// The splice descriptors.
+    descriptors: Vec<SpliceDescriptor>,
+}
+
+impl SpliceInfo {
+    const TABLE_ID: u8 = 0xFC;
+
+    /// Decodes a `splice_info_section` from its hex representation, with or
+    /// without the `0x` prefix.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::scte35::{SpliceCommand, SpliceInfo};
+    ///
+    /// let splice_info = SpliceInfo::from_hex(concat!(
+    ///     "0xFC302F000000000000FFFFF014054800008F7FEFFE7369C02EFE0052CCF5000000000",
+    ///     "00A0008435545490000013562DBA30A"
+    /// ))?;
+    ///
+    /// if let SpliceCommand::Insert(insert) = splice_info.command() {
+    ///     assert_eq!(insert.event_id(), 0x4800_008F);
+    ///     assert!(insert.is_out_of_network());
+    /// }
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function fails, if the input is not valid hex or if the decoded
+    /// bytes are not a valid `splice_info_section` (see [`Self::from_bytes`]).
+    pub fn from_hex(input: &str) -> crate::Result<Self> {
+        let input = input
+            .strip_prefix("0x")
+            .or_else(|| input.strip_prefix("0X"))
+            .unwrap_or(input);
+
+        Self::from_bytes(&hex::decode(input).map_err(Error::hex)?)
+    }
+
+    /// Decodes a binary `splice_info_section`.
+    ///
+    /// # Errors
+    ///
+    /// This function fails, if
+    /// - the section is truncated or has an invalid `table_id`,
+    /// - the `CRC_32` does not match the section,
+    /// - the section is encrypted or
+    /// - a splice command or a segmentation descriptor is malformed.
+    pub fn from_bytes(input: &[u8]) -> crate::Result<Self> {
+        let mut reader = BitReader::new(input);
+
+        let table_id = reader.read(8)? as u8;
+
+        if table_id != Self::TABLE_ID {
+            return Err(Error::invalid_attribute(format!(
+                "invalid table_id in splice_info_section: {:#04X}",
+                table_id
+            )));
+        }
+
+        // section_syntax_indicator, private_indicator and sap_type
+        reader.skip(4)?;
+        let section_length = reader.read(12)? as usize;
+
+        let section = input.get(..3 + section_length).ok_or_else(|| {
+            Error::invalid_attribute("the splice_info_section is shorter than its section_length")
+        })?;
+
+        if section.len() < 3 + 4 {
+            return Err(Error::invalid_attribute(
+                "the splice_info_section is truncated",
+            ));
+        }
+
+        let (data, crc) = section.split_at(section.len() - 4);
+        let crc = u32::from_be_bytes([crc[0], crc[1], crc[2], crc[3]]);
+
+        if crc32(data) != crc {
+            return Err(Error::invalid_attribute(
+                "CRC_32 mismatch in splice_info_section",
+            ));
+        }
+
+        let mut reader = BitReader::new(&data[3..]);
+
+        let protocol_version = reader.read(8)? as u8;
+
+        if reader.flag()? {
+            return Err(Error::invalid_attribute(
+                "encrypted splice_info_sections are not supported",
+            ));
+        }
+
+        // encryption_algorithm
+        reader.skip(6)?;
+        let pts_adjustment = reader.read(33)?;
+        // cw_index
+        reader.skip(8)?;
+        let tier = reader.read(12)? as u16;
+        let command_length = reader.read(12)? as usize;
+        let command_type = reader.read(8)? as u8;
+
+        let command = {
+            if command_length == 0xFFF {
+                // the length of legacy commands is unspecified:
+                SpliceCommand::read(command_type, &mut reader, false)?
+            } else {
+                let mut command = BitReader::new(reader.bytes(command_length)?);
+                SpliceCommand::read(command_type, &mut command, true)?
+            }
+        };
+
+        let descriptor_loop_length = reader.read(16)? as usize;
+        let mut descriptor_loop = BitReader::new(reader.bytes(descriptor_loop_length)?);
+        let mut descriptors = Vec::new();
+
+        while !descriptor_loop.is_empty() {
+            descriptors.push(SpliceDescriptor::read(&mut descriptor_loop)?);
+        }
+
+        Ok(Self {
+            protocol_version,
+            pts_adjustment,
+            tier,
+            command,
+            descriptors,
+        })
+    }
+
+    /// Returns the segmentation descriptors of the section.
+    pub fn segmentation_descriptors(&self) -> impl Iterator<Item = &SegmentationDescriptor> {
+        self.descriptors.iter().filter_map(|descriptor| {
+            if let SpliceDescriptor::Segmentation(value) = descriptor {
+                Some(value)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl FromStr for SpliceInfo {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> { Self::from_hex(input) }
+}
+
+/// The command of a [`SpliceInfo`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SpliceCommand {
+    /// The `splice_null` command (`0x00`), which is used as a heartbeat or to
+    /// carry descriptors.
+    Null,
+    /// The `splice_insert` command (`0x05`).
+    Insert(SpliceInsert),
+    /// The `time_signal` command (`0x06`), which is usually followed by
+    /// segmentation descriptors.
+    TimeSignal {
+        /// The time of the signal in 90 kHz ticks or `None`, if the signal
+        /// should be executed immediately.
+        pts_time: Option<u64>,
+    },
+    /// The `bandwidth_reservation` command (`0x07`).
+    BandwidthReservation,
+    /// Any other command (like `splice_schedule` or `private_command`).
+    Other {
+        /// The `splice_command_type`.
+        command_type: u8,
+        /// The raw bytes of the command.
+        data: Vec<u8>,
+    },
+}
+
+impl SpliceCommand {
+    fn read(command_type: u8, reader: &mut BitReader<'_>, has_length: bool) -> crate::Result<Self> {
+        match command_type {
+            0x00 => Ok(Self::Null),
+            0x05 => SpliceInsert::read(reader).map(Self::Insert),
+            0x06 => {
+                Ok(Self::TimeSignal {
+                    pts_time: read_splice_time(reader)?,
+                })
+            }
+            0x07 => Ok(Self::BandwidthReservation),
+            _ if has_length => {
+                Ok(Self::Other {
+                    command_type,
+                    data: reader.rest().to_vec(),
+                })
+            }
+            _ => {
+                Err(Error::invalid_attribute(format!(
+                    "splice_command_type {:#04X} requires a splice_command_length",
+                    command_type
+                )))
+            }
+        }
+    }
+}
+
+/// A `splice_insert` command, which signals a splice point (for example the
+/// start or the end of an ad break).
+///
+/// If the event is cancelled, all fields except [`SpliceInsert::event_id`]
+/// have their default values.
+#[derive(ShortHand, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[shorthand(enable(must_use, copy), disable(set))]
+pub struct SpliceInsert {
+    /// The id of the splice event.
+    event_id: u32,
+    /// `true`, if a previously sent splice event with the same id is
+    /// cancelled.
+    #[shorthand(rename("is_cancelled"))]
+    cancelled: bool,
+    /// `true`, if the splice point is an opportunity to exit from the network
+    /// feed (the start of a break) and `false`, if it is an opportunity to
+    /// return to the network feed (the end of a break).
+    #[shorthand(rename("is_out_of_network"))]
+    out_of_network: bool,
+    /// `true`, if the splice should happen at the nearest opportunity.
+    #[shorthand(rename("is_immediate"))]
+    immediate: bool,
+    /// The splice time of the program in 90 kHz ticks.
+    ///
+    /// This is `None` for immediate or component splices.
+    pts_time: Option<u64>,
+    /// The splice times of the individual components, if the splice is not a
+    /// program splice.
+    #[shorthand(disable(copy))]
+    components: Vec<SpliceComponent>,
+    /// The duration of the break.
+    break_duration: Option<BreakDuration>,
+    /// A unique identifier of the viewing event.
+    unique_program_id: u16,
+    /// The number of the avail within the viewing event.
+    avail_num: u8,
+    /// The expected number of avails within the viewing event.
+    avails_expected: u8,
+}
+
+impl SpliceInsert {
+    fn read(reader: &mut BitReader<'_>) -> crate::Result<Self> {
+        let mut result = Self {
+            event_id: reader.read(32)? as u32,
+            cancelled: reader.flag()?,
+            ..Self::default()
+        };
+
+        reader.skip(7)?;
+
+        if result.cancelled {
+            return Ok(result);
+        }
+
+        result.out_of_network = reader.flag()?;
+        let program_splice = reader.flag()?;
+        let has_duration = reader.flag()?;
+        result.immediate = reader.flag()?;
+        reader.skip(4)?;
+
+        if program_splice && !result.immediate {
+            result.pts_time = read_splice_time(reader)?;
+        }
+
+        if !program_splice {
+            let component_count = reader.read(8)?;
+
+            for _ in 0..component_count {
+                let tag = reader.read(8)? as u8;
+                let pts_time = {
+                    if result.immediate {
+                        None
+                    } else {
+                        read_splice_time(reader)?
+                    }
+                };
+
+                result.components.push(SpliceComponent { tag, pts_time });
+            }
+        }
+
+        if has_duration {
+            let auto_return = reader.flag()?;
+            reader.skip(6)?;
+
+            result.break_duration = Some(BreakDuration {
+                auto_return,
+                duration: reader.read(33)?,
+            });
+        }
+
+        result.unique_program_id = reader.read(16)? as u16;
+        result.avail_num = reader.read(8)? as u8;
+        result.avails_expected = reader.read(8)? as u8;
+
+        Ok(result)
+    }
+}
+
+/// The splice time of a single component of a [`SpliceInsert`].
+#[derive(ShortHand, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[shorthand(enable(must_use, copy), disable(set))]
+pub struct SpliceComponent {
+    /// The elementary PID stream containing the splice point.
+    tag: u8,
+    /// The splice time in 90 kHz ticks or `None`, if the splice is
+    /// immediate.
+    pts_time: Option<u64>,
+}
+
+/// The duration of a break, that is signaled by a [`SpliceInsert`].
+#[derive(ShortHand, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[shorthand(enable(must_use, copy), disable(set))]
+pub struct BreakDuration {
+    /// `true`, if the splicer should return to the network at the end of the
+    /// break without a second splice command.
+    #[shorthand(rename("is_auto_return"))]
+    auto_return: bool,
+    /// The duration in 90 kHz ticks.
+    duration: u64,
+}
+
+impl BreakDuration {
+    /// Returns the duration as a [`Duration`].
+    #[must_use]
+    pub fn to_duration(&self) -> Duration { ticks_to_duration(self.duration) }
+}
+
+/// A descriptor of a [`SpliceInfo`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SpliceDescriptor {
+    /// A `segmentation_descriptor` (`0x02`).
+    Segmentation(SegmentationDescriptor),
+    /// Any other descriptor.
+    Other {
+        /// The `splice_descriptor_tag`.
+        tag: u8,
+        /// The owner of the descriptor (`0x43554549` (`"CUEI"`) for the
+        /// descriptors defined by SCTE-35).
+        identifier: u32,
+        /// The raw bytes of the descriptor after the identifier.
+        data: Vec<u8>,
+    },
+}
+
+impl SpliceDescriptor {
+    const CUEI: u32 = 0x4355_4549;
+    const SEGMENTATION: u8 = 0x02;
+
+    fn read(reader: &mut BitReader<'_>) -> crate::Result<Self> {
+        let tag = reader.read(8)? as u8;
+        let length = reader.read(8)? as usize;
+        let mut descriptor = BitReader::new(reader.bytes(length)?);
+        let identifier = descriptor.read(32)? as u32;
+
+        if tag == Self::SEGMENTATION && identifier == Self::CUEI {
+            SegmentationDescriptor::read(&mut descriptor).map(Self::Segmentation)
+        } else {
+            Ok(Self::Other {
+                tag,
+                identifier,
+                data: descriptor.rest().to_vec(),
+            })
+        }
+    }
+}
+
+/// A `segmentation_descriptor`, which describes a segment of the content
+/// (like a program, a chapter or a placement opportunity for ads).
+///
+/// If the event is cancelled, all fields except
+/// [`SegmentationDescriptor::event_id`] have their default values.
+#[derive(ShortHand, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[shorthand(enable(must_use, copy), disable(set))]
+pub struct SegmentationDescriptor {
+    /// The id of the segmentation event.
+    event_id: u32,
+    /// `true`, if a previously sent segmentation event with the same id is
+    /// cancelled.
+    #[shorthand(rename("is_cancelled"))]
+    cancelled: bool,
+    /// The delivery restrictions of the segment or `None`, if the delivery
+    /// is not restricted.
+    delivery_restrictions: Option<DeliveryRestrictions>,
+    /// The offsets of the individual components in 90 kHz ticks, if the
+    /// segmentation does not apply to the whole program.
+    #[shorthand(disable(copy))]
+    components: Vec<(u8, u64)>,
+    /// The duration of the segment in 90 kHz ticks.
+    duration: Option<u64>,
+    /// The `segmentation_upid_type`.
+    upid_type: u8,
+    /// The `segmentation_upid`, which identifies the segment.
+    #[shorthand(disable(copy))]
+    upid: Vec<u8>,
+    /// The `segmentation_type_id` (for example `0x34` for the start of a
+    /// provider placement opportunity).
+    type_id: u8,
+    /// The number of the segment.
+    segment_num: u8,
+    /// The expected number of segments.
+    segments_expected: u8,
+    /// The number of the sub segment, if the type has sub segments.
+    sub_segment_num: Option<u8>,
+    /// The expected number of sub segments, if the type has sub segments.
+    sub_segments_expected: Option<u8>,
+}
+
+impl SegmentationDescriptor {
+    /// The segmentation types, that can have sub segments.
+    const SUB_SEGMENT_TYPES: [u8; 6] = [0x34, 0x36, 0x38, 0x3A, 0x44, 0x46];
+
+    /// Returns the duration of the segment as a [`Duration`].
+    #[must_use]
+    pub fn to_duration(&self) -> Option<Duration> { self.duration.map(ticks_to_duration) }
+
+    fn read(reader: &mut BitReader<'_>) -> crate::Result<Self> {
+        let mut result = Self {
+            event_id: reader.read(32)? as u32,
+            cancelled: reader.flag()?,
+            ..Self::default()
+        };
+
+        reader.skip(7)?;
+
+        if result.cancelled {
+            return Ok(result);
+        }
+
+        let program_segmentation = reader.flag()?;
+        let has_duration = reader.flag()?;
+        let delivery_not_restricted = reader.flag()?;
+
+        if delivery_not_restricted {
+            reader.skip(5)?;
+        } else {
+            result.delivery_restrictions = Some(DeliveryRestrictions {
+                web_delivery_allowed: reader.flag()?,
+                no_regional_blackout: reader.flag()?,
+                archive_allowed: reader.flag()?,
+                device_restrictions: reader.read(2)? as u8,
+            });
+        }
+
+        if !program_segmentation {
+            let component_count = reader.read(8)?;
+
+            for _ in 0..component_count {
+                let tag = reader.read(8)? as u8;
+                reader.skip(7)?;
+                result.components.push((tag, reader.read(33)?));
+            }
+        }
+
+        if has_duration {
+            result.duration = Some(reader.read(40)?);
+        }
+
+        result.upid_type = reader.read(8)? as u8;
+        let upid_length = reader.read(8)? as usize;
+        result.upid = reader.bytes(upid_length)?.to_vec();
+
+        result.type_id = reader.read(8)? as u8;
+        result.segment_num = reader.read(8)? as u8;
+        result.segments_expected = reader.read(8)? as u8;
+
+        // older encoders omit the sub segment fields:
+        if Self::SUB_SEGMENT_TYPES.contains(&result.type_id) && !reader.is_empty() {
+            result.sub_segment_num = Some(reader.read(8)? as u8);
+            result.sub_segments_expected = Some(reader.read(8)? as u8);
+        }
+
+        Ok(result)
+    }
+}
+
+/// The delivery restrictions of a [`SegmentationDescriptor`].
+#[derive(ShortHand, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[shorthand(enable(must_use, copy), disable(set))]
+pub struct DeliveryRestrictions {
+    /// `true`, if the segment may be delivered over the internet.
+    #[shorthand(rename("is_web_delivery_allowed"))]
+    web_delivery_allowed: bool,
+    /// `true`, if there are no regional blackout restrictions.
+    #[shorthand(rename("is_no_regional_blackout"))]
+    no_regional_blackout: bool,
+    /// `true`, if the segment may be recorded.
+    #[shorthand(rename("is_archive_allowed"))]
+    archive_allowed: bool,
+    /// The `device_restrictions` (`3` if there are no restrictions).
+    device_restrictions: u8,
+}
+
+/// Reads a `splice_time`.
+fn read_splice_time(reader: &mut BitReader<'_>) -> crate::Result<Option<u64>> {
+    if reader.flag()? {
+        reader.skip(6)?;
+        Ok(Some(reader.read(33)?))
+    } else {
+        reader.skip(7)?;
+        Ok(None)
+    }
+}
+
+/// Computes the CRC-32/MPEG-2 of the input.
+fn crc32(input: &[u8]) -> u32 {
+    input.iter().fold(0xFFFF_FFFF, |mut crc, byte| {
+        crc ^= u32::from(*byte) << 24;
+
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 == 0 {
+                crc << 1
+            } else {
+                (crc << 1) ^ 0x04C1_1DB7
+            };
+        }
+
+        crc
+    })
+}
+
+/// Reads big-endian bit fields.
+struct BitReader<'a> {
+    input: &'a [u8],
+    /// The position in bits.
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    const fn new(input: &'a [u8]) -> Self { Self { input, position: 0 } }
+
+    fn is_empty(&self) -> bool { self.position >= self.input.len() * 8 }
+
+    fn ensure(&self, bits: usize) -> crate::Result<()> {
+        if self.position + bits > self.input.len() * 8 {
+            Err(Error::invalid_attribute(
+                "the splice_info_section is truncated",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn read(&mut self, bits: usize) -> crate::Result<u64> {
+        self.ensure(bits)?;
+
+        let mut result = 0;
+
+        for _ in 0..bits {
+            let byte = self.input[self.position / 8];
+            let bit = (byte >> (7 - self.position % 8)) & 1;
+
+            result = (result << 1) | u64::from(bit);
+            self.position += 1;
+        }
+
+        Ok(result)
+    }
+
+    fn flag(&mut self) -> crate::Result<bool> { Ok(self.read(1)? == 1) }
+
+    fn skip(&mut self, bits: usize) -> crate::Result<()> {
+        self.ensure(bits)?;
+        self.position += bits;
+        Ok(())
+    }
+
+    /// Reads the next `length` bytes (the reader has to be byte aligned).
+    fn bytes(&mut self, length: usize) -> crate::Result<&'a [u8]> {
+        self.ensure(length * 8)?;
+
+        let start = self.position / 8;
+        self.position += length * 8;
+
+        Ok(&self.input[start..start + length])
+    }
+
+    /// Returns the remaining bytes (the reader has to be byte aligned).
+    fn rest(&mut self) -> &'a [u8] {
+        let start = (self.position / 8).min(self.input.len());
+        self.position = self.input.len() * 8;
+
+        &self.input[start..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const TIME_SIGNAL: &str = concat!(
+        "0xFC3034000000000000FFFFF00506FE72BD0050001E021C435545494800008E7FCF0001A599B008",
+        "08000000002CA0A18A3402009AC9D17E"
+    );
+
+    const SPLICE_INSERT: &str = concat!(
+        "0xFC302F000000000000FFFFF014054800008F7FEFFE7369C02EFE0052CCF500000000000A000843",
+        "5545490000013562DBA30A"
+    );
+
+    #[test]
+    fn test_time_signal() {
+        let splice_info = SpliceInfo::from_hex(TIME_SIGNAL).unwrap();
+
+        assert_eq!(splice_info.protocol_version(), 0);
+        assert_eq!(splice_info.pts_adjustment(), 0);
+        assert_eq!(splice_info.tier(), 0xFFF);
+        assert_eq!(
+            splice_info.command(),
+            &SpliceCommand::TimeSignal {
+                pts_time: Some(0x0_72BD_0050)
+            }
+        );
+
+        let descriptors = splice_info.segmentation_descriptors().collect::<Vec<_>>();
+        assert_eq!(descriptors.len(), 1);
+
+        let descriptor = descriptors[0];
+        assert_eq!(descriptor.event_id(), 0x4800_008E);
+        assert!(!descriptor.is_cancelled());
+        assert_eq!(
+            descriptor.delivery_restrictions(),
+            Some(DeliveryRestrictions {
+                web_delivery_allowed: false,
+                no_regional_blackout: true,
+                archive_allowed: true,
+                device_restrictions: 3,
+            })
+        );
+        assert!(descriptor.components().is_empty());
+        assert_eq!(descriptor.duration(), Some(27_630_000));
+        assert_eq!(descriptor.to_duration(), Some(Duration::from_secs(307)));
+        assert_eq!(descriptor.upid_type(), 0x08);
+        assert_eq!(descriptor.upid(), &[0, 0, 0, 0, 0x2C, 0xA0, 0xA1, 0x8A]);
+        assert_eq!(descriptor.type_id(), 0x34);
+        assert_eq!(descriptor.segment_num(), 2);
+        assert_eq!(descriptor.segments_expected(), 0);
+        assert_eq!(descriptor.sub_segment_num(), None);
+    }
+
+    #[test]
+    fn test_splice_insert() {
+        let splice_info = SPLICE_INSERT.parse::<SpliceInfo>().unwrap();
+
+        assert_eq!(
+            splice_info.command(),
+            &SpliceCommand::Insert(SpliceInsert {
+                event_id: 0x4800_008F,
+                cancelled: false,
+                out_of_network: true,
+                immediate: false,
+                pts_time: Some(0x0_7369_C02E),
+                components: vec![],
+                break_duration: Some(BreakDuration {
+                    auto_return: true,
+                    duration: 0x0052_CCF5,
+                }),
+                unique_program_id: 0,
+                avail_num: 0,
+                avails_expected: 0,
+            })
+        );
+
+        // the avail_descriptor is not decoded:
+        assert_eq!(
+            splice_info.descriptors(),
+            &[SpliceDescriptor::Other {
+                tag: 0x00,
+                identifier: 0x4355_4549,
+                data: vec![0x00, 0x00, 0x01, 0x35],
+            }]
+        );
+        assert_eq!(splice_info.segmentation_descriptors().count(), 0);
+    }
+
+    #[test]
+    fn test_splice_null() {
+        // without the 0x prefix and in lowercase:
+        let splice_info = SpliceInfo::from_hex("fc301100000000000000fff0000000007a4fbfff").unwrap();
+
+        assert_eq!(splice_info.command(), &SpliceCommand::Null);
+        assert!(splice_info.descriptors().is_empty());
+    }
+
+    #[test]
+    fn test_errors() {
+        // invalid hex:
+        assert!(SpliceInfo::from_hex("0xFC30ZZ").is_err());
+        // invalid table_id:
+        assert!(SpliceInfo::from_hex(&TIME_SIGNAL.replace("0xFC", "0xFD")).is_err());
+        // CRC mismatch:
+        assert!(SpliceInfo::from_hex(&TIME_SIGNAL.replace("9AC9D17E", "9AC9D17F")).is_err());
+        // truncated:
+        assert!(SpliceInfo::from_hex(&TIME_SIGNAL[..40]).is_err());
+        assert!(SpliceInfo::from_hex("0x").is_err());
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0x0376_E6E7);
+    }
+
+    #[test]
+    fn test_ticks_to_duration() {
+        assert_eq!(ticks_to_duration(0), Duration::from_secs(0));
+        assert_eq!(ticks_to_duration(90_000), Duration::from_secs(1));
+        assert_eq!(ticks_to_duration(45_000), Duration::from_millis(500));
+        assert_eq!(
+            ticks_to_duration(0x0052_CCF5),
+            Duration::from_nanos(60_293_566_666)
+        );
+    }
+}
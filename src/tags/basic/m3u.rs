@@ -17,6 +17,25 @@ pub(crate) struct ExtM3u;
 
 impl ExtM3u {
     pub(crate) const PREFIX: &'static str = "#EXTM3U";
+
+    /// Removes the header from the start of a playlist and returns everything,
+    /// that follows the tag on the same line (if there is anything) and the
+    /// rest of the playlist.
+    ///
+    /// Some tools write trailing whitespace or parameters after the tag, which
+    /// are tolerated if they are separated by whitespace from the tag.
+    pub(crate) fn parse_header(input: &str) -> crate::Result<(Option<&str>, &str)> {
+        let rest = tag(input, Self::PREFIX)?;
+        let (line, rest) = rest.split_at(rest.find('\n').unwrap_or(rest.len()));
+
+        if !line.is_empty() && !line.starts_with(char::is_whitespace) {
+            return Err(Error::missing_tag(Self::PREFIX, input));
+        }
+
+        let line = line.trim();
+
+        Ok((Some(line).filter(|value| !value.is_empty()), rest))
+    }
 }
 
 /// This tag requires [`ProtocolVersion::V1`].
@@ -53,6 +72,26 @@ mod test {
         assert!(ExtM3u::try_from("#EXTM2U").is_err());
     }
 
+    #[test]
+    fn test_parse_header() {
+        assert_eq!(
+            ExtM3u::parse_header("#EXTM3U\n#EXT-X-VERSION:1").unwrap(),
+            (None, "\n#EXT-X-VERSION:1")
+        );
+        assert_eq!(
+            ExtM3u::parse_header("#EXTM3U \r\n#EXT-X-VERSION:1").unwrap(),
+            (None, "\n#EXT-X-VERSION:1")
+        );
+        assert_eq!(
+            ExtM3u::parse_header("#EXTM3U\tTOOL=\"packager\"\n1.ts").unwrap(),
+            (Some("TOOL=\"packager\""), "\n1.ts")
+        );
+        assert_eq!(ExtM3u::parse_header("#EXTM3U").unwrap(), (None, ""));
+
+        assert!(ExtM3u::parse_header("#EXTM3UX\n").is_err());
+        assert!(ExtM3u::parse_header("#EXT-X-VERSION:1\n").is_err());
+    }
+
     #[test]
     fn test_required_version() {
         assert_eq!(ExtM3u.required_version(), ProtocolVersion::V1);
@@ -0,0 +1,180 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
+
+use shorthand::ShortHand;
+
+use crate::attribute::AttributePairs;
+use crate::types::{ProtocolVersion, UriRef};
+use crate::utils::{quote, tag, unquote};
+use crate::{Error, RequiredVersion};
+
+/// The [`ExtXContentSteering`] tag allows a server to steer the clients
+/// between multiple pathways (for example different content delivery
+/// networks), by providing a steering manifest, that is periodically reloaded
+/// by the client.
+///
+/// # Example
+///
+/// ```
+/// # use hls_m3u8::tags::ExtXContentSteering;
+/// let mut steering = ExtXContentSteering::new("https://example.com/manifest.json");
+/// steering.set_pathway_id(Some("CDN-A"));
+///
+/// assert_eq!(
+///     steering.to_string(),
+///     concat!(
+///         "#EXT-X-CONTENT-STEERING:SERVER-URI=\"https://example.com/manifest.json\",",
+///         "PATHWAY-ID=\"CDN-A\""
+///     )
+/// );
+/// ```
+#[derive(ShortHand, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[shorthand(enable(must_use, into))]
+pub struct ExtXContentSteering<'a> {
+    /// The `URI` of the steering manifest.
+    server_uri: UriRef<'a>,
+    /// The pathway, that should be used by the client, until the steering
+    /// manifest has been loaded.
+    ///
+    /// # Note
+    ///
+    /// This field is optional.
+    pathway_id: Option<Cow<'a, str>>,
+}
+
+impl<'a> ExtXContentSteering<'a> {
+    pub(crate) const PREFIX: &'static str = "#EXT-X-CONTENT-STEERING:";
+
+    /// Makes a new [`ExtXContentSteering`] tag.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtXContentSteering;
+    /// let steering = ExtXContentSteering::new("https://example.com/manifest.json");
+    /// ```
+    #[must_use]
+    pub fn new<T: Into<UriRef<'a>>>(server_uri: T) -> Self {
+        Self {
+            server_uri: server_uri.into(),
+            pathway_id: None,
+        }
+    }
+
+    /// Makes the struct independent of its lifetime, by taking ownership of all
+    /// internal [`Cow`]s.
+    ///
+    /// # Note
+    ///
+    /// This is a relatively expensive operation.
+    #[must_use]
+    pub fn into_owned(self) -> ExtXContentSteering<'static> {
+        ExtXContentSteering {
+            server_uri: self.server_uri.into_owned(),
+            pathway_id: self.pathway_id.map(|v| Cow::Owned(v.into_owned())),
+        }
+    }
+}
+
+/// This tag requires [`ProtocolVersion::V1`].
+impl<'a> RequiredVersion for ExtXContentSteering<'a> {
+    fn required_version(&self) -> ProtocolVersion { ProtocolVersion::V1 }
+}
+
+impl<'a> fmt::Display for ExtXContentSteering<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Self::PREFIX)?;
+        write!(f, "SERVER-URI={}", quote(&self.server_uri))?;
+
+        if let Some(value) = &self.pathway_id {
+            write!(f, ",PATHWAY-ID={}", quote(value))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> TryFrom<&'a str> for ExtXContentSteering<'a> {
+    type Error = Error;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        let input = tag(input, Self::PREFIX)?;
+
+        let mut server_uri = None;
+        let mut pathway_id = None;
+
+        for (key, value) in AttributePairs::new(input) {
+            match key {
+                "SERVER-URI" => server_uri = Some(unquote(value)),
+                "PATHWAY-ID" => pathway_id = Some(unquote(value)),
+                _ => {
+                    // [6.3.1. General Client Responsibilities]
+                    // > ignore any attribute/value pair with an unrecognized
+                    // AttributeName.
+                }
+            }
+        }
+
+        let server_uri = server_uri
+            .ok_or_else(|| Error::missing_value("SERVER-URI"))?
+            .into();
+
+        Ok(Self {
+            server_uri,
+            pathway_id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            ExtXContentSteering::new("manifest.json").to_string(),
+            "#EXT-X-CONTENT-STEERING:SERVER-URI=\"manifest.json\"".to_string()
+        );
+
+        let mut steering = ExtXContentSteering::new("manifest.json");
+        steering.set_pathway_id(Some("CDN-A"));
+
+        assert_eq!(
+            steering.to_string(),
+            "#EXT-X-CONTENT-STEERING:SERVER-URI=\"manifest.json\",PATHWAY-ID=\"CDN-A\"".to_string()
+        );
+    }
+
+    #[test]
+    fn test_parser() {
+        assert_eq!(
+            ExtXContentSteering::new("manifest.json"),
+            ExtXContentSteering::try_from("#EXT-X-CONTENT-STEERING:SERVER-URI=\"manifest.json\"")
+                .unwrap()
+        );
+
+        let mut steering = ExtXContentSteering::new("manifest.json");
+        steering.set_pathway_id(Some("CDN-A"));
+
+        assert_eq!(
+            steering,
+            ExtXContentSteering::try_from(
+                "#EXT-X-CONTENT-STEERING:PATHWAY-ID=\"CDN-A\",SERVER-URI=\"manifest.json\",UNKNOWN=1"
+            )
+            .unwrap()
+        );
+
+        assert!(ExtXContentSteering::try_from("#EXT-X-CONTENT-STEERING:PATHWAY-ID=\"a\"").is_err());
+    }
+
+    #[test]
+    fn test_required_version() {
+        assert_eq!(
+            ExtXContentSteering::new("manifest.json").required_version(),
+            ProtocolVersion::V1
+        );
+    }
+}
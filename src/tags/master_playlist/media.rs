@@ -7,8 +7,8 @@ use shorthand::ShortHand;
 
 use crate::attribute::AttributePairs;
 use crate::types::{Channels, InStreamId, MediaType, ProtocolVersion};
-use crate::utils::{parse_yes_or_no, quote, tag, unquote};
-use crate::{Error, RequiredVersion};
+use crate::utils::{intern, is_stable_id, parse_yes_or_no, quote, tag, unquote};
+use crate::{Error, RequiredVersion, VersionReason};
 
 /// An [`ExtXMedia`] tag is an alternative rendition of a [`VariantStream`].
 ///
@@ -170,9 +170,41 @@ pub struct ExtXMedia<'a> {
     #[builder(setter(strip_option), default)]
     #[shorthand(enable(skip))]
     pub channels: Option<Channels>,
+    /// The audio bit depth of the rendition (for example `16` or `24`).
+    ///
+    /// ### Note
+    ///
+    /// This field is optional and must only be present, if the media type is
+    /// [`MediaType::Audio`].
+    #[builder(setter(strip_option, into = false), default)]
+    #[shorthand(enable(skip))]
+    pub bit_depth: Option<u64>,
+    /// The audio sample rate of the rendition in Hz (for example `48000`).
+    ///
+    /// ### Note
+    ///
+    /// This field is optional and must only be present, if the media type is
+    /// [`MediaType::Audio`].
+    #[builder(setter(strip_option, into = false), default)]
+    #[shorthand(enable(skip))]
+    pub sample_rate: Option<u64>,
+    /// An identifier of the rendition, that stays the same, even if the
+    /// [`MasterPlaylist`] is regenerated and the other attributes change.
+    ///
+    /// It may only consist of the characters `[a-z]`, `[A-Z]`, `[0-9]`, `+`,
+    /// `/`, `=`, `.`, `-` and `_`.
+    ///
+    /// ### Note
+    ///
+    /// This field is optional.
+    ///
+    /// [`MasterPlaylist`]: crate::MasterPlaylist
+    #[builder(setter(strip_option), default)]
+    stable_rendition_id: Option<Cow<'a, str>>,
 }
 
 impl<'a> ExtXMediaBuilder<'a> {
+    #[allow(clippy::unnecessary_map_or)]
     fn validate(&self) -> Result<(), String> {
         // A MediaType is always required!
         let media_type = self
@@ -191,21 +223,47 @@ impl<'a> ExtXMediaBuilder<'a> {
                 return Err(Error::missing_attribute("INSTREAM-ID").to_string());
             }
         } else if self.instream_id.is_some() {
-            return Err(Error::custom(
+            return Err(Error::invalid_attribute(
                 "InStreamId should only be specified for an ExtXMedia tag with `MediaType::ClosedCaptions`"
             ).to_string());
         }
 
         if self.is_default.unwrap_or(false) && self.is_autoselect.map_or(false, |b| !b) {
-            return Err(Error::custom(format!(
+            return Err(Error::invalid_attribute(format!(
                 "If `DEFAULT` is true, `AUTOSELECT` has to be true too, if present. Default: {:?}, Autoselect: {:?}!",
                 self.is_default, self.is_autoselect
             ))
             .to_string());
         }
 
+        if media_type != MediaType::Audio
+            && (self.bit_depth.flatten().is_some() || self.sample_rate.flatten().is_some())
+        {
+            return Err(Error::invalid_attribute(format!(
+                concat!(
+                    "the bit depth and the sample rate must not be present, ",
+                    "unless the media_type is `MediaType::Audio`: ",
+                    "media_type: {:?}, bit_depth: {:?}, sample_rate: {:?}"
+                ),
+                media_type,
+                self.bit_depth.flatten(),
+                self.sample_rate.flatten()
+            ))
+            .to_string());
+        }
+
+        if let Some(Some(value)) = &self.stable_rendition_id {
+            if !is_stable_id(value) {
+                return Err(Error::invalid_attribute(format!(
+                    "invalid character in STABLE-RENDITION-ID: {:?}",
+                    value
+                ))
+                .to_string());
+            }
+        }
+
         if media_type != MediaType::Subtitles && self.is_forced.unwrap_or(false) {
-            return Err(Error::custom(format!(
+            return Err(Error::invalid_attribute(format!(
                 concat!(
                     "the forced attribute must not be present, ",
                     "unless the media_type is `MediaType::Subtitles`: ",
@@ -257,6 +315,9 @@ impl<'a> ExtXMedia<'a> {
             instream_id: None,
             characteristics: None,
             channels: None,
+            bit_depth: None,
+            sample_rate: None,
+            stable_rendition_id: None,
         }
     }
 
@@ -289,6 +350,52 @@ impl<'a> ExtXMedia<'a> {
     #[inline]
     pub fn builder() -> ExtXMediaBuilder<'a> { ExtXMediaBuilder::default() }
 
+    /// Normalizes the case of the [`ExtXMedia::language`] and the
+    /// [`ExtXMedia::assoc_language`] (`en-us` becomes `en-US`).
+    ///
+    /// This requires the `language-tags` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtXMedia;
+    /// use hls_m3u8::types::MediaType;
+    ///
+    /// let mut media = ExtXMedia::builder()
+    ///     .media_type(MediaType::Audio)
+    ///     .group_id("audio")
+    ///     .name("English")
+    ///     .language("en-us")
+    ///     .build()?;
+    ///
+    /// media.normalize_languages()?;
+    /// assert_eq!(media.language(), Some(&"en-US".into()));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function fails, if one of the languages is not a well-formed
+    /// language tag. Neither language is changed in that case.
+    #[cfg(feature = "language-tags")]
+    pub fn normalize_languages(&mut self) -> crate::Result<()> {
+        let language = self
+            .language
+            .as_deref()
+            .map(crate::utils::normalize_language)
+            .transpose()?;
+        let assoc_language = self
+            .assoc_language
+            .as_deref()
+            .map(crate::utils::normalize_language)
+            .transpose()?;
+
+        self.language = language.map(Cow::Owned);
+        self.assoc_language = assoc_language.map(Cow::Owned);
+
+        Ok(())
+    }
+
     /// Makes the struct independent of its lifetime, by taking ownership of all
     /// internal [`Cow`]s.
     ///
@@ -300,7 +407,7 @@ impl<'a> ExtXMedia<'a> {
         ExtXMedia {
             media_type: self.media_type,
             uri: self.uri.map(|v| Cow::Owned(v.into_owned())),
-            group_id: Cow::Owned(self.group_id.into_owned()),
+            group_id: intern(self.group_id),
             language: self.language.map(|v| Cow::Owned(v.into_owned())),
             assoc_language: self.assoc_language.map(|v| Cow::Owned(v.into_owned())),
             name: Cow::Owned(self.name.into_owned()),
@@ -310,6 +417,9 @@ impl<'a> ExtXMedia<'a> {
             instream_id: self.instream_id,
             characteristics: self.characteristics.map(|v| Cow::Owned(v.into_owned())),
             channels: self.channels,
+            bit_depth: self.bit_depth,
+            sample_rate: self.sample_rate,
+            stable_rendition_id: self.stable_rendition_id.map(|v| Cow::Owned(v.into_owned())),
         }
     }
 }
@@ -321,6 +431,10 @@ impl<'a> RequiredVersion for ExtXMedia<'a> {
         self.instream_id
             .map_or(ProtocolVersion::V1, |i| i.required_version())
     }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        self.instream_id.required_version_reasons()
+    }
 }
 
 impl<'a> fmt::Display for ExtXMedia<'a> {
@@ -360,6 +474,14 @@ impl<'a> fmt::Display for ExtXMedia<'a> {
             write!(f, ",INSTREAM-ID={}", quote(value))?;
         }
 
+        if let Some(value) = &self.bit_depth {
+            write!(f, ",BIT-DEPTH={}", value)?;
+        }
+
+        if let Some(value) = &self.sample_rate {
+            write!(f, ",SAMPLE-RATE={}", value)?;
+        }
+
         if let Some(value) = &self.characteristics {
             write!(f, ",CHARACTERISTICS={}", quote(value))?;
         }
@@ -367,6 +489,10 @@ impl<'a> fmt::Display for ExtXMedia<'a> {
         if let Some(value) = &self.channels {
             write!(f, ",CHANNELS={}", quote(value))?;
         }
+
+        if let Some(value) = &self.stable_rendition_id {
+            write!(f, ",STABLE-RENDITION-ID={}", quote(value))?;
+        }
         Ok(())
     }
 }
@@ -411,12 +537,29 @@ impl<'a> TryFrom<&'a str> for ExtXMedia<'a> {
                 "INSTREAM-ID" => {
                     builder.instream_id(unquote(value).parse::<InStreamId>()?);
                 }
+                "BIT-DEPTH" => {
+                    builder.bit_depth(
+                        value
+                            .parse::<u64>()
+                            .map_err(|e| Error::parse_int(value, e))?,
+                    );
+                }
+                "SAMPLE-RATE" => {
+                    builder.sample_rate(
+                        value
+                            .parse::<u64>()
+                            .map_err(|e| Error::parse_int(value, e))?,
+                    );
+                }
                 "CHARACTERISTICS" => {
                     builder.characteristics(unquote(value));
                 }
                 "CHANNELS" => {
                     builder.channels(unquote(value).parse::<Channels>()?);
                 }
+                "STABLE-RENDITION-ID" => {
+                    builder.stable_rendition_id(unquote(value));
+                }
                 _ => {
                     // [6.3.1. General Client Responsibilities]
                     // > ignore any attribute/value pair with an unrecognized
@@ -687,6 +830,28 @@ mod test {
                 "CHANNELS=\"2\""
             )
         },
+        {
+            ExtXMedia::builder()
+                .media_type(MediaType::Audio)
+                .group_id("audio-lossless")
+                .language("en")
+                .name("English (Lossless)")
+                .bit_depth(24)
+                .sample_rate(96_000)
+                .channels(Channels::new(2))
+                .build()
+                .unwrap(),
+            concat!(
+                "#EXT-X-MEDIA:",
+                "TYPE=AUDIO,",
+                "GROUP-ID=\"audio-lossless\",",
+                "LANGUAGE=\"en\",",
+                "NAME=\"English (Lossless)\",",
+                "BIT-DEPTH=24,",
+                "SAMPLE-RATE=96000,",
+                "CHANNELS=\"2\""
+            )
+        },
         {
             ExtXMedia::builder()
                 .media_type(MediaType::Subtitles)
@@ -741,6 +906,61 @@ mod test {
             ExtXMedia::new(MediaType::Audio, "foo", "bar"),
             "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"foo\",NAME=\"bar\""
         },
+        {
+            ExtXMedia::builder()
+                .media_type(MediaType::Audio)
+                .group_id("audio")
+                .name("English")
+                .stable_rendition_id("eng/Main_1.0+=-")
+                .build()
+                .unwrap(),
+            concat!(
+                "#EXT-X-MEDIA:",
+                "TYPE=AUDIO,",
+                "GROUP-ID=\"audio\",",
+                "NAME=\"English\",",
+                "STABLE-RENDITION-ID=\"eng/Main_1.0+=-\""
+            )
+        },
+    }
+
+    #[test]
+    #[cfg(feature = "language-tags")]
+    fn test_normalize_languages() {
+        let mut media = ExtXMedia::builder()
+            .media_type(MediaType::Audio)
+            .group_id("audio")
+            .name("Chinese")
+            .language("zh-hant-tw")
+            .assoc_language("EN")
+            .build()
+            .unwrap();
+
+        media.normalize_languages().unwrap();
+        assert_eq!(media.language(), Some(&"zh-Hant-TW".into()));
+        assert_eq!(media.assoc_language(), Some(&"en".into()));
+
+        media.set_assoc_language(Some("en_US"));
+        assert!(media.normalize_languages().is_err());
+        assert_eq!(media.language(), Some(&"zh-Hant-TW".into()));
+        assert_eq!(media.assoc_language(), Some(&"en_US".into()));
+    }
+
+    #[test]
+    fn test_bit_depth_and_sample_rate() {
+        assert!(ExtXMedia::builder()
+            .media_type(MediaType::Video)
+            .group_id("video")
+            .name("Camera 1")
+            .sample_rate(48_000)
+            .build()
+            .is_err());
+
+        assert!(ExtXMedia::try_from(concat!(
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"audio\",NAME=\"English\",",
+            "SAMPLE-RATE=48.5"
+        ))
+        .is_err());
     }
 
     #[test]
@@ -757,6 +977,11 @@ mod test {
         assert!(ExtXMedia::try_from("#EXT-X-MEDIA:TYPE=AUDIO,DEFAULT=YES,AUTOSELECT=NO").is_err());
 
         assert!(ExtXMedia::try_from("#EXT-X-MEDIA:TYPE=AUDIO,FORCED=YES").is_err());
+
+        assert!(ExtXMedia::try_from(
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"a\",NAME=\"b\",STABLE-RENDITION-ID=\"a b\""
+        )
+        .is_err());
     }
 
     #[test]
@@ -6,7 +6,7 @@ use derive_builder::Builder;
 use shorthand::ShortHand;
 
 use crate::attribute::AttributePairs;
-use crate::types::ProtocolVersion;
+use crate::types::{ProtocolVersion, SessionDataFormat, Value};
 use crate::utils::{quote, tag, unquote};
 use crate::{Error, RequiredVersion};
 
@@ -18,9 +18,11 @@ pub enum SessionData<'a> {
     /// If a [`language`] is specified, this variant should contain a
     /// human-readable string written in the specified language.
     ///
+    /// The value is usually a [`Value::String`].
+    ///
     /// [`data_id`]: ExtXSessionData::data_id
     /// [`language`]: ExtXSessionData::language
-    Value(Cow<'a, str>),
+    Value(Value<'a>),
     /// An [`URI`], which points to a [`json`] file.
     ///
     /// [`json`]: https://tools.ietf.org/html/rfc8259
@@ -38,7 +40,7 @@ impl<'a> SessionData<'a> {
     #[must_use]
     pub fn into_owned(self) -> SessionData<'static> {
         match self {
-            Self::Value(v) => SessionData::Value(Cow::Owned(v.into_owned())),
+            Self::Value(v) => SessionData::Value(v.into_owned()),
             Self::Uri(v) => SessionData::Uri(Cow::Owned(v.into_owned())),
         }
     }
@@ -48,7 +50,7 @@ impl<'a> SessionData<'a> {
 ///
 /// [`MasterPlaylist`]: crate::MasterPlaylist
 #[derive(ShortHand, Builder, Hash, Eq, Ord, Debug, PartialEq, Clone, PartialOrd)]
-#[builder(setter(into))]
+#[builder(setter(into), build_fn(validate = "Self::validate"))]
 #[shorthand(enable(must_use, into))]
 pub struct ExtXSessionData<'a> {
     /// This should conform to a [reverse DNS] naming convention, such as
@@ -81,10 +83,35 @@ pub struct ExtXSessionData<'a> {
     /// [RFC5646]: https://tools.ietf.org/html/rfc5646
     #[builder(setter(strip_option), default)]
     language: Option<Cow<'a, str>>,
+    /// The format of the resource, that is referenced by the
+    /// [`SessionData::Uri`].
+    ///
+    /// # Note
+    ///
+    /// This field is optional and must only be specified for a
+    /// [`SessionData::Uri`]. An absent value is equal to
+    /// [`SessionDataFormat::Json`].
+    #[builder(setter(strip_option), default)]
+    format: Option<SessionDataFormat>,
 }
 
 impl<'a> ExtXSessionData<'a> {
+    /// The [`data_id`] of the chapters of a presentation. The [`SessionData`]
+    /// of this tag is an [`SessionData::Uri`], which points to a json file
+    /// with the chapters for the [`language`].
+    ///
+    /// See [`MasterPlaylist::chapters_session_data`].
+    ///
+    /// [`data_id`]: ExtXSessionData::data_id
+    /// [`language`]: ExtXSessionData::language
+    /// [`MasterPlaylist::chapters_session_data`]:
+    /// crate::MasterPlaylist::chapters_session_data
+    pub const CHAPTERS: &'static str = "com.apple.hls.chapters";
     pub(crate) const PREFIX: &'static str = "#EXT-X-SESSION-DATA:";
+    /// The [`data_id`] of the human-readable title of a presentation.
+    ///
+    /// [`data_id`]: ExtXSessionData::data_id
+    pub const TITLE: &'static str = "com.apple.hls.title";
 
     /// Makes a new [`ExtXSessionData`] tag.
     ///
@@ -93,6 +120,7 @@ impl<'a> ExtXSessionData<'a> {
     /// ```
     /// # use hls_m3u8::tags::ExtXSessionData;
     /// use hls_m3u8::tags::SessionData;
+    /// use hls_m3u8::types::Value;
     ///
     /// let session_data = ExtXSessionData::new(
     ///     "com.example.movie.title",
@@ -105,6 +133,7 @@ impl<'a> ExtXSessionData<'a> {
             data_id: data_id.into(),
             data,
             language: None,
+            format: None,
         }
     }
 
@@ -115,12 +144,29 @@ impl<'a> ExtXSessionData<'a> {
     /// ```
     /// # use hls_m3u8::tags::ExtXSessionData;
     /// use hls_m3u8::tags::SessionData;
+    /// use hls_m3u8::types::Value;
     ///
     /// let session_data = ExtXSessionData::builder()
     ///     .data_id("com.example.movie.title")
-    ///     .data(SessionData::Value("some data".into()))
+    ///     .data(SessionData::Value(Value::string("some data")))
     ///     .language("en")
     ///     .build()?;
+    ///
+    /// // the format can only be specified for an uri:
+    /// use hls_m3u8::types::SessionDataFormat;
+    ///
+    /// let session_data = ExtXSessionData::builder()
+    ///     .data_id("com.example.movie.poster")
+    ///     .data(SessionData::Uri("poster.png".into()))
+    ///     .format(SessionDataFormat::Raw)
+    ///     .build()?;
+    ///
+    /// assert!(ExtXSessionData::builder()
+    ///     .data_id("com.example.movie.title")
+    ///     .data(SessionData::Value(Value::string("some data")))
+    ///     .format(SessionDataFormat::Raw)
+    ///     .build()
+    ///     .is_err());
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     #[must_use]
@@ -133,10 +179,11 @@ impl<'a> ExtXSessionData<'a> {
     /// ```
     /// # use hls_m3u8::tags::ExtXSessionData;
     /// use hls_m3u8::tags::SessionData;
+    /// use hls_m3u8::types::Value;
     ///
     /// let session_data = ExtXSessionData::with_language(
     ///     "com.example.movie.title",
-    ///     SessionData::Value("some data".into()),
+    ///     SessionData::Value(Value::string("some data")),
     ///     "en",
     /// );
     /// ```
@@ -150,9 +197,40 @@ impl<'a> ExtXSessionData<'a> {
             data_id: data_id.into(),
             data,
             language: Some(language.into()),
+            format: None,
         }
     }
 
+    /// Returns `true`, if the [`data_id`] conforms to the [reverse DNS] naming
+    /// convention (like `com.example.movie.title`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtXSessionData;
+    /// use hls_m3u8::tags::SessionData;
+    /// use hls_m3u8::types::Value;
+    ///
+    /// let data = SessionData::Value(Value::string("some data"));
+    ///
+    /// assert!(ExtXSessionData::new("com.example.movie.title", data.clone()).is_reverse_dns());
+    /// assert!(!ExtXSessionData::new("title", data.clone()).is_reverse_dns());
+    /// assert!(!ExtXSessionData::new("com..title", data).is_reverse_dns());
+    /// ```
+    ///
+    /// [`data_id`]: ExtXSessionData::data_id
+    /// [reverse DNS]: https://en.wikipedia.org/wiki/Reverse_domain_name_notation
+    #[must_use]
+    pub fn is_reverse_dns(&self) -> bool {
+        self.data_id.split('.').count() >= 2
+            && self.data_id.split('.').all(|label| {
+                !label.is_empty()
+                    && label
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            })
+    }
+
     /// Makes the struct independent of its lifetime, by taking ownership of all
     /// internal [`Cow`]s.
     ///
@@ -165,6 +243,7 @@ impl<'a> ExtXSessionData<'a> {
             data_id: Cow::Owned(self.data_id.into_owned()),
             data: self.data.into_owned(),
             language: self.language.map(|v| Cow::Owned(v.into_owned())),
+            format: self.format,
         }
     }
 }
@@ -180,10 +259,14 @@ impl<'a> fmt::Display for ExtXSessionData<'a> {
         write!(f, "DATA-ID={}", quote(&self.data_id))?;
 
         match &self.data {
-            SessionData::Value(value) => write!(f, ",VALUE={}", quote(value))?,
+            SessionData::Value(value) => write!(f, ",VALUE={}", value)?,
             SessionData::Uri(value) => write!(f, ",URI={}", quote(value))?,
         }
 
+        if let Some(value) = &self.format {
+            write!(f, ",FORMAT={}", value)?;
+        }
+
         if let Some(value) = &self.language {
             write!(f, ",LANGUAGE={}", quote(value))?;
         }
@@ -202,13 +285,21 @@ impl<'a> TryFrom<&'a str> for ExtXSessionData<'a> {
         let mut session_value = None;
         let mut uri = None;
         let mut language = None;
+        let mut format = None;
 
         for (key, value) in AttributePairs::new(input) {
             match key {
                 "DATA-ID" => data_id = Some(unquote(value)),
-                "VALUE" => session_value = Some(unquote(value)),
+                "VALUE" => {
+                    // an unquoted value, that is not a valid hexadecimal-sequence
+                    // (like `VALUE=0xZZ`), is accepted as a string:
+                    session_value = Some(
+                        Value::try_from(value).unwrap_or_else(|_| Value::String(unquote(value))),
+                    );
+                }
                 "URI" => uri = Some(unquote(value)),
                 "LANGUAGE" => language = Some(unquote(value)),
+                "FORMAT" => format = Some(value.parse().map_err(Error::strum)?),
                 _ => {
                     // [6.3.1. General Client Responsibilities]
                     // > ignore any attribute/value pair with an unrecognized
@@ -222,14 +313,18 @@ impl<'a> TryFrom<&'a str> for ExtXSessionData<'a> {
         let data = {
             if let Some(value) = session_value {
                 if uri.is_some() {
-                    return Err(Error::custom("unexpected URI"));
+                    return Err(Error::invalid_attribute("unexpected URI"));
+                }
+
+                if format.is_some() {
+                    return Err(Error::unexpected_attribute("FORMAT"));
                 }
 
                 SessionData::Value(value)
             } else if let Some(uri) = uri {
                 SessionData::Uri(uri)
             } else {
-                return Err(Error::custom(
+                return Err(Error::invalid_attribute(
                     "expected either `SessionData::Uri` or `SessionData::Value`",
                 ));
             }
@@ -239,13 +334,25 @@ impl<'a> TryFrom<&'a str> for ExtXSessionData<'a> {
             data_id,
             data,
             language,
+            format,
         })
     }
 }
 
+impl<'a> ExtXSessionDataBuilder<'a> {
+    fn validate(&self) -> Result<(), String> {
+        if self.format.flatten().is_some() && matches!(self.data, Some(SessionData::Value(_))) {
+            return Err(Error::unexpected_attribute("FORMAT").to_string());
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::types::Float;
     use pretty_assertions::assert_eq;
 
     macro_rules! generate_tests {
@@ -282,6 +389,26 @@ mod test {
                     ))
                     .is_err()
                 );
+
+                assert!(
+                    ExtXSessionData::try_from(concat!(
+                        "#EXT-X-SESSION-DATA:",
+                        "DATA-ID=\"foo\",",
+                        "VALUE=\"VALUE\",",
+                        "FORMAT=RAW"
+                    ))
+                    .is_err()
+                );
+
+                assert!(
+                    ExtXSessionData::try_from(concat!(
+                        "#EXT-X-SESSION-DATA:",
+                        "DATA-ID=\"foo\",",
+                        "URI=\"foo.xml\",",
+                        "FORMAT=XML"
+                    ))
+                    .is_err()
+                );
             }
 
         }
@@ -302,7 +429,7 @@ mod test {
         {
             ExtXSessionData::with_language(
                 "com.example.title",
-                SessionData::Value("This is an example".into()),
+                SessionData::Value(Value::string("This is an example")),
                 "en"
             ),
             concat!(
@@ -315,7 +442,7 @@ mod test {
         {
             ExtXSessionData::with_language(
                 "com.example.title",
-                SessionData::Value("Este es un ejemplo".into()),
+                SessionData::Value(Value::string("Este es un ejemplo")),
                 "es"
             ),
             concat!(
@@ -324,7 +451,59 @@ mod test {
                 "VALUE=\"Este es un ejemplo\",",
                 "LANGUAGE=\"es\""
             )
-        }
+        },
+        {
+            ExtXSessionData::new(
+                "com.example.rating",
+                SessionData::Value(Value::from(Float::new(4.5)))
+            ),
+            concat!(
+                "#EXT-X-SESSION-DATA:",
+                "DATA-ID=\"com.example.rating\",",
+                "VALUE=4.5"
+            )
+        },
+        {
+            ExtXSessionData::builder()
+                .data_id("com.example.poster")
+                .data(SessionData::Uri("poster.png".into()))
+                .format(SessionDataFormat::Raw)
+                .build()
+                .unwrap(),
+            concat!(
+                "#EXT-X-SESSION-DATA:",
+                "DATA-ID=\"com.example.poster\",",
+                "URI=\"poster.png\",",
+                "FORMAT=RAW"
+            )
+        },
+    }
+
+    #[test]
+    fn test_unquoted_value() {
+        assert_eq!(
+            ExtXSessionData::try_from("#EXT-X-SESSION-DATA:DATA-ID=\"foo\",VALUE=0xZZ").unwrap(),
+            ExtXSessionData::new("foo", SessionData::Value(Value::string("0xZZ")))
+        );
+        assert_eq!(
+            ExtXSessionData::try_from("#EXT-X-SESSION-DATA:DATA-ID=\"foo\",VALUE=abc").unwrap(),
+            ExtXSessionData::new("foo", SessionData::Value(Value::string("abc")))
+        );
+    }
+
+    #[test]
+    fn test_is_reverse_dns() {
+        let data = || SessionData::Uri("chapters.json".into());
+
+        assert!(ExtXSessionData::new(ExtXSessionData::CHAPTERS, data()).is_reverse_dns());
+        assert!(ExtXSessionData::new("com.example.movie_title", data()).is_reverse_dns());
+        assert!(ExtXSessionData::new("com.example-1.title", data()).is_reverse_dns());
+
+        assert!(!ExtXSessionData::new("", data()).is_reverse_dns());
+        assert!(!ExtXSessionData::new("title", data()).is_reverse_dns());
+        assert!(!ExtXSessionData::new(".com.example", data()).is_reverse_dns());
+        assert!(!ExtXSessionData::new("com.example.", data()).is_reverse_dns());
+        assert!(!ExtXSessionData::new("com.example movie", data()).is_reverse_dns());
     }
 
     #[test]
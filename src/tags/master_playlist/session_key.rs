@@ -6,7 +6,7 @@ use derive_more::{AsMut, AsRef, From};
 use crate::tags::ExtXKey;
 use crate::types::{DecryptionKey, ProtocolVersion};
 use crate::utils::tag;
-use crate::{Error, RequiredVersion};
+use crate::{Error, RequiredVersion, VersionReason};
 
 /// The [`ExtXSessionKey`] tag allows encryption keys from [`MediaPlaylist`]s
 /// to be specified in a [`MasterPlaylist`]. This allows the client to
@@ -62,7 +62,7 @@ impl<'a> TryFrom<ExtXKey<'a>> for ExtXSessionKey<'a> {
         if let ExtXKey(Some(inner)) = value {
             Ok(Self(inner))
         } else {
-            Err(Error::custom("missing decryption key"))
+            Err(Error::invalid_attribute("missing decryption key"))
         }
     }
 }
@@ -71,6 +71,8 @@ impl<'a> TryFrom<ExtXKey<'a>> for ExtXSessionKey<'a> {
 /// `DecryptionKey::required_version`.
 impl<'a> RequiredVersion for ExtXSessionKey<'a> {
     fn required_version(&self) -> ProtocolVersion { self.0.required_version() }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> { self.0.required_version_reasons() }
 }
 
 impl<'a> fmt::Display for ExtXSessionKey<'a> {
@@ -7,8 +7,8 @@ use crate::attribute::AttributePairs;
 use crate::tags::ExtXMedia;
 use crate::traits::RequiredVersion;
 use crate::types::{ClosedCaptions, MediaType, ProtocolVersion, StreamData, UFloat};
-use crate::utils::{quote, tag, unquote};
-use crate::Error;
+use crate::utils::{intern, quote, tag, unquote};
+use crate::{Error, VersionReason};
 
 /// A server may offer multiple [`MediaPlaylist`] files to provide different
 /// encodings of the same presentation.
@@ -204,6 +204,7 @@ impl<'a> VariantStream<'a> {
     /// ));
     /// ```
     #[must_use]
+    #[allow(clippy::unnecessary_map_or)]
     pub fn is_associated(&self, media: &ExtXMedia<'_>) -> bool {
         match &self {
             Self::ExtXIFrame { stream_data, .. } => {
@@ -266,8 +267,8 @@ impl<'a> VariantStream<'a> {
                 VariantStream::ExtXStreamInf {
                     uri: Cow::Owned(uri.into_owned()),
                     frame_rate,
-                    audio: audio.map(|v| Cow::Owned(v.into_owned())),
-                    subtitles: subtitles.map(|v| Cow::Owned(v.into_owned())),
+                    audio: audio.map(intern),
+                    subtitles: subtitles.map(intern),
                     closed_captions: closed_captions.map(ClosedCaptions::into_owned),
                     stream_data: stream_data.into_owned(),
                 }
@@ -276,9 +277,13 @@ impl<'a> VariantStream<'a> {
     }
 }
 
-/// This tag requires [`ProtocolVersion::V1`].
+/// This tag requires the [`ProtocolVersion`] of its [`StreamData`].
 impl<'a> RequiredVersion for VariantStream<'a> {
-    fn required_version(&self) -> ProtocolVersion { ProtocolVersion::V1 }
+    fn required_version(&self) -> ProtocolVersion { self.deref().required_version() }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        self.deref().required_version_reasons()
+    }
 
     fn introduced_version(&self) -> ProtocolVersion {
         match &self {
@@ -360,7 +365,13 @@ impl<'a> TryFrom<&'a str> for VariantStream<'a> {
             let first_line = lines
                 .next()
                 .ok_or_else(|| Error::missing_value("first_line"))?;
-            let uri = lines.next().ok_or_else(|| Error::missing_value("URI"))?;
+            // blank lines and comments might appear between the tag and its uri:
+            let uri = lines
+                .map(str::trim)
+                .find(|line| {
+                    !line.is_empty() && (!line.starts_with('#') || line.starts_with("#EXT"))
+                })
+                .ok_or_else(|| Error::missing_value("URI"))?;
 
             let mut frame_rate = None;
             let mut audio = None;
@@ -506,4 +517,36 @@ mod tests {
                 .unwrap(),
         ));
     }
+
+    #[test]
+    fn test_parse_uri_after_blank_lines() {
+        let expected = VariantStream::ExtXStreamInf {
+            uri: "low/index.m3u8".into(),
+            frame_rate: None,
+            audio: None,
+            subtitles: None,
+            closed_captions: None,
+            stream_data: StreamData::new(150_000),
+        };
+
+        assert_eq!(
+            VariantStream::try_from("#EXT-X-STREAM-INF:BANDWIDTH=150000\nlow/index.m3u8").unwrap(),
+            expected
+        );
+        assert_eq!(
+            VariantStream::try_from("#EXT-X-STREAM-INF:BANDWIDTH=150000\n\n\nlow/index.m3u8")
+                .unwrap(),
+            expected
+        );
+        assert_eq!(
+            VariantStream::try_from(
+                "#EXT-X-STREAM-INF:BANDWIDTH=150000\r\n# a comment\r\n\r\nlow/index.m3u8\r\n"
+            )
+            .unwrap(),
+            expected
+        );
+
+        assert!(VariantStream::try_from("#EXT-X-STREAM-INF:BANDWIDTH=150000\n\n").is_err());
+        assert!(VariantStream::try_from("#EXT-X-STREAM-INF:BANDWIDTH=150000\n# comment").is_err());
+    }
 }
@@ -0,0 +1,77 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::types::ProtocolVersion;
+use crate::utils::{parse_yes_or_no, tag};
+use crate::{Error, RequiredVersion};
+
+/// Indicates whether the client may cache downloaded media segments for later
+/// replay.
+///
+/// This tag has been removed in [`ProtocolVersion::V7`], but it is still
+/// found in older playlists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) struct ExtXAllowCache(pub bool);
+
+impl ExtXAllowCache {
+    pub(crate) const PREFIX: &'static str = "#EXT-X-ALLOW-CACHE:";
+}
+
+/// This tag requires [`ProtocolVersion::V1`].
+impl RequiredVersion for ExtXAllowCache {
+    fn required_version(&self) -> ProtocolVersion { ProtocolVersion::V1 }
+}
+
+impl fmt::Display for ExtXAllowCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", Self::PREFIX, if self.0 { "YES" } else { "NO" })
+    }
+}
+
+impl TryFrom<&str> for ExtXAllowCache {
+    type Error = Error;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let input = tag(input, Self::PREFIX)?;
+
+        Ok(Self(parse_yes_or_no(input)?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            ExtXAllowCache(true).to_string(),
+            "#EXT-X-ALLOW-CACHE:YES".to_string()
+        );
+        assert_eq!(
+            ExtXAllowCache(false).to_string(),
+            "#EXT-X-ALLOW-CACHE:NO".to_string()
+        );
+    }
+
+    #[test]
+    fn test_parser() {
+        assert_eq!(
+            ExtXAllowCache(true),
+            ExtXAllowCache::try_from("#EXT-X-ALLOW-CACHE:YES").unwrap()
+        );
+        assert_eq!(
+            ExtXAllowCache(false),
+            ExtXAllowCache::try_from("#EXT-X-ALLOW-CACHE:NO").unwrap()
+        );
+
+        assert!(ExtXAllowCache::try_from("#EXT-X-ALLOW-CACHE:MAYBE").is_err());
+        assert!(ExtXAllowCache::try_from("#EXT-X-ALLOW-CACHE").is_err());
+    }
+
+    #[test]
+    fn test_required_version() {
+        assert_eq!(ExtXAllowCache(true).required_version(), ProtocolVersion::V1);
+    }
+}
@@ -1,9 +1,9 @@
 use std::convert::TryFrom;
 use std::fmt;
 
-use crate::types::ProtocolVersion;
+use crate::types::{Feature, ProtocolVersion};
 use crate::utils::tag;
-use crate::{Error, RequiredVersion};
+use crate::{Error, RequiredVersion, VersionReason};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub(crate) struct ExtXIFramesOnly;
@@ -15,6 +15,10 @@ impl ExtXIFramesOnly {
 /// This tag requires [`ProtocolVersion::V4`].
 impl RequiredVersion for ExtXIFramesOnly {
     fn required_version(&self) -> ProtocolVersion { ProtocolVersion::V4 }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        vec![VersionReason::from(Feature::IFramesOnly)]
+    }
 }
 
 impl fmt::Display for ExtXIFramesOnly {
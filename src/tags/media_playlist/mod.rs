@@ -1,11 +1,23 @@
+pub(crate) mod allow_cache;
 pub(crate) mod discontinuity_sequence;
 pub(crate) mod end_list;
 pub(crate) mod i_frames_only;
 pub(crate) mod media_sequence;
+pub(crate) mod part_inf;
+pub(crate) mod preload_hint;
+pub(crate) mod rendition_report;
+pub(crate) mod server_control;
+pub(crate) mod skip;
 pub(crate) mod target_duration;
 
+pub(crate) use allow_cache::*;
 pub(crate) use discontinuity_sequence::*;
 pub(crate) use end_list::*;
 pub(crate) use i_frames_only::*;
 pub(crate) use media_sequence::*;
+pub(crate) use part_inf::*;
+pub use preload_hint::*;
+pub use rendition_report::*;
+pub use server_control::*;
+pub use skip::*;
 pub(crate) use target_duration::*;
@@ -0,0 +1,88 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::Duration;
+
+use crate::attribute::AttributePairs;
+use crate::types::ProtocolVersion;
+use crate::utils::tag;
+use crate::{Error, RequiredVersion};
+
+/// Provides information about the partial segments of a Low-Latency HLS
+/// playlist.
+///
+/// The only attribute is the `PART-TARGET`, which is the maximum duration of
+/// a partial segment. Unlike the target duration, it may be a fraction of a
+/// second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub(crate) struct ExtXPartInf(pub Duration);
+
+impl ExtXPartInf {
+    pub(crate) const PREFIX: &'static str = "#EXT-X-PART-INF:";
+}
+
+/// This tag requires [`ProtocolVersion::V1`].
+impl RequiredVersion for ExtXPartInf {
+    fn required_version(&self) -> ProtocolVersion { ProtocolVersion::V1 }
+}
+
+impl fmt::Display for ExtXPartInf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}PART-TARGET={}", Self::PREFIX, self.0.as_secs_f64())
+    }
+}
+
+impl TryFrom<&str> for ExtXPartInf {
+    type Error = Error;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let input = tag(input, Self::PREFIX)?;
+
+        let value = AttributePairs::new(input)
+            .find_map(|(key, value)| (key == "PART-TARGET").then_some(value))
+            .ok_or_else(|| Error::missing_attribute("PART-TARGET"))?;
+
+        let seconds: f64 = value.parse().map_err(|e| Error::parse_float(value, e))?;
+
+        Duration::try_from_secs_f64(seconds)
+            .map(Self)
+            .map_err(|_| Error::invalid_attribute(format!("invalid part target: {:?}", value)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            ExtXPartInf(Duration::from_secs_f64(0.33334)).to_string(),
+            "#EXT-X-PART-INF:PART-TARGET=0.33334".to_string()
+        );
+        assert_eq!(
+            ExtXPartInf(Duration::from_secs(1)).to_string(),
+            "#EXT-X-PART-INF:PART-TARGET=1".to_string()
+        );
+    }
+
+    #[test]
+    fn test_required_version() {
+        assert_eq!(
+            ExtXPartInf(Duration::from_secs(1)).required_version(),
+            ProtocolVersion::V1
+        );
+    }
+
+    #[test]
+    fn test_parser() {
+        assert_eq!(
+            ExtXPartInf(Duration::from_secs_f64(0.33334)),
+            ExtXPartInf::try_from("#EXT-X-PART-INF:PART-TARGET=0.33334").unwrap()
+        );
+
+        assert!(ExtXPartInf::try_from("#EXT-X-PART-INF:").is_err());
+        assert!(ExtXPartInf::try_from("#EXT-X-PART-INF:PART-TARGET=-1").is_err());
+        assert!(ExtXPartInf::try_from("#EXT-X-PART-INF:PART-TARGET=NaN").is_err());
+    }
+}
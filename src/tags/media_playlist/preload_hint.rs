@@ -0,0 +1,219 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use shorthand::ShortHand;
+
+use crate::attribute::AttributePairs;
+use crate::types::{PreloadHintType, ProtocolVersion, UriRef};
+use crate::utils::{quote, tag, unquote};
+use crate::{Error, RequiredVersion};
+
+/// The [`ExtXPreloadHint`] tag allows a client to request a resource (like
+/// the next partial segment), before it is added to the playlist.
+///
+/// # Example
+///
+/// ```
+/// # use hls_m3u8::tags::ExtXPreloadHint;
+/// use hls_m3u8::types::PreloadHintType;
+///
+/// let mut hint = ExtXPreloadHint::new(PreloadHintType::Part, "part3.mp4");
+/// hint.set_byte_range_start(Some(9000));
+///
+/// assert_eq!(
+///     hint.to_string(),
+///     "#EXT-X-PRELOAD-HINT:TYPE=PART,URI=\"part3.mp4\",BYTERANGE-START=9000"
+/// );
+/// ```
+#[derive(ShortHand, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[shorthand(enable(must_use, into))]
+pub struct ExtXPreloadHint<'a> {
+    /// The type of the announced resource.
+    #[shorthand(enable(copy), disable(into))]
+    hint_type: PreloadHintType,
+    /// The `URI` of the announced resource.
+    uri: UriRef<'a>,
+    /// The offset of the first byte of the resource, that the hint refers to.
+    ///
+    /// If it is missing, the resource starts at the first byte.
+    #[shorthand(enable(copy), disable(into))]
+    byte_range_start: Option<usize>,
+    /// The number of bytes of the resource, that the hint refers to.
+    ///
+    /// If it is missing, the hint refers to all bytes after the
+    /// [`ExtXPreloadHint::byte_range_start`].
+    #[shorthand(enable(copy), disable(into))]
+    byte_range_length: Option<usize>,
+}
+
+impl<'a> ExtXPreloadHint<'a> {
+    pub(crate) const PREFIX: &'static str = "#EXT-X-PRELOAD-HINT:";
+
+    /// Makes a new [`ExtXPreloadHint`] tag.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtXPreloadHint;
+    /// use hls_m3u8::types::PreloadHintType;
+    ///
+    /// let hint = ExtXPreloadHint::new(PreloadHintType::Map, "init.mp4");
+    /// ```
+    #[must_use]
+    pub fn new<T: Into<UriRef<'a>>>(hint_type: PreloadHintType, uri: T) -> Self {
+        Self {
+            hint_type,
+            uri: uri.into(),
+            byte_range_start: None,
+            byte_range_length: None,
+        }
+    }
+
+    /// Makes the struct independent of its lifetime, by taking ownership of all
+    /// internal [`Cow`]s.
+    ///
+    /// # Note
+    ///
+    /// This is a relatively expensive operation.
+    ///
+    /// [`Cow`]: std::borrow::Cow
+    #[must_use]
+    pub fn into_owned(self) -> ExtXPreloadHint<'static> {
+        ExtXPreloadHint {
+            hint_type: self.hint_type,
+            uri: self.uri.into_owned(),
+            byte_range_start: self.byte_range_start,
+            byte_range_length: self.byte_range_length,
+        }
+    }
+}
+
+/// This tag requires [`ProtocolVersion::V1`].
+impl<'a> RequiredVersion for ExtXPreloadHint<'a> {
+    fn required_version(&self) -> ProtocolVersion { ProtocolVersion::V1 }
+}
+
+impl<'a> fmt::Display for ExtXPreloadHint<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Self::PREFIX)?;
+        write!(f, "TYPE={}", self.hint_type)?;
+        write!(f, ",URI={}", quote(&self.uri))?;
+
+        if let Some(value) = &self.byte_range_start {
+            write!(f, ",BYTERANGE-START={}", value)?;
+        }
+
+        if let Some(value) = &self.byte_range_length {
+            write!(f, ",BYTERANGE-LENGTH={}", value)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> TryFrom<&'a str> for ExtXPreloadHint<'a> {
+    type Error = Error;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        let input = tag(input, Self::PREFIX)?;
+
+        let mut hint_type = None;
+        let mut uri = None;
+        let mut byte_range_start = None;
+        let mut byte_range_length = None;
+
+        for (key, value) in AttributePairs::new(input) {
+            match key {
+                "TYPE" => hint_type = Some(value.parse::<PreloadHintType>()?),
+                "URI" => uri = Some(unquote(value)),
+                "BYTERANGE-START" => {
+                    byte_range_start = Some(value.parse().map_err(|e| Error::parse_int(value, e))?);
+                }
+                "BYTERANGE-LENGTH" => {
+                    byte_range_length =
+                        Some(value.parse().map_err(|e| Error::parse_int(value, e))?);
+                }
+                _ => {
+                    // [6.3.1. General Client Responsibilities]
+                    // > ignore any attribute/value pair with an unrecognized
+                    // AttributeName.
+                }
+            }
+        }
+
+        let hint_type = hint_type.ok_or_else(|| Error::missing_value("TYPE"))?;
+        let uri = uri.ok_or_else(|| Error::missing_value("URI"))?.into();
+
+        Ok(Self {
+            hint_type,
+            uri,
+            byte_range_start,
+            byte_range_length,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            ExtXPreloadHint::new(PreloadHintType::Map, "init.mp4").to_string(),
+            "#EXT-X-PRELOAD-HINT:TYPE=MAP,URI=\"init.mp4\"".to_string()
+        );
+
+        let mut hint = ExtXPreloadHint::new(PreloadHintType::Part, "part.mp4");
+        hint.set_byte_range_start(Some(10))
+            .set_byte_range_length(Some(20));
+
+        assert_eq!(
+            hint.to_string(),
+            concat!(
+                "#EXT-X-PRELOAD-HINT:TYPE=PART,URI=\"part.mp4\",",
+                "BYTERANGE-START=10,BYTERANGE-LENGTH=20"
+            )
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_parser() {
+        assert_eq!(
+            ExtXPreloadHint::new(PreloadHintType::Map, "init.mp4"),
+            ExtXPreloadHint::try_from("#EXT-X-PRELOAD-HINT:TYPE=MAP,URI=\"init.mp4\"").unwrap()
+        );
+
+        let mut hint = ExtXPreloadHint::new(PreloadHintType::Part, "part.mp4");
+        hint.set_byte_range_length(Some(20));
+
+        assert_eq!(
+            hint,
+            ExtXPreloadHint::try_from(concat!(
+                "#EXT-X-PRELOAD-HINT:BYTERANGE-LENGTH=20,URI=\"part.mp4\",",
+                "TYPE=PART,UNKNOWN=IGNORED"
+            ))
+            .unwrap()
+        );
+
+        assert!(ExtXPreloadHint::try_from("#EXT-X-PRELOAD-HINT:URI=\"part.mp4\"").is_err());
+        assert!(ExtXPreloadHint::try_from("#EXT-X-PRELOAD-HINT:TYPE=PART").is_err());
+        assert!(
+            ExtXPreloadHint::try_from("#EXT-X-PRELOAD-HINT:TYPE=SEGMENT,URI=\"a.ts\"").is_err()
+        );
+        assert!(ExtXPreloadHint::try_from(
+            "#EXT-X-PRELOAD-HINT:TYPE=PART,URI=\"a.mp4\",BYTERANGE-START=-1"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_required_version() {
+        assert_eq!(
+            ExtXPreloadHint::new(PreloadHintType::Part, "part.mp4").required_version(),
+            ProtocolVersion::V1
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use shorthand::ShortHand;
+
+use crate::attribute::AttributePairs;
+use crate::types::{ProtocolVersion, UriRef};
+use crate::utils::{quote, tag, unquote};
+use crate::{Error, RequiredVersion};
+
+/// The [`ExtXRenditionReport`] tag informs the client about the most recent
+/// [`MediaSegment`] and partial segment of another rendition, so a client can
+/// switch to it without reloading its playlist first.
+///
+/// # Example
+///
+/// ```
+/// # use hls_m3u8::tags::ExtXRenditionReport;
+/// let mut report = ExtXRenditionReport::new("../1M/waitForMSN.php");
+/// report.set_last_msn(Some(273)).set_last_part(Some(2));
+///
+/// assert_eq!(
+///     report.to_string(),
+///     "#EXT-X-RENDITION-REPORT:URI=\"../1M/waitForMSN.php\",LAST-MSN=273,LAST-PART=2"
+/// );
+/// ```
+///
+/// [`MediaSegment`]: crate::MediaSegment
+#[derive(ShortHand, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[shorthand(enable(must_use, into))]
+pub struct ExtXRenditionReport<'a> {
+    /// The `URI` of the [`MediaPlaylist`] of the other rendition (relative to
+    /// the uri of the playlist, that contains this tag).
+    ///
+    /// [`MediaPlaylist`]: crate::MediaPlaylist
+    uri: UriRef<'a>,
+    /// The [`MediaSegment::number`] of the last segment in the other
+    /// rendition.
+    ///
+    /// [`MediaSegment::number`]: crate::MediaSegment::number
+    #[shorthand(enable(copy), disable(into))]
+    last_msn: Option<usize>,
+    /// The index of the last partial segment in the other rendition.
+    #[shorthand(enable(copy), disable(into))]
+    last_part: Option<usize>,
+}
+
+impl<'a> ExtXRenditionReport<'a> {
+    pub(crate) const PREFIX: &'static str = "#EXT-X-RENDITION-REPORT:";
+
+    /// Makes a new [`ExtXRenditionReport`] tag.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtXRenditionReport;
+    /// let report = ExtXRenditionReport::new("../1M/waitForMSN.php");
+    /// ```
+    #[must_use]
+    pub fn new<T: Into<UriRef<'a>>>(uri: T) -> Self {
+        Self {
+            uri: uri.into(),
+            last_msn: None,
+            last_part: None,
+        }
+    }
+
+    /// Makes the struct independent of its lifetime, by taking ownership of all
+    /// internal [`Cow`]s.
+    ///
+    /// # Note
+    ///
+    /// This is a relatively expensive operation.
+    ///
+    /// [`Cow`]: std::borrow::Cow
+    #[must_use]
+    pub fn into_owned(self) -> ExtXRenditionReport<'static> {
+        ExtXRenditionReport {
+            uri: self.uri.into_owned(),
+            last_msn: self.last_msn,
+            last_part: self.last_part,
+        }
+    }
+}
+
+/// This tag requires [`ProtocolVersion::V1`].
+impl<'a> RequiredVersion for ExtXRenditionReport<'a> {
+    fn required_version(&self) -> ProtocolVersion { ProtocolVersion::V1 }
+}
+
+impl<'a> fmt::Display for ExtXRenditionReport<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Self::PREFIX)?;
+        write!(f, "URI={}", quote(&self.uri))?;
+
+        if let Some(value) = &self.last_msn {
+            write!(f, ",LAST-MSN={}", value)?;
+        }
+
+        if let Some(value) = &self.last_part {
+            write!(f, ",LAST-PART={}", value)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> TryFrom<&'a str> for ExtXRenditionReport<'a> {
+    type Error = Error;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        let input = tag(input, Self::PREFIX)?;
+
+        let mut uri = None;
+        let mut last_msn = None;
+        let mut last_part = None;
+
+        for (key, value) in AttributePairs::new(input) {
+            match key {
+                "URI" => uri = Some(unquote(value)),
+                "LAST-MSN" => {
+                    last_msn = Some(value.parse().map_err(|e| Error::parse_int(value, e))?);
+                }
+                "LAST-PART" => {
+                    last_part = Some(value.parse().map_err(|e| Error::parse_int(value, e))?);
+                }
+                _ => {
+                    // [6.3.1. General Client Responsibilities]
+                    // > ignore any attribute/value pair with an unrecognized
+                    // AttributeName.
+                }
+            }
+        }
+
+        let uri = uri.ok_or_else(|| Error::missing_value("URI"))?.into();
+
+        Ok(Self {
+            uri,
+            last_msn,
+            last_part,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            ExtXRenditionReport::new("a.m3u8").to_string(),
+            "#EXT-X-RENDITION-REPORT:URI=\"a.m3u8\"".to_string()
+        );
+
+        let mut report = ExtXRenditionReport::new("a.m3u8");
+        report.set_last_msn(Some(10)).set_last_part(Some(0));
+
+        assert_eq!(
+            report.to_string(),
+            "#EXT-X-RENDITION-REPORT:URI=\"a.m3u8\",LAST-MSN=10,LAST-PART=0".to_string()
+        );
+    }
+
+    #[test]
+    fn test_parser() {
+        assert_eq!(
+            ExtXRenditionReport::new("a.m3u8"),
+            ExtXRenditionReport::try_from("#EXT-X-RENDITION-REPORT:URI=\"a.m3u8\"").unwrap()
+        );
+
+        let mut report = ExtXRenditionReport::new("a.m3u8");
+        report.set_last_msn(Some(10));
+
+        assert_eq!(
+            report,
+            ExtXRenditionReport::try_from(
+                "#EXT-X-RENDITION-REPORT:LAST-MSN=10,URI=\"a.m3u8\",UNKNOWN=IGNORED"
+            )
+            .unwrap()
+        );
+
+        assert!(ExtXRenditionReport::try_from("#EXT-X-RENDITION-REPORT:LAST-MSN=10").is_err());
+        assert!(ExtXRenditionReport::try_from(
+            "#EXT-X-RENDITION-REPORT:URI=\"a.m3u8\",LAST-PART=-1"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_required_version() {
+        assert_eq!(
+            ExtXRenditionReport::new("a.m3u8").required_version(),
+            ProtocolVersion::V1
+        );
+    }
+}
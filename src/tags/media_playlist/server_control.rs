@@ -0,0 +1,245 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::Duration;
+
+use shorthand::ShortHand;
+
+use crate::attribute::AttributePairs;
+use crate::types::ProtocolVersion;
+use crate::utils::{parse_yes_or_no, tag};
+use crate::{Error, RequiredVersion};
+
+/// The [`ExtXServerControl`] tag allows the server to indicate support for
+/// the delivery directives of Low-Latency HLS (like blocking playlist reloads
+/// and playlist delta updates).
+///
+/// # Example
+///
+/// ```
+/// # use hls_m3u8::tags::ExtXServerControl;
+/// use std::time::Duration;
+///
+/// let mut server_control = ExtXServerControl::new();
+///
+/// server_control
+///     .set_can_block_reload(true)
+///     .set_part_hold_back(Some(Duration::from_secs(1)));
+///
+/// assert_eq!(
+///     server_control.to_string(),
+///     "#EXT-X-SERVER-CONTROL:PART-HOLD-BACK=1,CAN-BLOCK-RELOAD=YES"
+/// );
+/// ```
+#[derive(ShortHand, Default, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[shorthand(enable(must_use, copy))]
+pub struct ExtXServerControl {
+    /// The duration from the end of the playlist, before which the server can
+    /// skip [`MediaSegment`]s in a playlist delta update.
+    ///
+    /// It must be at least six times the [`MediaPlaylist::target_duration`].
+    ///
+    /// [`MediaSegment`]: crate::MediaSegment
+    /// [`MediaPlaylist::target_duration`]:
+    /// crate::MediaPlaylist::target_duration
+    can_skip_until: Option<Duration>,
+    /// Whether the server can also skip the `#EXT-X-DATERANGE` tags of the
+    /// skipped segments in a playlist delta update.
+    ///
+    /// This requires [`ExtXServerControl::can_skip_until`].
+    can_skip_dateranges: bool,
+    /// The minimum distance from the end of the playlist, at which clients
+    /// should begin to play or to which they should seek.
+    ///
+    /// It must be at least three times the
+    /// [`MediaPlaylist::target_duration`].
+    ///
+    /// [`MediaPlaylist::target_duration`]:
+    /// crate::MediaPlaylist::target_duration
+    hold_back: Option<Duration>,
+    /// Like [`ExtXServerControl::hold_back`], but for playback in low-latency
+    /// mode.
+    part_hold_back: Option<Duration>,
+    /// Whether the server supports blocking playlist reloads.
+    can_block_reload: bool,
+}
+
+impl ExtXServerControl {
+    pub(crate) const PREFIX: &'static str = "#EXT-X-SERVER-CONTROL:";
+
+    /// Makes a new [`ExtXServerControl`] tag, which does not indicate support
+    /// for any delivery directive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtXServerControl;
+    /// let server_control = ExtXServerControl::new();
+    ///
+    /// assert_eq!(server_control.can_block_reload(), false);
+    /// ```
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            can_skip_until: None,
+            can_skip_dateranges: false,
+            hold_back: None,
+            part_hold_back: None,
+            can_block_reload: false,
+        }
+    }
+}
+
+/// This tag requires [`ProtocolVersion::V1`].
+impl RequiredVersion for ExtXServerControl {
+    fn required_version(&self) -> ProtocolVersion { ProtocolVersion::V1 }
+}
+
+impl fmt::Display for ExtXServerControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Self::PREFIX)?;
+
+        let mut attributes = vec![];
+
+        if let Some(value) = &self.can_skip_until {
+            attributes.push(format!("CAN-SKIP-UNTIL={}", value.as_secs_f64()));
+        }
+
+        if self.can_skip_dateranges {
+            attributes.push("CAN-SKIP-DATERANGES=YES".to_string());
+        }
+
+        if let Some(value) = &self.hold_back {
+            attributes.push(format!("HOLD-BACK={}", value.as_secs_f64()));
+        }
+
+        if let Some(value) = &self.part_hold_back {
+            attributes.push(format!("PART-HOLD-BACK={}", value.as_secs_f64()));
+        }
+
+        if self.can_block_reload {
+            attributes.push("CAN-BLOCK-RELOAD=YES".to_string());
+        }
+
+        write!(f, "{}", attributes.join(","))
+    }
+}
+
+impl TryFrom<&str> for ExtXServerControl {
+    type Error = Error;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let input = tag(input, Self::PREFIX)?;
+
+        let mut result = Self::new();
+
+        for (key, value) in AttributePairs::new(input) {
+            match key {
+                "CAN-SKIP-UNTIL" => result.can_skip_until = Some(parse_duration(value)?),
+                "CAN-SKIP-DATERANGES" => result.can_skip_dateranges = parse_yes_or_no(value)?,
+                "HOLD-BACK" => result.hold_back = Some(parse_duration(value)?),
+                "PART-HOLD-BACK" => result.part_hold_back = Some(parse_duration(value)?),
+                "CAN-BLOCK-RELOAD" => result.can_block_reload = parse_yes_or_no(value)?,
+                _ => {
+                    // [6.3.1. General Client Responsibilities]
+                    // > ignore any attribute/value pair with an unrecognized
+                    // AttributeName.
+                }
+            }
+        }
+
+        if result.can_skip_dateranges && result.can_skip_until.is_none() {
+            return Err(Error::missing_attribute("CAN-SKIP-UNTIL"));
+        }
+
+        Ok(result)
+    }
+}
+
+fn parse_duration(value: &str) -> crate::Result<Duration> {
+    let seconds: f64 = value.parse().map_err(|e| Error::parse_float(value, e))?;
+
+    if !seconds.is_finite() || seconds < 0.0 {
+        return Err(Error::invalid_attribute(format!(
+            "invalid duration: {:?}",
+            value
+        )));
+    }
+
+    Ok(Duration::from_secs_f64(seconds))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            ExtXServerControl::new().to_string(),
+            "#EXT-X-SERVER-CONTROL:".to_string()
+        );
+
+        let mut server_control = ExtXServerControl::new();
+        server_control
+            .set_can_skip_until(Some(Duration::from_secs(36)))
+            .set_can_skip_dateranges(true)
+            .set_hold_back(Some(Duration::from_secs(18)))
+            .set_part_hold_back(Some(Duration::from_millis(1500)))
+            .set_can_block_reload(true);
+
+        assert_eq!(
+            server_control.to_string(),
+            concat!(
+                "#EXT-X-SERVER-CONTROL:",
+                "CAN-SKIP-UNTIL=36,",
+                "CAN-SKIP-DATERANGES=YES,",
+                "HOLD-BACK=18,",
+                "PART-HOLD-BACK=1.5,",
+                "CAN-BLOCK-RELOAD=YES"
+            )
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_parser() {
+        assert_eq!(
+            ExtXServerControl::new(),
+            ExtXServerControl::try_from("#EXT-X-SERVER-CONTROL:").unwrap()
+        );
+
+        let mut server_control = ExtXServerControl::new();
+        server_control
+            .set_can_skip_until(Some(Duration::from_secs(36)))
+            .set_part_hold_back(Some(Duration::from_millis(1500)))
+            .set_can_block_reload(true);
+
+        assert_eq!(
+            server_control,
+            ExtXServerControl::try_from(concat!(
+                "#EXT-X-SERVER-CONTROL:",
+                "CAN-BLOCK-RELOAD=YES,",
+                "PART-HOLD-BACK=1.5,",
+                "CAN-SKIP-UNTIL=36.0,",
+                "UNKNOWN=IGNORED"
+            ))
+            .unwrap()
+        );
+
+        assert!(
+            ExtXServerControl::try_from("#EXT-X-SERVER-CONTROL:CAN-SKIP-DATERANGES=YES").is_err()
+        );
+        assert!(ExtXServerControl::try_from("#EXT-X-SERVER-CONTROL:HOLD-BACK=-1").is_err());
+        assert!(ExtXServerControl::try_from("#EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=1").is_err());
+        assert!(ExtXServerControl::try_from("#EXT-X-SERVER-CONTROL").is_err());
+    }
+
+    #[test]
+    fn test_required_version() {
+        assert_eq!(
+            ExtXServerControl::new().required_version(),
+            ProtocolVersion::V1
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
+
+use shorthand::ShortHand;
+
+use crate::attribute::AttributePairs;
+use crate::types::ProtocolVersion;
+use crate::utils::{quote, tag, unquote};
+use crate::{Error, RequiredVersion};
+
+/// The [`ExtXSkip`] tag replaces the [`MediaSegment`]s at the start of a
+/// playlist delta update, that have been skipped by the server.
+///
+/// The skipped segments follow the [`MediaPlaylist::media_sequence`], so the
+/// first [`MediaSegment`] after this tag has the number
+/// `media_sequence + skipped_segments`.
+///
+/// [`MediaSegment`]: crate::MediaSegment
+/// [`MediaPlaylist::media_sequence`]: crate::MediaPlaylist::media_sequence
+#[derive(ShortHand, Default, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[shorthand(enable(must_use, into))]
+pub struct ExtXSkip<'a> {
+    /// The number of skipped [`MediaSegment`]s.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtXSkip;
+    /// let mut skip = ExtXSkip::new(10);
+    /// # assert_eq!(skip.skipped_segments(), 10);
+    /// skip.set_skipped_segments(20);
+    ///
+    /// assert_eq!(skip.skipped_segments(), 20);
+    /// ```
+    ///
+    /// [`MediaSegment`]: crate::MediaSegment
+    #[shorthand(enable(copy), disable(into))]
+    skipped_segments: usize,
+    /// The ids of the `#EXT-X-DATERANGE` tags, that have been removed from the
+    /// playlist since the last playlist update.
+    #[shorthand(enable(collection_magic))]
+    recently_removed_dateranges: Vec<Cow<'a, str>>,
+}
+
+impl<'a> ExtXSkip<'a> {
+    pub(crate) const PREFIX: &'static str = "#EXT-X-SKIP:";
+
+    /// Makes a new [`ExtXSkip`] tag.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtXSkip;
+    /// let skip = ExtXSkip::new(10);
+    ///
+    /// assert_eq!(skip.to_string(), "#EXT-X-SKIP:SKIPPED-SEGMENTS=10");
+    /// ```
+    #[must_use]
+    pub const fn new(skipped_segments: usize) -> Self {
+        Self {
+            skipped_segments,
+            recently_removed_dateranges: Vec::new(),
+        }
+    }
+
+    /// Makes the struct independent of its lifetime, by taking ownership of all
+    /// internal [`Cow`]s.
+    ///
+    /// # Note
+    ///
+    /// This is a relatively expensive operation.
+    #[must_use]
+    pub fn into_owned(self) -> ExtXSkip<'static> {
+        ExtXSkip {
+            skipped_segments: self.skipped_segments,
+            recently_removed_dateranges: self
+                .recently_removed_dateranges
+                .into_iter()
+                .map(|v| Cow::Owned(v.into_owned()))
+                .collect(),
+        }
+    }
+}
+
+/// This tag requires [`ProtocolVersion::V1`].
+impl<'a> RequiredVersion for ExtXSkip<'a> {
+    fn required_version(&self) -> ProtocolVersion { ProtocolVersion::V1 }
+}
+
+impl<'a> fmt::Display for ExtXSkip<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Self::PREFIX)?;
+        write!(f, "SKIPPED-SEGMENTS={}", self.skipped_segments)?;
+
+        if !self.recently_removed_dateranges.is_empty() {
+            write!(
+                f,
+                ",RECENTLY-REMOVED-DATERANGES={}",
+                quote(self.recently_removed_dateranges.join("\t"))
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> TryFrom<&'a str> for ExtXSkip<'a> {
+    type Error = Error;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        let input = tag(input, Self::PREFIX)?;
+
+        let mut skipped_segments = None;
+        let mut recently_removed_dateranges = Vec::new();
+
+        for (key, value) in AttributePairs::new(input) {
+            match key {
+                "SKIPPED-SEGMENTS" => {
+                    skipped_segments = Some(value.parse().map_err(|e| Error::parse_int(value, e))?);
+                }
+                "RECENTLY-REMOVED-DATERANGES" => {
+                    recently_removed_dateranges = match unquote(value) {
+                        Cow::Borrowed(value) => {
+                            value
+                                .split('\t')
+                                .filter(|id| !id.is_empty())
+                                .map(Cow::Borrowed)
+                                .collect()
+                        }
+                        Cow::Owned(value) => {
+                            value
+                                .split('\t')
+                                .filter(|id| !id.is_empty())
+                                .map(|id| Cow::Owned(id.to_string()))
+                                .collect()
+                        }
+                    };
+                }
+                _ => {
+                    // [6.3.1. General Client Responsibilities]
+                    // > ignore any attribute/value pair with an unrecognized
+                    // AttributeName.
+                }
+            }
+        }
+
+        let skipped_segments =
+            skipped_segments.ok_or_else(|| Error::missing_value("SKIPPED-SEGMENTS"))?;
+
+        Ok(Self {
+            skipped_segments,
+            recently_removed_dateranges,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            ExtXSkip::new(3).to_string(),
+            "#EXT-X-SKIP:SKIPPED-SEGMENTS=3".to_string()
+        );
+
+        let mut skip = ExtXSkip::new(3);
+        skip.set_recently_removed_dateranges(vec!["ad1".into(), "ad2".into()]);
+
+        assert_eq!(
+            skip.to_string(),
+            "#EXT-X-SKIP:SKIPPED-SEGMENTS=3,RECENTLY-REMOVED-DATERANGES=\"ad1\tad2\"".to_string()
+        );
+    }
+
+    #[test]
+    fn test_parser() {
+        assert_eq!(
+            ExtXSkip::new(3),
+            ExtXSkip::try_from("#EXT-X-SKIP:SKIPPED-SEGMENTS=3").unwrap()
+        );
+
+        let mut skip = ExtXSkip::new(3);
+        skip.set_recently_removed_dateranges(vec!["ad1".into(), "ad2".into()]);
+
+        assert_eq!(
+            skip,
+            ExtXSkip::try_from(
+                "#EXT-X-SKIP:RECENTLY-REMOVED-DATERANGES=\"ad1\tad2\",SKIPPED-SEGMENTS=3"
+            )
+            .unwrap()
+        );
+
+        assert!(ExtXSkip::try_from("#EXT-X-SKIP:SKIPPED-SEGMENTS=-1").is_err());
+        assert!(ExtXSkip::try_from("#EXT-X-SKIP:RECENTLY-REMOVED-DATERANGES=\"a\"").is_err());
+    }
+
+    #[test]
+    fn test_required_version() {
+        assert_eq!(ExtXSkip::new(3).required_version(), ProtocolVersion::V1);
+    }
+}
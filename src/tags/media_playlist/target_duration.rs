@@ -7,6 +7,10 @@ use crate::utils::tag;
 use crate::{Error, RequiredVersion};
 
 /// Specifies the maximum `MediaSegment` duration.
+///
+/// The value of the tag must be an integer, so a sub-second duration is
+/// rounded up to the next full second, when it is written. Otherwise the
+/// longest segments would exceed the written target duration.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
 pub(crate) struct ExtXTargetDuration(pub Duration);
 
@@ -21,7 +25,9 @@ impl RequiredVersion for ExtXTargetDuration {
 
 impl fmt::Display for ExtXTargetDuration {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}{}", Self::PREFIX, self.0.as_secs())
+        let seconds = self.0.as_secs() + u64::from(self.0.subsec_nanos() > 0);
+
+        write!(f, "{}{}", Self::PREFIX, seconds)
     }
 }
 
@@ -48,6 +54,14 @@ mod test {
             ExtXTargetDuration(Duration::from_secs(5)).to_string(),
             "#EXT-X-TARGETDURATION:5".to_string()
         );
+        assert_eq!(
+            ExtXTargetDuration(Duration::from_millis(5001)).to_string(),
+            "#EXT-X-TARGETDURATION:6".to_string()
+        );
+        assert_eq!(
+            ExtXTargetDuration(Duration::from_millis(500)).to_string(),
+            "#EXT-X-TARGETDURATION:1".to_string()
+        );
     }
 
     #[test]
@@ -0,0 +1,67 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::types::ProtocolVersion;
+use crate::utils::tag;
+use crate::{Error, RequiredVersion};
+
+/// The approximate bitrate of the `MediaSegment`s in kbit/s, that follow the
+/// tag, until the next `ExtXBitrate` tag.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct ExtXBitrate(pub u64);
+
+impl ExtXBitrate {
+    pub(crate) const PREFIX: &'static str = "#EXT-X-BITRATE:";
+}
+
+/// This tag requires [`ProtocolVersion::V1`].
+impl RequiredVersion for ExtXBitrate {
+    fn required_version(&self) -> ProtocolVersion { ProtocolVersion::V1 }
+}
+
+impl fmt::Display for ExtXBitrate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", Self::PREFIX, self.0)
+    }
+}
+
+impl TryFrom<&str> for ExtXBitrate {
+    type Error = Error;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let input = tag(input, Self::PREFIX)?;
+        let bitrate = input.parse().map_err(|e| Error::parse_int(input, e))?;
+
+        Ok(Self(bitrate))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            ExtXBitrate(4500).to_string(),
+            "#EXT-X-BITRATE:4500".to_string()
+        );
+    }
+
+    #[test]
+    fn test_required_version() {
+        assert_eq!(ExtXBitrate(4500).required_version(), ProtocolVersion::V1);
+    }
+
+    #[test]
+    fn test_parser() {
+        assert_eq!(
+            ExtXBitrate(4500),
+            ExtXBitrate::try_from("#EXT-X-BITRATE:4500").unwrap()
+        );
+
+        assert!(ExtXBitrate::try_from("#EXT-X-BITRATE:-1").is_err());
+        assert!(ExtXBitrate::try_from("#EXT-X-BITRATE:").is_err());
+    }
+}
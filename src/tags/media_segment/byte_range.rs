@@ -5,9 +5,9 @@ use core::ops::{Add, AddAssign, Sub, SubAssign};
 
 use derive_more::{AsMut, AsRef, Deref, DerefMut, From};
 
-use crate::types::{ByteRange, ProtocolVersion};
+use crate::types::{ByteRange, Feature, ProtocolVersion};
 use crate::utils::tag;
-use crate::{Error, RequiredVersion};
+use crate::{Error, RequiredVersion, VersionReason};
 
 /// Indicates that a [`MediaSegment`] is a sub-range of the resource identified
 /// by its `URI`.
@@ -130,11 +130,117 @@ impl ExtXByteRange {
     #[inline]
     #[must_use]
     pub const fn as_byte_range(&self) -> &ByteRange { &self.0 }
+
+    /// Makes a new [`ExtXByteRange`] from the values of a `BYTERANGE-START`
+    /// and a `BYTERANGE-LENGTH` attribute, like they are used by the
+    /// `EXT-X-PRELOAD-HINT` tag.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtXByteRange;
+    /// assert_eq!(
+    ///     ExtXByteRange::from_attributes(Some(2), 13),
+    ///     ExtXByteRange::from(2..15)
+    /// );
+    ///
+    /// assert_eq!(
+    ///     ExtXByteRange::from_attributes(None, 22),
+    ///     ExtXByteRange::from(..22)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn from_attributes(start: Option<usize>, length: usize) -> Self {
+        match start {
+            Some(start) => Self::from(start..start.saturating_add(length)),
+            None => Self::from(..length),
+        }
+    }
+
+    /// Returns the values of the `BYTERANGE-START` and the `BYTERANGE-LENGTH`
+    /// attribute, that describe this range.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtXByteRange;
+    /// assert_eq!(ExtXByteRange::from(2..15).to_attributes(), (Some(2), 13));
+    /// assert_eq!(ExtXByteRange::from(..22).to_attributes(), (None, 22));
+    /// ```
+    #[must_use]
+    pub fn to_attributes(&self) -> (Option<usize>, usize) { (self.start(), self.len()) }
+
+    /// Parses the unquoted values of a `BYTERANGE-START` and a
+    /// `BYTERANGE-LENGTH` attribute.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtXByteRange;
+    /// assert_eq!(
+    ///     ExtXByteRange::parse_attributes(Some("2"), "13")?,
+    ///     ExtXByteRange::from(2..15)
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails, if one of the values is not a valid integer.
+    pub fn parse_attributes(start: Option<&str>, length: &str) -> crate::Result<Self> {
+        let start = start
+            .map(|value| value.parse().map_err(|e| Error::parse_int(value, e)))
+            .transpose()?;
+
+        let length = length.parse().map_err(|e| Error::parse_int(length, e))?;
+
+        Ok(Self::from_attributes(start, length))
+    }
+
+    /// Returns a value, that displays this range as `BYTERANGE-START` and
+    /// `BYTERANGE-LENGTH` attributes. The `BYTERANGE-START` attribute is
+    /// omitted, if there is no start.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtXByteRange;
+    /// assert_eq!(
+    ///     ExtXByteRange::from(2..15).attributes().to_string(),
+    ///     "BYTERANGE-START=2,BYTERANGE-LENGTH=13"
+    /// );
+    ///
+    /// assert_eq!(
+    ///     ExtXByteRange::from(..22).attributes().to_string(),
+    ///     "BYTERANGE-LENGTH=22"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn attributes(&self) -> impl fmt::Display {
+        struct Attributes(Option<usize>, usize);
+
+        impl fmt::Display for Attributes {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                if let Some(start) = self.0 {
+                    write!(f, "BYTERANGE-START={},", start)?;
+                }
+
+                write!(f, "BYTERANGE-LENGTH={}", self.1)
+            }
+        }
+
+        let (start, length) = self.to_attributes();
+        Attributes(start, length)
+    }
 }
 
 /// This tag requires [`ProtocolVersion::V4`].
 impl RequiredVersion for ExtXByteRange {
     fn required_version(&self) -> ProtocolVersion { ProtocolVersion::V4 }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        vec![VersionReason::from(Feature::ByteRange)]
+    }
 }
 
 #[allow(clippy::from_over_into)] // Some magic `From` blanket impl is going on that means this can't be done.
@@ -148,6 +254,7 @@ where
 {
     type Output = Self;
 
+    #[allow(unused_attributes)]
     #[must_use]
     #[inline]
     fn sub(self, rhs: T) -> Self::Output { Self(self.0.sub(rhs)) }
@@ -167,6 +274,7 @@ where
 {
     type Output = Self;
 
+    #[allow(unused_attributes)]
     #[must_use]
     #[inline]
     fn add(self, rhs: T) -> Self::Output { Self(self.0.add(rhs)) }
@@ -229,6 +337,31 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_attributes() {
+        for range in &[ExtXByteRange::from(2..15), ExtXByteRange::from(..22)] {
+            let (start, length) = range.to_attributes();
+
+            assert_eq!(ExtXByteRange::from_attributes(start, length), *range);
+            assert_eq!(
+                ExtXByteRange::parse_attributes(
+                    start.map(|v| v.to_string()).as_deref(),
+                    &length.to_string()
+                )
+                .unwrap(),
+                *range
+            );
+        }
+
+        assert_eq!(
+            ExtXByteRange::from(0..1).attributes().to_string(),
+            "BYTERANGE-START=0,BYTERANGE-LENGTH=1"
+        );
+
+        assert!(ExtXByteRange::parse_attributes(Some("a"), "1").is_err());
+        assert!(ExtXByteRange::parse_attributes(None, "-1").is_err());
+    }
+
     #[test]
     fn test_deref() {
         let byte_range = ExtXByteRange::from(0..22);
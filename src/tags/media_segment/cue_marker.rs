@@ -0,0 +1,501 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::Duration;
+
+use crate::attribute::AttributePairs;
+use crate::tags::ExtXDateRange;
+use crate::types::ProtocolVersion;
+use crate::utils::{decode_base64, intern, unquote};
+use crate::{DurationPrecision, Error, RequiredVersion};
+
+/// A non-standard ad marker (`#EXT-X-CUE-OUT`, `#EXT-X-CUE-OUT-CONT` or
+/// `#EXT-X-CUE-IN`), that is still written by many packagers instead of an
+/// [`ExtXDateRange`] with SCTE-35 attributes.
+///
+/// The markers are not part of the specification, so they are only parsed,
+/// if [`ParseOptions::parse_cue_markers`] is enabled. Otherwise they are kept
+/// in [`MediaPlaylist::unknown`].
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::tags::CueMarker;
+/// use std::convert::TryFrom;
+/// use std::time::Duration;
+///
+/// assert_eq!(
+///     CueMarker::try_from("#EXT-X-CUE-OUT:30")?,
+///     CueMarker::Out {
+///         duration: Some(Duration::from_secs(30))
+///     }
+/// );
+///
+/// assert_eq!(
+///     CueMarker::try_from("#EXT-X-CUE-OUT-CONT:10/30")?,
+///     CueMarker::Cont {
+///         elapsed: Some(Duration::from_secs(10)),
+///         duration: Some(Duration::from_secs(30)),
+///         scte35: None,
+///     }
+/// );
+///
+/// assert_eq!(CueMarker::try_from("#EXT-X-CUE-IN")?, CueMarker::In);
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+///
+/// [`ParseOptions::parse_cue_markers`]: crate::ParseOptions::parse_cue_markers
+/// [`MediaPlaylist::unknown`]: crate::MediaPlaylist::unknown
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CueMarker<'a> {
+    /// `#EXT-X-CUE-OUT` starts an ad break before the segment.
+    Out {
+        /// The expected duration of the ad break.
+        duration: Option<Duration>,
+    },
+    /// `#EXT-X-CUE-OUT-CONT` marks a segment inside of an ad break, so clients
+    /// joining in the middle of the break know about it.
+    Cont {
+        /// The time, that has elapsed since the start of the ad break.
+        elapsed: Option<Duration>,
+        /// The expected duration of the ad break.
+        duration: Option<Duration>,
+        /// The base64 encoded SCTE-35 splice info, that started the break.
+        scte35: Option<Cow<'a, str>>,
+    },
+    /// `#EXT-X-CUE-IN` ends the ad break before the segment.
+    In,
+}
+
+impl<'a> CueMarker<'a> {
+    pub(crate) const PREFIX_CUE_IN: &'static str = "#EXT-X-CUE-IN";
+    pub(crate) const PREFIX_CUE_OUT: &'static str = "#EXT-X-CUE-OUT";
+    pub(crate) const PREFIX_CUE_OUT_CONT: &'static str = "#EXT-X-CUE-OUT-CONT";
+    /// A SCTE-35 `splice_info_section` with a `splice_null` command, which is
+    /// used for date ranges, when the marker does not carry a splice info.
+    pub const SPLICE_NULL: &'static str = "0xFC301100000000000000FFF0000000007A4FBFFF";
+
+    /// Returns the [`CueMarker`], that corresponds to the SCTE-35 attributes of
+    /// the `date_range`.
+    ///
+    /// A range with a `SCTE35-OUT` attribute becomes a [`CueMarker::Out`]
+    /// with its duration (or planned duration) and a range with only a
+    /// `SCTE35-IN` attribute becomes a [`CueMarker::In`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::tags::{CueMarker, ExtXDateRange};
+    /// use std::convert::TryFrom;
+    /// use std::time::Duration;
+    ///
+    /// let date_range = ExtXDateRange::try_from(concat!(
+    ///     "#EXT-X-DATERANGE:ID=\"ad1\",START-DATE=\"2020-01-01T00:00:10Z\",",
+    ///     "PLANNED-DURATION=30,SCTE35-OUT=0xFC002F"
+    /// ))?;
+    ///
+    /// assert_eq!(
+    ///     CueMarker::from_date_range(&date_range),
+    ///     Some(CueMarker::Out {
+    ///         duration: Some(Duration::from_secs(30))
+    ///     })
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub fn from_date_range(date_range: &ExtXDateRange<'_>) -> Option<Self> {
+        if date_range.scte35_out().is_some() {
+            Some(Self::Out {
+                duration: date_range.duration.or(date_range.planned_duration),
+            })
+        } else if date_range.scte35_in().is_some() {
+            Some(Self::In)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the SCTE-35 attributes of the `date_range`, so it has the same
+    /// meaning as this marker, and returns `true`.
+    ///
+    /// A [`CueMarker::Out`] sets the `SCTE35-OUT` and the planned duration, a
+    /// [`CueMarker::In`] sets the `SCTE35-IN` attribute. The attributes
+    /// contain the [`CueMarker::SPLICE_NULL`] section, because the markers
+    /// do not carry a splice info. A [`CueMarker::Cont`] has no equivalent,
+    /// so the `date_range` is not changed and `false` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::tags::{CueMarker, ExtXDateRange};
+    /// use std::time::Duration;
+    ///
+    /// # #[cfg(feature = "chrono")]
+    /// # let start_date = "2020-01-01T00:00:10Z".parse().unwrap();
+    /// # #[cfg(not(feature = "chrono"))]
+    /// let start_date = "2020-01-01T00:00:10Z";
+    /// let mut date_range = ExtXDateRange::new("ad1", start_date);
+    ///
+    /// let marker = CueMarker::Out {
+    ///     duration: Some(Duration::from_secs(30)),
+    /// };
+    ///
+    /// assert!(marker.apply_to(&mut date_range));
+    /// assert_eq!(
+    ///     date_range.scte35_out().map(AsRef::as_ref),
+    ///     Some(CueMarker::SPLICE_NULL)
+    /// );
+    /// assert_eq!(date_range.planned_duration, Some(Duration::from_secs(30)));
+    /// assert_eq!(CueMarker::from_date_range(&date_range), Some(marker));
+    /// ```
+    pub fn apply_to(&self, date_range: &mut ExtXDateRange<'_>) -> bool {
+        match self {
+            Self::Out { duration } => {
+                date_range.set_scte35_out(Some(Self::SPLICE_NULL));
+                date_range.planned_duration = *duration;
+                true
+            }
+            Self::In => {
+                date_range.set_scte35_in(Some(Self::SPLICE_NULL));
+                true
+            }
+            Self::Cont { .. } => false,
+        }
+    }
+
+    /// Decodes the base64 encoded SCTE-35 splice info of a
+    /// [`CueMarker::Cont`] into bytes.
+    ///
+    /// Returns `None`, if the marker has no splice info.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the splice info is not valid base64.
+    #[must_use]
+    pub fn scte35_bytes(&self) -> Option<crate::Result<Vec<u8>>> {
+        match self {
+            Self::Cont {
+                scte35: Some(value),
+                ..
+            } => Some(decode_base64(value)),
+            _ => None,
+        }
+    }
+
+    /// Makes the struct independent of its lifetime, by taking ownership of all
+    /// internal [`Cow`]s.
+    ///
+    /// # Note
+    ///
+    /// This is a relatively expensive operation.
+    #[must_use]
+    pub fn into_owned(self) -> CueMarker<'static> {
+        match self {
+            Self::Out { duration } => CueMarker::Out { duration },
+            Self::Cont {
+                elapsed,
+                duration,
+                scte35,
+            } => {
+                CueMarker::Cont {
+                    elapsed,
+                    duration,
+                    scte35: scte35.map(intern),
+                }
+            }
+            Self::In => CueMarker::In,
+        }
+    }
+}
+
+/// This tag requires [`ProtocolVersion::V1`].
+impl<'a> RequiredVersion for CueMarker<'a> {
+    fn required_version(&self) -> ProtocolVersion { ProtocolVersion::V1 }
+}
+
+impl<'a> fmt::Display for CueMarker<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format = |duration: Duration| DurationPrecision::Shortest.format(duration);
+
+        match self {
+            Self::Out { duration } => {
+                write!(f, "{}", Self::PREFIX_CUE_OUT)?;
+
+                if let Some(value) = duration {
+                    write!(f, ":{}", format(*value))?;
+                }
+            }
+            Self::Cont {
+                elapsed: Some(elapsed),
+                duration: Some(duration),
+                scte35: None,
+            } => {
+                write!(
+                    f,
+                    "{}:{}/{}",
+                    Self::PREFIX_CUE_OUT_CONT,
+                    format(*elapsed),
+                    format(*duration)
+                )?;
+            }
+            Self::Cont {
+                elapsed,
+                duration,
+                scte35,
+            } => {
+                write!(f, "{}", Self::PREFIX_CUE_OUT_CONT)?;
+
+                let mut separator = ':';
+
+                if let Some(value) = elapsed {
+                    write!(f, "{}ElapsedTime={}", separator, format(*value))?;
+                    separator = ',';
+                }
+
+                if let Some(value) = duration {
+                    write!(f, "{}Duration={}", separator, format(*value))?;
+                    separator = ',';
+                }
+
+                if let Some(value) = scte35 {
+                    write!(f, "{}SCTE35={}", separator, value)?;
+                }
+            }
+            Self::In => write!(f, "{}", Self::PREFIX_CUE_IN)?,
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> TryFrom<&'a str> for CueMarker<'a> {
+    type Error = Error;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        let (name, value) = match input.find(':') {
+            Some(index) => (&input[..index], Some(input[index + 1..].trim())),
+            None => (input, None),
+        };
+
+        let value = value.filter(|value| !value.is_empty());
+
+        match name {
+            Self::PREFIX_CUE_OUT => {
+                let duration = match value {
+                    Some(value) if value.contains('=') => {
+                        AttributePairs::new(value)
+                            .find(|(key, _)| key.eq_ignore_ascii_case("DURATION"))
+                            .map(|(_, value)| parse_duration(&unquote(value)))
+                            .transpose()?
+                    }
+                    Some(value) => Some(parse_duration(value)?),
+                    None => None,
+                };
+
+                Ok(Self::Out { duration })
+            }
+            Self::PREFIX_CUE_OUT_CONT => {
+                let mut elapsed = None;
+                let mut duration = None;
+                let mut scte35 = None;
+
+                match value {
+                    Some(value) if value.contains('=') => {
+                        for (key, value) in AttributePairs::new(value) {
+                            if key.eq_ignore_ascii_case("ELAPSEDTIME") {
+                                elapsed = Some(parse_duration(&unquote(value))?);
+                            } else if key.eq_ignore_ascii_case("DURATION") {
+                                duration = Some(parse_duration(&unquote(value))?);
+                            } else if key.eq_ignore_ascii_case("SCTE35") {
+                                scte35 = Some(unquote(value));
+                            }
+                            // other attributes are ignored
+                        }
+                    }
+                    Some(value) => {
+                        let mut values = value.splitn(2, '/');
+
+                        elapsed = values.next().map(parse_duration).transpose()?;
+                        duration = values.next().map(parse_duration).transpose()?;
+                    }
+                    None => {}
+                }
+
+                Ok(Self::Cont {
+                    elapsed,
+                    duration,
+                    scte35,
+                })
+            }
+            Self::PREFIX_CUE_IN => Ok(Self::In),
+            _ => Err(Error::custom(format!("not a cue marker: {:?}", input))),
+        }
+    }
+}
+
+fn parse_duration(value: &str) -> crate::Result<Duration> {
+    let seconds: f64 = value.parse().map_err(|e| Error::parse_float(value, e))?;
+
+    Duration::try_from_secs_f64(seconds)
+        .map_err(|_| Error::invalid_attribute(format!("invalid duration: {:?}", value)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            CueMarker::Out {
+                duration: Some(Duration::from_millis(30500))
+            }
+            .to_string(),
+            "#EXT-X-CUE-OUT:30.5".to_string()
+        );
+        assert_eq!(
+            CueMarker::Out { duration: None }.to_string(),
+            "#EXT-X-CUE-OUT".to_string()
+        );
+        assert_eq!(
+            CueMarker::Cont {
+                elapsed: Some(Duration::from_secs(10)),
+                duration: Some(Duration::from_secs(30)),
+                scte35: None,
+            }
+            .to_string(),
+            "#EXT-X-CUE-OUT-CONT:10/30".to_string()
+        );
+        assert_eq!(
+            CueMarker::Cont {
+                elapsed: Some(Duration::from_secs(10)),
+                duration: None,
+                scte35: Some("/DAlAAAA".into()),
+            }
+            .to_string(),
+            "#EXT-X-CUE-OUT-CONT:ElapsedTime=10,SCTE35=/DAlAAAA".to_string()
+        );
+        assert_eq!(
+            CueMarker::Cont {
+                elapsed: None,
+                duration: None,
+                scte35: None,
+            }
+            .to_string(),
+            "#EXT-X-CUE-OUT-CONT".to_string()
+        );
+        assert_eq!(CueMarker::In.to_string(), "#EXT-X-CUE-IN".to_string());
+    }
+
+    #[test]
+    fn test_parser() {
+        assert_eq!(
+            CueMarker::try_from("#EXT-X-CUE-OUT:30.000").unwrap(),
+            CueMarker::Out {
+                duration: Some(Duration::from_secs(30))
+            }
+        );
+        assert_eq!(
+            CueMarker::try_from("#EXT-X-CUE-OUT:DURATION=15").unwrap(),
+            CueMarker::Out {
+                duration: Some(Duration::from_secs(15))
+            }
+        );
+        assert_eq!(
+            CueMarker::try_from("#EXT-X-CUE-OUT").unwrap(),
+            CueMarker::Out { duration: None }
+        );
+        assert_eq!(
+            CueMarker::try_from(
+                "#EXT-X-CUE-OUT-CONT:ElapsedTime=5.005,Duration=30,SCTE35=/DAlAAAA"
+            )
+            .unwrap(),
+            CueMarker::Cont {
+                elapsed: Some(Duration::from_millis(5005)),
+                duration: Some(Duration::from_secs(30)),
+                scte35: Some("/DAlAAAA".into()),
+            }
+        );
+        assert_eq!(
+            CueMarker::try_from("#EXT-X-CUE-OUT-CONT:10/30").unwrap(),
+            CueMarker::Cont {
+                elapsed: Some(Duration::from_secs(10)),
+                duration: Some(Duration::from_secs(30)),
+                scte35: None,
+            }
+        );
+        assert_eq!(CueMarker::try_from("#EXT-X-CUE-IN").unwrap(), CueMarker::In);
+
+        assert!(CueMarker::try_from("#EXT-X-CUE-OUT:thirty").is_err());
+        assert!(CueMarker::try_from("#EXT-X-CUE-OUT:-1").is_err());
+        assert!(CueMarker::try_from("#EXT-X-CUE").is_err());
+        assert!(CueMarker::try_from("#EXT-X-CUE-INSERT").is_err());
+    }
+
+    #[test]
+    fn test_date_range() {
+        let date_range = ExtXDateRange::try_from(concat!(
+            "#EXT-X-DATERANGE:ID=\"ad1\",START-DATE=\"2020-01-01T00:00:10Z\",",
+            "SCTE35-IN=0xFC002F"
+        ))
+        .unwrap();
+
+        assert_eq!(CueMarker::from_date_range(&date_range), Some(CueMarker::In));
+
+        let mut other = ExtXDateRange::try_from(
+            "#EXT-X-DATERANGE:ID=\"ad1\",START-DATE=\"2020-01-01T00:00:10Z\"",
+        )
+        .unwrap();
+
+        assert_eq!(CueMarker::from_date_range(&other), None);
+        assert!(!CueMarker::Cont {
+            elapsed: None,
+            duration: None,
+            scte35: None,
+        }
+        .apply_to(&mut other));
+        assert!(CueMarker::In.apply_to(&mut other));
+        assert_eq!(
+            other.scte35_in().map(AsRef::as_ref),
+            Some(CueMarker::SPLICE_NULL)
+        );
+    }
+
+    #[test]
+    fn test_scte35_bytes() {
+        let marker = CueMarker::Cont {
+            elapsed: None,
+            duration: None,
+            scte35: Some("/DARAAAAAAAAAP/wAAAAAHpPv/8=".into()),
+        };
+
+        assert_eq!(
+            marker.scte35_bytes().unwrap().unwrap(),
+            hex::decode(&CueMarker::SPLICE_NULL[2..]).unwrap()
+        );
+        assert_eq!(CueMarker::In.scte35_bytes(), None);
+    }
+
+    #[test]
+    fn test_into_owned() {
+        let input = String::from("#EXT-X-CUE-OUT-CONT:SCTE35=/DAlAAAA");
+        let marker = CueMarker::try_from(input.as_str()).unwrap().into_owned();
+        drop(input);
+
+        assert_eq!(
+            marker,
+            CueMarker::Cont {
+                elapsed: None,
+                duration: None,
+                scte35: Some("/DAlAAAA".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_required_version() {
+        assert_eq!(CueMarker::In.required_version(), ProtocolVersion::V1);
+    }
+}
@@ -2,7 +2,7 @@ use std::borrow::Cow;
 use std::collections::BTreeMap;
 use std::convert::TryFrom;
 use std::fmt;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 #[cfg(feature = "chrono")]
 use chrono::{DateTime, FixedOffset, SecondsFormat};
@@ -10,14 +10,16 @@ use derive_builder::Builder;
 use shorthand::ShortHand;
 
 use crate::attribute::AttributePairs;
-use crate::types::{ProtocolVersion, Value};
+#[cfg(feature = "scte35")]
+use crate::scte35::SpliceInfo;
+use crate::types::{Cue, CueHint, ProtocolVersion, Value};
 use crate::utils::{quote, tag, unquote};
 use crate::{Error, RequiredVersion};
 
 /// The [`ExtXDateRange`] tag associates a date range (i.e., a range of time
 /// defined by a starting and ending date) with a set of attribute/value pairs.
 #[derive(ShortHand, Builder, Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
-#[builder(setter(into))]
+#[builder(setter(into), build_fn(validate = "Self::validate"))]
 #[shorthand(enable(must_use, into))]
 pub struct ExtXDateRange<'a> {
     /// A string that uniquely identifies an [`ExtXDateRange`] in the playlist.
@@ -58,6 +60,18 @@ pub struct ExtXDateRange<'a> {
     #[cfg(not(feature = "chrono"))]
     #[builder(setter(strip_option), default)]
     start_date: Option<Cow<'a, str>>,
+    /// The set of hints, when the action of the [`ExtXDateRange`] should be
+    /// triggered.
+    ///
+    /// [`Cue::Pre`] and [`Cue::Post`] are mutually exclusive.
+    ///
+    /// ## Note
+    ///
+    /// This field is optional. It is mostly used by
+    /// [HLS Interstitials](crate::interstitial).
+    #[builder(default, setter(into))]
+    #[shorthand(enable(copy))]
+    cue: CueHint,
     /// The date at which the [`ExtXDateRange`] ends. It must be equal to or
     /// later than the value of the [`start-date`] attribute.
     ///
@@ -196,6 +210,19 @@ impl<'a> ExtXDateRangeBuilder<'a> {
 
         self
     }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(cue) = self.cue {
+            if cue.contains(Cue::Pre) && cue.contains(Cue::Post) {
+                return Err(Error::invalid_attribute(
+                    "`CUE` can not contain both `PRE` and `POST`",
+                )
+                .to_string());
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl<'a> ExtXDateRange<'a> {
@@ -245,6 +272,7 @@ let date_range = ExtXDateRange::new("id", "2010-02-19T14:54:23.031+08:00");
             start_date: Some(start_date),
             #[cfg(not(feature = "chrono"))]
             start_date: Some(start_date.into()),
+            cue: CueHint::empty(),
             end_date: None,
             duration: None,
             planned_duration: None,
@@ -314,6 +342,76 @@ let date_range = ExtXDateRange::builder()
     #[inline]
     pub fn builder() -> ExtXDateRangeBuilder<'a> { ExtXDateRangeBuilder::default() }
 
+    /// Returns `true`, if the [`ExtXDateRange::cue`] contains the [`Cue`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtXDateRange;
+    /// use hls_m3u8::types::Cue;
+    /// use std::convert::TryFrom;
+    ///
+    /// let date_range = ExtXDateRange::try_from(concat!(
+    ///     "#EXT-X-DATERANGE:",
+    ///     "ID=\"ad\",",
+    ///     "CLASS=\"com.apple.hls.interstitial\",",
+    ///     "CUE=\"PRE,ONCE\""
+    /// ))?;
+    ///
+    /// assert!(date_range.has_cue(Cue::Pre));
+    /// assert!(date_range.has_cue(Cue::Once));
+    /// assert!(!date_range.has_cue(Cue::Post));
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub fn has_cue(&self, cue: Cue) -> bool { self.cue.contains(cue) }
+
+    /// Decodes the [`ExtXDateRange::scte35_cmd`] into a [`SpliceInfo`].
+    ///
+    /// Returns `None`, if the attribute is missing.
+    ///
+    /// [`SpliceInfo`]: crate::scte35::SpliceInfo
+    #[cfg(feature = "scte35")]
+    #[must_use]
+    pub fn splice_info_cmd(&self) -> Option<crate::Result<SpliceInfo>> {
+        self.scte35_cmd.as_deref().map(SpliceInfo::from_hex)
+    }
+
+    /// Decodes the [`ExtXDateRange::scte35_out`] into a [`SpliceInfo`].
+    ///
+    /// Returns `None`, if the attribute is missing.
+    ///
+    /// [`SpliceInfo`]: crate::scte35::SpliceInfo
+    #[cfg(feature = "scte35")]
+    #[must_use]
+    pub fn splice_info_out(&self) -> Option<crate::Result<SpliceInfo>> {
+        self.scte35_out.as_deref().map(SpliceInfo::from_hex)
+    }
+
+    /// Decodes the [`ExtXDateRange::scte35_in`] into a [`SpliceInfo`].
+    ///
+    /// Returns `None`, if the attribute is missing.
+    ///
+    /// [`SpliceInfo`]: crate::scte35::SpliceInfo
+    #[cfg(feature = "scte35")]
+    #[must_use]
+    pub fn splice_info_in(&self) -> Option<crate::Result<SpliceInfo>> {
+        self.scte35_in.as_deref().map(SpliceInfo::from_hex)
+    }
+
+    /// Returns the [`ExtXDateRange::start_date`] as a [`SystemTime`] or
+    /// `None`, if it is missing or not a valid date-time.
+    pub(crate) fn start_time(&self) -> Option<SystemTime> {
+        #[cfg(feature = "chrono")]
+        {
+            self.start_date.map(SystemTime::from)
+        }
+        #[cfg(not(feature = "chrono"))]
+        {
+            crate::utils::parse_date_time(self.start_date.as_deref()?)
+        }
+    }
+
     /// Makes the struct independent of its lifetime, by taking ownership of all
     /// internal [`Cow`]s.
     ///
@@ -329,6 +427,7 @@ let date_range = ExtXDateRange::builder()
             start_date: self.start_date.map(|v| Cow::Owned(v.into_owned())),
             #[cfg(feature = "chrono")]
             start_date: self.start_date,
+            cue: self.cue,
             #[cfg(not(feature = "chrono"))]
             end_date: self.end_date.map(|v| Cow::Owned(v.into_owned())),
             #[cfg(feature = "chrono")]
@@ -363,6 +462,7 @@ impl<'a> TryFrom<&'a str> for ExtXDateRange<'a> {
         let mut id = None;
         let mut class = None;
         let mut start_date = None;
+        let mut cue = CueHint::empty();
         let mut end_date = None;
         let mut duration = None;
         let mut planned_duration = None;
@@ -387,6 +487,7 @@ impl<'a> TryFrom<&'a str> for ExtXDateRange<'a> {
                         start_date = Some(unquote(value));
                     }
                 }
+                "CUE" => cue = unquote(value).parse()?,
                 "END-DATE" => {
                     #[cfg(feature = "chrono")]
                     {
@@ -412,7 +513,7 @@ impl<'a> TryFrom<&'a str> for ExtXDateRange<'a> {
                 "SCTE35-IN" => scte35_in = Some(unquote(value)),
                 "END-ON-NEXT" => {
                     if value != "YES" {
-                        return Err(Error::custom("`END-ON-NEXT` must be `YES`"));
+                        return Err(Error::invalid_attribute("`END-ON-NEXT` must be `YES`"));
                     }
                     end_on_next = true;
                 }
@@ -423,7 +524,7 @@ impl<'a> TryFrom<&'a str> for ExtXDateRange<'a> {
                                 || !c.is_ascii()
                                 || !(c.is_alphanumeric() || c == '-')
                         }) {
-                            return Err(Error::custom(
+                            return Err(Error::invalid_attribute(
                                 "a client attribute can only consist of uppercase ascii characters, numbers or `-`",
                             ));
                         }
@@ -440,6 +541,12 @@ impl<'a> TryFrom<&'a str> for ExtXDateRange<'a> {
 
         let id = id.ok_or_else(|| Error::missing_value("ID"))?;
 
+        if cue.contains(Cue::Pre) && cue.contains(Cue::Post) {
+            return Err(Error::invalid_attribute(
+                "`CUE` can not contain both `PRE` and `POST`",
+            ));
+        }
+
         if end_on_next && class.is_none() {
             return Err(Error::missing_attribute("CLASS"));
         } else if end_on_next && duration.is_some() {
@@ -458,7 +565,7 @@ impl<'a> TryFrom<&'a str> for ExtXDateRange<'a> {
                 &end_date,
             ) {
                 if start_date + duration != *end_date {
-                    return Err(Error::custom(
+                    return Err(Error::invalid_attribute(
                         "end_date must be equal to start_date + duration",
                     ));
                 }
@@ -469,6 +576,7 @@ impl<'a> TryFrom<&'a str> for ExtXDateRange<'a> {
             id,
             class,
             start_date,
+            cue,
             end_date,
             duration,
             planned_duration,
@@ -506,6 +614,10 @@ impl<'a> fmt::Display for ExtXDateRange<'a> {
             }
         }
 
+        if !self.cue.is_empty() {
+            write!(f, ",CUE={}", quote(self.cue))?;
+        }
+
         if let Some(value) = &self.end_date {
             #[cfg(feature = "chrono")]
             {
@@ -582,6 +694,13 @@ mod test {
                 assert!(ExtXDateRange::try_from("#EXT-X-DATERANGE:END-ON-NEXT=NO")
                     .is_err());
 
+                assert!(ExtXDateRange::try_from(
+                    "#EXT-X-DATERANGE:ID=\"a\",CUE=\"PRE,POST\""
+                )
+                .is_err());
+                assert!(ExtXDateRange::try_from("#EXT-X-DATERANGE:ID=\"a\",CUE=\"MID\"")
+                    .is_err());
+
                 assert!(ExtXDateRange::try_from("garbage").is_err());
                 assert!(ExtXDateRange::try_from("").is_err());
 
@@ -677,6 +796,55 @@ mod test {
         },
     }
 
+    #[test]
+    fn test_cue() {
+        let date_range = ExtXDateRange::try_from(concat!(
+            "#EXT-X-DATERANGE:",
+            "ID=\"ad\",",
+            "CLASS=\"com.apple.hls.interstitial\",",
+            "START-DATE=\"2020-01-01T00:00:00Z\",",
+            "CUE=\"PRE, ONCE\""
+        ))
+        .unwrap();
+
+        assert_eq!(date_range.cue(), Cue::Pre | Cue::Once);
+        assert!(date_range.has_cue(Cue::Once));
+        assert!(!date_range.has_cue(Cue::Post));
+        assert_eq!(
+            date_range.to_string(),
+            concat!(
+                "#EXT-X-DATERANGE:",
+                "ID=\"ad\",",
+                "CLASS=\"com.apple.hls.interstitial\",",
+                "START-DATE=\"2020-01-01T00:00:00Z\",",
+                "CUE=\"PRE,ONCE\""
+            )
+        );
+
+        assert!(ExtXDateRange::builder()
+            .id("ad")
+            .cue(vec![Cue::Pre, Cue::Post])
+            .build()
+            .is_err());
+        assert!(ExtXDateRange::builder()
+            .id("ad")
+            .cue(vec![Cue::Post, Cue::Once])
+            .build()
+            .is_ok());
+
+        assert_eq!(
+            ExtXDateRange::try_from("#EXT-X-DATERANGE:ID=\"ad\",CUE=\"ONCE,POST,ONCE\"")
+                .unwrap()
+                .cue(),
+            Cue::Post | Cue::Once
+        );
+        assert!(ExtXDateRange::builder()
+            .id("ad")
+            .cue(Cue::Pre | Cue::Post)
+            .build()
+            .is_err());
+    }
+
     #[test]
     fn test_required_version() {
         assert_eq!(
@@ -0,0 +1,56 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::types::ProtocolVersion;
+use crate::{Error, RequiredVersion};
+
+/// The `ExtXGap` tag indicates that the resource of the `MediaSegment`, that
+/// follows it, is not available and should not be loaded by clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) struct ExtXGap;
+
+impl ExtXGap {
+    pub(crate) const PREFIX: &'static str = "#EXT-X-GAP";
+}
+
+/// This tag requires [`ProtocolVersion::V1`].
+impl RequiredVersion for ExtXGap {
+    fn required_version(&self) -> ProtocolVersion { ProtocolVersion::V1 }
+}
+
+impl fmt::Display for ExtXGap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { Self::PREFIX.fmt(f) }
+}
+
+impl TryFrom<&str> for ExtXGap {
+    type Error = Error;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        // the parser assumes that only a single line is passed as input,
+        // which should be "#EXT-X-GAP"
+        if input == Self::PREFIX {
+            Ok(Self)
+        } else {
+            Err(Error::unexpected_data(input))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display() { assert_eq!(ExtXGap.to_string(), "#EXT-X-GAP".to_string(),) }
+
+    #[test]
+    fn test_parser() {
+        assert_eq!(ExtXGap, ExtXGap::try_from("#EXT-X-GAP").unwrap());
+
+        assert!(ExtXGap::try_from("#EXT-X-GAP:YES").is_err());
+    }
+
+    #[test]
+    fn test_required_version() { assert_eq!(ExtXGap.required_version(), ProtocolVersion::V1) }
+}
@@ -5,9 +5,9 @@ use std::time::Duration;
 
 use derive_more::AsRef;
 
-use crate::types::ProtocolVersion;
+use crate::types::{Feature, ProtocolVersion};
 use crate::utils::tag;
-use crate::{Error, RequiredVersion};
+use crate::{DurationPrecision, Error, RequiredVersion, VersionReason};
 
 /// Specifies the duration of a [`Media Segment`].
 ///
@@ -151,12 +151,24 @@ impl<'a> RequiredVersion for ExtInf<'a> {
             ProtocolVersion::V3
         }
     }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        if self.required_version() == ProtocolVersion::V3 {
+            vec![VersionReason::from(Feature::DecimalDuration)]
+        } else {
+            Vec::new()
+        }
+    }
 }
 
-impl<'a> fmt::Display for ExtInf<'a> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl<'a> ExtInf<'a> {
+    pub(crate) fn write_to<W: fmt::Write>(
+        &self,
+        f: &mut W,
+        precision: DurationPrecision,
+    ) -> fmt::Result {
         write!(f, "{}", Self::PREFIX)?;
-        write!(f, "{},", self.duration.as_secs_f64())?;
+        write!(f, "{},", precision.format(self.duration))?;
 
         if let Some(value) = &self.title {
             write!(f, "{}", value)?;
@@ -165,6 +177,12 @@ impl<'a> fmt::Display for ExtInf<'a> {
     }
 }
 
+impl<'a> fmt::Display for ExtInf<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_to(f, DurationPrecision::Shortest)
+    }
+}
+
 impl<'a> TryFrom<&'a str> for ExtInf<'a> {
     type Error = Error;
 
@@ -1,9 +1,10 @@
 use std::convert::TryFrom;
 use std::fmt;
 
+use crate::attribute::AttributePairs;
 use crate::types::{DecryptionKey, ProtocolVersion};
 use crate::utils::tag;
-use crate::{Error, RequiredVersion};
+use crate::{Error, RequiredVersion, VersionReason};
 
 /// Specifies how to decrypt encrypted data from the server.
 ///
@@ -186,6 +187,8 @@ impl<'a> RequiredVersion for ExtXKey<'a> {
             .as_ref()
             .map_or(ProtocolVersion::V1, |i| i.required_version())
     }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> { self.0.required_version_reasons() }
 }
 
 impl<'a> TryFrom<&'a str> for ExtXKey<'a> {
@@ -194,7 +197,19 @@ impl<'a> TryFrom<&'a str> for ExtXKey<'a> {
     fn try_from(input: &'a str) -> Result<Self, Self::Error> {
         let input = tag(input, Self::PREFIX)?;
 
-        if input.trim() == "METHOD=NONE" {
+        if AttributePairs::new(input).any(|(key, value)| key == "METHOD" && value == "NONE") {
+            // > If the encryption method is NONE, other attributes MUST NOT be present.
+            let attribute = AttributePairs::new(input).find_map(|(key, _)| {
+                matches!(key, "URI" | "IV" | "KEYFORMAT" | "KEYFORMATVERSIONS").then_some(key)
+            });
+
+            if let Some(attribute) = attribute {
+                return Err(Error::invalid_attribute(format!(
+                    "the attribute {} must not be present, if the METHOD is NONE",
+                    attribute
+                )));
+            }
+
             Ok(Self(None))
         } else {
             Ok(DecryptionKey::try_from(input)?.into())
@@ -263,6 +278,17 @@ mod test {
                 );
                 assert!(ExtXKey::try_from("#EXT-X-KEY:METHOD=AES-128,URI=").is_err());
                 assert!(ExtXKey::try_from("garbage").is_err());
+
+                assert_eq!(
+                    ExtXKey::try_from("#EXT-X-KEY:METHOD=NONE,UNKNOWNTAG=abcd").unwrap(),
+                    ExtXKey::empty()
+                );
+                assert_eq!(
+                    ExtXKey::try_from("#EXT-X-KEY:METHOD=NONE,URI=\"key.bin\"")
+                        .unwrap_err()
+                        .to_string(),
+                    "the attribute URI must not be present, if the METHOD is NONE"
+                );
             }
         }
     }
@@ -1,4 +1,3 @@
-use std::borrow::Cow;
 use std::convert::{TryFrom, TryInto};
 use std::fmt;
 
@@ -6,9 +5,9 @@ use shorthand::ShortHand;
 
 use crate::attribute::AttributePairs;
 use crate::tags::ExtXKey;
-use crate::types::{ByteRange, DecryptionKey, ProtocolVersion};
+use crate::types::{ByteRange, DecryptionKey, Feature, ProtocolVersion, UriRef};
 use crate::utils::{quote, tag, unquote};
-use crate::{Decryptable, Error, RequiredVersion};
+use crate::{Decryptable, Error, RequiredVersion, VersionReason};
 
 /// The [`ExtXMap`] tag specifies how to obtain the [Media Initialization
 /// Section], required to parse the applicable [`MediaSegment`]s.
@@ -37,7 +36,7 @@ use crate::{Decryptable, Error, RequiredVersion};
 pub struct ExtXMap<'a> {
     /// The `URI` that identifies a resource, that contains the media
     /// initialization section.
-    uri: Cow<'a, str>,
+    uri: UriRef<'a>,
     /// The range of the media initialization section.
     #[shorthand(enable(copy))]
     range: Option<ByteRange>,
@@ -57,7 +56,7 @@ impl<'a> ExtXMap<'a> {
     /// let map = ExtXMap::new("https://prod.mediaspace.com/init.bin");
     /// ```
     #[must_use]
-    pub fn new<T: Into<Cow<'a, str>>>(uri: T) -> Self {
+    pub fn new<T: Into<UriRef<'a>>>(uri: T) -> Self {
         Self {
             uri: uri.into(),
             range: None,
@@ -76,7 +75,7 @@ impl<'a> ExtXMap<'a> {
     /// let map = ExtXMap::with_range("https://prod.mediaspace.com/init.bin", 2..11);
     /// ```
     #[must_use]
-    pub fn with_range<I: Into<Cow<'a, str>>, B: Into<ByteRange>>(uri: I, range: B) -> Self {
+    pub fn with_range<I: Into<UriRef<'a>>, B: Into<ByteRange>>(uri: I, range: B) -> Self {
         Self {
             uri: uri.into(),
             range: Some(range.into()),
@@ -93,7 +92,7 @@ impl<'a> ExtXMap<'a> {
     #[must_use]
     pub fn into_owned(self) -> ExtXMap<'static> {
         ExtXMap {
-            uri: Cow::Owned(self.uri.into_owned()),
+            uri: self.uri.into_owned(),
             range: self.range,
             keys: self.keys.into_iter().map(ExtXKey::into_owned).collect(),
         }
@@ -122,6 +121,10 @@ impl<'a> RequiredVersion for ExtXMap<'a> {
     fn required_version(&self) -> ProtocolVersion { ProtocolVersion::V6 }
 
     fn introduced_version(&self) -> ProtocolVersion { ProtocolVersion::V5 }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        vec![VersionReason::from(Feature::Map)]
+    }
 }
 
 impl<'a> fmt::Display for ExtXMap<'a> {
@@ -160,7 +163,7 @@ impl<'a> TryFrom<&'a str> for ExtXMap<'a> {
             }
         }
 
-        let uri = uri.ok_or_else(|| Error::missing_value("URI"))?;
+        let uri = uri.ok_or_else(|| Error::missing_value("URI"))?.into();
 
         Ok(Self {
             uri,
@@ -1,15 +1,23 @@
+pub(crate) mod bitrate;
 pub(crate) mod byte_range;
+pub(crate) mod cue_marker;
 pub(crate) mod date_range;
 pub(crate) mod discontinuity;
+pub(crate) mod gap;
 pub(crate) mod inf;
 pub(crate) mod key;
 pub(crate) mod map;
+pub(crate) mod part;
 pub(crate) mod program_date_time;
 
+pub(crate) use bitrate::*;
 pub use byte_range::*;
+pub use cue_marker::CueMarker;
 pub use date_range::ExtXDateRange;
 pub(crate) use discontinuity::*;
+pub(crate) use gap::*;
 pub use inf::*;
 pub use key::ExtXKey;
 pub use map::*;
+pub use part::*;
 pub use program_date_time::*;
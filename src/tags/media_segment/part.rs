@@ -0,0 +1,250 @@
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+use std::time::Duration;
+
+use shorthand::ShortHand;
+
+use crate::attribute::AttributePairs;
+use crate::types::{ByteRange, ProtocolVersion, UriRef};
+use crate::utils::{parse_yes_or_no, quote, tag, unquote};
+use crate::{DurationPrecision, Error, RequiredVersion};
+
+/// The [`ExtXPart`] tag identifies a partial segment of a [`MediaSegment`],
+/// which is used by Low-Latency HLS to publish a segment, before it is
+/// complete.
+///
+/// The partial segments of a [`MediaSegment`] appear in the playlist before
+/// the `#EXTINF` tag and the uri of the segment, which contains the same media
+/// as all of its partial segments together.
+///
+/// [`MediaSegment`]: crate::MediaSegment
+#[derive(ShortHand, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[shorthand(enable(must_use, into))]
+pub struct ExtXPart<'a> {
+    /// The `URI` of the partial segment.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtXPart;
+    /// use std::time::Duration;
+    ///
+    /// let mut part = ExtXPart::new(Duration::from_millis(333), "part1.mp4");
+    /// # assert_eq!(part.uri(), "part1.mp4");
+    /// part.set_uri("part2.mp4");
+    ///
+    /// assert_eq!(part.uri(), "part2.mp4");
+    /// ```
+    uri: UriRef<'a>,
+    /// The duration of the partial segment.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtXPart;
+    /// use std::time::Duration;
+    ///
+    /// let part = ExtXPart::new(Duration::from_millis(333), "part1.mp4");
+    ///
+    /// assert_eq!(part.duration(), Duration::from_millis(333));
+    /// ```
+    #[shorthand(enable(copy))]
+    duration: Duration,
+    /// The sub-range of the resource, that contains the partial segment.
+    #[shorthand(enable(copy))]
+    byte_range: Option<ByteRange>,
+    /// Whether the partial segment contains an independent frame.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtXPart;
+    /// use std::time::Duration;
+    ///
+    /// let mut part = ExtXPart::new(Duration::from_millis(333), "part1.mp4");
+    /// # assert_eq!(part.is_independent(), false);
+    /// part.set_is_independent(true);
+    ///
+    /// assert_eq!(part.is_independent(), true);
+    /// ```
+    is_independent: bool,
+    /// Whether the partial segment is not available.
+    has_gap: bool,
+}
+
+impl<'a> ExtXPart<'a> {
+    pub(crate) const PREFIX: &'static str = "#EXT-X-PART:";
+
+    /// Makes a new [`ExtXPart`] tag.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtXPart;
+    /// use std::time::Duration;
+    ///
+    /// let part = ExtXPart::new(Duration::from_millis(333), "part1.mp4");
+    /// ```
+    #[must_use]
+    pub fn new<T: Into<UriRef<'a>>>(duration: Duration, uri: T) -> Self {
+        Self {
+            uri: uri.into(),
+            duration,
+            byte_range: None,
+            is_independent: false,
+            has_gap: false,
+        }
+    }
+
+    /// Makes the struct independent of its lifetime, by taking ownership of all
+    /// internal [`Cow`]s.
+    ///
+    /// # Note
+    ///
+    /// This is a relatively expensive operation.
+    ///
+    /// [`Cow`]: std::borrow::Cow
+    #[must_use]
+    pub fn into_owned(self) -> ExtXPart<'static> {
+        ExtXPart {
+            uri: self.uri.into_owned(),
+            duration: self.duration,
+            byte_range: self.byte_range,
+            is_independent: self.is_independent,
+            has_gap: self.has_gap,
+        }
+    }
+}
+
+/// This tag requires [`ProtocolVersion::V1`].
+impl<'a> RequiredVersion for ExtXPart<'a> {
+    fn required_version(&self) -> ProtocolVersion { ProtocolVersion::V1 }
+}
+
+impl<'a> fmt::Display for ExtXPart<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Self::PREFIX)?;
+        write!(
+            f,
+            "DURATION={}",
+            DurationPrecision::Shortest.format(self.duration)
+        )?;
+        write!(f, ",URI={}", quote(&self.uri))?;
+
+        if self.is_independent {
+            write!(f, ",INDEPENDENT=YES")?;
+        }
+
+        if let Some(value) = &self.byte_range {
+            write!(f, ",BYTERANGE={}", quote(value))?;
+        }
+
+        if self.has_gap {
+            write!(f, ",GAP=YES")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> TryFrom<&'a str> for ExtXPart<'a> {
+    type Error = Error;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        let input = tag(input, Self::PREFIX)?;
+
+        let mut uri = None;
+        let mut duration = None;
+        let mut byte_range = None;
+        let mut is_independent = false;
+        let mut has_gap = false;
+
+        for (key, value) in AttributePairs::new(input) {
+            match key {
+                "URI" => uri = Some(unquote(value)),
+                "DURATION" => {
+                    duration = Some(Duration::from_secs_f64(
+                        value.parse().map_err(|e| Error::parse_float(value, e))?,
+                    ));
+                }
+                "BYTERANGE" => byte_range = Some(unquote(value).try_into()?),
+                "INDEPENDENT" => is_independent = parse_yes_or_no(value)?,
+                "GAP" => has_gap = parse_yes_or_no(value)?,
+                _ => {
+                    // [6.3.1. General Client Responsibilities]
+                    // > ignore any attribute/value pair with an unrecognized
+                    // AttributeName.
+                }
+            }
+        }
+
+        let uri = uri.ok_or_else(|| Error::missing_value("URI"))?.into();
+        let duration = duration.ok_or_else(|| Error::missing_value("DURATION"))?;
+
+        Ok(Self {
+            uri,
+            duration,
+            byte_range,
+            is_independent,
+            has_gap,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            ExtXPart::new(Duration::from_millis(333), "part.mp4").to_string(),
+            "#EXT-X-PART:DURATION=0.333,URI=\"part.mp4\"".to_string()
+        );
+
+        let mut part = ExtXPart::new(Duration::from_millis(500), "part.mp4");
+        part.set_is_independent(true)
+            .set_byte_range(Some(ByteRange::from(..20)))
+            .set_has_gap(true);
+
+        assert_eq!(
+            part.to_string(),
+            "#EXT-X-PART:DURATION=0.5,URI=\"part.mp4\",INDEPENDENT=YES,BYTERANGE=\"20\",GAP=YES"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_parser() {
+        assert_eq!(
+            ExtXPart::new(Duration::from_millis(333), "part.mp4"),
+            ExtXPart::try_from("#EXT-X-PART:DURATION=0.333,URI=\"part.mp4\"").unwrap()
+        );
+
+        let mut part = ExtXPart::new(Duration::from_millis(500), "part.mp4");
+        part.set_is_independent(true)
+            .set_byte_range(Some(ByteRange::from(10..30)));
+
+        assert_eq!(
+            part,
+            ExtXPart::try_from(concat!(
+                "#EXT-X-PART:DURATION=0.5,URI=\"part.mp4\",INDEPENDENT=YES,",
+                "BYTERANGE=\"20@10\",UNKNOWN=IGNORED"
+            ))
+            .unwrap()
+        );
+
+        assert!(ExtXPart::try_from("#EXT-X-PART:URI=\"part.mp4\"").is_err());
+        assert!(ExtXPart::try_from("#EXT-X-PART:DURATION=0.5").is_err());
+        assert!(ExtXPart::try_from("#EXT-X-PART:DURATION=0.5,URI=\"a\",GAP=MAYBE").is_err());
+    }
+
+    #[test]
+    fn test_required_version() {
+        assert_eq!(
+            ExtXPart::new(Duration::from_millis(333), "part.mp4").required_version(),
+            ProtocolVersion::V1
+        );
+    }
+}
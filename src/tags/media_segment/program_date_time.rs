@@ -3,6 +3,7 @@ use std::borrow::Cow;
 use std::convert::TryFrom;
 use std::fmt;
 use std::marker::PhantomData;
+use std::time::SystemTime;
 
 #[cfg(feature = "chrono")]
 use chrono::{DateTime, FixedOffset, SecondsFormat};
@@ -85,6 +86,19 @@ impl<'a> ExtXProgramDateTime<'a> {
         }
     }
 
+    /// Returns the date-time as a [`SystemTime`] or `None`, if it is not a
+    /// valid date-time.
+    pub(crate) fn system_time(&self) -> Option<SystemTime> {
+        #[cfg(feature = "chrono")]
+        {
+            Some(SystemTime::from(self.date_time))
+        }
+        #[cfg(not(feature = "chrono"))]
+        {
+            crate::utils::parse_date_time(&self.date_time)
+        }
+    }
+
     /// Makes the struct independent of its lifetime, by taking ownership of all
     /// internal [`Cow`]s.
     ///
@@ -1,8 +1,9 @@
 use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 
 use stable_vec::StableVec;
 
-use crate::types::{DecryptionKey, ProtocolVersion};
+use crate::types::{DecryptionKey, Feature, ProtocolVersion};
 
 mod private {
     pub trait Sealed {}
@@ -57,6 +58,56 @@ pub trait Decryptable<'a>: private::Sealed {
     fn is_empty(&self) -> bool { <Self as Decryptable>::len(self) == 0 }
 }
 
+/// A tag, that is not supported by this crate (for example a vendor specific
+/// tag), but should be written into a playlist.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::{CustomTag, MediaPlaylist, MediaSegment};
+/// use std::fmt;
+/// use std::time::Duration;
+///
+/// struct CueIn(usize);
+///
+/// impl fmt::Display for CueIn {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "#EXT-X-CUE-IN") }
+/// }
+///
+/// impl CustomTag for CueIn {
+///     fn position(&self) -> Option<usize> { Some(self.0) }
+/// }
+///
+/// let playlist = MediaPlaylist::builder()
+///     .target_duration(Duration::from_secs(10))
+///     .push_segment(
+///         MediaSegment::builder()
+///             .duration(Duration::from_secs(10))
+///             .uri("1.ts")
+///             .build()?,
+///     )
+///     .push_custom_tag(CueIn(0))
+///     .build()?;
+///
+/// assert_eq!(playlist.positioned_tags, vec![(0, "#EXT-X-CUE-IN".into())]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub trait CustomTag: fmt::Display {
+    /// Returns the position of the tag in the playlist, which is the
+    /// [`MediaSegment::number`] of the segment in a [`MediaPlaylist`] or the
+    /// index of the [`VariantStream`] in a [`MasterPlaylist`], before which
+    /// the tag is written.
+    ///
+    /// By default this is `None` and the tag is written after all other tags.
+    ///
+    /// [`MediaSegment::number`]: crate::MediaSegment::number
+    /// [`MediaPlaylist`]: crate::MediaPlaylist
+    /// [`VariantStream`]: crate::tags::VariantStream
+    /// [`MasterPlaylist`]: crate::MasterPlaylist
+    #[must_use]
+    fn position(&self) -> Option<usize> { None }
+}
+
 #[doc(hidden)]
 pub trait RequiredVersion {
     /// Returns the protocol compatibility version that this tag requires.
@@ -71,6 +122,57 @@ pub trait RequiredVersion {
     /// The protocol version, in which the tag has been introduced.
     #[must_use]
     fn introduced_version(&self) -> ProtocolVersion { self.required_version() }
+
+    /// Returns the features, that raise the
+    /// [`RequiredVersion::required_version`] above [`ProtocolVersion::V1`].
+    #[must_use]
+    fn required_version_reasons(&self) -> Vec<VersionReason> { Vec::new() }
+}
+
+/// A feature, that requires a specific [`ProtocolVersion`].
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::types::ProtocolVersion;
+/// use hls_m3u8::VersionReason;
+///
+/// let reason = VersionReason::new("KEYFORMAT", ProtocolVersion::V5);
+///
+/// assert_eq!(reason.feature(), "KEYFORMAT");
+/// assert_eq!(reason.version(), ProtocolVersion::V5);
+/// assert_eq!(reason.to_string(), "KEYFORMAT requires V5");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VersionReason {
+    feature: &'static str,
+    version: ProtocolVersion,
+}
+
+impl VersionReason {
+    /// Makes a new [`VersionReason`].
+    #[must_use]
+    pub const fn new(feature: &'static str, version: ProtocolVersion) -> Self {
+        Self { feature, version }
+    }
+
+    /// Returns the name of the feature (for example an attribute or a tag).
+    #[must_use]
+    pub const fn feature(&self) -> &'static str { self.feature }
+
+    /// Returns the [`ProtocolVersion`], the feature requires.
+    #[must_use]
+    pub const fn version(&self) -> ProtocolVersion { self.version }
+}
+
+impl From<Feature> for VersionReason {
+    fn from(value: Feature) -> Self { Self::new(value.name(), value.required_version()) }
+}
+
+impl fmt::Display for VersionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} requires V{}", self.feature, self.version)
+    }
 }
 
 impl<T: RequiredVersion> RequiredVersion for Vec<T> {
@@ -81,6 +183,12 @@ impl<T: RequiredVersion> RequiredVersion for Vec<T> {
             // return ProtocolVersion::V1, if the iterator is empty:
             .unwrap_or_default()
     }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        self.iter()
+            .flat_map(RequiredVersion::required_version_reasons)
+            .collect()
+    }
 }
 
 impl<K, V: RequiredVersion> RequiredVersion for BTreeMap<K, V> {
@@ -90,6 +198,12 @@ impl<K, V: RequiredVersion> RequiredVersion for BTreeMap<K, V> {
             .max()
             .unwrap_or_default()
     }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        self.values()
+            .flat_map(RequiredVersion::required_version_reasons)
+            .collect()
+    }
 }
 
 impl<T: RequiredVersion> RequiredVersion for Option<T> {
@@ -99,6 +213,12 @@ impl<T: RequiredVersion> RequiredVersion for Option<T> {
             .max()
             .unwrap_or_default()
     }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        self.iter()
+            .flat_map(RequiredVersion::required_version_reasons)
+            .collect()
+    }
 }
 
 impl<K, V: RequiredVersion, S> RequiredVersion for HashMap<K, V, S> {
@@ -108,6 +228,12 @@ impl<K, V: RequiredVersion, S> RequiredVersion for HashMap<K, V, S> {
             .max()
             .unwrap_or_default()
     }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        self.values()
+            .flat_map(RequiredVersion::required_version_reasons)
+            .collect()
+    }
 }
 
 impl<T: RequiredVersion> RequiredVersion for StableVec<T> {
@@ -118,6 +244,12 @@ impl<T: RequiredVersion> RequiredVersion for StableVec<T> {
             // return ProtocolVersion::V1, if the iterator is empty:
             .unwrap_or_default()
     }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        self.values()
+            .flat_map(RequiredVersion::required_version_reasons)
+            .collect()
+    }
 }
 
 #[cfg(test)]
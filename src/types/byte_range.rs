@@ -21,6 +21,7 @@ use crate::Error;
 /// let range = ByteRange::from(..20);
 /// ```
 #[derive(ShortHand, Copy, Hash, Eq, Ord, Debug, PartialEq, Clone, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[shorthand(enable(must_use, copy), disable(option_as_ref, set))]
 pub struct ByteRange {
     /// Returns the `start` of the [`ByteRange`], if there is one.
@@ -88,6 +89,7 @@ impl ByteRange {
     ///
     /// This function will panic, if the `new_start` is larger, than the
     /// [`end`](ByteRange::end).
+    #[allow(clippy::unnecessary_map_or)]
     pub fn set_start(&mut self, new_start: Option<usize>) -> &mut Self {
         if new_start.map_or(false, |s| s > self.end) {
             panic!(
@@ -252,6 +254,7 @@ impl ByteRange {
 impl Sub<usize> for ByteRange {
     type Output = Self;
 
+    #[allow(unused_attributes)]
     #[must_use]
     #[inline]
     fn sub(self, rhs: usize) -> Self::Output {
@@ -270,6 +273,7 @@ impl SubAssign<usize> for ByteRange {
 impl Add<usize> for ByteRange {
     type Output = Self;
 
+    #[allow(unused_attributes)]
     #[must_use]
     #[inline]
     fn add(self, rhs: usize) -> Self::Output {
@@ -2,17 +2,53 @@ use core::fmt;
 use core::str::FromStr;
 
 use shorthand::ShortHand;
+use strum::{Display, EnumString};
 
 use crate::Error;
 
+/// A special usage of the audio channels of a rendition, which is listed in
+/// the third parameter of the `CHANNELS` attribute.
+#[non_exhaustive]
+#[derive(Ord, PartialOrd, Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString)]
+#[strum(serialize_all = "SCREAMING-KEBAB-CASE")]
+pub enum ChannelUsage {
+    /// The audio is binaural (either recorded or synthesized) and should be
+    /// played with headphones.
+    Binaural,
+    /// The audio is pre-processed content, that should not be processed by
+    /// the client (for example by spatializing it again).
+    Immersive,
+    /// The audio is a downmix of content with more channels.
+    Downmix,
+}
+
 /// The maximum number of independent, simultaneous audio channels present in
 /// any [`MediaSegment`] in the rendition.
 ///
 /// For example, an `AC-3 5.1` rendition would have a maximum channel number of
 /// 6.
 ///
+/// The attribute can have two more parameters, that are separated by a `/`:
+/// the audio coding identifiers (like `JOC` for Dolby Atmos) and the special
+/// usage identifiers (like `BINAURAL`). An empty list of audio coding
+/// identifiers is written as `-`.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::types::{ChannelUsage, Channels};
+///
+/// let channels = "12/-/BINAURAL".parse::<Channels>()?;
+///
+/// assert_eq!(channels.number(), 12);
+/// assert!(channels.audio_coding_identifiers().is_empty());
+/// assert!(channels.has_usage(ChannelUsage::Binaural));
+/// assert_eq!(channels.to_string(), "12/-/BINAURAL");
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+///
 /// [`MediaSegment`]: crate::MediaSegment
-#[derive(ShortHand, Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(ShortHand, Debug, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[shorthand(enable(must_use))]
 pub struct Channels {
     /// The maximum number of independent simultaneous audio channels.
@@ -28,6 +64,12 @@ pub struct Channels {
     /// assert_eq!(channels.number(), 5);
     /// ```
     number: u64,
+    /// The second parameter, which is `Some(vec![])`, if it is `-`.
+    #[shorthand(enable(skip))]
+    audio_coding: Option<Vec<String>>,
+    /// The third parameter.
+    #[shorthand(enable(skip))]
+    special_usage: Option<Vec<String>>,
 }
 
 impl Channels {
@@ -44,16 +86,166 @@ impl Channels {
     /// ```
     //#[inline]
     #[must_use]
-    pub const fn new(number: u64) -> Self { Self { number } }
+    pub const fn new(number: u64) -> Self {
+        Self {
+            number,
+            audio_coding: None,
+            special_usage: None,
+        }
+    }
+
+    /// Returns the audio coding identifiers, which indicate the audio object
+    /// coding of the rendition (for example `JOC` for Dolby Atmos in Dolby
+    /// Digital Plus).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::Channels;
+    /// let channels = "16/JOC".parse::<Channels>()?;
+    ///
+    /// assert_eq!(channels.audio_coding_identifiers(), &["JOC".to_string()]);
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub fn audio_coding_identifiers(&self) -> &[String] {
+        self.audio_coding.as_deref().unwrap_or_default()
+    }
+
+    /// Sets the audio coding identifiers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::Channels;
+    /// let mut channels = Channels::new(16);
+    /// channels.set_audio_coding_identifiers(vec!["JOC"]);
+    ///
+    /// assert_eq!(channels.to_string(), "16/JOC");
+    /// ```
+    pub fn set_audio_coding_identifiers<I, T>(&mut self, identifiers: I) -> &mut Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.audio_coding = Some(identifiers.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Returns `true`, if the audio is coded with Dolby Atmos joint object
+    /// coding (the audio coding identifier `JOC`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::Channels;
+    /// assert!("16/JOC".parse::<Channels>()?.is_joc());
+    /// assert!(!"6".parse::<Channels>()?.is_joc());
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub fn is_joc(&self) -> bool {
+        self.audio_coding_identifiers()
+            .iter()
+            .any(|identifier| identifier == "JOC")
+    }
+
+    /// Returns the special usage identifiers (including unknown ones).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::Channels;
+    /// let channels = "2/-/BINAURAL,DOWNMIX".parse::<Channels>()?;
+    ///
+    /// assert_eq!(
+    ///     channels.special_usage_identifiers(),
+    ///     &["BINAURAL".to_string(), "DOWNMIX".to_string()]
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub fn special_usage_identifiers(&self) -> &[String] {
+        self.special_usage.as_deref().unwrap_or_default()
+    }
+
+    /// Returns the known special usages of the audio.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::types::{ChannelUsage, Channels};
+    ///
+    /// let channels = "2/-/BINAURAL,UNKNOWN".parse::<Channels>()?;
+    ///
+    /// assert_eq!(
+    ///     channels.usages().collect::<Vec<_>>(),
+    ///     vec![ChannelUsage::Binaural]
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    pub fn usages(&self) -> impl Iterator<Item = ChannelUsage> + '_ {
+        self.special_usage_identifiers()
+            .iter()
+            .filter_map(|identifier| identifier.parse().ok())
+    }
+
+    /// Returns `true`, if the audio has the special usage.
+    #[must_use]
+    pub fn has_usage(&self, usage: ChannelUsage) -> bool { self.usages().any(|v| v == usage) }
+
+    /// Sets the special usage identifiers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::types::{ChannelUsage, Channels};
+    ///
+    /// let mut channels = Channels::new(2);
+    /// channels.set_usages(vec![ChannelUsage::Binaural]);
+    ///
+    /// assert_eq!(channels.to_string(), "2/-/BINAURAL");
+    /// ```
+    pub fn set_usages<I: IntoIterator<Item = ChannelUsage>>(&mut self, usages: I) -> &mut Self {
+        self.special_usage = Some(usages.into_iter().map(|v| v.to_string()).collect());
+        self
+    }
+}
+
+/// Parses the comma separated identifiers of a parameter, where `-` is an
+/// empty list.
+fn identifiers(input: &str) -> crate::Result<Vec<String>> {
+    if input == "-" {
+        return Ok(Vec::new());
+    }
+
+    input
+        .split(',')
+        .map(|identifier| {
+            if identifier.is_empty() {
+                Err(Error::invalid_attribute(format!(
+                    "empty identifier in CHANNELS: {:?}",
+                    input
+                )))
+            } else {
+                Ok(identifier.to_string())
+            }
+        })
+        .collect()
 }
 
 impl FromStr for Channels {
     type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        Ok(Self::new(
-            input.parse().map_err(|e| Error::parse_int(input, e))?,
-        ))
+        let mut parameters = input.splitn(3, '/');
+        let number = parameters.next().unwrap_or_default();
+
+        let mut result = Self::new(number.parse().map_err(|e| Error::parse_int(number, e))?);
+        result.audio_coding = parameters.next().map(identifiers).transpose()?;
+        result.special_usage = parameters.next().map(identifiers).transpose()?;
+
+        Ok(result)
     }
 }
 
@@ -61,6 +253,20 @@ impl fmt::Display for Channels {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.number)?;
 
+        if self.audio_coding.is_some() || self.special_usage.is_some() {
+            let identifiers = self.audio_coding_identifiers();
+
+            if identifiers.is_empty() {
+                write!(f, "/-")?;
+            } else {
+                write!(f, "/{}", identifiers.join(","))?;
+            }
+        }
+
+        if let Some(value) = &self.special_usage {
+            write!(f, "/{}", value.join(","))?;
+        }
+
         Ok(())
     }
 }
@@ -75,6 +281,13 @@ mod tests {
         assert_eq!(Channels::new(6).to_string(), "6".to_string());
 
         assert_eq!(Channels::new(7).to_string(), "7".to_string());
+
+        let mut channels = Channels::new(2);
+        channels.set_usages(vec![ChannelUsage::Binaural, ChannelUsage::Downmix]);
+        assert_eq!(channels.to_string(), "2/-/BINAURAL,DOWNMIX".to_string());
+
+        channels.set_audio_coding_identifiers(vec!["JOC"]);
+        assert_eq!(channels.to_string(), "2/JOC/BINAURAL,DOWNMIX".to_string());
     }
 
     #[test]
@@ -83,5 +296,31 @@ mod tests {
 
         assert!(Channels::from_str("garbage").is_err());
         assert!(Channels::from_str("").is_err());
+        assert!(Channels::from_str("6/").is_err());
+        assert!(Channels::from_str("6/JOC,/BINAURAL").is_err());
+        assert!(Channels::from_str("a/JOC").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for input in &[
+            "6",
+            "6/-",
+            "16/JOC",
+            "12/-/BINAURAL",
+            "2/JOC,FOO/IMMERSIVE,BAR",
+        ] {
+            assert_eq!(Channels::from_str(input).unwrap().to_string(), *input);
+        }
+
+        let channels = Channels::from_str("2/JOC,FOO/IMMERSIVE,BAR").unwrap();
+        assert!(channels.is_joc());
+        assert_eq!(
+            channels.audio_coding_identifiers(),
+            &["JOC".to_string(), "FOO".to_string()]
+        );
+        assert!(channels.has_usage(ChannelUsage::Immersive));
+        assert!(!channels.has_usage(ChannelUsage::Binaural));
+        assert_eq!(channels.usages().count(), 1);
     }
 }
@@ -2,11 +2,23 @@ use core::convert::{Infallible, TryFrom};
 use std::borrow::Cow;
 use std::fmt;
 
-use crate::utils::{quote, unquote};
+use crate::utils::{intern, quote, unquote};
 
 /// The identifier of a closed captions group or its absence.
+///
+/// If the `serde` feature is enabled, it is serialized with a `kind` tag:
+///
+/// ```json
+/// { "kind": "group_id", "group_id": "cc" }
+/// { "kind": "none" }
+/// ```
 #[non_exhaustive]
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "kind", content = "group_id", rename_all = "snake_case")
+)]
 pub enum ClosedCaptions<'a> {
     /// It indicates the set of closed-caption renditions that can be used when
     /// playing the presentation.
@@ -54,6 +66,24 @@ impl<'a> ClosedCaptions<'a> {
         Self::GroupId(value.into())
     }
 
+    /// Returns the group id or `None` for [`ClosedCaptions::None`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::types::ClosedCaptions;
+    ///
+    /// assert_eq!(ClosedCaptions::group_id("vg1").as_group_id(), Some("vg1"));
+    /// assert_eq!(ClosedCaptions::None.as_group_id(), None);
+    /// ```
+    #[must_use]
+    pub fn as_group_id(&self) -> Option<&str> {
+        match self {
+            Self::GroupId(value) => Some(value),
+            Self::None => None,
+        }
+    }
+
     /// Makes the struct independent of its lifetime, by taking ownership of all
     /// internal [`Cow`]s.
     ///
@@ -63,7 +93,7 @@ impl<'a> ClosedCaptions<'a> {
     #[must_use]
     pub fn into_owned(self) -> ClosedCaptions<'static> {
         match self {
-            Self::GroupId(id) => ClosedCaptions::GroupId(Cow::Owned(id.into_owned())),
+            Self::GroupId(id) => ClosedCaptions::GroupId(intern(id)),
             Self::None => ClosedCaptions::None,
         }
     }
@@ -87,6 +117,7 @@ impl<'a> fmt::Display for ClosedCaptions<'a> {
     }
 }
 
+#[allow(clippy::infallible_try_from)]
 impl<'a> TryFrom<&'a str> for ClosedCaptions<'a> {
     type Error = Infallible;
 
@@ -4,6 +4,7 @@ use std::borrow::Cow;
 
 use derive_more::{AsMut, AsRef, Deref, DerefMut};
 
+use crate::utils::intern;
 use crate::Error;
 
 /// A list of formats, where each format specifies a media sample type that is
@@ -52,11 +53,7 @@ impl<'a> Codecs<'a> {
     #[must_use]
     pub fn into_owned(self) -> Codecs<'static> {
         Codecs {
-            list: self
-                .list
-                .into_iter()
-                .map(|v| Cow::Owned(v.into_owned()))
-                .collect(),
+            list: self.list.into_iter().map(intern).collect(),
         }
     }
 }
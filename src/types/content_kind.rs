@@ -0,0 +1,18 @@
+/// The kind of media, that is contained in the [`MediaSegment`]s of a
+/// [`MediaPlaylist`].
+///
+/// This is not part of the playlist file and is only used to validate a
+/// playlist.
+///
+/// [`MediaSegment`]: crate::MediaSegment
+/// [`MediaPlaylist`]: crate::MediaPlaylist
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ContentKind {
+    /// The segments contain audio (and possibly video).
+    Audio,
+    /// The segments contain video.
+    Video,
+    /// The segments contain subtitles (for example WebVTT or IMSC1).
+    Subtitles,
+}
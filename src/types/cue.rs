@@ -0,0 +1,221 @@
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::BitOr;
+use std::str::FromStr;
+
+use strum::{Display, EnumString};
+
+use crate::Error;
+
+/// A hint, when the action of an [`ExtXDateRange`] should be triggered.
+///
+/// [`ExtXDateRange`]: crate::tags::ExtXDateRange
+#[non_exhaustive]
+#[derive(Ord, PartialOrd, Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString)]
+#[strum(serialize_all = "SCREAMING-KEBAB-CASE")]
+pub enum Cue {
+    /// The action should be triggered before the playback of the primary asset
+    /// begins, regardless of where the playback begins.
+    Pre,
+    /// The action should be triggered after the primary asset has been played
+    /// to its end.
+    Post,
+    /// The action should be triggered at most once.
+    Once,
+}
+
+impl Cue {
+    const ALL: [Self; 3] = [Self::Pre, Self::Post, Self::Once];
+
+    const fn bit(self) -> u8 {
+        match self {
+            Self::Pre => 1,
+            Self::Post => 1 << 1,
+            Self::Once => 1 << 2,
+        }
+    }
+}
+
+/// The set of [`Cue`]s of the `CUE` attribute of an [`ExtXDateRange`].
+///
+/// Every [`Cue`] is contained at most once, no matter how often it has been
+/// inserted, and the hints are always written in the same order.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::types::{Cue, CueHint};
+///
+/// let mut hint = CueHint::from(Cue::Once);
+/// hint.insert(Cue::Pre);
+/// hint.insert(Cue::Once);
+///
+/// assert!(hint.contains(Cue::Pre));
+/// assert!(!hint.contains(Cue::Post));
+/// assert_eq!(hint.iter().collect::<Vec<_>>(), vec![Cue::Pre, Cue::Once]);
+/// assert_eq!(hint.to_string(), "PRE,ONCE");
+/// assert_eq!("ONCE,PRE".parse::<CueHint>()?, Cue::Pre | Cue::Once);
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+///
+/// [`ExtXDateRange`]: crate::tags::ExtXDateRange
+#[derive(Default, Ord, PartialOrd, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CueHint(u8);
+
+impl CueHint {
+    /// Returns an empty set.
+    #[must_use]
+    pub const fn empty() -> Self { Self(0) }
+
+    /// Returns `true`, if the set contains no [`Cue`].
+    #[must_use]
+    pub const fn is_empty(self) -> bool { self.0 == 0 }
+
+    /// Returns `true`, if the set contains the [`Cue`].
+    #[must_use]
+    pub const fn contains(self, cue: Cue) -> bool { self.0 & cue.bit() != 0 }
+
+    /// Adds the [`Cue`] to the set.
+    pub fn insert(&mut self, cue: Cue) { self.0 |= cue.bit(); }
+
+    /// Removes the [`Cue`] from the set.
+    pub fn remove(&mut self, cue: Cue) { self.0 &= !cue.bit(); }
+
+    /// Returns an iterator over the [`Cue`]s in the set.
+    pub fn iter(self) -> impl Iterator<Item = Cue> {
+        Cue::ALL
+            .iter()
+            .copied()
+            .filter(move |cue| self.contains(*cue))
+    }
+}
+
+impl From<Cue> for CueHint {
+    fn from(value: Cue) -> Self { Self(value.bit()) }
+}
+
+impl From<Vec<Cue>> for CueHint {
+    fn from(value: Vec<Cue>) -> Self { value.into_iter().collect() }
+}
+
+impl FromIterator<Cue> for CueHint {
+    fn from_iter<I: IntoIterator<Item = Cue>>(iter: I) -> Self {
+        let mut result = Self::empty();
+        result.extend(iter);
+        result
+    }
+}
+
+impl Extend<Cue> for CueHint {
+    fn extend<I: IntoIterator<Item = Cue>>(&mut self, iter: I) {
+        for cue in iter {
+            self.insert(cue);
+        }
+    }
+}
+
+impl BitOr for Cue {
+    type Output = CueHint;
+
+    fn bitor(self, rhs: Self) -> Self::Output { CueHint::from(self) | rhs }
+}
+
+impl BitOr<Cue> for CueHint {
+    type Output = Self;
+
+    fn bitor(mut self, rhs: Cue) -> Self::Output {
+        self.insert(rhs);
+        self
+    }
+}
+
+impl BitOr for CueHint {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output { Self(self.0 | rhs.0) }
+}
+
+impl fmt::Display for CueHint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, cue) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+
+            write!(f, "{}", cue)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for CueHint {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        input
+            .split(',')
+            .map(|value| value.trim().parse::<Cue>().map_err(Error::strum))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Cue::Pre.to_string(), "PRE".to_string());
+        assert_eq!(Cue::Post.to_string(), "POST".to_string());
+        assert_eq!(Cue::Once.to_string(), "ONCE".to_string());
+    }
+
+    #[test]
+    fn test_parser() {
+        assert_eq!(Cue::Pre, "PRE".parse().unwrap());
+        assert_eq!(Cue::Post, "POST".parse().unwrap());
+        assert_eq!(Cue::Once, "ONCE".parse().unwrap());
+
+        assert!("pre".parse::<Cue>().is_err());
+    }
+
+    #[test]
+    fn test_cue_hint() {
+        let mut hint = CueHint::empty();
+        assert!(hint.is_empty());
+        assert_eq!(hint.to_string(), "".to_string());
+
+        hint.insert(Cue::Once);
+        hint.insert(Cue::Post);
+        hint.insert(Cue::Once);
+
+        assert_eq!(hint, Cue::Post | Cue::Once);
+        assert_eq!(hint.iter().collect::<Vec<_>>(), vec![Cue::Post, Cue::Once]);
+        assert_eq!(hint.to_string(), "POST,ONCE".to_string());
+
+        hint.remove(Cue::Post);
+        assert_eq!(hint, CueHint::from(Cue::Once));
+
+        assert_eq!(
+            CueHint::from(vec![Cue::Once, Cue::Pre, Cue::Once]),
+            Cue::Pre | Cue::Once
+        );
+    }
+
+    #[test]
+    fn test_cue_hint_parser() {
+        assert_eq!(
+            "PRE, ONCE".parse::<CueHint>().unwrap(),
+            Cue::Pre | Cue::Once
+        );
+        assert_eq!(
+            "ONCE,POST,ONCE".parse::<CueHint>().unwrap(),
+            Cue::Post | Cue::Once
+        );
+
+        assert!("PRE,LATER".parse::<CueHint>().is_err());
+        assert!("".parse::<CueHint>().is_err());
+    }
+}
@@ -1,4 +1,3 @@
-use std::borrow::Cow;
 use std::convert::TryFrom;
 use std::fmt;
 
@@ -7,10 +6,11 @@ use shorthand::ShortHand;
 
 use crate::attribute::AttributePairs;
 use crate::types::{
-    EncryptionMethod, InitializationVector, KeyFormat, KeyFormatVersions, ProtocolVersion,
+    EncryptionMethod, Feature, InitializationVector, KeyFormat, KeyFormatVersions, ProtocolVersion,
+    UriRef,
 };
-use crate::utils::{quote, unquote};
-use crate::{Error, RequiredVersion};
+use crate::utils::{decode_base64, quote, unquote};
+use crate::{Error, RequiredVersion, VersionReason};
 
 /// Specifies how to decrypt encrypted data from the server.
 #[derive(ShortHand, Builder, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -46,7 +46,7 @@ pub struct DecryptionKey<'a> {
     /// This field is required.
     #[builder(setter(into, strip_option), default)]
     #[shorthand(disable(skip))]
-    pub(crate) uri: Cow<'a, str>,
+    pub(crate) uri: UriRef<'a>,
     /// An initialization vector (IV) is a fixed size input that can be used
     /// along with a secret key for data encryption.
     ///
@@ -69,7 +69,7 @@ pub struct DecryptionKey<'a> {
     ///
     /// This field is optional.
     #[builder(setter(into, strip_option), default)]
-    pub format: Option<KeyFormat>,
+    pub format: Option<KeyFormat<'a>>,
     /// A list of numbers that can be used to indicate which version(s)
     /// this instance complies with, if more than one version of a particular
     /// [`KeyFormat`] is defined.
@@ -95,7 +95,7 @@ impl<'a> DecryptionKey<'a> {
     /// ```
     #[must_use]
     #[inline]
-    pub fn new<I: Into<Cow<'a, str>>>(method: EncryptionMethod, uri: I) -> Self {
+    pub fn new<I: Into<UriRef<'a>>>(method: EncryptionMethod, uri: I) -> Self {
         Self {
             method,
             uri: uri.into(),
@@ -138,22 +138,171 @@ impl<'a> DecryptionKey<'a> {
     pub fn into_owned(self) -> DecryptionKey<'static> {
         DecryptionKey {
             method: self.method,
-            uri: Cow::Owned(self.uri.into_owned()),
+            uri: self.uri.into_owned(),
             iv: self.iv,
-            format: self.format,
+            format: self.format.map(KeyFormat::into_owned),
             versions: self.versions,
         }
     }
+
+    /// Returns `true`, if both keys describe the same key.
+    ///
+    /// Unlike `==`, a missing [`DecryptionKey::format`] is equal to
+    /// [`KeyFormat::Identity`] and missing [`DecryptionKey::versions`] are
+    /// equal to `KEYFORMATVERSIONS="1"`, because these are the defaults.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::DecryptionKey;
+    /// use hls_m3u8::types::{EncryptionMethod, KeyFormat};
+    ///
+    /// let key = DecryptionKey::new(EncryptionMethod::Aes128, "https://www.example.com/");
+    ///
+    /// let mut other = key.clone();
+    /// other.format = Some(KeyFormat::Identity);
+    /// other.versions = Some([1].into());
+    ///
+    /// assert_ne!(key, other);
+    /// assert!(key.is_equivalent(&other));
+    /// ```
+    #[must_use]
+    pub fn is_equivalent(&self, other: &DecryptionKey<'_>) -> bool {
+        self.normalized() == other.normalized()
+    }
+
+    /// Returns `true`, if the key is embedded in the playlist with a `data:`
+    /// uri.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::DecryptionKey;
+    /// use hls_m3u8::types::EncryptionMethod;
+    ///
+    /// let key = DecryptionKey::new(
+    ///     EncryptionMethod::Aes128,
+    ///     "data:text/plain;base64,AAECAwQFBgcICQoLDA0ODw==",
+    /// );
+    /// assert!(key.is_inline_key());
+    ///
+    /// let key = DecryptionKey::new(EncryptionMethod::Aes128, "https://www.example.com/");
+    /// assert!(!key.is_inline_key());
+    /// ```
+    #[must_use]
+    pub fn is_inline_key(&self) -> bool { self.data_uri().is_some() }
+
+    /// Decodes the key, that is embedded in the playlist with a `data:` uri.
+    ///
+    /// The payload is decoded as base64, if the media type ends with
+    /// `;base64` and as hex (with an optional `0x` prefix) otherwise.
+    ///
+    /// Returns `None`, if the uri is not a `data:` uri.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::DecryptionKey;
+    /// use hls_m3u8::types::EncryptionMethod;
+    ///
+    /// let key = DecryptionKey::new(
+    ///     EncryptionMethod::Aes128,
+    ///     "data:text/plain;base64,AAECAwQFBgcICQoLDA0ODw==",
+    /// );
+    ///
+    /// assert_eq!(
+    ///     key.inline_key_bytes().unwrap()?,
+    ///     (0..16).collect::<Vec<u8>>()
+    /// );
+    ///
+    /// let key = DecryptionKey::new(
+    ///     EncryptionMethod::Aes128,
+    ///     "data:text/plain,0x000102030405060708090a0b0c0d0e0f",
+    /// );
+    ///
+    /// assert_eq!(
+    ///     key.inline_key_bytes().unwrap()?,
+    ///     (0..16).collect::<Vec<u8>>()
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function fails, if the `data:` uri has no `,` or if the payload
+    /// is not valid base64 or hex.
+    #[must_use]
+    pub fn inline_key_bytes(&self) -> Option<crate::Result<Vec<u8>>> {
+        let data = self.data_uri()?;
+
+        let result = data
+            .find(',')
+            .ok_or_else(|| {
+                Error::invalid_attribute(format!("missing `,` in the data uri: {:?}", self.uri))
+            })
+            .and_then(|index| {
+                let (media_type, payload) = (&data[..index], &data[index + 1..]);
+
+                let is_base64 = media_type
+                    .rsplit(';')
+                    .next()
+                    .is_some_and(|value| value.eq_ignore_ascii_case("base64"));
+
+                if is_base64 {
+                    decode_base64(payload)
+                } else {
+                    let payload = payload
+                        .strip_prefix("0x")
+                        .or_else(|| payload.strip_prefix("0X"))
+                        .unwrap_or(payload);
+
+                    hex::decode(payload).map_err(Error::hex)
+                }
+            });
+
+        Some(result)
+    }
+
+    /// Returns everything after the `data:` scheme of the uri.
+    fn data_uri(&self) -> Option<&str> {
+        let scheme = self.uri.get(..5)?;
+
+        if scheme.eq_ignore_ascii_case("data:") {
+            Some(&self.uri[5..])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the [`KeyFormat`], which defaults to [`KeyFormat::Identity`].
+    pub(crate) fn key_format(&self) -> KeyFormat<'a> { self.format.clone().unwrap_or_default() }
+
+    /// Removes the default values of [`DecryptionKey::format`] and
+    /// [`DecryptionKey::versions`], so keys can be compared by their meaning.
+    pub(crate) fn normalized(&self) -> Self {
+        let mut result = self.clone();
+
+        result.format = result.format.filter(|value| *value != KeyFormat::default());
+        result.versions = result.versions.filter(|value| !value.is_default());
+
+        result
+    }
+
+    fn has_versions(&self) -> bool {
+        self.versions
+            .as_ref()
+            .is_some_and(|value| !value.is_default())
+    }
 }
 
 /// This tag requires [`ProtocolVersion::V5`], if [`KeyFormat`] or
-/// [`KeyFormatVersions`] is specified and [`ProtocolVersion::V2`] if an iv is
-/// specified.
+/// [`KeyFormatVersions`] (other than the default `"1"`, which is not
+/// written) is specified and [`ProtocolVersion::V2`] if an iv is specified.
 ///
 /// Otherwise [`ProtocolVersion::V1`] is required.
 impl<'a> RequiredVersion for DecryptionKey<'a> {
     fn required_version(&self) -> ProtocolVersion {
-        if self.format.is_some() || self.versions.is_some() {
+        if self.format.is_some() || self.has_versions() {
             ProtocolVersion::V5
         } else if self.iv.is_some() {
             ProtocolVersion::V2
@@ -161,6 +310,24 @@ impl<'a> RequiredVersion for DecryptionKey<'a> {
             ProtocolVersion::V1
         }
     }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        let mut result = Vec::new();
+
+        if self.iv.is_some() {
+            result.push(VersionReason::from(Feature::Iv));
+        }
+
+        if self.format.is_some() {
+            result.push(VersionReason::from(Feature::KeyFormat));
+        }
+
+        if self.has_versions() {
+            result.push(VersionReason::from(Feature::KeyFormatVersions));
+        }
+
+        result
+    }
 }
 
 impl<'a> TryFrom<&'a str> for DecryptionKey<'a> {
@@ -175,6 +342,11 @@ impl<'a> TryFrom<&'a str> for DecryptionKey<'a> {
 
         for (key, value) in AttributePairs::new(input) {
             match key {
+                "METHOD" if value == "NONE" => {
+                    return Err(Error::invalid_attribute(
+                        "a DecryptionKey can not have the METHOD NONE (use `ExtXKey::empty`)",
+                    ));
+                }
                 "METHOD" => method = Some(value.parse().map_err(Error::strum)?),
                 "URI" => {
                     let unquoted_uri = unquote(value);
@@ -184,7 +356,7 @@ impl<'a> TryFrom<&'a str> for DecryptionKey<'a> {
                     }
                 }
                 "IV" => iv = Some(value.parse()?),
-                "KEYFORMAT" => format = Some(value.parse()?),
+                "KEYFORMAT" => format = Some(KeyFormat::from(value)),
                 "KEYFORMATVERSIONS" => versions = Some(value.parse()?),
                 _ => {
                     // [6.3.1. General Client Responsibilities]
@@ -195,7 +367,7 @@ impl<'a> TryFrom<&'a str> for DecryptionKey<'a> {
         }
 
         let method = method.ok_or_else(|| Error::missing_value("METHOD"))?;
-        let uri = uri.ok_or_else(|| Error::missing_value("URI"))?;
+        let uri = uri.ok_or_else(|| Error::missing_value("URI"))?.into();
         let iv = iv.unwrap_or_default();
 
         Ok(Self {
@@ -217,7 +389,7 @@ impl<'a> fmt::Display for DecryptionKey<'a> {
         }
 
         if let Some(value) = &self.format {
-            write!(f, ",KEYFORMAT={}", quote(value))?;
+            write!(f, ",KEYFORMAT={}", value)?;
         }
 
         if let Some(value) = &self.versions {
@@ -235,11 +407,13 @@ impl<'a> DecryptionKeyBuilder<'a> {
         // a decryption key must contain a uri and a method
         if self.method.is_none() {
             return Err(Error::missing_field("DecryptionKey", "method").to_string());
-        } else if self.uri.is_none() {
-            return Err(Error::missing_field("DecryptionKey", "uri").to_string());
         }
 
-        Ok(())
+        // like the parser, an empty uri is treated as a missing uri
+        match &self.uri {
+            Some(uri) if !uri.trim().is_empty() => Ok(()),
+            _ => Err(Error::missing_field("DecryptionKey", "uri").to_string()),
+        }
     }
 }
 
@@ -305,6 +479,14 @@ mod test {
             .method(EncryptionMethod::Aes128)
             .build()
             .is_err());
+
+        // the parser does not accept an empty uri either:
+        assert!(DecryptionKey::builder()
+            .method(EncryptionMethod::Aes128)
+            .uri(" ")
+            .build()
+            .is_err());
+        assert!(DecryptionKey::try_from("METHOD=AES-128,URI=\" \"").is_err());
     }
 
     generate_tests! {
@@ -348,6 +530,32 @@ mod test {
                 "KEYFORMATVERSIONS=\"1/2/3\""
             )
         },
+        {
+            DecryptionKey::builder()
+                .method(EncryptionMethod::SampleAes)
+                .uri("skd://key-id")
+                .format(KeyFormat::FairPlay)
+                .build()
+                .unwrap(),
+            concat!(
+                "METHOD=SAMPLE-AES,",
+                "URI=\"skd://key-id\",",
+                "KEYFORMAT=\"com.apple.streamingkeydelivery\""
+            )
+        },
+        {
+            DecryptionKey::builder()
+                .method(EncryptionMethod::SampleAes)
+                .uri("data:text/plain;base64,AAAA")
+                .format(KeyFormat::Uuid(KeyFormat::WIDEVINE_UUID.into()))
+                .build()
+                .unwrap(),
+            concat!(
+                "METHOD=SAMPLE-AES,",
+                "URI=\"data:text/plain;base64,AAAA\",",
+                "KEYFORMAT=\"urn:uuid:edef8ba9-79d6-4ace-a3c8-27dcd51d21ed\""
+            )
+        },
     }
 
     #[test]
@@ -380,5 +588,63 @@ mod test {
                 .required_version(),
             ProtocolVersion::V2
         );
+
+        assert_eq!(
+            DecryptionKey::builder()
+                .method(EncryptionMethod::Aes128)
+                .uri("https://www.example.com/")
+                .versions(vec![1])
+                .build()
+                .unwrap()
+                .required_version(),
+            ProtocolVersion::V1
+        );
+    }
+
+    #[test]
+    fn test_inline_key_bytes() {
+        let key = |uri: &'static str| DecryptionKey::new(EncryptionMethod::Aes128, uri);
+        let expected = (0..16).collect::<Vec<u8>>();
+
+        for uri in &[
+            "data:text/plain;base64,AAECAwQFBgcICQoLDA0ODw==",
+            "DATA:;BASE64,AAECAwQFBgcICQoLDA0ODw",
+            "data:application/octet-stream;base64,AAECAwQFBgcICQoLDA0ODw==",
+            "data:,000102030405060708090A0B0C0D0E0F",
+            "data:text/plain,0x000102030405060708090a0b0c0d0e0f",
+        ] {
+            assert!(key(uri).is_inline_key());
+            assert_eq!(key(uri).inline_key_bytes().unwrap().unwrap(), expected);
+        }
+
+        assert!(!key("https://www.example.com/data:,00").is_inline_key());
+        assert!(key("https://www.example.com/").inline_key_bytes().is_none());
+        assert!(key("key").inline_key_bytes().is_none());
+
+        assert!(key("data:text/plain").inline_key_bytes().unwrap().is_err());
+        assert!(key("data:;base64,A").inline_key_bytes().unwrap().is_err());
+        assert!(key("data:,0x0g").inline_key_bytes().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_is_equivalent() {
+        let key = DecryptionKey::new(EncryptionMethod::Aes128, "https://www.example.com/");
+
+        let parsed = DecryptionKey::try_from(concat!(
+            "KEYFORMATVERSIONS=\"1\",KEYFORMAT=\"identity\",",
+            "URI=\"https://www.example.com/\",METHOD=AES-128"
+        ))
+        .unwrap();
+
+        assert!(key.is_equivalent(&parsed));
+        assert!(parsed.is_equivalent(&key));
+
+        let mut other = parsed.clone();
+        other.versions = Some(KeyFormatVersions::from([1, 2]));
+        assert!(!key.is_equivalent(&other));
+
+        let mut other = parsed;
+        other.method = EncryptionMethod::SampleAes;
+        assert!(!key.is_equivalent(&other));
     }
 }
@@ -0,0 +1,116 @@
+use std::fmt;
+
+use crate::types::ProtocolVersion;
+
+/// A feature of the protocol, that requires a [`ProtocolVersion`] higher than
+/// [`ProtocolVersion::V1`].
+///
+/// The features are listed in
+/// [rfc8216#section-7](https://tools.ietf.org/html/rfc8216#section-7).
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::types::{Feature, ProtocolVersion};
+///
+/// assert!(ProtocolVersion::V4.supports(Feature::ByteRange));
+/// assert!(!ProtocolVersion::V3.supports(Feature::ByteRange));
+///
+/// assert_eq!(Feature::ByteRange.required_version(), ProtocolVersion::V4);
+/// assert_eq!(Feature::ByteRange.to_string(), "EXT-X-BYTERANGE");
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Feature {
+    /// The `IV` attribute of the `EXT-X-KEY` tag.
+    Iv,
+    /// Floating-point durations in the `EXTINF` tag.
+    DecimalDuration,
+    /// The `EXT-X-BYTERANGE` tag.
+    ByteRange,
+    /// The `EXT-X-I-FRAMES-ONLY` tag.
+    IFramesOnly,
+    /// The `KEYFORMAT` attribute of the `EXT-X-KEY` tag.
+    KeyFormat,
+    /// The `KEYFORMATVERSIONS` attribute of the `EXT-X-KEY` tag.
+    KeyFormatVersions,
+    /// The `EXT-X-MAP` tag in a playlist with an `EXT-X-I-FRAMES-ONLY` tag.
+    MapInIFramesOnly,
+    /// The `EXT-X-MAP` tag in a playlist without an `EXT-X-I-FRAMES-ONLY`
+    /// tag.
+    Map,
+    /// The `SERVICE` values of the `INSTREAM-ID` attribute.
+    InStreamIdService,
+    /// The `REQ-VIDEO-LAYOUT` attribute of the `EXT-X-STREAM-INF` tag.
+    ReqVideoLayout,
+}
+
+impl Feature {
+    /// All features in the order of their [`Feature::required_version`].
+    pub const ALL: &'static [Self] = &[
+        Self::Iv,
+        Self::DecimalDuration,
+        Self::ByteRange,
+        Self::IFramesOnly,
+        Self::KeyFormat,
+        Self::KeyFormatVersions,
+        Self::MapInIFramesOnly,
+        Self::Map,
+        Self::InStreamIdService,
+        Self::ReqVideoLayout,
+    ];
+
+    /// Returns the [`ProtocolVersion`], that introduced the feature.
+    #[must_use]
+    pub const fn required_version(self) -> ProtocolVersion {
+        match self {
+            Self::Iv => ProtocolVersion::V2,
+            Self::DecimalDuration => ProtocolVersion::V3,
+            Self::ByteRange | Self::IFramesOnly => ProtocolVersion::V4,
+            Self::KeyFormat | Self::KeyFormatVersions | Self::MapInIFramesOnly => {
+                ProtocolVersion::V5
+            }
+            Self::Map => ProtocolVersion::V6,
+            Self::InStreamIdService => ProtocolVersion::V7,
+            Self::ReqVideoLayout => ProtocolVersion::V12,
+        }
+    }
+
+    /// Returns the name of the tag or the attribute (like `EXT-X-BYTERANGE`).
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Iv => "IV",
+            Self::DecimalDuration => "decimal EXTINF duration",
+            Self::ByteRange => "EXT-X-BYTERANGE",
+            Self::IFramesOnly => "EXT-X-I-FRAMES-ONLY",
+            Self::KeyFormat => "KEYFORMAT",
+            Self::KeyFormatVersions => "KEYFORMATVERSIONS",
+            Self::MapInIFramesOnly => "EXT-X-MAP in EXT-X-I-FRAMES-ONLY",
+            Self::Map => "EXT-X-MAP",
+            Self::InStreamIdService => "INSTREAM-ID=SERVICE",
+            Self::ReqVideoLayout => "REQ-VIDEO-LAYOUT",
+        }
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.name()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_all() {
+        let mut sorted = Feature::ALL.to_vec();
+        sorted.sort_by_key(|feature| feature.required_version());
+
+        assert_eq!(Feature::ALL, sorted.as_slice());
+        assert!(Feature::ALL
+            .iter()
+            .all(|feature| feature.required_version() > ProtocolVersion::V1));
+    }
+}
@@ -78,11 +78,14 @@ impl TryFrom<f32> for Float {
 
     fn try_from(float: f32) -> Result<Self, Self::Error> {
         if float.is_infinite() {
-            return Err(Error::custom(format!("float must be finite: `{}`", float)));
+            return Err(Error::invalid_attribute(format!(
+                "float must be finite: `{}`",
+                float
+            )));
         }
 
         if float.is_nan() {
-            return Err(Error::custom("float must not be `NaN`"));
+            return Err(Error::invalid_attribute("float must not be `NaN`"));
         }
 
         Ok(Self(float))
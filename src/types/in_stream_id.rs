@@ -1,7 +1,10 @@
-use strum::{Display, EnumString};
+use core::str::FromStr;
 
-use crate::traits::RequiredVersion;
-use crate::types::ProtocolVersion;
+use strum::Display;
+
+use crate::traits::{RequiredVersion, VersionReason};
+use crate::types::{Feature, ProtocolVersion};
+use crate::Error;
 
 /// Identifier of a rendition within the [`MediaSegment`]s in a
 /// [`MediaPlaylist`].
@@ -19,7 +22,7 @@ use crate::types::ProtocolVersion;
 /// [`MediaPlaylist`]: crate::MediaPlaylist
 #[non_exhaustive]
 #[allow(missing_docs)]
-#[derive(Ord, PartialOrd, Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString)]
+#[derive(Ord, PartialOrd, Debug, Clone, Copy, PartialEq, Eq, Hash, Display)]
 #[strum(serialize_all = "UPPERCASE")]
 pub enum InStreamId {
     Cc1,
@@ -91,6 +94,150 @@ pub enum InStreamId {
     Service63,
 }
 
+impl InStreamId {
+    const SERVICES: [Self; 63] = [
+        Self::Service1,
+        Self::Service2,
+        Self::Service3,
+        Self::Service4,
+        Self::Service5,
+        Self::Service6,
+        Self::Service7,
+        Self::Service8,
+        Self::Service9,
+        Self::Service10,
+        Self::Service11,
+        Self::Service12,
+        Self::Service13,
+        Self::Service14,
+        Self::Service15,
+        Self::Service16,
+        Self::Service17,
+        Self::Service18,
+        Self::Service19,
+        Self::Service20,
+        Self::Service21,
+        Self::Service22,
+        Self::Service23,
+        Self::Service24,
+        Self::Service25,
+        Self::Service26,
+        Self::Service27,
+        Self::Service28,
+        Self::Service29,
+        Self::Service30,
+        Self::Service31,
+        Self::Service32,
+        Self::Service33,
+        Self::Service34,
+        Self::Service35,
+        Self::Service36,
+        Self::Service37,
+        Self::Service38,
+        Self::Service39,
+        Self::Service40,
+        Self::Service41,
+        Self::Service42,
+        Self::Service43,
+        Self::Service44,
+        Self::Service45,
+        Self::Service46,
+        Self::Service47,
+        Self::Service48,
+        Self::Service49,
+        Self::Service50,
+        Self::Service51,
+        Self::Service52,
+        Self::Service53,
+        Self::Service54,
+        Self::Service55,
+        Self::Service56,
+        Self::Service57,
+        Self::Service58,
+        Self::Service59,
+        Self::Service60,
+        Self::Service61,
+        Self::Service62,
+        Self::Service63,
+    ];
+
+    /// Returns the `Service` variant with the given service block number.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::InStreamId;
+    /// assert_eq!(InStreamId::service(1)?, InStreamId::Service1);
+    /// assert_eq!(InStreamId::service(63)?, InStreamId::Service63);
+    ///
+    /// assert!(InStreamId::service(0).is_err());
+    /// assert!(InStreamId::service(64).is_err());
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function fails, if the number is not in the range `1..=63`.
+    pub fn service(number: u8) -> crate::Result<Self> {
+        number
+            .checked_sub(1)
+            .and_then(|i| Self::SERVICES.get(usize::from(i)))
+            .copied()
+            .ok_or_else(|| {
+                Error::invalid_attribute(format!(
+                    "service block number must be in the range 1..=63: {}",
+                    number
+                ))
+            })
+    }
+
+    /// Returns the service block number of a `Service` variant or `None` for
+    /// the [CEA608] channels.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::InStreamId;
+    /// assert_eq!(InStreamId::Service12.as_service_number(), Some(12));
+    /// assert_eq!(InStreamId::Cc1.as_service_number(), None);
+    /// ```
+    ///
+    /// [CEA608]: https://tools.ietf.org/html/rfc8216#ref-CEA608
+    #[must_use]
+    pub fn as_service_number(&self) -> Option<u8> {
+        Self::SERVICES
+            .iter()
+            .position(|service| service == self)
+            // the position is at most 62, so it always fits into an u8:
+            .map(|i| i as u8 + 1)
+    }
+}
+
+impl FromStr for InStreamId {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "CC1" => Ok(Self::Cc1),
+            "CC2" => Ok(Self::Cc2),
+            "CC3" => Ok(Self::Cc3),
+            "CC4" => Ok(Self::Cc4),
+            _ => {
+                let number = input
+                    .strip_prefix("SERVICE")
+                    // leading zeros or signs are not allowed
+                    .filter(|n| !n.starts_with('0') && n.bytes().all(|b| b.is_ascii_digit()))
+                    .and_then(|n| n.parse::<u8>().ok())
+                    .ok_or_else(|| {
+                        Error::invalid_attribute(format!("invalid INSTREAM-ID: {:?}", input))
+                    })?;
+
+                Self::service(number)
+            }
+        }
+    }
+}
+
 /// The variants [`InStreamId::Cc1`], [`InStreamId::Cc2`], [`InStreamId::Cc3`]
 /// and [`InStreamId::Cc4`] require [`ProtocolVersion::V1`], the other
 /// [`ProtocolVersion::V7`].
@@ -101,6 +248,14 @@ impl RequiredVersion for InStreamId {
             _ => ProtocolVersion::V7,
         }
     }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        if self.required_version() == ProtocolVersion::V7 {
+            vec![VersionReason::from(Feature::InStreamIdService)]
+        } else {
+            Vec::new()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -123,10 +278,46 @@ mod tests {
                     assert_eq!($enum, $string.parse::<InStreamId>().unwrap());
                 )*
                 assert!("invalid_input".parse::<InStreamId>().is_err());
+                assert!("CC5".parse::<InStreamId>().is_err());
+                assert!("SERVICE0".parse::<InStreamId>().is_err());
+                assert!("SERVICE01".parse::<InStreamId>().is_err());
+                assert!("SERVICE+1".parse::<InStreamId>().is_err());
+                assert!("SERVICE64".parse::<InStreamId>().is_err());
+                assert!("SERVICE".parse::<InStreamId>().is_err());
             }
         };
     }
 
+    #[test]
+    fn test_service() {
+        for number in 1..=63 {
+            assert_eq!(
+                InStreamId::service(number).unwrap().as_service_number(),
+                Some(number)
+            );
+        }
+
+        assert_eq!(InStreamId::service(7).unwrap(), InStreamId::Service7);
+        assert!(InStreamId::service(0).is_err());
+        assert!(InStreamId::service(64).is_err());
+
+        assert_eq!(InStreamId::Cc1.as_service_number(), None);
+        assert_eq!(InStreamId::Cc4.as_service_number(), None);
+    }
+
+    #[test]
+    fn test_parser_error() {
+        assert_eq!(
+            "SERVICE64".parse::<InStreamId>().unwrap_err().to_string(),
+            "service block number must be in the range 1..=63: 64".to_string()
+        );
+
+        assert_eq!(
+            "CC5".parse::<InStreamId>().unwrap_err().to_string(),
+            "invalid INSTREAM-ID: \"CC5\"".to_string()
+        );
+    }
+
     gen_tests![
         "CC1" => InStreamId::Cc1,
         "CC2" => InStreamId::Cc2,
@@ -148,6 +148,7 @@ impl InitializationVector {
     pub fn is_none(&self) -> bool { *self == Self::Missing }
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for InitializationVector {
     fn default() -> Self { Self::Missing }
 }
@@ -191,11 +192,13 @@ impl FromStr for InitializationVector {
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         if !(input.starts_with("0x") || input.starts_with("0X")) {
-            return Err(Error::custom("An IV should either start with `0x` or `0X`"));
+            return Err(Error::invalid_attribute(
+                "An IV should either start with `0x` or `0X`",
+            ));
         }
 
         if input.len() - 2 != 32 {
-            return Err(Error::custom(
+            return Err(Error::invalid_attribute(
                 "An IV must be 32 bytes long + 2 bytes for 0x/0X",
             ));
         }
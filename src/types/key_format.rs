@@ -1,43 +1,191 @@
+use std::borrow::Cow;
 use std::fmt;
-use std::str::FromStr;
 
 use crate::types::ProtocolVersion;
-use crate::utils::{quote, tag, unquote};
-use crate::{Error, RequiredVersion};
+use crate::utils::{intern, quote, unquote};
+use crate::RequiredVersion;
 
 /// Specifies how the key is represented in the resource identified by the
 /// `URI`.
+///
+/// The well-known key systems have their own variants, all other values are
+/// preserved as they are, so they can be written again.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::types::KeyFormat;
+///
+/// assert_eq!(
+///     KeyFormat::from("com.apple.streamingkeydelivery"),
+///     KeyFormat::FairPlay
+/// );
+///
+/// let format = KeyFormat::from("\"urn:uuid:edef8ba9-79d6-4ace-a3c8-27dcd51d21ed\"");
+///
+/// assert_eq!(format.uuid(), Some(KeyFormat::WIDEVINE_UUID));
+/// assert!(format.is_widevine());
+/// assert_eq!(
+///     format.to_string(),
+///     "\"urn:uuid:edef8ba9-79d6-4ace-a3c8-27dcd51d21ed\""
+/// );
+/// ```
 #[non_exhaustive]
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
-pub enum KeyFormat {
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum KeyFormat<'a> {
     /// An [`EncryptionMethod::Aes128`] uses 16-octet (16 byte/128 bit) keys. If
     /// the format is [`KeyFormat::Identity`], the key file is a single packed
     /// array of 16 octets (16 byte/128 bit) in binary format.
     ///
     /// [`EncryptionMethod::Aes128`]: crate::types::EncryptionMethod::Aes128
     Identity,
+    /// Apple FairPlay Streaming (`com.apple.streamingkeydelivery`).
+    FairPlay,
+    /// Google Widevine (`com.widevine.alpha`).
+    Widevine,
+    /// Microsoft PlayReady (`com.microsoft.playready`).
+    PlayReady,
+    /// A key system, that is identified by its system id (`urn:uuid:...`).
+    ///
+    /// The [`Cow`] contains only the uuid without the `urn:uuid:` prefix.
+    Uuid(Cow<'a, str>),
+    /// Any other format.
+    Other(Cow<'a, str>),
 }
 
-impl Default for KeyFormat {
+#[allow(clippy::derivable_impls)]
+impl<'a> Default for KeyFormat<'a> {
     fn default() -> Self { Self::Identity }
 }
 
-impl FromStr for KeyFormat {
-    type Err = Error;
+impl<'a> KeyFormat<'a> {
+    /// The system id of Apple FairPlay Streaming.
+    pub const FAIRPLAY_UUID: &'static str = "94ce86fb-07ff-4f43-adb8-93d2fa968ca2";
+    /// The system id of Microsoft PlayReady.
+    pub const PLAYREADY_UUID: &'static str = "9a04f079-9840-4286-ab92-e65be0885f95";
+    const URN_UUID: &'static str = "urn:uuid:";
+    /// The system id of Google Widevine.
+    pub const WIDEVINE_UUID: &'static str = "edef8ba9-79d6-4ace-a3c8-27dcd51d21ed";
 
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        tag(&unquote(input), "identity")?; // currently only KeyFormat::Identity exists!
+    /// Returns the uuid of a [`KeyFormat::Uuid`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::types::KeyFormat;
+    ///
+    /// assert_eq!(
+    ///     KeyFormat::from("urn:uuid:1077efec-c0b2-4d02-ace3-3c1e52e2fb4b").uuid(),
+    ///     Some("1077efec-c0b2-4d02-ace3-3c1e52e2fb4b")
+    /// );
+    /// assert_eq!(KeyFormat::Identity.uuid(), None);
+    /// ```
+    #[must_use]
+    pub fn uuid(&self) -> Option<&str> {
+        match self {
+            Self::Uuid(value) => Some(value),
+            _ => None,
+        }
+    }
 
-        Ok(Self::Identity)
+    /// Returns `true`, if the format is [`KeyFormat::Identity`].
+    #[must_use]
+    pub fn is_identity(&self) -> bool { *self == Self::Identity }
+
+    /// Returns `true`, if the format is [`KeyFormat::FairPlay`] or the
+    /// [`KeyFormat::Uuid`] of FairPlay.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::types::KeyFormat;
+    ///
+    /// assert!(KeyFormat::FairPlay.is_fairplay());
+    /// assert!(KeyFormat::from("urn:uuid:94CE86FB-07FF-4F43-ADB8-93D2FA968CA2").is_fairplay());
+    /// assert!(!KeyFormat::Widevine.is_fairplay());
+    /// ```
+    #[must_use]
+    pub fn is_fairplay(&self) -> bool {
+        *self == Self::FairPlay || self.has_uuid(Self::FAIRPLAY_UUID)
+    }
+
+    /// Returns `true`, if the format is [`KeyFormat::Widevine`] or the
+    /// [`KeyFormat::Uuid`] of Widevine.
+    #[must_use]
+    pub fn is_widevine(&self) -> bool {
+        *self == Self::Widevine || self.has_uuid(Self::WIDEVINE_UUID)
+    }
+
+    /// Returns `true`, if the format is [`KeyFormat::PlayReady`] or the
+    /// [`KeyFormat::Uuid`] of PlayReady.
+    #[must_use]
+    pub fn is_playready(&self) -> bool {
+        *self == Self::PlayReady || self.has_uuid(Self::PLAYREADY_UUID)
+    }
+
+    fn has_uuid(&self, uuid: &str) -> bool {
+        self.uuid()
+            .is_some_and(|value| value.eq_ignore_ascii_case(uuid))
+    }
+
+    /// Makes the struct independent of its lifetime, by taking ownership of all
+    /// internal [`Cow`]s.
+    ///
+    /// # Note
+    ///
+    /// This is a relatively expensive operation.
+    #[must_use]
+    pub fn into_owned(self) -> KeyFormat<'static> {
+        match self {
+            Self::Identity => KeyFormat::Identity,
+            Self::FairPlay => KeyFormat::FairPlay,
+            Self::Widevine => KeyFormat::Widevine,
+            Self::PlayReady => KeyFormat::PlayReady,
+            Self::Uuid(value) => KeyFormat::Uuid(intern(value)),
+            Self::Other(value) => KeyFormat::Other(intern(value)),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for KeyFormat<'a> {
+    fn from(input: &'a str) -> Self {
+        let input = unquote(input);
+
+        match input.as_ref() {
+            "identity" => Self::Identity,
+            "com.apple.streamingkeydelivery" => Self::FairPlay,
+            "com.widevine.alpha" => Self::Widevine,
+            "com.microsoft.playready" => Self::PlayReady,
+            value if value.starts_with(Self::URN_UUID) => {
+                match input {
+                    Cow::Borrowed(value) => {
+                        Self::Uuid(Cow::Borrowed(&value[Self::URN_UUID.len()..]))
+                    }
+                    Cow::Owned(value) => {
+                        Self::Uuid(Cow::Owned(value[Self::URN_UUID.len()..].into()))
+                    }
+                }
+            }
+            _ => Self::Other(input),
+        }
     }
 }
 
-impl fmt::Display for KeyFormat {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", quote("identity")) }
+impl<'a> fmt::Display for KeyFormat<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Identity => write!(f, "{}", quote("identity")),
+            Self::FairPlay => write!(f, "{}", quote("com.apple.streamingkeydelivery")),
+            Self::Widevine => write!(f, "{}", quote("com.widevine.alpha")),
+            Self::PlayReady => write!(f, "{}", quote("com.microsoft.playready")),
+            Self::Uuid(value) => write!(f, "{}", quote(format!("{}{}", Self::URN_UUID, value))),
+            Self::Other(value) => write!(f, "{}", quote(value)),
+        }
+    }
 }
 
 /// This tag requires [`ProtocolVersion::V5`].
-impl RequiredVersion for KeyFormat {
+impl<'a> RequiredVersion for KeyFormat<'a> {
     fn required_version(&self) -> ProtocolVersion { ProtocolVersion::V5 }
 }
 
@@ -49,15 +197,78 @@ mod tests {
     #[test]
     fn test_display() {
         assert_eq!(KeyFormat::Identity.to_string(), quote("identity"));
+        assert_eq!(
+            KeyFormat::FairPlay.to_string(),
+            quote("com.apple.streamingkeydelivery")
+        );
+        assert_eq!(KeyFormat::Widevine.to_string(), quote("com.widevine.alpha"));
+        assert_eq!(
+            KeyFormat::PlayReady.to_string(),
+            quote("com.microsoft.playready")
+        );
+        assert_eq!(
+            KeyFormat::Uuid(KeyFormat::PLAYREADY_UUID.into()).to_string(),
+            quote("urn:uuid:9a04f079-9840-4286-ab92-e65be0885f95")
+        );
+        assert_eq!(
+            KeyFormat::Other("com.example.drm".into()).to_string(),
+            quote("com.example.drm")
+        );
     }
 
     #[test]
     fn test_parser() {
-        assert_eq!(KeyFormat::Identity, quote("identity").parse().unwrap());
+        assert_eq!(KeyFormat::Identity, KeyFormat::from("\"identity\""));
+        assert_eq!(KeyFormat::Identity, KeyFormat::from("identity"));
+        assert_eq!(
+            KeyFormat::FairPlay,
+            KeyFormat::from("\"com.apple.streamingkeydelivery\"")
+        );
+        assert_eq!(KeyFormat::Widevine, KeyFormat::from("com.widevine.alpha"));
+        assert_eq!(
+            KeyFormat::PlayReady,
+            KeyFormat::from("com.microsoft.playready")
+        );
+        assert_eq!(
+            KeyFormat::Uuid(KeyFormat::WIDEVINE_UUID.into()),
+            KeyFormat::from("\"urn:uuid:edef8ba9-79d6-4ace-a3c8-27dcd51d21ed\"")
+        );
+        assert_eq!(
+            KeyFormat::Other("garbage".into()),
+            KeyFormat::from("garbage")
+        );
+        // the values are case-sensitive:
+        assert_eq!(
+            KeyFormat::Other("IDENTITY".into()),
+            KeyFormat::from("IDENTITY")
+        );
+    }
 
-        assert_eq!(KeyFormat::Identity, "identity".parse().unwrap());
+    #[test]
+    fn test_predicates() {
+        assert!(KeyFormat::Identity.is_identity());
+        assert!(!KeyFormat::FairPlay.is_identity());
+
+        assert!(KeyFormat::FairPlay.is_fairplay());
+        assert!(KeyFormat::Uuid(KeyFormat::FAIRPLAY_UUID.into()).is_fairplay());
+
+        assert!(KeyFormat::Widevine.is_widevine());
+        assert!(KeyFormat::Uuid(KeyFormat::WIDEVINE_UUID.into()).is_widevine());
+        assert!(!KeyFormat::Uuid(KeyFormat::WIDEVINE_UUID.into()).is_playready());
+
+        assert!(KeyFormat::PlayReady.is_playready());
+        assert!(KeyFormat::Uuid("9A04F079-9840-4286-AB92-E65BE0885F95".into()).is_playready());
+
+        assert!(!KeyFormat::Other("com.example.drm".into()).is_fairplay());
+    }
+
+    #[test]
+    fn test_into_owned() {
+        let input = String::from("urn:uuid:1077efec-c0b2-4d02-ace3-3c1e52e2fb4b");
+        let format = KeyFormat::from(input.as_str()).into_owned();
+        drop(input);
 
-        assert!("garbage".parse::<KeyFormat>().is_err());
+        assert_eq!(format.uuid(), Some("1077efec-c0b2-4d02-ace3-3c1e52e2fb4b"));
     }
 
     #[test]
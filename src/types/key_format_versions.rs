@@ -235,7 +235,7 @@ impl PartialEq for KeyFormatVersions {
     fn eq(&self, other: &Self) -> bool {
         if self.len() == other.len() {
             // only compare the parts in the buffer, that are used:
-            self.as_ref() == self.as_ref()
+            self.as_ref() == other.as_ref()
         } else {
             false
         }
@@ -265,12 +265,14 @@ impl Hash for KeyFormatVersions {
 
 impl AsRef<[u8]> for KeyFormatVersions {
     #[inline]
+    #[allow(unused_attributes)]
     #[must_use]
     fn as_ref(&self) -> &[u8] { &self.buffer[..self.len()] }
 }
 
 impl AsMut<[u8]> for KeyFormatVersions {
     #[inline]
+    #[allow(unused_attributes)]
     #[must_use]
     fn as_mut(&mut self) -> &mut [u8] {
         // this temporary variable is required, because the compiler does not resolve
@@ -368,7 +370,7 @@ impl FromStr for KeyFormatVersions {
             let item = item?;
 
             if result.remaining() == 0 {
-                return Err(Error::custom(
+                return Err(Error::invalid_attribute(
                     "reached maximum number of elements in KeyFormatVersions",
                 ));
             }
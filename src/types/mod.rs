@@ -3,8 +3,11 @@ pub(crate) mod byte_range;
 pub(crate) mod channels;
 pub(crate) mod closed_captions;
 pub(crate) mod codecs;
+pub(crate) mod content_kind;
+pub(crate) mod cue;
 pub(crate) mod decryption_key;
 pub(crate) mod encryption_method;
+pub(crate) mod feature;
 pub(crate) mod hdcp_level;
 pub(crate) mod in_stream_id;
 pub(crate) mod initialization_vector;
@@ -12,10 +15,15 @@ pub(crate) mod key_format;
 pub(crate) mod key_format_versions;
 pub(crate) mod media_type;
 pub(crate) mod playlist_type;
+pub(crate) mod preload_hint_type;
 pub(crate) mod protocol_version;
 pub(crate) mod resolution;
+pub(crate) mod session_data_format;
 pub(crate) mod stream_data;
+pub(crate) mod uri_ref;
 pub(crate) mod value;
+pub(crate) mod video_layout;
+pub(crate) mod video_range;
 
 pub(crate) mod float;
 pub(crate) mod ufloat;
@@ -24,8 +32,11 @@ pub use byte_range::*;
 pub use channels::*;
 pub use closed_captions::*;
 pub use codecs::*;
+pub use content_kind::*;
+pub use cue::*;
 pub use decryption_key::DecryptionKey;
 pub use encryption_method::*;
+pub use feature::Feature;
 pub use hdcp_level::*;
 pub use in_stream_id::*;
 pub use initialization_vector::*;
@@ -33,10 +44,15 @@ pub use key_format::*;
 pub use key_format_versions::*;
 pub use media_type::*;
 pub use playlist_type::*;
+pub use preload_hint_type::*;
 pub use protocol_version::*;
 pub use resolution::*;
+pub use session_data_format::SessionDataFormat;
 pub use stream_data::StreamData;
+pub use uri_ref::UriRef;
 pub use value::*;
+pub use video_layout::{VideoLayout, VideoLayoutEntry};
+pub use video_range::VideoRange;
 
 pub use float::Float;
 pub use ufloat::UFloat;
@@ -51,7 +51,12 @@ impl TryFrom<&str> for PlaylistType {
         match input {
             "EVENT" => Ok(Self::Event),
             "VOD" => Ok(Self::Vod),
-            _ => Err(Error::custom(format!("unknown playlist type: {:?}", input))),
+            _ => {
+                Err(Error::invalid_attribute(format!(
+                    "unknown playlist type: {:?}",
+                    input
+                )))
+            }
         }
     }
 }
@@ -0,0 +1,33 @@
+use strum::{Display, EnumString};
+
+/// The type of the resource, that is announced by an [`ExtXPreloadHint`].
+///
+/// [`ExtXPreloadHint`]: crate::tags::ExtXPreloadHint
+#[non_exhaustive]
+#[derive(Ord, PartialOrd, Display, EnumString, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[strum(serialize_all = "SCREAMING-KEBAB-CASE")]
+pub enum PreloadHintType {
+    /// The resource is a partial segment.
+    Part,
+    /// The resource is a media initialization section.
+    Map,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parser() {
+        assert_eq!(PreloadHintType::Part, "PART".parse().unwrap());
+        assert_eq!(PreloadHintType::Map, "MAP".parse().unwrap());
+        assert!("SEGMENT".parse::<PreloadHintType>().is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(PreloadHintType::Part.to_string(), "PART".to_string());
+        assert_eq!(PreloadHintType::Map.to_string(), "MAP".to_string());
+    }
+}
@@ -1,6 +1,7 @@
 use std::fmt;
 use std::str::FromStr;
 
+use crate::types::Feature;
 use crate::Error;
 
 /// The [`ProtocolVersion`] specifies which `m3u8` revision is required, to
@@ -8,6 +9,7 @@ use crate::Error;
 #[non_exhaustive]
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ProtocolVersion {
     V1,
     V2,
@@ -16,6 +18,11 @@ pub enum ProtocolVersion {
     V5,
     V6,
     V7,
+    V8,
+    V9,
+    V10,
+    V11,
+    V12,
 }
 
 impl ProtocolVersion {
@@ -26,11 +33,49 @@ impl ProtocolVersion {
     ///
     /// ```
     /// # use hls_m3u8::types::ProtocolVersion;
-    /// assert_eq!(ProtocolVersion::latest(), ProtocolVersion::V7);
+    /// assert_eq!(ProtocolVersion::latest(), ProtocolVersion::V12);
     /// ```
     #[must_use]
     #[inline]
-    pub const fn latest() -> Self { Self::V7 }
+    pub const fn latest() -> Self { Self::V12 }
+
+    /// Returns `true`, if the [`Feature`] can be used with this version.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::ProtocolVersion;
+    /// use hls_m3u8::types::Feature;
+    ///
+    /// assert!(ProtocolVersion::V5.supports(Feature::KeyFormat));
+    /// assert!(!ProtocolVersion::V5.supports(Feature::Map));
+    /// ```
+    #[must_use]
+    pub fn supports(self, feature: Feature) -> bool { feature.required_version() <= self }
+
+    /// Returns all [`Feature`]s, that can be used with this version.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::ProtocolVersion;
+    /// use hls_m3u8::types::Feature;
+    ///
+    /// assert_eq!(ProtocolVersion::V1.features(), vec![]);
+    /// assert_eq!(
+    ///     ProtocolVersion::V3.features(),
+    ///     vec![Feature::Iv, Feature::DecimalDuration]
+    /// );
+    /// assert_eq!(ProtocolVersion::V12.features(), Feature::ALL);
+    /// ```
+    #[must_use]
+    pub fn features(self) -> Vec<Feature> {
+        Feature::ALL
+            .iter()
+            .copied()
+            .filter(|feature| self.supports(*feature))
+            .collect()
+    }
 }
 
 impl fmt::Display for ProtocolVersion {
@@ -43,6 +88,11 @@ impl fmt::Display for ProtocolVersion {
             Self::V5 => write!(f, "5"),
             Self::V6 => write!(f, "6"),
             Self::V7 => write!(f, "7"),
+            Self::V8 => write!(f, "8"),
+            Self::V9 => write!(f, "9"),
+            Self::V10 => write!(f, "10"),
+            Self::V11 => write!(f, "11"),
+            Self::V12 => write!(f, "12"),
         }
     }
 }
@@ -60,6 +110,11 @@ impl FromStr for ProtocolVersion {
                 "5" => Self::V5,
                 "6" => Self::V6,
                 "7" => Self::V7,
+                "8" => Self::V8,
+                "9" => Self::V9,
+                "10" => Self::V10,
+                "11" => Self::V11,
+                "12" => Self::V12,
                 _ => return Err(Error::unknown_protocol_version(input)),
             }
         })
@@ -85,6 +140,7 @@ mod tests {
         assert_eq!(ProtocolVersion::V5.to_string(), "5".to_string());
         assert_eq!(ProtocolVersion::V6.to_string(), "6".to_string());
         assert_eq!(ProtocolVersion::V7.to_string(), "7".to_string());
+        assert_eq!(ProtocolVersion::V12.to_string(), "12".to_string());
     }
 
     #[test]
@@ -96,9 +152,11 @@ mod tests {
         assert_eq!(ProtocolVersion::V5, "5".parse().unwrap());
         assert_eq!(ProtocolVersion::V6, "6".parse().unwrap());
         assert_eq!(ProtocolVersion::V7, "7".parse().unwrap());
+        assert_eq!(ProtocolVersion::V12, "12".parse().unwrap());
 
         assert_eq!(ProtocolVersion::V7, " 7 ".parse().unwrap());
         assert!("garbage".parse::<ProtocolVersion>().is_err());
+        assert!("13".parse::<ProtocolVersion>().is_err());
     }
 
     #[test]
@@ -108,6 +166,6 @@ mod tests {
 
     #[test]
     fn test_latest() {
-        assert_eq!(ProtocolVersion::latest(), ProtocolVersion::V7);
+        assert_eq!(ProtocolVersion::latest(), ProtocolVersion::V12);
     }
 }
@@ -0,0 +1,39 @@
+use strum::{Display, EnumString};
+
+/// The format of the resource, that is referenced by the `URI` of an
+/// [`ExtXSessionData`].
+///
+/// [`ExtXSessionData`]: crate::tags::ExtXSessionData
+#[non_exhaustive]
+#[derive(
+    Ord, PartialOrd, Display, EnumString, Debug, Clone, Copy, PartialEq, Eq, Hash, Default,
+)]
+#[strum(serialize_all = "SCREAMING-KEBAB-CASE")]
+pub enum SessionDataFormat {
+    /// The resource is a [json] file. This is the default.
+    ///
+    /// [json]: https://tools.ietf.org/html/rfc8259
+    #[default]
+    Json,
+    /// The resource is a binary file, that is stored as it is.
+    Raw,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parser() {
+        assert_eq!(SessionDataFormat::Json, "JSON".parse().unwrap());
+        assert_eq!(SessionDataFormat::Raw, "RAW".parse().unwrap());
+        assert!("XML".parse::<SessionDataFormat>().is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(SessionDataFormat::Json.to_string(), "JSON".to_string());
+        assert_eq!(SessionDataFormat::Raw.to_string(), "RAW".to_string());
+    }
+}
@@ -6,9 +6,11 @@ use derive_builder::Builder;
 use shorthand::ShortHand;
 
 use crate::attribute::AttributePairs;
-use crate::types::{Codecs, HdcpLevel, ProtocolVersion, Resolution};
-use crate::utils::{quote, unquote};
-use crate::{Error, RequiredVersion};
+use crate::types::{
+    Codecs, HdcpLevel, ProtocolVersion, Resolution, UFloat, VideoLayout, VideoRange,
+};
+use crate::utils::{intern, quote, unquote};
+use crate::{Error, RequiredVersion, VersionReason};
 
 /// The [`StreamData`] struct contains the data that is shared between both
 /// variants of the [`VariantStream`].
@@ -94,6 +96,32 @@ pub struct StreamData<'a> {
     #[builder(default)]
     #[shorthand(enable(copy), disable(into, option_as_ref))]
     average_bandwidth: Option<u64>,
+    /// The relative preference of the [`VariantStream`] compared to the other
+    /// variants of the [`MasterPlaylist`]. A variant with a higher score is
+    /// considered to be better than a variant with a lower score.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::StreamData;
+    /// use hls_m3u8::types::UFloat;
+    /// #
+    /// let mut stream = StreamData::new(20);
+    ///
+    /// stream.set_score(Some(UFloat::new(1.5)));
+    /// assert_eq!(stream.score(), Some(UFloat::new(1.5)));
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// This field is optional, but if one variant has a score, all other
+    /// variants should have one as well.
+    ///
+    /// [`MasterPlaylist`]: crate::MasterPlaylist
+    /// [`VariantStream`]: crate::tags::VariantStream
+    #[builder(default, setter(into))]
+    #[shorthand(enable(copy))]
+    score: Option<UFloat>,
     /// A list of formats, where each format specifies a media sample type that
     /// is present in one or more renditions specified by the [`VariantStream`].
     ///
@@ -183,6 +211,54 @@ pub struct StreamData<'a> {
     #[builder(default)]
     #[shorthand(enable(copy), disable(into))]
     hdcp_level: Option<HdcpLevel>,
+    /// The dynamic range of the video in the [`VariantStream`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::StreamData;
+    /// use hls_m3u8::types::VideoRange;
+    /// #
+    /// let mut stream = StreamData::new(20);
+    ///
+    /// stream.set_video_range(Some(VideoRange::Pq));
+    /// assert_eq!(stream.video_range(), Some(VideoRange::Pq));
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// This field is optional and a missing value indicates
+    /// [`VideoRange::Sdr`].
+    ///
+    /// [`VariantStream`]: crate::tags::VariantStream
+    #[builder(default)]
+    #[shorthand(enable(copy), disable(into))]
+    video_range: Option<VideoRange>,
+    /// The layout of the video in the [`VariantStream`], which a client must
+    /// support to play the stream.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::StreamData;
+    /// use hls_m3u8::types::{VideoLayout, VideoLayoutEntry};
+    /// #
+    /// let mut stream = StreamData::new(20);
+    ///
+    /// stream.set_req_video_layout(Some(VideoLayout::from(vec![VideoLayoutEntry::ChStereo])));
+    /// assert_eq!(
+    ///     stream.req_video_layout(),
+    ///     Some(&VideoLayout::from(vec![VideoLayoutEntry::ChStereo]))
+    /// );
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// This field is optional and requires [`ProtocolVersion::V12`].
+    ///
+    /// [`VariantStream`]: crate::tags::VariantStream
+    #[builder(default, setter(into))]
+    req_video_layout: Option<VideoLayout>,
     /// It indicates the set of video renditions, that should be used when
     /// playing the presentation.
     ///
@@ -212,6 +288,49 @@ pub struct StreamData<'a> {
     /// [`ExtXMedia::media_type`]: crate::tags::ExtXMedia::media_type
     #[builder(default, setter(into))]
     video: Option<Cow<'a, str>>,
+    /// The legacy `PROGRAM-ID` attribute, which identified a particular
+    /// presentation within the scope of the playlist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::StreamData;
+    /// #
+    /// let mut stream = StreamData::new(20);
+    ///
+    /// stream.set_program_id(Some(1));
+    /// assert_eq!(stream.program_id(), Some(1));
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// This field is optional. The attribute has been removed in
+    /// [`ProtocolVersion::V6`], but many old encoders still write it, so it is
+    /// kept to write the playlist like it has been read.
+    #[builder(default)]
+    #[shorthand(enable(copy), disable(into))]
+    program_id: Option<u64>,
+    /// The non-standard `NAME` attribute, which some encoders use to give the
+    /// [`VariantStream`] a human-readable name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::StreamData;
+    /// #
+    /// let mut stream = StreamData::new(20);
+    ///
+    /// stream.set_name(Some("720p"));
+    /// assert_eq!(stream.name(), Some(&"720p".into()));
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// This field is optional.
+    ///
+    /// [`VariantStream`]: crate::tags::VariantStream
+    #[builder(default, setter(into))]
+    name: Option<Cow<'a, str>>,
 }
 
 impl<'a> StreamData<'a> {
@@ -229,10 +348,15 @@ impl<'a> StreamData<'a> {
         Self {
             bandwidth,
             average_bandwidth: None,
+            score: None,
             codecs: None,
             resolution: None,
             hdcp_level: None,
+            video_range: None,
+            req_video_layout: None,
             video: None,
+            program_id: None,
+            name: None,
         }
     }
 
@@ -267,21 +391,34 @@ impl<'a> StreamData<'a> {
         StreamData {
             bandwidth: self.bandwidth,
             average_bandwidth: self.average_bandwidth,
+            score: self.score,
             codecs: self.codecs.map(Codecs::into_owned),
             resolution: self.resolution,
             hdcp_level: self.hdcp_level,
-            video: self.video.map(|v| Cow::Owned(v.into_owned())),
+            video_range: self.video_range,
+            req_video_layout: self.req_video_layout,
+            video: self.video.map(intern),
+            program_id: self.program_id,
+            name: self.name.map(|value| Cow::Owned(value.into_owned())),
         }
     }
 }
 
 impl<'a> fmt::Display for StreamData<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // old encoders write the PROGRAM-ID in front of the BANDWIDTH:
+        if let Some(value) = &self.program_id {
+            write!(f, "PROGRAM-ID={},", value)?;
+        }
+
         write!(f, "BANDWIDTH={}", self.bandwidth)?;
 
         if let Some(value) = &self.average_bandwidth {
             write!(f, ",AVERAGE-BANDWIDTH={}", value)?;
         }
+        if let Some(value) = &self.score {
+            write!(f, ",SCORE={}", value)?;
+        }
         if let Some(value) = &self.codecs {
             write!(f, ",CODECS={}", quote(value))?;
         }
@@ -291,9 +428,18 @@ impl<'a> fmt::Display for StreamData<'a> {
         if let Some(value) = &self.hdcp_level {
             write!(f, ",HDCP-LEVEL={}", value)?;
         }
+        if let Some(value) = &self.video_range {
+            write!(f, ",VIDEO-RANGE={}", value)?;
+        }
+        if let Some(value) = &self.req_video_layout {
+            write!(f, ",REQ-VIDEO-LAYOUT={}", quote(value))?;
+        }
         if let Some(value) = &self.video {
             write!(f, ",VIDEO={}", quote(value))?;
         }
+        if let Some(value) = &self.name {
+            write!(f, ",NAME={}", quote(value))?;
+        }
         Ok(())
     }
 }
@@ -304,10 +450,15 @@ impl<'a> TryFrom<&'a str> for StreamData<'a> {
     fn try_from(input: &'a str) -> Result<Self, Self::Error> {
         let mut bandwidth = None;
         let mut average_bandwidth = None;
+        let mut score = None;
         let mut codecs = None;
         let mut resolution = None;
         let mut hdcp_level = None;
+        let mut video_range = None;
+        let mut req_video_layout = None;
         let mut video = None;
+        let mut program_id = None;
+        let mut name = None;
 
         for (key, value) in AttributePairs::new(input) {
             match key {
@@ -325,12 +476,25 @@ impl<'a> TryFrom<&'a str> for StreamData<'a> {
                             .map_err(|e| Error::parse_int(value, e))?,
                     );
                 }
+                "SCORE" => score = Some(value.parse()?),
                 "CODECS" => codecs = Some(TryFrom::try_from(unquote(value))?),
                 "RESOLUTION" => resolution = Some(value.parse()?),
                 "HDCP-LEVEL" => {
                     hdcp_level = Some(value.parse::<HdcpLevel>().map_err(Error::strum)?);
                 }
+                "VIDEO-RANGE" => {
+                    video_range = Some(value.parse::<VideoRange>().map_err(Error::strum)?);
+                }
+                "REQ-VIDEO-LAYOUT" => req_video_layout = Some(unquote(value).parse()?),
                 "VIDEO" => video = Some(unquote(value)),
+                "PROGRAM-ID" => {
+                    program_id = Some(
+                        value
+                            .parse::<u64>()
+                            .map_err(|e| Error::parse_int(value, e))?,
+                    );
+                }
+                "NAME" => name = Some(unquote(value)),
                 _ => {
                     // [6.3.1. General Client Responsibilities]
                     // > ignore any attribute/value pair with an unrecognized
@@ -344,39 +508,54 @@ impl<'a> TryFrom<&'a str> for StreamData<'a> {
         Ok(Self {
             bandwidth,
             average_bandwidth,
+            score,
             codecs,
             resolution,
             hdcp_level,
+            video_range,
+            req_video_layout,
             video,
+            program_id,
+            name,
         })
     }
 }
 
-/// This struct requires [`ProtocolVersion::V1`].
+/// This struct requires [`ProtocolVersion::V1`] or [`ProtocolVersion::V12`],
+/// if it has a [`StreamData::req_video_layout`].
 impl<'a> RequiredVersion for StreamData<'a> {
-    fn required_version(&self) -> ProtocolVersion { ProtocolVersion::V1 }
+    fn required_version(&self) -> ProtocolVersion { self.req_video_layout.required_version() }
 
     fn introduced_version(&self) -> ProtocolVersion {
-        if self.video.is_some() {
+        if self.req_video_layout.is_some() {
+            self.req_video_layout.required_version()
+        } else if self.video.is_some() {
             ProtocolVersion::V4
         } else {
             ProtocolVersion::V1
         }
     }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        self.req_video_layout.required_version_reasons()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::{Feature, VideoLayoutEntry};
     use pretty_assertions::assert_eq;
 
     #[test]
     fn test_display() {
         let mut stream_data = StreamData::new(200);
         stream_data.set_average_bandwidth(Some(15));
+        stream_data.set_score(Some(UFloat::new(1.5)));
         stream_data.set_codecs(Some(&["mp4a.40.2", "avc1.4d401e"]));
         stream_data.set_resolution(Some((1920, 1080)));
         stream_data.set_hdcp_level(Some(HdcpLevel::Type0));
+        stream_data.set_video_range(Some(VideoRange::Hlg));
         stream_data.set_video(Some("video"));
 
         assert_eq!(
@@ -384,9 +563,11 @@ mod tests {
             concat!(
                 "BANDWIDTH=200,",
                 "AVERAGE-BANDWIDTH=15,",
+                "SCORE=1.5,",
                 "CODECS=\"mp4a.40.2,avc1.4d401e\",",
                 "RESOLUTION=1920x1080,",
                 "HDCP-LEVEL=TYPE-0,",
+                "VIDEO-RANGE=HLG,",
                 "VIDEO=\"video\""
             )
             .to_string()
@@ -397,9 +578,11 @@ mod tests {
     fn test_parser() {
         let mut stream_data = StreamData::new(200);
         stream_data.set_average_bandwidth(Some(15));
+        stream_data.set_score(Some(UFloat::new(1.5)));
         stream_data.set_codecs(Some(&["mp4a.40.2", "avc1.4d401e"]));
         stream_data.set_resolution(Some((1920, 1080)));
         stream_data.set_hdcp_level(Some(HdcpLevel::Type0));
+        stream_data.set_video_range(Some(VideoRange::Hlg));
         stream_data.set_video(Some("video"));
 
         assert_eq!(
@@ -407,14 +590,63 @@ mod tests {
             StreamData::try_from(concat!(
                 "BANDWIDTH=200,",
                 "AVERAGE-BANDWIDTH=15,",
+                "SCORE=1.5,",
                 "CODECS=\"mp4a.40.2,avc1.4d401e\",",
                 "RESOLUTION=1920x1080,",
                 "HDCP-LEVEL=TYPE-0,",
+                "VIDEO-RANGE=HLG,",
                 "VIDEO=\"video\""
             ))
             .unwrap()
         );
 
         assert!(StreamData::try_from("garbage").is_err());
+        assert!(StreamData::try_from("BANDWIDTH=200,SCORE=-1").is_err());
+    }
+
+    #[test]
+    fn test_legacy_attributes() {
+        let mut stream_data = StreamData::new(200);
+        stream_data.set_program_id(Some(1));
+        stream_data.set_name(Some("720p"));
+
+        assert_eq!(
+            stream_data.to_string(),
+            "PROGRAM-ID=1,BANDWIDTH=200,NAME=\"720p\"".to_string()
+        );
+        assert_eq!(
+            stream_data,
+            StreamData::try_from("PROGRAM-ID=1,BANDWIDTH=200,NAME=\"720p\"").unwrap()
+        );
+        assert_eq!(stream_data.required_version(), ProtocolVersion::V1);
+
+        assert!(StreamData::try_from("PROGRAM-ID=one,BANDWIDTH=200").is_err());
+    }
+
+    #[test]
+    fn test_req_video_layout() {
+        let mut stream_data = StreamData::new(200);
+        stream_data.set_req_video_layout(Some(VideoLayout::from(vec![
+            VideoLayoutEntry::ChStereo,
+            VideoLayoutEntry::ChMono,
+        ])));
+
+        assert_eq!(
+            stream_data.to_string(),
+            "BANDWIDTH=200,REQ-VIDEO-LAYOUT=\"CH-STEREO,CH-MONO\"".to_string()
+        );
+        assert_eq!(
+            stream_data,
+            StreamData::try_from("BANDWIDTH=200,REQ-VIDEO-LAYOUT=\"CH-STEREO,CH-MONO\"").unwrap()
+        );
+
+        assert_eq!(stream_data.required_version(), ProtocolVersion::V12);
+        assert_eq!(
+            stream_data.required_version_reasons(),
+            vec![VersionReason::from(Feature::ReqVideoLayout)]
+        );
+        assert_eq!(StreamData::new(200).required_version(), ProtocolVersion::V1);
+
+        assert!(StreamData::try_from("BANDWIDTH=200,REQ-VIDEO-LAYOUT=\"CH-TRIPLE\"").is_err());
     }
 }
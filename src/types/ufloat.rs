@@ -81,15 +81,18 @@ impl TryFrom<f32> for UFloat {
 
     fn try_from(float: f32) -> Result<Self, Self::Error> {
         if float.is_infinite() {
-            return Err(Error::custom(format!("float must be finite: `{}`", float)));
+            return Err(Error::invalid_attribute(format!(
+                "float must be finite: `{}`",
+                float
+            )));
         }
 
         if float.is_nan() {
-            return Err(Error::custom("float must not be `NaN`"));
+            return Err(Error::invalid_attribute("float must not be `NaN`"));
         }
 
         if float.is_sign_negative() {
-            return Err(Error::custom(format!(
+            return Err(Error::invalid_attribute(format!(
                 "float must be positive: `{}`",
                 float
             )));
@@ -304,7 +307,7 @@ mod tests {
 
         assert_eq!(
             UFloat::try_from(-1.1_f32),
-            Err(Error::custom("float must be positive: `-1.1`"))
+            Err(Error::invalid_attribute("float must be positive: `-1.1`"))
         );
         assert!(UFloat::try_from(f32::INFINITY).is_err());
         assert!(UFloat::try_from(f32::NAN).is_err());
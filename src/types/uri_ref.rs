@@ -0,0 +1,284 @@
+use core::fmt;
+use core::ops::Deref;
+use std::borrow::Cow;
+
+/// The uri of a [`MediaSegment`], an [`ExtXMap`] or a [`DecryptionKey`].
+///
+/// This is a light wrapper around a string, that allows to inspect and modify
+/// the query of the uri without parsing it completely. It dereferences to
+/// [`str`], so it can be used like a string.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::types::UriRef;
+///
+/// let uri = UriRef::from("https://cdn.example.com/1.ts?cdn=a");
+///
+/// assert_eq!(uri.path(), "https://cdn.example.com/1.ts");
+/// assert_eq!(uri.query_pairs().collect::<Vec<_>>(), vec![("cdn", "a")]);
+///
+/// let uri = uri
+///     .with_query_param("cdn", "b")
+///     .with_query_param("token", "x y");
+/// assert_eq!(uri, "https://cdn.example.com/1.ts?cdn=b&token=x%20y");
+/// ```
+///
+/// [`MediaSegment`]: crate::MediaSegment
+/// [`ExtXMap`]: crate::tags::ExtXMap
+/// [`DecryptionKey`]: crate::types::DecryptionKey
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UriRef<'a>(Cow<'a, str>);
+
+impl<'a> UriRef<'a> {
+    /// Returns the uri as a string slice.
+    #[must_use]
+    pub fn as_str(&self) -> &str { &self.0 }
+
+    /// Returns everything in front of the query and the fragment.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::UriRef;
+    /// assert_eq!(UriRef::from("/video/1.ts?a=b#c").path(), "/video/1.ts");
+    /// assert_eq!(UriRef::from("1.ts").path(), "1.ts");
+    /// ```
+    #[must_use]
+    pub fn path(&self) -> &str {
+        let end = self.0.find(['?', '#']).unwrap_or(self.0.len());
+        &self.0[..end]
+    }
+
+    /// Returns the query (without the leading `?`), if there is one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::UriRef;
+    /// assert_eq!(UriRef::from("1.ts?a=b#c").query(), Some("a=b"));
+    /// assert_eq!(UriRef::from("1.ts#c?d").query(), None);
+    /// ```
+    #[must_use]
+    pub fn query(&self) -> Option<&str> {
+        let rest = self.0[self.path().len()..].strip_prefix('?')?;
+        let end = rest.find('#').unwrap_or(rest.len());
+
+        Some(&rest[..end])
+    }
+
+    /// Returns an iterator over the `key=value` pairs of the query.
+    ///
+    /// The keys and values are not percent-decoded. A pair without a `=` has
+    /// an empty value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::UriRef;
+    /// let uri = UriRef::from("1.ts?a=1&b&c=x%20y");
+    ///
+    /// assert_eq!(
+    ///     uri.query_pairs().collect::<Vec<_>>(),
+    ///     vec![("a", "1"), ("b", ""), ("c", "x%20y")]
+    /// );
+    /// ```
+    pub fn query_pairs(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.query()
+            .unwrap_or("")
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let mut pair = pair.splitn(2, '=');
+                (pair.next().unwrap_or(""), pair.next().unwrap_or(""))
+            })
+    }
+
+    /// Sets the query parameter `key` to `value`.
+    ///
+    /// If the parameter already exists, its first occurrence is replaced and
+    /// all other occurrences are removed, otherwise the parameter is appended
+    /// to the query. The fragment is preserved. The `key` and the `value` are
+    /// percent-encoded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::UriRef;
+    /// assert_eq!(
+    ///     UriRef::from("1.ts?token=old&a=1#t=10").with_query_param("token", "new"),
+    ///     "1.ts?token=new&a=1#t=10"
+    /// );
+    ///
+    /// assert_eq!(
+    ///     UriRef::from("1.ts").with_query_param("a&b", "="),
+    ///     "1.ts?a%26b=%3D"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_query_param(self, key: &str, value: &str) -> UriRef<'static> {
+        let key = encode(key);
+        let value = encode(value);
+
+        let mut pairs = Vec::new();
+        let mut is_replaced = false;
+
+        for pair in self.query().unwrap_or("").split('&') {
+            if pair.is_empty() {
+                continue;
+            }
+
+            if pair.split('=').next() == Some(key.as_str()) {
+                if !is_replaced {
+                    pairs.push(format!("{}={}", key, value));
+                    is_replaced = true;
+                }
+            } else {
+                pairs.push(pair.to_string());
+            }
+        }
+
+        if !is_replaced {
+            pairs.push(format!("{}={}", key, value));
+        }
+
+        let fragment = self.0.find('#').map_or("", |i| &self.0[i..]);
+
+        UriRef(Cow::Owned(format!(
+            "{}?{}{}",
+            self.path(),
+            pairs.join("&"),
+            fragment
+        )))
+    }
+
+    /// Makes the struct independent of its lifetime, by taking ownership of all
+    /// internal [`Cow`]s.
+    ///
+    /// # Note
+    ///
+    /// This is a relatively expensive operation.
+    #[must_use]
+    pub fn into_owned(self) -> UriRef<'static> { UriRef(Cow::Owned(self.0.into_owned())) }
+}
+
+/// Percent-encodes everything except the unreserved characters of
+/// [rfc3986#section-2.3](https://tools.ietf.org/html/rfc3986#section-2.3).
+fn encode(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            result.push(char::from(byte));
+        } else {
+            result.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    result
+}
+
+impl<'a> Deref for UriRef<'a> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target { &self.0 }
+}
+
+impl<'a> AsRef<str> for UriRef<'a> {
+    fn as_ref(&self) -> &str { &self.0 }
+}
+
+impl<'a> fmt::Display for UriRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { self.0.fmt(f) }
+}
+
+impl<'a> From<&'a str> for UriRef<'a> {
+    fn from(value: &'a str) -> Self { Self(Cow::Borrowed(value)) }
+}
+
+impl<'a> From<&'a String> for UriRef<'a> {
+    fn from(value: &'a String) -> Self { Self(Cow::Borrowed(value)) }
+}
+
+impl<'a> From<String> for UriRef<'a> {
+    fn from(value: String) -> Self { Self(Cow::Owned(value)) }
+}
+
+impl<'a> From<Cow<'a, str>> for UriRef<'a> {
+    fn from(value: Cow<'a, str>) -> Self { Self(value) }
+}
+
+impl<'a> From<UriRef<'a>> for Cow<'a, str> {
+    fn from(value: UriRef<'a>) -> Self { value.0 }
+}
+
+impl<'a> PartialEq<str> for UriRef<'a> {
+    fn eq(&self, other: &str) -> bool { self.0 == other }
+}
+
+impl<'a, 'b> PartialEq<&'b str> for UriRef<'a> {
+    fn eq(&self, other: &&'b str) -> bool { self.0 == *other }
+}
+
+impl<'a> PartialEq<String> for UriRef<'a> {
+    fn eq(&self, other: &String) -> bool { self.0 == other.as_str() }
+}
+
+impl<'a, 'b> PartialEq<Cow<'b, str>> for UriRef<'a> {
+    fn eq(&self, other: &Cow<'b, str>) -> bool { self.0 == *other }
+}
+
+impl<'a> PartialEq<UriRef<'a>> for str {
+    fn eq(&self, other: &UriRef<'a>) -> bool { self == other.as_str() }
+}
+
+impl<'a> PartialEq<UriRef<'a>> for &str {
+    fn eq(&self, other: &UriRef<'a>) -> bool { *self == other.as_str() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_path_and_query() {
+        let uri = UriRef::from("https://example.com/1.ts?a=1&b=2#c?d=3");
+
+        assert_eq!(uri.path(), "https://example.com/1.ts");
+        assert_eq!(uri.query(), Some("a=1&b=2"));
+        assert_eq!(
+            uri.query_pairs().collect::<Vec<_>>(),
+            vec![("a", "1"), ("b", "2")]
+        );
+
+        let uri = UriRef::from("1.ts?");
+        assert_eq!(uri.query(), Some(""));
+        assert_eq!(uri.query_pairs().count(), 0);
+    }
+
+    #[test]
+    fn test_with_query_param() {
+        assert_eq!(UriRef::from("1.ts?").with_query_param("a", "1"), "1.ts?a=1");
+        assert_eq!(
+            UriRef::from("1.ts?flag&a=1&a=2&b=").with_query_param("a", "3"),
+            "1.ts?flag&a=3&b="
+        );
+        assert_eq!(
+            UriRef::from("1.ts#t").with_query_param("é", "ü/"),
+            "1.ts?%C3%A9=%C3%BC%2F#t"
+        );
+    }
+
+    #[test]
+    fn test_eq() {
+        let uri = UriRef::from("1.ts");
+
+        assert_eq!(uri, "1.ts");
+        assert_eq!("1.ts", uri);
+        assert_eq!(uri, "1.ts".to_string());
+        assert_eq!(uri, Cow::Borrowed("1.ts"));
+        assert_eq!(uri.to_string(), "1.ts");
+        assert_eq!(&*uri.clone().into_owned(), "1.ts");
+    }
+}
@@ -6,19 +6,117 @@ use crate::types::Float;
 use crate::utils::{quote, unquote};
 use crate::Error;
 
-/// A `Value`.
+/// The value of an attribute, that is not known to this crate.
+///
+/// This is used for the [`ExtXDateRange::client_attributes`], where the
+/// attribute name is chosen by the client and the value can be either a
+/// quoted-string, a hexadecimal-sequence or a decimal-floating-point. The same
+/// model is used for the [`SessionData::Value`] and for the unknown attributes
+/// passed to [`ParseOptions::on_ignored`].
+///
+/// # Example
+///
+/// ```
+/// # use hls_m3u8::types::Value;
+/// use core::convert::TryFrom;
+/// use hls_m3u8::types::Float;
+///
+/// assert_eq!(Value::try_from("\"XYZ123\"")?, Value::string("XYZ123"));
+/// assert_eq!(Value::try_from("0x0A0B")?, Value::from(vec![0x0A, 0x0B]));
+/// assert_eq!(Value::try_from("1.5")?, Value::from(Float::new(1.5)));
+///
+/// assert_eq!(
+///     Value::string("XYZ123").to_string(),
+///     "\"XYZ123\"".to_string()
+/// );
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+///
+/// [`ExtXDateRange::client_attributes`]:
+/// crate::tags::ExtXDateRange::client_attributes
+/// [`SessionData::Value`]: crate::tags::SessionData::Value
+/// [`ParseOptions::on_ignored`]: crate::ParseOptions::on_ignored
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
 pub enum Value<'a> {
-    /// A `String`.
+    /// A quoted-string, which will be written with surrounding double quotes.
     String(Cow<'a, str>),
-    /// A sequence of bytes.
+    /// A hexadecimal-sequence, which will be written with a leading `0x`.
     Hex(Vec<u8>),
     /// A floating point number, that's neither NaN nor infinite.
     Float(Float),
 }
 
 impl<'a> Value<'a> {
+    /// Creates a [`Value::String`] from the provided string. Surrounding
+    /// quotes are removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::Value;
+    /// assert_eq!(Value::string("value"), Value::String("value".into()));
+    /// assert_eq!(Value::string("\"value\""), Value::String("value".into()));
+    /// ```
+    #[must_use]
+    pub fn string<T: Into<Cow<'a, str>>>(value: T) -> Self { Self::from(value.into()) }
+
+    /// Returns the contained string, if this is a [`Value::String`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::Value;
+    /// assert_eq!(Value::string("value").as_str(), Some("value"));
+    /// assert_eq!(Value::from(vec![1, 2, 3]).as_str(), None);
+    /// ```
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        if let Self::String(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the contained bytes, if this is a [`Value::Hex`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::Value;
+    /// assert_eq!(Value::from(vec![1, 2, 3]).as_hex(), Some(&[1, 2, 3][..]));
+    /// assert_eq!(Value::string("value").as_hex(), None);
+    /// ```
+    #[must_use]
+    pub fn as_hex(&self) -> Option<&[u8]> {
+        if let Self::Hex(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the contained [`Float`], if this is a [`Value::Float`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::Value;
+    /// use hls_m3u8::types::Float;
+    ///
+    /// assert_eq!(Value::from(1_u8).as_float(), Some(Float::new(1.0)));
+    /// assert_eq!(Value::string("value").as_float(), None);
+    /// ```
+    #[must_use]
+    pub fn as_float(&self) -> Option<Float> {
+        if let Self::Float(value) = self {
+            Some(*value)
+        } else {
+            None
+        }
+    }
+
     /// Makes the struct independent of its lifetime, by taking ownership of all
     /// internal [`Cow`]s.
     ///
@@ -75,6 +173,15 @@ impl From<String> for Value<'static> {
     fn from(value: String) -> Self { Self::String(Cow::Owned(unquote(&value).into_owned())) }
 }
 
+impl<'a> From<Cow<'a, str>> for Value<'a> {
+    fn from(value: Cow<'a, str>) -> Self {
+        match value {
+            Cow::Borrowed(value) => Self::String(unquote(value)),
+            Cow::Owned(value) => Self::String(Cow::Owned(unquote(&value).into_owned())),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,5 +229,21 @@ mod tests {
             Value::String("&str".into())
         );
         assert_eq!(Value::from(vec![1, 2, 3]), Value::Hex(vec![1, 2, 3]));
+        assert_eq!(Value::string("&str"), Value::String("&str".into()));
+        assert_eq!(Value::string("\"&str\""), Value::String("&str".into()));
+        assert_eq!(
+            Value::from(Cow::Borrowed("&str")),
+            Value::String("&str".into())
+        );
+    }
+
+    #[test]
+    fn test_accessors() {
+        assert_eq!(Value::string("&str").as_str(), Some("&str"));
+        assert_eq!(Value::string("&str").as_hex(), None);
+        assert_eq!(Value::string("&str").as_float(), None);
+
+        assert_eq!(Value::from(vec![1, 2]).as_hex(), Some(&[1, 2][..]));
+        assert_eq!(Value::from(1_u8).as_float(), Some(Float::new(1.0)));
     }
 }
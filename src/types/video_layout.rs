@@ -0,0 +1,170 @@
+use core::fmt;
+use core::iter::FromIterator;
+use core::str::FromStr;
+
+use derive_more::{AsMut, AsRef, Deref, DerefMut};
+use strum::{Display, EnumString};
+
+use crate::types::{Feature, ProtocolVersion};
+use crate::{Error, RequiredVersion, VersionReason};
+
+/// A specifier of a [`VideoLayout`], which describes one characteristic of
+/// the video (for example the number of video channels).
+#[non_exhaustive]
+#[derive(Ord, PartialOrd, Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString)]
+#[strum(serialize_all = "SCREAMING-KEBAB-CASE")]
+pub enum VideoLayoutEntry {
+    /// The video has a single channel.
+    ChMono,
+    /// The video has two channels, one for each eye (stereoscopic video).
+    ChStereo,
+    /// The video uses a rectilinear projection.
+    ProjRect,
+    /// The video uses an equirectangular projection (360 degrees).
+    ProjEqui,
+    /// The video uses a half equirectangular projection (180 degrees).
+    ProjHequ,
+    /// The video uses a parametric immersive projection.
+    ProjPrim,
+    /// The video uses the Apple Immersive Video projection.
+    ProjAiv,
+}
+
+/// The layout of the video in a [`VariantStream`], which a client must
+/// understand to play the stream, for example stereoscopic video for spatial
+/// displays.
+///
+/// The entries are ordered by preference, so `CH-STEREO,CH-MONO` indicates,
+/// that the stream contains mostly stereoscopic video, but may contain
+/// monoscopic video as well.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::types::{VideoLayout, VideoLayoutEntry};
+///
+/// let layout = VideoLayout::from(vec![VideoLayoutEntry::ChStereo, VideoLayoutEntry::ChMono]);
+///
+/// assert_eq!(layout.to_string(), "CH-STEREO,CH-MONO");
+/// assert_eq!(layout, "CH-STEREO,CH-MONO".parse()?);
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+///
+/// [`VariantStream`]: crate::tags::VariantStream
+#[derive(
+    AsMut, AsRef, Deref, DerefMut, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default,
+)]
+pub struct VideoLayout {
+    entries: Vec<VideoLayoutEntry>,
+}
+
+impl VideoLayout {
+    /// Makes a new (empty) [`VideoLayout`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::VideoLayout;
+    /// let layout = VideoLayout::new();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl From<Vec<VideoLayoutEntry>> for VideoLayout {
+    fn from(entries: Vec<VideoLayoutEntry>) -> Self { Self { entries } }
+}
+
+impl FromIterator<VideoLayoutEntry> for VideoLayout {
+    fn from_iter<I: IntoIterator<Item = VideoLayoutEntry>>(iter: I) -> Self {
+        Self {
+            entries: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl fmt::Display for VideoLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, entry) in self.entries.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+
+            write!(f, "{}", entry)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for VideoLayout {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        input
+            .split(',')
+            .map(|entry| entry.trim().parse().map_err(Error::strum))
+            .collect()
+    }
+}
+
+/// This type requires [`ProtocolVersion::V12`].
+impl RequiredVersion for VideoLayout {
+    fn required_version(&self) -> ProtocolVersion { Feature::ReqVideoLayout.required_version() }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        vec![VersionReason::from(Feature::ReqVideoLayout)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            VideoLayoutEntry::ChStereo.to_string(),
+            "CH-STEREO".to_string()
+        );
+        assert_eq!(
+            VideoLayoutEntry::ProjAiv.to_string(),
+            "PROJ-AIV".to_string()
+        );
+
+        assert_eq!(
+            VideoLayout::from(vec![VideoLayoutEntry::ChStereo, VideoLayoutEntry::ChMono])
+                .to_string(),
+            "CH-STEREO,CH-MONO".to_string()
+        );
+        assert_eq!(VideoLayout::new().to_string(), "".to_string());
+    }
+
+    #[test]
+    fn test_parser() {
+        assert_eq!(
+            VideoLayout::from(vec![VideoLayoutEntry::ChStereo, VideoLayoutEntry::ChMono]),
+            "CH-STEREO,CH-MONO".parse().unwrap()
+        );
+        assert_eq!(
+            VideoLayout::from(vec![VideoLayoutEntry::ProjEqui]),
+            "PROJ-EQUI".parse().unwrap()
+        );
+
+        assert!("CH-TRIPLE".parse::<VideoLayout>().is_err());
+    }
+
+    #[test]
+    fn test_required_version() {
+        assert_eq!(
+            VideoLayout::from(vec![VideoLayoutEntry::ChMono]).required_version(),
+            ProtocolVersion::V12
+        );
+    }
+}
@@ -53,11 +53,26 @@ macro_rules! required_version {
     }
 }
 
+macro_rules! required_version_reasons {
+    ( $( $tag:expr ),* ) => {
+        ::core::iter::empty()
+            $(
+                .chain($tag.required_version_reasons())
+            )*
+            .collect()
+    }
+}
+
 pub(crate) fn parse_yes_or_no<T: AsRef<str>>(s: T) -> crate::Result<bool> {
     match s.as_ref() {
         "YES" => Ok(true),
         "NO" => Ok(false),
-        _ => Err(Error::invalid_input()),
+        value => {
+            Err(Error::invalid_attribute(format!(
+                "expected `YES` or `NO`, but got {:?}",
+                value
+            )))
+        }
     }
 }
 
@@ -115,11 +130,404 @@ where
     Ok(input.trim().split_at(tag.as_ref().len()).1)
 }
 
+/// Returns the number of blank lines before the first line, that is not
+/// blank, so the line numbers of the input after the `#EXTM3U` header can be
+/// calculated.
+pub(crate) fn leading_lines(input: &str) -> usize {
+    input[..input.len() - input.trim_start().len()]
+        .matches('\n')
+        .count()
+}
+
+/// Returns the name of an unknown tag line, without the leading `#` and
+/// without its value (`EXT-X-FOO` for `#EXT-X-FOO:1`).
+pub(crate) fn tag_name(line: &str) -> &str {
+    let line = line.strip_prefix('#').unwrap_or(line);
+
+    line.split(':').next().unwrap_or(line)
+}
+
+/// Returns the unknown tags, whose name starts with the prefix. The leading
+/// `#` of the prefix is optional.
+pub(crate) fn tags_with_prefix<'b>(
+    tags: &'b [Cow<'_, str>],
+    prefix: &'b str,
+) -> impl Iterator<Item = &'b str> + 'b {
+    let prefix = prefix.strip_prefix('#').unwrap_or(prefix);
+
+    tags.iter()
+        .map(AsRef::as_ref)
+        .filter(move |line| tag_name(line).starts_with(prefix))
+}
+
+/// Removes all unknown tags with the name and returns them. The leading `#`
+/// of the name is optional.
+pub(crate) fn remove_tags<'a>(tags: &mut Vec<Cow<'a, str>>, name: &str) -> Vec<Cow<'a, str>> {
+    let name = name.strip_prefix('#').unwrap_or(name);
+    let (removed, kept) = std::mem::take(tags)
+        .into_iter()
+        .partition(|line| tag_name(line) == name);
+
+    *tags = kept;
+    removed
+}
+
+/// Removes all duplicates, but keeps the first occurrence of each value.
+pub(crate) fn dedup_tags(tags: &mut Vec<Cow<'_, str>>) {
+    let mut seen = std::collections::HashSet::new();
+
+    tags.retain(|line| seen.insert(line.clone()));
+}
+
+/// Returns `true`, if the value is a valid `STABLE-VARIANT-ID` or
+/// `STABLE-RENDITION-ID`, which may only contain the characters `[a-z]`,
+/// `[A-Z]`, `[0-9]`, `+`, `/`, `=`, `.`, `-` and `_`.
+pub(crate) fn is_stable_id(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .bytes()
+            .all(|c| c.is_ascii_alphanumeric() || b"+/=.-_".contains(&c))
+}
+
+/// Decodes base64 (with the standard or the url-safe alphabet), the padding
+/// is optional.
+pub(crate) fn decode_base64(input: &str) -> crate::Result<Vec<u8>> {
+    let data = input.trim_end_matches('=');
+    let mut result = Vec::with_capacity(data.len() * 3 / 4);
+
+    let mut buffer = 0_u32;
+    let mut bits = 0;
+
+    for c in data.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => {
+                return Err(Error::invalid_attribute(format!(
+                    "invalid base64: {:?}",
+                    input
+                )))
+            }
+        };
+
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            result.push((buffer >> bits) as u8);
+        }
+    }
+
+    // a single character can not encode a complete byte:
+    if bits >= 6 {
+        return Err(Error::invalid_attribute(format!(
+            "invalid base64: {:?}",
+            input
+        )));
+    }
+
+    Ok(result)
+}
+
+/// Takes ownership of a string, that is likely to be repeated many times
+/// (like codecs, group ids or key formats).
+///
+/// If the `intern` feature is enabled, equal strings will share a single
+/// allocation, which is never freed. To bound the memory, that is held by the
+/// interner, only [`INTERN_MAX_STRINGS`] strings with at most
+/// [`INTERN_MAX_LENGTH`] bytes are interned, all other strings are copied.
+#[cfg(feature = "intern")]
+pub(crate) fn intern(value: Cow<'_, str>) -> Cow<'static, str> {
+    use std::collections::HashSet;
+    use std::sync::{Mutex, OnceLock, PoisonError};
+
+    static STRINGS: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+
+    if value.len() > INTERN_MAX_LENGTH {
+        return Cow::Owned(value.into_owned());
+    }
+
+    let mut strings = STRINGS
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+
+    intern_into(&mut strings, value)
+}
+
+/// Interns `value` into `strings`, unless [`INTERN_MAX_STRINGS`] have already
+/// been interned.
+#[cfg(feature = "intern")]
+fn intern_into(
+    strings: &mut std::collections::HashSet<&'static str>,
+    value: Cow<'_, str>,
+) -> Cow<'static, str> {
+    if let Some(interned) = strings.get(&*value) {
+        return Cow::Borrowed(interned);
+    }
+
+    if strings.len() >= INTERN_MAX_STRINGS {
+        return Cow::Owned(value.into_owned());
+    }
+
+    let interned: &'static str = Box::leak(value.into_owned().into_boxed_str());
+    strings.insert(interned);
+
+    Cow::Borrowed(interned)
+}
+
+/// The maximum number of strings, that are interned by [`intern`].
+#[cfg(feature = "intern")]
+const INTERN_MAX_STRINGS: usize = 4096;
+
+/// The maximum length in bytes of a string, that is interned by [`intern`].
+#[cfg(feature = "intern")]
+const INTERN_MAX_LENGTH: usize = 256;
+
+/// Takes ownership of a string, that is likely to be repeated many times
+/// (like codecs, group ids or key formats).
+///
+/// If the `intern` feature is enabled, equal strings will share a single
+/// allocation, which is never freed.
+#[cfg(not(feature = "intern"))]
+pub(crate) fn intern(value: Cow<'_, str>) -> Cow<'static, str> { Cow::Owned(value.into_owned()) }
+
+/// Returns the language tag in its normalized form (`en-us` becomes
+/// `en-US`) or an error, if it is not well-formed according to [RFC5646].
+///
+/// [RFC5646]: https://tools.ietf.org/html/rfc5646
+#[cfg(feature = "language-tags")]
+pub(crate) fn normalize_language(value: &str) -> crate::Result<String> {
+    language_tags::LanguageTag::parse(value)
+        .map(language_tags::LanguageTag::into_string)
+        .map_err(|error| {
+            Error::invalid_attribute(format!(
+                "the language tag {:?} is malformed: {}",
+                value, error
+            ))
+        })
+}
+
+/// Serializes a `Duration` as a number of seconds.
+#[cfg(feature = "serde")]
+pub(crate) mod duration_secs {
+    use std::time::Duration;
+
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        value: &Duration,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(value.as_secs_f64())
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Duration, D::Error> {
+        Duration::try_from_secs_f64(f64::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+/// Parses a date-time in the format of [rfc3339] (like
+/// `2010-02-19T14:54:23.031+08:00`), as it is used by the
+/// `#EXT-X-PROGRAM-DATE-TIME` and the `#EXT-X-DATERANGE` tags.
+///
+/// This is only used if the `chrono` feature is disabled.
+///
+/// [rfc3339]: https://tools.ietf.org/html/rfc3339
+#[cfg(not(feature = "chrono"))]
+pub(crate) fn parse_date_time(input: &str) -> Option<std::time::SystemTime> {
+    use std::ops::RangeInclusive;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn number(input: &str, range: RangeInclusive<i64>) -> Option<i64> {
+        if input.is_empty() || !input.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        input.parse().ok().filter(|value| range.contains(value))
+    }
+
+    let (date, time) = input.split_at(input.find(['T', 't', ' '])?);
+    let (time, offset) = time[1..].split_at(time[1..].find(['Z', 'z', '+', '-'])?);
+
+    let mut date = date.splitn(3, '-');
+    let year = number(date.next()?, 0..=9999)?;
+    let month = number(date.next()?, 1..=12)?;
+    let day = number(date.next()?, 1..=31)?;
+
+    let mut time = time.splitn(3, ':');
+    let hours = number(time.next()?, 0..=23)?;
+    let minutes = number(time.next()?, 0..=59)?;
+    let seconds = time.next()?;
+    let (seconds, fraction) = seconds.split_once('.').unwrap_or((seconds, ""));
+    let seconds = number(seconds, 0..=60)?;
+
+    let nanos = if fraction.is_empty() {
+        0
+    } else {
+        let digits = &fraction[..fraction.len().min(9)];
+        number(digits, 0..=999_999_999)? * 10_i64.pow(9 - digits.len() as u32)
+    };
+
+    let offset = match offset {
+        "Z" | "z" => 0,
+        _ => {
+            let (hours, minutes) = offset[1..].split_once(':')?;
+            let value = number(hours, 0..=23)? * 3600 + number(minutes, 0..=59)? * 60;
+
+            if offset.starts_with('-') {
+                -value
+            } else {
+                value
+            }
+        }
+    };
+
+    // the number of days since 1970-01-01 in the proleptic gregorian calendar
+    // (http://howardhinnant.github.io/date_algorithms.html#days_from_civil)
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let seconds = days * 86_400 + hours * 3600 + minutes * 60 + seconds - offset;
+    let nanos = Duration::from_nanos(nanos as u64);
+
+    if seconds >= 0 {
+        UNIX_EPOCH.checked_add(Duration::from_secs(seconds as u64) + nanos)
+    } else {
+        UNIX_EPOCH
+            .checked_sub(Duration::from_secs(seconds.unsigned_abs()))?
+            .checked_add(nanos)
+    }
+}
+
+/// Formats a [`SystemTime`] as a date-time in the format of [rfc3339] in UTC
+/// with millisecond precision (like `2010-02-19T06:54:23.031Z`). This is the
+/// counterpart of [`parse_date_time`].
+///
+/// This is only used if the `chrono` feature is disabled.
+///
+/// [`SystemTime`]: std::time::SystemTime
+/// [rfc3339]: https://tools.ietf.org/html/rfc3339
+#[cfg(not(feature = "chrono"))]
+pub(crate) fn format_date_time(time: std::time::SystemTime) -> String {
+    use std::time::UNIX_EPOCH;
+
+    let millis = match time.duration_since(UNIX_EPOCH) {
+        Ok(value) => value.as_millis() as i64,
+        Err(e) => -(e.duration().as_millis() as i64),
+    };
+
+    let days = millis.div_euclid(86_400_000);
+    let millis = millis.rem_euclid(86_400_000);
+
+    // the date in the proleptic gregorian calendar
+    // (http://howardhinnant.github.io/date_algorithms.html#civil_from_days)
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn test_decode_base64() {
+        assert_eq!(decode_base64("").unwrap(), b"");
+        assert_eq!(decode_base64("Zg==").unwrap(), b"f");
+        assert_eq!(decode_base64("Zm8").unwrap(), b"fo");
+        assert_eq!(decode_base64("Zm9v").unwrap(), b"foo");
+        assert_eq!(decode_base64("Zm9vYg==").unwrap(), b"foob");
+        assert_eq!(decode_base64("+/+/").unwrap(), [0xFB, 0xFF, 0xBF]);
+        assert_eq!(decode_base64("-_-_").unwrap(), [0xFB, 0xFF, 0xBF]);
+
+        assert!(decode_base64("Zm9vY").is_err());
+        assert!(decode_base64("Zm 9v").is_err());
+    }
+
+    #[test]
+    fn test_is_stable_id() {
+        assert!(is_stable_id("abc-DEF_0.1+/="));
+        assert!(!is_stable_id(""));
+        assert!(!is_stable_id("a b"));
+        assert!(!is_stable_id("ä"));
+    }
+
+    #[test]
+    #[cfg(not(feature = "chrono"))]
+    fn test_format_date_time() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        assert_eq!(format_date_time(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            format_date_time(UNIX_EPOCH + Duration::from_millis(1_266_562_463_031)),
+            "2010-02-19T06:54:23.031Z"
+        );
+        assert_eq!(
+            format_date_time(UNIX_EPOCH - Duration::from_millis(500)),
+            "1969-12-31T23:59:59.500Z"
+        );
+
+        for input in &["2000-02-29T12:00:00.000Z", "2024-12-31T23:59:59.999Z"] {
+            assert_eq!(&format_date_time(parse_date_time(input).unwrap()), input);
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "chrono"))]
+    fn test_parse_date_time() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        assert_eq!(parse_date_time("1970-01-01T00:00:00Z"), Some(UNIX_EPOCH));
+        assert_eq!(
+            parse_date_time("2010-02-19T14:54:23.031+08:00"),
+            Some(UNIX_EPOCH + Duration::from_millis(1_266_562_463_031))
+        );
+        assert_eq!(
+            parse_date_time("2010-02-19T06:54:23.031z"),
+            parse_date_time("2010-02-19T14:54:23.031+08:00")
+        );
+        assert_eq!(
+            parse_date_time("1969-12-31T23:00:00.5-01:00"),
+            Some(UNIX_EPOCH + Duration::from_millis(500))
+        );
+
+        assert_eq!(parse_date_time("2010-02-19"), None);
+        assert_eq!(parse_date_time("2010-13-19T14:54:23Z"), None);
+        assert_eq!(parse_date_time("2010-02-19T14:54:23"), None);
+        assert_eq!(parse_date_time("2010-02-19T14:54:2x+08:00"), None);
+    }
+
     #[test]
     fn test_parse_yes_or_no() {
         assert!(parse_yes_or_no("YES").unwrap());
@@ -183,4 +591,61 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_intern() {
+        assert_eq!(intern(Cow::Borrowed("avc1.4d401e")), "avc1.4d401e");
+        assert_eq!(intern(Cow::Owned("mp4a.40.2".into())), "mp4a.40.2");
+    }
+
+    #[test]
+    #[cfg(feature = "intern")]
+    fn test_intern_shared() {
+        let first = intern(Cow::Owned("com.example.shared".into()));
+        let second = intern(Cow::Borrowed("com.example.shared"));
+
+        assert!(matches!(first, Cow::Borrowed(_)));
+        assert_eq!(first.as_ptr(), second.as_ptr());
+    }
+
+    #[test]
+    #[cfg(feature = "intern")]
+    fn test_intern_bounded() {
+        let long = "a".repeat(INTERN_MAX_LENGTH + 1);
+
+        assert!(matches!(intern(Cow::Borrowed(&long)), Cow::Owned(_)));
+        assert_eq!(intern(Cow::Borrowed(&long)), long);
+
+        // once the interner is full, new strings are no longer interned
+        let mut strings = std::collections::HashSet::new();
+
+        for i in 0..INTERN_MAX_STRINGS {
+            let value = intern_into(&mut strings, Cow::Owned(i.to_string()));
+            assert!(matches!(value, Cow::Borrowed(_)));
+        }
+
+        assert!(matches!(
+            intern_into(&mut strings, Cow::Borrowed("0")),
+            Cow::Borrowed(_)
+        ));
+        assert!(matches!(
+            intern_into(&mut strings, Cow::Borrowed("not interned")),
+            Cow::Owned(_)
+        ));
+        assert_eq!(strings.len(), INTERN_MAX_STRINGS);
+    }
+
+    #[test]
+    #[cfg(feature = "intern")]
+    fn test_intern_key_format() {
+        use crate::types::KeyFormat;
+
+        let first = KeyFormat::from("urn:uuid:1077efec-c0b2-4d02-ace3-3c1e52e2fb4b").into_owned();
+        let second = KeyFormat::from("urn:uuid:1077efec-c0b2-4d02-ace3-3c1e52e2fb4b").into_owned();
+
+        assert_eq!(
+            first.uuid().map(str::as_ptr),
+            second.uuid().map(str::as_ptr)
+        );
+    }
 }
@@ -0,0 +1,1494 @@
+use std::fmt;
+use std::panic::RefUnwindSafe;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use shorthand::ShortHand;
+
+use crate::media_playlist::rounded_duration;
+use crate::tags::VariantStream;
+use crate::tags::{ExtXSessionData, SessionData};
+use crate::types::ContentKind;
+use crate::{Error, MasterPlaylist, MediaPlaylist, UriKind, UriNormalizer};
+
+/// How serious a [`Diagnostic`] is.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    /// The playlist is valid, but might not work with some players.
+    Warning,
+    /// The playlist violates the specification.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A single problem, that has been found by a [`Validator`].
+#[derive(ShortHand, Debug, Clone, PartialEq, Eq, Hash)]
+#[shorthand(enable(must_use), disable(set))]
+pub struct Diagnostic {
+    /// How serious the problem is.
+    #[shorthand(enable(copy))]
+    severity: Severity,
+    /// The [`MediaSegment::number`] of the segment, the problem has been found
+    /// in.
+    ///
+    /// [`MediaSegment::number`]: crate::MediaSegment::number
+    #[shorthand(enable(copy))]
+    segment: Option<usize>,
+    /// The number of the line (starting at 1), the problem has been found in.
+    ///
+    /// This is only known for problems, that have been found while parsing
+    /// the playlist with [`ParseOptions`].
+    ///
+    /// [`ParseOptions`]: crate::ParseOptions
+    #[shorthand(enable(copy))]
+    line: Option<usize>,
+    /// A human-readable description of the problem.
+    message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.severity)?;
+
+        if let Some(number) = self.segment {
+            write!(f, " (segment {})", number)?;
+        }
+
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// All problems, that have been found by a [`Validator`].
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ValidationReport {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl ValidationReport {
+    /// Returns all [`Diagnostic`]s in the order they have been found.
+    #[must_use]
+    pub fn diagnostics(&self) -> &[Diagnostic] { &self.diagnostics }
+
+    /// Returns an iterator over all [`Diagnostic`]s with
+    /// [`Severity::Warning`].
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.with_severity(Severity::Warning)
+    }
+
+    /// Returns an iterator over all [`Diagnostic`]s with [`Severity::Error`].
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.with_severity(Severity::Error)
+    }
+
+    /// Returns `true`, if no [`Diagnostic`] with [`Severity::Error`] has been
+    /// found.
+    #[must_use]
+    pub fn is_ok(&self) -> bool { self.errors().next().is_none() }
+
+    /// Returns `true`, if nothing has been found.
+    #[must_use]
+    pub fn is_empty(&self) -> bool { self.diagnostics.is_empty() }
+
+    fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(move |diagnostic| diagnostic.severity == severity)
+    }
+
+    pub(crate) fn push<T: Into<String>>(
+        &mut self,
+        severity: Severity,
+        segment: Option<usize>,
+        message: T,
+    ) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            segment,
+            line: None,
+            message: message.into(),
+        });
+    }
+
+    /// Adds a violation, that has been found by the parser, as a warning.
+    pub(crate) fn push_violation(&mut self, error: &Error) {
+        self.diagnostics.push(Diagnostic {
+            severity: Severity::Warning,
+            segment: None,
+            line: error.line(),
+            message: error.to_string(),
+        });
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for diagnostic in &self.diagnostics {
+            writeln!(f, "{}", diagnostic)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A violation of the rules for updating a [`MediaPlaylist`], found by
+/// [`MediaPlaylist::compatible_update`].
+///
+/// The rules are described in
+/// [rfc8216#section-6.2.1](https://tools.ietf.org/html/rfc8216#section-6.2.1).
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, thiserror::Error)]
+pub enum UpdateViolation {
+    /// The [`MediaPlaylist::target_duration`] has been changed.
+    #[error("the target duration changed from {previous:?} to {next:?}")]
+    TargetDurationChanged {
+        /// The target duration of the previous playlist.
+        previous: Duration,
+        /// The target duration of the next playlist.
+        next: Duration,
+    },
+    /// The [`MediaPlaylist::media_sequence`] has been decreased.
+    #[error("the media sequence decreased from {previous} to {next}")]
+    MediaSequenceDecreased {
+        /// The media sequence of the previous playlist.
+        previous: usize,
+        /// The media sequence of the next playlist.
+        next: usize,
+    },
+    /// The [`MediaPlaylist::discontinuity_sequence`] has been decreased.
+    #[error("the discontinuity sequence decreased from {previous} to {next}")]
+    DiscontinuitySequenceDecreased {
+        /// The discontinuity sequence of the previous playlist.
+        previous: usize,
+        /// The discontinuity sequence of the next playlist.
+        next: usize,
+    },
+    /// A segment of the previous playlist has been removed, although it
+    /// should still be in the next playlist. Segments can only be removed
+    /// from the start of a playlist and never from a playlist with the
+    /// [`PlaylistType::Event`].
+    ///
+    /// [`PlaylistType::Event`]: crate::types::PlaylistType::Event
+    #[error("the segment {number} has been removed")]
+    SegmentRemoved {
+        /// The [`MediaSegment::number`] of the segment.
+        ///
+        /// [`MediaSegment::number`]: crate::MediaSegment::number
+        number: usize,
+    },
+    /// The uri or the duration of a segment has been changed.
+    #[error("the segment {number} has been changed")]
+    SegmentChanged {
+        /// The [`MediaSegment::number`] of the segment.
+        ///
+        /// [`MediaSegment::number`]: crate::MediaSegment::number
+        number: usize,
+    },
+    /// The previous playlist has been marked with
+    /// [`MediaPlaylist::has_end_list`] or it has the
+    /// [`PlaylistType::Vod`], but the next playlist is different.
+    ///
+    /// [`PlaylistType::Vod`]: crate::types::PlaylistType::Vod
+    #[error("the playlist changed, although it must not change anymore")]
+    ChangedAfterEnd,
+}
+
+/// The source of the current time for the checks of a [`Validator`], that
+/// compare dates of a playlist with the current time.
+///
+/// It is implemented for closures, so the time can be fixed in tests or
+/// replaced with the time of a recording, when old playlists are replayed:
+///
+/// ```
+/// use hls_m3u8::Validator;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let mut validator = Validator::new();
+/// validator.set_clock(|| UNIX_EPOCH + Duration::from_secs(1_266_562_463));
+/// ```
+pub trait Clock: Send + Sync + RefUnwindSafe {
+    /// Returns the current time.
+    fn now(&self) -> SystemTime;
+}
+
+impl<F> Clock for F
+where
+    F: Fn() -> SystemTime + Send + Sync + RefUnwindSafe,
+{
+    fn now(&self) -> SystemTime { self() }
+}
+
+/// A [`Clock`], that returns [`SystemTime::now`].
+///
+/// This is the default clock of a [`Validator`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime { SystemTime::now() }
+}
+
+/// Checks playlists for problems, that are not covered by the specification,
+/// but are known to break some players.
+///
+/// # Example
+///
+/// ```
+/// # use hls_m3u8::MediaPlaylist;
+/// use hls_m3u8::types::ContentKind;
+/// use hls_m3u8::{MediaSegment, Validator};
+/// use std::time::Duration;
+///
+/// let playlist = MediaPlaylist::builder()
+///     .target_duration(Duration::from_secs(9))
+///     .content_kind(ContentKind::Subtitles)
+///     .segments(vec![
+///         MediaSegment::builder()
+///             .duration(Duration::from_secs(9))
+///             .uri("1.vtt")
+///             .build()?,
+///         MediaSegment::builder()
+///             .duration(Duration::from_secs(6))
+///             .uri("2.vtt")
+///             .build()?,
+///     ])
+///     .build()?;
+///
+/// let mut validator = Validator::new();
+/// validator.set_ladder_target_duration(Some(Duration::from_secs(6)));
+///
+/// // the first segment does not end on a segment boundary of the video
+/// assert_eq!(
+///     validator
+///         .validate_media_playlist(&playlist)
+///         .diagnostics()
+///         .len(),
+///     1
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(ShortHand, Clone)]
+#[shorthand(enable(must_use, copy))]
+#[non_exhaustive]
+pub struct Validator {
+    /// The target duration of the other playlists in the variant ladder.
+    ///
+    /// If this is set, the durations of subtitle segments must be a multiple
+    /// of it, so the segments are aligned with the segments of the video.
+    ///
+    /// By default this is `None`.
+    ladder_target_duration: Option<Duration>,
+    /// The maximum duration of a subtitle segment. Some players fail to play
+    /// subtitle segments, that are longer than this.
+    ///
+    /// By default this is 60 seconds.
+    max_subtitle_duration: Duration,
+    #[shorthand(enable(skip))]
+    clock: Arc<dyn Clock>,
+    #[shorthand(enable(skip))]
+    uri_normalizer: Option<Arc<dyn UriNormalizer>>,
+}
+
+impl fmt::Debug for Validator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Validator")
+            .field("ladder_target_duration", &self.ladder_target_duration)
+            .field("max_subtitle_duration", &self.max_subtitle_duration)
+            .field("clock", &"..")
+            .field(
+                "uri_normalizer",
+                &self.uri_normalizer.as_ref().map(|_| ".."),
+            )
+            .finish()
+    }
+}
+
+impl Validator {
+    /// Makes a new [`Validator`] with the default settings.
+    #[must_use]
+    pub fn new() -> Self { Self::default() }
+
+    /// Sets the [`Clock`], that is used to check the dates of a playlist
+    /// against the current time.
+    ///
+    /// By default this is the [`SystemClock`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::Validator;
+    /// use std::convert::TryFrom;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXT-X-PROGRAM-DATE-TIME:2010-02-19T14:54:23.031+08:00\n",
+    ///     "#EXTINF:10,\n",
+    ///     "1.ts\n",
+    /// ))?;
+    ///
+    /// let mut validator = Validator::new();
+    ///
+    /// // the live playlist has been written a few seconds ago
+    /// validator.set_clock(|| UNIX_EPOCH + Duration::from_secs(1_266_562_480));
+    /// assert!(validator.validate_media_playlist(&playlist).is_empty());
+    ///
+    /// // the playlist has not been updated for more than 3 target durations
+    /// validator.set_clock(|| UNIX_EPOCH + Duration::from_secs(1_266_562_600));
+    /// assert_eq!(
+    ///     validator
+    ///         .validate_media_playlist(&playlist)
+    ///         .warnings()
+    ///         .count(),
+    ///     1
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    pub fn set_clock<C: Clock + 'static>(&mut self, clock: C) -> &mut Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Sets the [`UriNormalizer`], that is used to warn about every uri of a
+    /// playlist, that is not normalized.
+    ///
+    /// By default the URIs are not checked.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::{DefaultUriNormalizer, Validator};
+    /// use std::convert::TryFrom;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXTINF:10,\n",
+    ///     "first segment.ts\n",
+    /// ))?;
+    ///
+    /// let mut validator = Validator::new();
+    /// assert!(validator.validate_media_playlist(&playlist).is_empty());
+    ///
+    /// validator.set_uri_normalizer(DefaultUriNormalizer);
+    /// assert_eq!(
+    ///     validator.validate_media_playlist(&playlist).to_string(),
+    ///     "warning (segment 0): the uri \"first segment.ts\" should be written as \"first%20segment.ts\"\n"
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    pub fn set_uri_normalizer<N: UriNormalizer + 'static>(&mut self, normalizer: N) -> &mut Self {
+        self.uri_normalizer = Some(Arc::new(normalizer));
+        self
+    }
+
+    /// Checks a [`MediaPlaylist`].
+    #[must_use]
+    pub fn validate_media_playlist(&self, playlist: &MediaPlaylist<'_>) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        if playlist.content_kind == Some(ContentKind::Subtitles) {
+            self.validate_subtitles(playlist, &mut report);
+        }
+
+        Self::validate_byte_ranges(playlist, &mut report);
+        Self::validate_containers(playlist, &mut report);
+        self.validate_dates(playlist, &mut report);
+        Self::validate_date_ranges(playlist, &mut report);
+        Self::validate_cues(playlist, &mut report);
+        Self::validate_low_latency(playlist, &mut report);
+        self.validate_media_uris(playlist, &mut report);
+
+        report
+    }
+
+    /// Checks a [`MasterPlaylist`].
+    #[must_use]
+    pub fn validate_master_playlist(&self, playlist: &MasterPlaylist<'_>) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        Self::validate_session_data(playlist, &mut report);
+        #[cfg(feature = "language-tags")]
+        Self::validate_languages(playlist, &mut report);
+        self.validate_master_uris(playlist, &mut report);
+
+        report
+    }
+
+    /// Warns, if the [`Validator::set_uri_normalizer`] changes the `uri`.
+    fn validate_uri(
+        &self,
+        report: &mut ValidationReport,
+        segment: Option<usize>,
+        kind: UriKind,
+        uri: &str,
+    ) {
+        if let Some(normalizer) = &self.uri_normalizer {
+            let normalized = normalizer.normalize(kind, uri);
+
+            if normalized != uri {
+                report.push(
+                    Severity::Warning,
+                    segment,
+                    format!("the uri {:?} should be written as {:?}", uri, normalized),
+                );
+            }
+        }
+    }
+
+    fn validate_media_uris(&self, playlist: &MediaPlaylist<'_>, report: &mut ValidationReport) {
+        if self.uri_normalizer.is_none() {
+            return;
+        }
+
+        for segment in playlist.segments.values() {
+            let number = Some(segment.number);
+
+            for key in segment.keys.iter().filter_map(|key| key.as_ref()) {
+                self.validate_uri(report, number, UriKind::Key, key.uri());
+            }
+
+            if let Some(map) = &segment.map {
+                self.validate_uri(report, number, UriKind::Map, map.uri());
+            }
+
+            self.validate_uri(report, number, UriKind::Segment, segment.uri());
+        }
+    }
+
+    fn validate_master_uris(&self, playlist: &MasterPlaylist<'_>, report: &mut ValidationReport) {
+        if self.uri_normalizer.is_none() {
+            return;
+        }
+
+        for media in &playlist.media {
+            if let Some(uri) = media.uri() {
+                self.validate_uri(report, None, UriKind::Media, uri);
+            }
+        }
+
+        for variant_stream in &playlist.variant_streams {
+            match variant_stream {
+                VariantStream::ExtXIFrame { uri, .. } => {
+                    self.validate_uri(report, None, UriKind::IFrame, uri);
+                }
+                VariantStream::ExtXStreamInf { uri, .. } => {
+                    self.validate_uri(report, None, UriKind::StreamInf, uri);
+                }
+            }
+        }
+
+        for session_data in &playlist.session_data {
+            if let SessionData::Uri(uri) = &session_data.data {
+                self.validate_uri(report, None, UriKind::SessionData, uri);
+            }
+        }
+
+        for session_key in &playlist.session_keys {
+            self.validate_uri(report, None, UriKind::SessionKey, session_key.0.uri());
+        }
+    }
+
+    /// The `DATA-ID` of session data should use the reverse DNS naming
+    /// convention to prevent collisions and the chapters have to be stored in
+    /// a json file.
+    fn validate_session_data(playlist: &MasterPlaylist<'_>, report: &mut ValidationReport) {
+        for session_data in &playlist.session_data {
+            if !session_data.is_reverse_dns() {
+                report.push(
+                    Severity::Warning,
+                    None,
+                    format!(
+                        "the DATA-ID {:?} does not use the reverse DNS naming convention",
+                        session_data.data_id()
+                    ),
+                );
+            }
+
+            if session_data.data_id() == ExtXSessionData::CHAPTERS
+                && !matches!(session_data.data, SessionData::Uri(_))
+            {
+                report.push(
+                    Severity::Warning,
+                    None,
+                    format!(
+                        "the session data {:?} should have an URI, that points to a json file",
+                        ExtXSessionData::CHAPTERS
+                    ),
+                );
+            }
+        }
+    }
+
+    /// The languages of the renditions should be well-formed and normalized
+    /// language tags, because malformed tags break the track selection of
+    /// some clients.
+    #[cfg(feature = "language-tags")]
+    fn validate_languages(playlist: &MasterPlaylist<'_>, report: &mut ValidationReport) {
+        for media in &playlist.media {
+            let languages = [
+                ("LANGUAGE", media.language()),
+                ("ASSOC-LANGUAGE", media.assoc_language()),
+            ];
+
+            for &(name, value) in &languages {
+                let value = match value {
+                    Some(value) => value,
+                    None => continue,
+                };
+
+                match crate::utils::normalize_language(value) {
+                    Ok(normalized) if normalized != value.as_ref() => {
+                        report.push(
+                            Severity::Warning,
+                            None,
+                            format!(
+                                "the {} {:?} of the rendition {:?} should be written as {:?}",
+                                name,
+                                value,
+                                media.name(),
+                                normalized
+                            ),
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(error) => report.push(Severity::Warning, None, error.to_string()),
+                }
+            }
+        }
+    }
+
+    /// Program date times and date ranges should not be in the future and a
+    /// live playlist should not end much earlier than the current time, which
+    /// indicates a stale playlist or a wrong clock of the packager.
+    fn validate_dates(&self, playlist: &MediaPlaylist<'_>, report: &mut ValidationReport) {
+        let now = self.clock.now();
+        let mut end = None;
+
+        for segment in playlist.segments.values() {
+            if let Some(time) = segment
+                .program_date_time
+                .as_ref()
+                .and_then(|value| value.system_time())
+            {
+                if let Some(offset) = future(time, now) {
+                    report.push(
+                        Severity::Warning,
+                        Some(segment.number),
+                        format!("the program date time is {:?} in the future", offset),
+                    );
+                }
+
+                end = Some(time);
+            }
+
+            if let Some(date_range) = &segment.date_range {
+                if let Some(offset) = date_range.start_time().and_then(|time| future(time, now)) {
+                    report.push(
+                        Severity::Warning,
+                        Some(segment.number),
+                        format!(
+                            "the date range {:?} starts {:?} in the future",
+                            date_range.id(),
+                            offset
+                        ),
+                    );
+                }
+            }
+
+            end = end.map(|time| time + segment.duration.duration());
+        }
+
+        let max_age = playlist.target_duration * 3;
+
+        if let Some(age) = end
+            .filter(|_| !playlist.has_end_list && max_age > Duration::ZERO)
+            .and_then(|end| now.duration_since(end).ok())
+            .filter(|age| *age > max_age)
+        {
+            report.push(
+                Severity::Warning,
+                None,
+                format!(
+                    "the live playlist ends {:?} before the current time, which is more than 3 target durations",
+                    age
+                ),
+            );
+        }
+    }
+
+    /// The `START-DATE` of a date range should be inside of the time span, that
+    /// is covered by the program date times of the playlist and a date range
+    /// with `END-ON-NEXT` is ended by the next date range of the same class,
+    /// which has to exist eventually. Both are common mistakes of ad markers.
+    fn validate_date_ranges(playlist: &MediaPlaylist<'_>, report: &mut ValidationReport) {
+        let mut span: Option<(SystemTime, SystemTime)> = None;
+        let mut time = None;
+
+        for segment in playlist.segments.values() {
+            if let Some(value) = segment
+                .program_date_time
+                .as_ref()
+                .and_then(|value| value.system_time())
+            {
+                time = Some(value);
+            }
+
+            if let Some(start) = time {
+                let end = start + segment.duration.duration();
+
+                span = Some(span.map_or((start, end), |(first, last)| {
+                    (first.min(start), last.max(end))
+                }));
+                time = Some(end);
+            }
+        }
+
+        let date_ranges = playlist
+            .segments
+            .values()
+            .filter_map(|segment| segment.date_range.as_ref().map(|value| (segment, value)))
+            .collect::<Vec<_>>();
+
+        for (i, (segment, date_range)) in date_ranges.iter().enumerate() {
+            if let (Some((first, last)), Some(start)) = (span, date_range.start_time()) {
+                if let Ok(offset) = first.duration_since(start) {
+                    if offset > Duration::ZERO {
+                        report.push(
+                            Severity::Warning,
+                            Some(segment.number),
+                            format!(
+                                "the date range {:?} starts {:?} before the first program date time",
+                                date_range.id(),
+                                offset
+                            ),
+                        );
+                    }
+                }
+
+                if let Ok(offset) = start.duration_since(last) {
+                    if offset > Duration::ZERO {
+                        report.push(
+                            Severity::Warning,
+                            Some(segment.number),
+                            format!(
+                                "the date range {:?} starts {:?} after the end of the playlist",
+                                date_range.id(),
+                                offset
+                            ),
+                        );
+                    }
+                }
+            }
+
+            if date_range.end_on_next
+                && !date_ranges[i + 1..]
+                    .iter()
+                    .any(|(_, next)| next.class() == date_range.class())
+            {
+                report.push(
+                    Severity::Warning,
+                    Some(segment.number),
+                    format!(
+                        "the date range {:?} has END-ON-NEXT, but no following date range of the same class",
+                        date_range.id()
+                    ),
+                );
+            }
+        }
+    }
+
+    /// The `CUE` attribute of a date range is only defined for
+    /// [HLS Interstitials](crate::interstitial), other classes will most
+    /// likely be ignored by players.
+    fn validate_cues(playlist: &MediaPlaylist<'_>, report: &mut ValidationReport) {
+        for segment in playlist.segments.values() {
+            if let Some(date_range) = &segment.date_range {
+                if !date_range.cue().is_empty()
+                    && date_range.class().map(AsRef::as_ref) != Some(crate::interstitial::CLASS)
+                {
+                    report.push(
+                        Severity::Warning,
+                        Some(segment.number),
+                        format!(
+                            "the date range {:?} has a CUE attribute, but is not an interstitial",
+                            date_range.id()
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Checks the durations of a playlist against its target duration and
+    /// the part target of the `#EXT-X-PART-INF` tag, like it is required for
+    /// low-latency playlists by
+    /// [rfc8216bis](https://tools.ietf.org/html/draft-pantos-hls-rfc8216bis-13):
+    ///
+    /// - the rounded duration of a segment must not exceed the target duration,
+    /// - the part target must not exceed the target duration,
+    /// - a partial segment must not be longer than the part target and should
+    ///   be at least 85% of it, unless it is independent or the last part of
+    ///   its segment,
+    /// - the `HOLD-BACK` must be at least three times the target duration and
+    ///   the `PART-HOLD-BACK` at least twice the part target.
+    fn validate_low_latency(playlist: &MediaPlaylist<'_>, report: &mut ValidationReport) {
+        let target_duration = playlist.target_duration;
+        let part_target = playlist.part_target;
+
+        for segment in playlist.segments.values() {
+            let duration = segment.duration.duration();
+
+            if rounded_duration(duration) > target_duration {
+                report.push(
+                    Severity::Error,
+                    Some(segment.number),
+                    format!(
+                        "segment duration {:?} exceeds the target duration {:?}",
+                        duration, target_duration
+                    ),
+                );
+            }
+        }
+
+        if let Some(server_control) = &playlist.server_control {
+            if let Some(hold_back) = server_control.hold_back() {
+                if hold_back < target_duration * 3 {
+                    report.push(
+                        Severity::Error,
+                        None,
+                        format!(
+                            "HOLD-BACK {:?} is less than three times the target duration {:?}",
+                            hold_back, target_duration
+                        ),
+                    );
+                }
+            }
+        }
+
+        let pending_number = playlist
+            .segments
+            .values()
+            .last()
+            .map_or(playlist.media_sequence, |segment| segment.number + 1);
+
+        let segments = playlist
+            .segments
+            .values()
+            .map(|segment| (segment.number, segment.parts.as_slice()))
+            .chain(
+                playlist
+                    .pending_segment
+                    .iter()
+                    .map(|segment| (pending_number, segment.parts.as_slice())),
+            )
+            .filter(|(_, parts)| !parts.is_empty())
+            .collect::<Vec<_>>();
+
+        let part_target = match part_target {
+            Some(part_target) => part_target,
+            None => {
+                if let Some((number, _)) = segments.first() {
+                    report.push(
+                        Severity::Error,
+                        Some(*number),
+                        "the playlist has partial segments, but no PART-TARGET",
+                    );
+                }
+
+                return;
+            }
+        };
+
+        if part_target > target_duration {
+            report.push(
+                Severity::Error,
+                None,
+                format!(
+                    "PART-TARGET {:?} exceeds the target duration {:?}",
+                    part_target, target_duration
+                ),
+            );
+        }
+
+        if let Some(server_control) = &playlist.server_control {
+            if let Some(part_hold_back) = server_control.part_hold_back() {
+                if part_hold_back < part_target * 2 {
+                    report.push(
+                        Severity::Error,
+                        None,
+                        format!(
+                            "PART-HOLD-BACK {:?} is less than twice the PART-TARGET {:?}",
+                            part_hold_back, part_target
+                        ),
+                    );
+                }
+            }
+        }
+
+        for (number, parts) in segments {
+            for (i, part) in parts.iter().enumerate() {
+                let duration = part.duration();
+
+                if duration > part_target {
+                    report.push(
+                        Severity::Error,
+                        Some(number),
+                        format!(
+                            "partial segment {} has a duration of {:?}, which exceeds the PART-TARGET {:?}",
+                            i, duration, part_target
+                        ),
+                    );
+                }
+
+                // the last part of a pending segment is not checked either,
+                // because it might become the last part of its segment
+                let is_last = i + 1 == parts.len();
+                let is_short = duration.as_secs_f64() < part_target.as_secs_f64() * 0.85;
+
+                if is_short && !is_last && !part.is_independent() {
+                    report.push(
+                        Severity::Warning,
+                        Some(number),
+                        format!(
+                            "partial segment {} has a duration of {:?}, which is less than 85% of the PART-TARGET {:?}",
+                            i, duration, part_target
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Byte ranges of the same resource should follow each other, so an
+    /// origin can serve them from a single file with progressive range
+    /// requests.
+    fn validate_byte_ranges(playlist: &MediaPlaylist<'_>, report: &mut ValidationReport) {
+        let mut previous: Option<(&str, usize)> = None;
+
+        for segment in playlist.segments.values() {
+            let range = match &segment.byte_range {
+                Some(range) => range,
+                None => {
+                    previous = None;
+                    continue;
+                }
+            };
+
+            let start = range.start().unwrap_or(0);
+
+            if let Some((uri, end)) = previous {
+                if uri == segment.uri().as_str() && start != end {
+                    report.push(
+                        Severity::Warning,
+                        Some(segment.number),
+                        format!(
+                            "byte range {}..{} of {} is not contiguous with the previous range ending at {}",
+                            start,
+                            range.end(),
+                            uri,
+                            end
+                        ),
+                    );
+                }
+            }
+
+            previous = Some((segment.uri().as_str(), range.end()));
+        }
+    }
+
+    /// MPEG-TS and fragmented MP4 segments should not be mixed in the same
+    /// playlist, because this breaks several players and is usually a
+    /// packaging bug.
+    fn validate_containers(playlist: &MediaPlaylist<'_>, report: &mut ValidationReport) {
+        let mut previous: Option<Container> = None;
+
+        for segment in playlist.segments.values() {
+            let container = match Container::of(segment.uri(), segment.map.is_some()) {
+                Some(container) => container,
+                None => continue,
+            };
+
+            if let Some(previous) = previous.filter(|previous| *previous != container) {
+                report.push(
+                    Severity::Warning,
+                    Some(segment.number),
+                    format!(
+                        "the segment is {}, but the previous segments are {}, which breaks some players",
+                        container.name(),
+                        previous.name()
+                    ),
+                );
+            }
+
+            previous = Some(container);
+        }
+    }
+
+    fn validate_subtitles(&self, playlist: &MediaPlaylist<'_>, report: &mut ValidationReport) {
+        let last = playlist
+            .segments
+            .values()
+            .last()
+            .map(|segment| segment.number);
+
+        for segment in playlist.segments.values() {
+            let duration = segment.duration.duration();
+
+            if duration > self.max_subtitle_duration {
+                report.push(
+                    Severity::Warning,
+                    Some(segment.number),
+                    format!(
+                        "subtitle segment duration {:?} exceeds {:?}, which is not supported by some players",
+                        duration, self.max_subtitle_duration
+                    ),
+                );
+            }
+
+            // the last segment is allowed to be shorter
+            if Some(segment.number) == last {
+                continue;
+            }
+
+            if let Some(target) = self.ladder_target_duration.filter(|d| *d > Duration::ZERO) {
+                let multiple = (duration.as_secs_f64() / target.as_secs_f64()).round();
+                let offset = (duration.as_secs_f64() - multiple * target.as_secs_f64()).abs();
+
+                // allow the same rounding as for the target duration
+                if multiple < 1.0 || offset >= 0.5 {
+                    report.push(
+                        Severity::Warning,
+                        Some(segment.number),
+                        format!(
+                            "subtitle segment duration {:?} is not a multiple of the ladder target duration {:?}",
+                            duration, target
+                        ),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// The container format of a segment, guessed from the extension of its uri.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Container {
+    Ts,
+    Fmp4,
+}
+
+impl Container {
+    /// Falls back to [`Container::Fmp4`] for an unknown extension, if the
+    /// segment has an `#EXT-X-MAP` tag (which is required for fMP4, but
+    /// optional for MPEG-TS).
+    fn of(uri: &str, has_map: bool) -> Option<Self> {
+        let path = uri.split(['?', '#']).next().unwrap_or(uri);
+        let extension = path
+            .rsplit('/')
+            .next()
+            .and_then(|name| name.rsplit_once('.'))
+            .map(|(_, extension)| extension.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("ts") => Some(Self::Ts),
+            Some("mp4" | "m4s" | "m4a" | "m4v" | "cmfv" | "cmfa") => Some(Self::Fmp4),
+            _ if has_map => Some(Self::Fmp4),
+            _ => None,
+        }
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Ts => "MPEG-TS",
+            Self::Fmp4 => "fMP4",
+        }
+    }
+}
+
+/// Returns how far the `time` is in the future.
+fn future(time: SystemTime, now: SystemTime) -> Option<Duration> {
+    time.duration_since(now)
+        .ok()
+        .filter(|offset| *offset > Duration::ZERO)
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        Self {
+            ladder_target_duration: None,
+            max_subtitle_duration: Duration::from_secs(60),
+            clock: Arc::new(SystemClock),
+            uri_normalizer: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tags::ExtXMap;
+    use crate::types::Value;
+    use crate::MediaSegment;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_validate_uris() {
+        use crate::DefaultUriNormalizer;
+        use std::convert::TryFrom;
+
+        let playlist = MasterPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"English\",URI=\"/audio/./en.m3u8\"\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=150000,AUDIO=\"aac\"\n",
+            "low/index.m3u8\n",
+            "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=86000,URI=\"low/iframe index.m3u8\"\n",
+            "#EXT-X-SESSION-DATA:DATA-ID=\"com.example.lyrics\",URI=\"lyrics.json\"\n",
+        ))
+        .unwrap();
+
+        let mut validator = Validator::new();
+        assert!(validator.validate_master_playlist(&playlist).is_empty());
+
+        validator.set_uri_normalizer(DefaultUriNormalizer);
+        assert_eq!(
+            validator
+                .validate_master_playlist(&playlist)
+                .diagnostics()
+                .iter()
+                .map(Diagnostic::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "warning: the uri \"/audio/./en.m3u8\" should be written as \"/audio/en.m3u8\""
+                    .to_string(),
+                "warning: the uri \"low/iframe index.m3u8\" should be written as \"low/iframe%20index.m3u8\"".to_string(),
+            ]
+        );
+
+        // only the kinds, that are changed by the normalizer are reported:
+        validator.set_uri_normalizer(|kind: UriKind, uri: &str| {
+            if kind == UriKind::Media {
+                uri.to_uppercase()
+            } else {
+                uri.to_string()
+            }
+        });
+
+        assert_eq!(
+            validator
+                .validate_master_playlist(&playlist)
+                .warnings()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_validate_session_data() {
+        let playlist = MasterPlaylist::builder()
+            .session_data(vec![
+                ExtXSessionData::new("com.example.title", SessionData::Value(Value::string("a"))),
+                ExtXSessionData::new("title", SessionData::Value(Value::string("b"))),
+                ExtXSessionData::new(
+                    ExtXSessionData::CHAPTERS,
+                    SessionData::Value(Value::string("c")),
+                ),
+            ])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            Validator::new()
+                .validate_master_playlist(&playlist)
+                .diagnostics()
+                .iter()
+                .map(Diagnostic::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "warning: the DATA-ID \"title\" does not use the reverse DNS naming convention"
+                    .to_string(),
+                "warning: the session data \"com.apple.hls.chapters\" should have an URI, that points to a json file".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "language-tags")]
+    fn test_validate_languages() {
+        use crate::tags::ExtXMedia;
+        use crate::types::MediaType;
+
+        let media = |name: &'static str, language: &'static str| {
+            ExtXMedia::builder()
+                .media_type(MediaType::Audio)
+                .group_id("audio")
+                .name(name)
+                .language(language)
+                .build()
+                .unwrap()
+        };
+
+        let playlist = MasterPlaylist::builder()
+            .media(vec![
+                media("English", "en-US"),
+                media("Spanish", "es-mx"),
+                media("German", "de_DE"),
+            ])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            Validator::new()
+                .validate_master_playlist(&playlist)
+                .diagnostics()
+                .iter()
+                .map(Diagnostic::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "warning: the LANGUAGE \"es-mx\" of the rendition \"Spanish\" should be written as \"es-MX\"".to_string(),
+                "warning: the language tag \"de_DE\" is malformed: the given language subtag is invalid".to_string(),
+            ]
+        );
+    }
+
+    fn subtitles(durations: &[u64]) -> MediaPlaylist<'static> {
+        MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(*durations.iter().max().unwrap()))
+            .content_kind(ContentKind::Subtitles)
+            .segments(durations.iter().map(|d| {
+                MediaSegment::builder()
+                    .duration(Duration::from_secs(*d))
+                    .uri("subtitles.vtt")
+                    .build()
+                    .unwrap()
+            }))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_max_subtitle_duration() {
+        let report = Validator::new().validate_media_playlist(&subtitles(&[60, 61, 10]));
+
+        assert!(report.is_ok());
+        assert_eq!(report.warnings().count(), 1);
+        assert_eq!(report.diagnostics()[0].segment(), Some(1));
+        assert_eq!(report.diagnostics()[0].severity(), Severity::Warning);
+    }
+
+    #[test]
+    fn test_ladder_target_duration() {
+        let mut validator = Validator::new();
+        validator.set_ladder_target_duration(Some(Duration::from_secs(6)));
+
+        assert!(validator
+            .validate_media_playlist(&subtitles(&[6, 12, 18, 2]))
+            .is_empty());
+
+        let report = validator.validate_media_playlist(&subtitles(&[6, 9, 4, 2]));
+
+        assert_eq!(
+            report
+                .diagnostics()
+                .iter()
+                .map(Diagnostic::segment)
+                .collect::<Vec<_>>(),
+            vec![Some(1), Some(2)]
+        );
+    }
+
+    #[test]
+    fn test_content_kind() {
+        let mut playlist = subtitles(&[120]);
+        assert!(!playlist.validation_report().is_empty());
+
+        playlist.content_kind = Some(ContentKind::Video);
+        assert!(playlist.validation_report().is_empty());
+
+        playlist.content_kind = None;
+        assert!(playlist.validation_report().is_empty());
+    }
+
+    #[test]
+    fn test_containers() {
+        let segment = |uri: &str, map: Option<&str>| {
+            let mut builder = MediaSegment::builder();
+            builder
+                .duration(Duration::from_secs(1))
+                .uri(uri.to_string());
+
+            if let Some(map) = map {
+                builder.map(ExtXMap::new(map.to_string()));
+            }
+
+            builder.build().unwrap()
+        };
+
+        let playlist = MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(1))
+            .segments(vec![
+                segment("1.ts", None),
+                segment("2.TS?token=abc", None),
+                segment("3", None),
+                segment("4", Some("init.mp4")),
+                segment("5.m4s", Some("init.mp4")),
+                segment("6.ts", Some("init.ts")),
+            ])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            playlist.validation_report().to_string(),
+            concat!(
+                "warning (segment 3): the segment is fMP4, but the previous segments are",
+                " MPEG-TS, which breaks some players\n",
+                "warning (segment 5): the segment is MPEG-TS, but the previous segments are",
+                " fMP4, which breaks some players\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_byte_ranges() {
+        let playlist = MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(1))
+            .segments(vec![
+                MediaSegment::builder()
+                    .duration(Duration::from_secs(1))
+                    .byte_range(0..100)
+                    .uri("video.mp4")
+                    .build()
+                    .unwrap(),
+                MediaSegment::builder()
+                    .duration(Duration::from_secs(1))
+                    .byte_range(100..200)
+                    .uri("video.mp4")
+                    .build()
+                    .unwrap(),
+                MediaSegment::builder()
+                    .duration(Duration::from_secs(1))
+                    .byte_range(250..300)
+                    .uri("video.mp4")
+                    .build()
+                    .unwrap(),
+                MediaSegment::builder()
+                    .duration(Duration::from_secs(1))
+                    .byte_range(0..50)
+                    .uri("audio.mp4")
+                    .build()
+                    .unwrap(),
+            ])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            playlist.validation_report().to_string(),
+            concat!(
+                "warning (segment 2): byte range 250..300 of video.mp4 is not contiguous",
+                " with the previous range ending at 200\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_dates() {
+        use std::convert::TryFrom;
+        use std::time::UNIX_EPOCH;
+
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-PROGRAM-DATE-TIME:1970-01-01T00:00:00Z\n",
+            "#EXTINF:10,\n",
+            "1.ts\n",
+            "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"1970-01-01T00:00:15Z\"\n",
+            "#EXTINF:10,\n",
+            "2.ts\n",
+        ))
+        .unwrap();
+
+        let mut validator = Validator::new();
+
+        validator.set_clock(|| UNIX_EPOCH + Duration::from_secs(10));
+        assert!(validator.validate_media_playlist(&playlist).is_ok());
+        assert_eq!(
+            validator.validate_media_playlist(&playlist).to_string(),
+            "warning (segment 1): the date range \"ad\" starts 5s in the future\n"
+        );
+
+        validator.set_clock(|| UNIX_EPOCH + Duration::from_secs(45));
+        assert!(validator.validate_media_playlist(&playlist).is_empty());
+
+        validator.set_clock(|| UNIX_EPOCH + Duration::from_secs(200));
+        assert_eq!(
+            validator.validate_media_playlist(&playlist).to_string(),
+            concat!(
+                "warning: the live playlist ends 180s before the current time,",
+                " which is more than 3 target durations\n"
+            )
+        );
+
+        let mut playlist = playlist;
+        playlist.has_end_list = true;
+        assert!(validator.validate_media_playlist(&playlist).is_empty());
+
+        validator.set_clock(|| UNIX_EPOCH);
+        assert_eq!(
+            validator
+                .validate_media_playlist(&playlist)
+                .warnings()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_date_ranges() {
+        use std::convert::TryFrom;
+
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-ENDLIST\n",
+            "#EXT-X-PROGRAM-DATE-TIME:1970-01-01T00:00:10Z\n",
+            "#EXT-X-DATERANGE:ID=\"early\",START-DATE=\"1970-01-01T00:00:05Z\"\n",
+            "#EXTINF:10,\n",
+            "1.ts\n",
+            "#EXT-X-DATERANGE:ID=\"a\",CLASS=\"ad\",START-DATE=\"1970-01-01T00:00:20Z\",END-ON-NEXT=YES\n",
+            "#EXTINF:10,\n",
+            "2.ts\n",
+            "#EXT-X-DATERANGE:ID=\"b\",CLASS=\"ad\",START-DATE=\"1970-01-01T00:00:30Z\",END-ON-NEXT=YES\n",
+            "#EXTINF:10,\n",
+            "3.ts\n",
+            "#EXT-X-DATERANGE:ID=\"late\",START-DATE=\"1970-01-01T00:01:00Z\"\n",
+            "#EXTINF:10,\n",
+            "4.ts\n",
+        ))
+        .unwrap();
+
+        let mut validator = Validator::new();
+        validator.set_clock(|| std::time::UNIX_EPOCH + Duration::from_secs(60));
+
+        assert_eq!(
+            validator.validate_media_playlist(&playlist).to_string(),
+            concat!(
+                "warning (segment 0): the date range \"early\" starts 5s before the first program date time\n",
+                "warning (segment 2): the date range \"b\" has END-ON-NEXT, but no following date range of the same class\n",
+                "warning (segment 3): the date range \"late\" starts 10s after the end of the playlist\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_cues() {
+        use std::convert::TryFrom;
+
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-DATERANGE:ID=\"pre\",CLASS=\"com.apple.hls.interstitial\",CUE=\"PRE\"\n",
+            "#EXTINF:10,\n",
+            "1.ts\n",
+            "#EXT-X-DATERANGE:ID=\"post\",CUE=\"POST\"\n",
+            "#EXTINF:10,\n",
+            "2.ts\n",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            Validator::new().validate_media_playlist(&playlist).to_string(),
+            "warning (segment 1): the date range \"post\" has a CUE attribute, but is not an interstitial\n"
+        );
+    }
+
+    #[test]
+    fn test_low_latency() {
+        use std::convert::TryFrom;
+
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:2\n",
+            "#EXT-X-SERVER-CONTROL:HOLD-BACK=5.0,PART-HOLD-BACK=0.9\n",
+            "#EXT-X-PART-INF:PART-TARGET=0.5\n",
+            "#EXT-X-PART:DURATION=0.5,URI=\"1.0.mp4\"\n",
+            "#EXT-X-PART:DURATION=0.3,URI=\"1.1.mp4\"\n",
+            "#EXT-X-PART:DURATION=0.3,URI=\"1.2.mp4\",INDEPENDENT=YES\n",
+            "#EXT-X-PART:DURATION=0.2,URI=\"1.3.mp4\"\n",
+            "#EXTINF:1.3,\n",
+            "1.mp4\n",
+            "#EXT-X-PART:DURATION=0.6,URI=\"2.0.mp4\"\n",
+            "#EXT-X-PART:DURATION=0.1,URI=\"2.1.mp4\"\n",
+        );
+
+        let playlist = MediaPlaylist::try_from(input).unwrap();
+
+        assert_eq!(
+            Validator::new()
+                .validate_media_playlist(&playlist)
+                .to_string(),
+            concat!(
+                "error: HOLD-BACK 5s is less than three times the target duration 2s\n",
+                "error: PART-HOLD-BACK 900ms is less than twice the PART-TARGET 500ms\n",
+                "warning (segment 0): partial segment 1 has a duration of 300ms, which is less ",
+                "than 85% of the PART-TARGET 500ms\n",
+                "error (segment 1): partial segment 0 has a duration of 600ms, which exceeds the ",
+                "PART-TARGET 500ms\n",
+            )
+        );
+
+        let input = input.replace("#EXT-X-PART-INF", "#X");
+        let playlist = MediaPlaylist::try_from(input.as_str()).unwrap();
+
+        assert_eq!(
+            Validator::new()
+                .validate_media_playlist(&playlist)
+                .to_string(),
+            concat!(
+                "error: HOLD-BACK 5s is less than three times the target duration 2s\n",
+                "error (segment 0): the playlist has partial segments, but no PART-TARGET\n",
+            )
+        );
+
+        let playlist = MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(2))
+            .allowable_excess_duration(Duration::from_secs(1))
+            .segments(vec![MediaSegment::builder()
+                .duration(Duration::from_millis(2600))
+                .uri("1.ts")
+                .build()
+                .unwrap()])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            Validator::new()
+                .validate_media_playlist(&playlist)
+                .to_string(),
+            "error (segment 0): segment duration 2.6s exceeds the target duration 2s\n"
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let report = Validator::new().validate_media_playlist(&subtitles(&[61]));
+
+        assert_eq!(
+            report.to_string(),
+            concat!(
+                "warning (segment 0): subtitle segment duration 61s exceeds 60s,",
+                " which is not supported by some players\n"
+            )
+        );
+    }
+}
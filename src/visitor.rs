@@ -0,0 +1,274 @@
+use std::borrow::Cow;
+use std::time::Duration;
+
+use crate::media_segment::PendingSegment;
+use crate::tags::{
+    CueMarker, ExtInf, ExtXByteRange, ExtXDateRange, ExtXKey, ExtXMap, ExtXPart, ExtXPreloadHint,
+    ExtXProgramDateTime, ExtXRenditionReport, ExtXServerControl, ExtXSkip, ExtXStart,
+};
+use crate::types::{PlaylistType, UriRef};
+use crate::MediaSegment;
+
+/// Visits the tags of a [`MediaPlaylist`] in the order in which they appear in
+/// the playlist.
+///
+/// Every method has an empty default implementation, so only the tags, that
+/// are of interest, have to be implemented. Tags that are added to the crate in
+/// the future will get a new method with a default implementation, which means
+/// that an existing visitor will not break.
+///
+/// The [`TagVisitor::visit_segment`] method is called before the tags of the
+/// [`MediaSegment`] are visited.
+///
+/// # Note
+///
+/// The [`MediaSegment::keys`] are visited for every segment, even if they
+/// would not be repeated, when the playlist is written.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::tags::ExtXKey;
+/// use hls_m3u8::{MediaPlaylist, TagVisitor};
+/// use std::convert::TryFrom;
+///
+/// #[derive(Default)]
+/// struct CountKeys(usize);
+///
+/// impl<'a> TagVisitor<'a> for CountKeys {
+///     fn visit_key(&mut self, key: &ExtXKey<'a>) {
+///         if key.is_some() {
+///             self.0 += 1;
+///         }
+///     }
+/// }
+///
+/// let playlist = MediaPlaylist::try_from(concat!(
+///     "#EXTM3U\n",
+///     "#EXT-X-TARGETDURATION:10\n",
+///     "#EXT-X-KEY:METHOD=AES-128,URI=\"https://www.example.com/key\"\n",
+///     "#EXTINF:10,\n",
+///     "1.ts\n",
+///     "#EXTINF:10,\n",
+///     "2.ts\n",
+/// ))?;
+///
+/// let mut visitor = CountKeys::default();
+/// playlist.visit(&mut visitor);
+///
+/// assert_eq!(visitor.0, 2);
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+///
+/// [`MediaPlaylist`]: crate::MediaPlaylist
+#[allow(unused_variables)]
+pub trait TagVisitor<'a> {
+    /// Visits the `EXT-X-TARGETDURATION` tag.
+    fn visit_target_duration(&mut self, target_duration: Duration) {}
+
+    /// Visits the `EXT-X-MEDIA-SEQUENCE` tag.
+    fn visit_media_sequence(&mut self, media_sequence: usize) {}
+
+    /// Visits the `EXT-X-DISCONTINUITY-SEQUENCE` tag.
+    fn visit_discontinuity_sequence(&mut self, discontinuity_sequence: usize) {}
+
+    /// Visits the `EXT-X-PLAYLIST-TYPE` tag.
+    fn visit_playlist_type(&mut self, playlist_type: PlaylistType) {}
+
+    /// Visits the legacy `EXT-X-ALLOW-CACHE` tag.
+    fn visit_allow_cache(&mut self, allow_cache: bool) {}
+
+    /// Visits the `EXT-X-I-FRAMES-ONLY` tag.
+    fn visit_i_frames_only(&mut self) {}
+
+    /// Visits the `EXT-X-INDEPENDENT-SEGMENTS` tag.
+    fn visit_independent_segments(&mut self) {}
+
+    /// Visits the `EXT-X-START` tag.
+    fn visit_start(&mut self, start: &ExtXStart) {}
+
+    /// Visits the `EXT-X-SERVER-CONTROL` tag.
+    fn visit_server_control(&mut self, server_control: &ExtXServerControl) {}
+
+    /// Visits the `PART-TARGET` of the `EXT-X-PART-INF` tag.
+    fn visit_part_inf(&mut self, part_target: Duration) {}
+
+    /// Visits the `EXT-X-SKIP` tag.
+    fn visit_skip(&mut self, skip: &ExtXSkip<'a>) {}
+
+    /// Visits a [`MediaSegment`], before its tags are visited.
+    fn visit_segment(&mut self, segment: &MediaSegment<'a>) {}
+
+    /// Visits an `EXT-X-KEY` tag.
+    fn visit_key(&mut self, key: &ExtXKey<'a>) {}
+
+    /// Visits an `EXT-X-CUE-OUT`, `EXT-X-CUE-OUT-CONT` or `EXT-X-CUE-IN` tag.
+    fn visit_cue_marker(&mut self, cue_marker: &CueMarker<'a>) {}
+
+    /// Visits an `EXT-X-MAP` tag.
+    fn visit_map(&mut self, map: &ExtXMap<'a>) {}
+
+    /// Visits an `EXT-X-BYTERANGE` tag.
+    fn visit_byte_range(&mut self, byte_range: &ExtXByteRange) {}
+
+    /// Visits an `EXT-X-DATERANGE` tag.
+    fn visit_date_range(&mut self, date_range: &ExtXDateRange<'a>) {}
+
+    /// Visits an `EXT-X-DISCONTINUITY` tag.
+    fn visit_discontinuity(&mut self) {}
+
+    /// Visits an `EXT-X-GAP` tag.
+    fn visit_gap(&mut self) {}
+
+    /// Visits the `EXT-X-BITRATE` of a segment (in kbit/s).
+    fn visit_bitrate(&mut self, bitrate: u64) {}
+
+    /// Visits an `EXT-X-PROGRAM-DATE-TIME` tag.
+    fn visit_program_date_time(&mut self, program_date_time: &ExtXProgramDateTime<'a>) {}
+
+    /// Visits an `EXT-X-PART` tag.
+    fn visit_part(&mut self, part: &ExtXPart<'a>) {}
+
+    /// Visits an `EXTINF` tag.
+    fn visit_inf(&mut self, inf: &ExtInf<'a>) {}
+
+    /// Visits the uri of a [`MediaSegment`].
+    fn visit_uri(&mut self, uri: &UriRef<'a>) {}
+
+    /// Visits the [`PendingSegment`], before its tags are visited.
+    fn visit_pending_segment(&mut self, segment: &PendingSegment<'a>) {}
+
+    /// Visits an `EXT-X-PRELOAD-HINT` tag.
+    fn visit_preload_hint(&mut self, preload_hint: &ExtXPreloadHint<'a>) {}
+
+    /// Visits an `EXT-X-RENDITION-REPORT` tag.
+    fn visit_rendition_report(&mut self, rendition_report: &ExtXRenditionReport<'a>) {}
+
+    /// Visits an unknown tag.
+    fn visit_unknown(&mut self, tag: &str) {}
+
+    /// Visits the `EXT-X-ENDLIST` tag.
+    fn visit_end_list(&mut self) {}
+}
+
+/// Like [`TagVisitor`], but the tags can be modified.
+///
+/// The [`TagVisitorMut::visit_segment`] and
+/// [`TagVisitorMut::visit_pending_segment`] methods are called before the tags
+/// of a segment are visited, so they can be used to add or remove tags of a
+/// segment.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::{MediaPlaylist, MediaSegment, TagVisitorMut};
+/// use std::convert::TryFrom;
+///
+/// struct StripDateRanges;
+///
+/// impl<'a> TagVisitorMut<'a> for StripDateRanges {
+///     fn visit_segment(&mut self, segment: &mut MediaSegment<'a>) { segment.date_range = None; }
+/// }
+///
+/// let mut playlist = MediaPlaylist::try_from(concat!(
+///     "#EXTM3U\n",
+///     "#EXT-X-TARGETDURATION:10\n",
+///     "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2020-01-01T00:00:00Z\"\n",
+///     "#EXTINF:10,\n",
+///     "1.ts\n",
+/// ))?;
+///
+/// playlist.visit_mut(&mut StripDateRanges);
+///
+/// assert_eq!(playlist.segments[0].date_range, None);
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+#[allow(unused_variables)]
+pub trait TagVisitorMut<'a> {
+    /// Visits the `EXT-X-TARGETDURATION` tag.
+    fn visit_target_duration(&mut self, target_duration: &mut Duration) {}
+
+    /// Visits the `EXT-X-MEDIA-SEQUENCE` tag.
+    fn visit_media_sequence(&mut self, media_sequence: &mut usize) {}
+
+    /// Visits the `EXT-X-DISCONTINUITY-SEQUENCE` tag.
+    fn visit_discontinuity_sequence(&mut self, discontinuity_sequence: &mut usize) {}
+
+    /// Visits the `EXT-X-PLAYLIST-TYPE` tag.
+    fn visit_playlist_type(&mut self, playlist_type: &mut PlaylistType) {}
+
+    /// Visits the legacy `EXT-X-ALLOW-CACHE` tag.
+    fn visit_allow_cache(&mut self, allow_cache: &mut bool) {}
+
+    /// Visits the `EXT-X-I-FRAMES-ONLY` tag.
+    fn visit_i_frames_only(&mut self) {}
+
+    /// Visits the `EXT-X-INDEPENDENT-SEGMENTS` tag.
+    fn visit_independent_segments(&mut self) {}
+
+    /// Visits the `EXT-X-START` tag.
+    fn visit_start(&mut self, start: &mut ExtXStart) {}
+
+    /// Visits the `EXT-X-SERVER-CONTROL` tag.
+    fn visit_server_control(&mut self, server_control: &mut ExtXServerControl) {}
+
+    /// Visits the `PART-TARGET` of the `EXT-X-PART-INF` tag.
+    fn visit_part_inf(&mut self, part_target: &mut Duration) {}
+
+    /// Visits the `EXT-X-SKIP` tag.
+    fn visit_skip(&mut self, skip: &mut ExtXSkip<'a>) {}
+
+    /// Visits a [`MediaSegment`], before its tags are visited.
+    fn visit_segment(&mut self, segment: &mut MediaSegment<'a>) {}
+
+    /// Visits an `EXT-X-KEY` tag.
+    fn visit_key(&mut self, key: &mut ExtXKey<'a>) {}
+
+    /// Visits an `EXT-X-CUE-OUT`, `EXT-X-CUE-OUT-CONT` or `EXT-X-CUE-IN` tag.
+    fn visit_cue_marker(&mut self, cue_marker: &mut CueMarker<'a>) {}
+
+    /// Visits an `EXT-X-MAP` tag.
+    fn visit_map(&mut self, map: &mut ExtXMap<'a>) {}
+
+    /// Visits an `EXT-X-BYTERANGE` tag.
+    fn visit_byte_range(&mut self, byte_range: &mut ExtXByteRange) {}
+
+    /// Visits an `EXT-X-DATERANGE` tag.
+    fn visit_date_range(&mut self, date_range: &mut ExtXDateRange<'a>) {}
+
+    /// Visits an `EXT-X-DISCONTINUITY` tag.
+    fn visit_discontinuity(&mut self) {}
+
+    /// Visits an `EXT-X-GAP` tag.
+    fn visit_gap(&mut self) {}
+
+    /// Visits the `EXT-X-BITRATE` of a segment (in kbit/s).
+    fn visit_bitrate(&mut self, bitrate: &mut u64) {}
+
+    /// Visits an `EXT-X-PROGRAM-DATE-TIME` tag.
+    fn visit_program_date_time(&mut self, program_date_time: &mut ExtXProgramDateTime<'a>) {}
+
+    /// Visits an `EXT-X-PART` tag.
+    fn visit_part(&mut self, part: &mut ExtXPart<'a>) {}
+
+    /// Visits an `EXTINF` tag.
+    fn visit_inf(&mut self, inf: &mut ExtInf<'a>) {}
+
+    /// Visits the uri of a [`MediaSegment`].
+    fn visit_uri(&mut self, uri: &mut UriRef<'a>) {}
+
+    /// Visits the [`PendingSegment`], before its tags are visited.
+    fn visit_pending_segment(&mut self, segment: &mut PendingSegment<'a>) {}
+
+    /// Visits an `EXT-X-PRELOAD-HINT` tag.
+    fn visit_preload_hint(&mut self, preload_hint: &mut ExtXPreloadHint<'a>) {}
+
+    /// Visits an `EXT-X-RENDITION-REPORT` tag.
+    fn visit_rendition_report(&mut self, rendition_report: &mut ExtXRenditionReport<'a>) {}
+
+    /// Visits an unknown tag.
+    fn visit_unknown(&mut self, tag: &mut Cow<'a, str>) {}
+
+    /// Visits the `EXT-X-ENDLIST` tag.
+    fn visit_end_list(&mut self) {}
+}
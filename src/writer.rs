@@ -0,0 +1,1973 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::panic::RefUnwindSafe;
+use std::sync::Arc;
+use std::time::Duration;
+
+use shorthand::ShortHand;
+
+use crate::attribute::AttributePairs;
+use crate::tags::ExtXProgramDateTime;
+use crate::types::{Feature, ProtocolVersion};
+use crate::{
+    Error, MasterPlaylist, MediaPlaylist, RequiredVersion, Severity, ValidationReport,
+    VersionReason,
+};
+
+/// Decides when the `#EXT-X-KEY` tags of a [`MediaPlaylist`] are written.
+///
+/// An [`ExtXKey`] applies to every [`MediaSegment`] between it and the next
+/// [`ExtXKey`] with the same [`KeyFormat`], so it is usually enough to
+/// write a key once. Some clients (and CDNs) only join a stream correctly
+/// if the key is repeated in front of every segment.
+///
+/// [`ExtXKey`]: crate::tags::ExtXKey
+/// [`MediaSegment`]: crate::MediaSegment
+/// [`KeyFormat`]: crate::types::KeyFormat
+#[non_exhaustive]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum KeyWritePolicy {
+    /// An encrypting key is only written, if it differs from the key, that is
+    /// currently in effect for its [`KeyFormat`]. Every empty key in
+    /// [`MediaSegment::keys`] is written.
+    ///
+    /// This is the output of [`Display`] and the default.
+    ///
+    /// [`KeyFormat`]: crate::types::KeyFormat
+    /// [`MediaSegment::keys`]: crate::MediaSegment::keys
+    /// [`Display`]: core::fmt::Display
+    #[default]
+    Changed,
+    /// Like [`KeyWritePolicy::Changed`], but an empty key is only written for
+    /// the first unencrypted segment after an encrypted one.
+    ///
+    /// This produces the smallest output.
+    Dedup,
+    /// The keys of every encrypted segment are repeated in front of the
+    /// segment. An empty key is only written for the first unencrypted
+    /// segment after an encrypted one.
+    PerSegment,
+    /// Every key in [`MediaSegment::keys`] is written in front of its
+    /// segment, including empty keys.
+    ///
+    /// [`MediaSegment::keys`]: crate::MediaSegment::keys
+    Always,
+}
+
+/// The compression of the bytes returned by [`MediaPlaylist::to_bytes`].
+///
+/// Live playlists are requested very often and compress well, so an origin
+/// can store the compressed body and serve it with `Content-Encoding: gzip`.
+/// Gzip compression requires the `flate2` feature.
+#[non_exhaustive]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Compression {
+    /// The playlist is not compressed.
+    ///
+    /// This is the default.
+    #[default]
+    None,
+    /// The playlist is compressed with gzip at the given level, which ranges
+    /// from `0` (no compression) to `9` (best compression).
+    #[cfg(feature = "flate2")]
+    Gzip(u32),
+}
+
+impl Compression {
+    pub(crate) fn compress(self, input: Vec<u8>) -> crate::Result<Vec<u8>> {
+        match self {
+            Self::None => Ok(input),
+            #[cfg(feature = "flate2")]
+            Self::Gzip(level) => {
+                use std::io::Write;
+
+                if level > 9 {
+                    return Err(Error::custom(format_args!(
+                        "the gzip level must be between 0 and 9, but is {}",
+                        level
+                    )));
+                }
+
+                let mut encoder = flate2::write::GzEncoder::new(
+                    Vec::with_capacity(input.len() / 4),
+                    flate2::Compression::new(level),
+                );
+
+                encoder.write_all(&input).map_err(Error::custom)?;
+                encoder.finish().map_err(Error::custom)
+            }
+        }
+    }
+}
+
+/// Decides how the durations of the `#EXTINF` tags are written.
+///
+/// When a [`MediaPlaylist`] is parsed, the precision of the input is kept in
+/// [`MediaPlaylist::duration_precision`], so the playlist is written like it
+/// has been read.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::DurationPrecision;
+/// use std::time::Duration;
+///
+/// let duration = Duration::from_millis(4500);
+///
+/// assert_eq!(DurationPrecision::Shortest.format(duration), "4.5");
+/// assert_eq!(DurationPrecision::Decimals(3).format(duration), "4.500");
+/// assert_eq!(DurationPrecision::Decimals(1).format(duration), "4.5");
+/// ```
+#[non_exhaustive]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DurationPrecision {
+    /// The shortest representation, that is parsed to the same duration, is
+    /// written (`4`, `4.5`, `4.004`).
+    ///
+    /// This is the default.
+    #[default]
+    Shortest,
+    /// The duration is always written with the given number of decimal
+    /// places (`4.000`, `4.500`, `4.004`).
+    Decimals(u8),
+}
+
+impl DurationPrecision {
+    /// Returns the duration in seconds, as it would be written with this
+    /// precision.
+    #[must_use]
+    pub fn format(self, duration: Duration) -> String {
+        match self {
+            Self::Shortest => duration.as_secs_f64().to_string(),
+            Self::Decimals(decimals) => {
+                format!("{:.*}", usize::from(decimals), duration.as_secs_f64())
+            }
+        }
+    }
+
+    /// Returns the precision, that reproduces the durations of all
+    /// `#EXTINF` tags in the `input`.
+    ///
+    /// If [`DurationPrecision::Shortest`] reproduces the durations or they
+    /// have different numbers of decimal places, it is returned.
+    pub(crate) fn infer(input: &str) -> Self {
+        let mut decimals = None;
+        let mut is_shortest = true;
+
+        let durations = input
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("#EXTINF:"))
+            .map(|value| value.split(',').next().unwrap_or(value).trim());
+
+        for duration in durations {
+            let count = duration.find('.').map_or(0, |i| duration.len() - i - 1);
+
+            if decimals.is_some_and(|decimals| decimals != count) {
+                return Self::Shortest;
+            }
+
+            decimals = Some(count);
+            is_shortest &= duration
+                .parse::<f64>()
+                .is_ok_and(|value| value.to_string() == duration);
+        }
+
+        match decimals.and_then(|value| u8::try_from(value).ok()) {
+            Some(decimals) if !is_shortest => Self::Decimals(decimals),
+            _ => Self::Shortest,
+        }
+    }
+}
+
+/// Decimal durations require [`ProtocolVersion::V3`].
+impl RequiredVersion for DurationPrecision {
+    fn required_version(&self) -> ProtocolVersion {
+        match self {
+            Self::Decimals(decimals) if *decimals > 0 => ProtocolVersion::V3,
+            _ => ProtocolVersion::V1,
+        }
+    }
+
+    fn required_version_reasons(&self) -> Vec<VersionReason> {
+        if self.required_version() == ProtocolVersion::V3 {
+            vec![VersionReason::from(Feature::DecimalDuration)]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Decides how the offset of a date-time in UTC is written.
+///
+/// Both forms are valid [rfc3339] date-times, but some players and validators
+/// only accept one of them.
+///
+/// [rfc3339]: https://tools.ietf.org/html/rfc3339
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UtcFormat {
+    /// The offset is written as `Z` (`2010-02-19T06:54:23.031Z`).
+    Z,
+    /// The offset is written as `+00:00` (`2010-02-19T06:54:23.031+00:00`).
+    Numeric,
+}
+
+/// Decides how the URIs of a playlist are written.
+///
+/// URIs are only rewritten when a playlist is written, so the same playlist
+/// can be served with different URIs (for example relative URIs to an edge
+/// server and absolute URIs to an origin server).
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::UriPolicy;
+///
+/// let policy = UriPolicy::Absolute("https://example.com/video/index.m3u8".into());
+/// assert_eq!(
+///     policy.apply("low/1.ts"),
+///     "https://example.com/video/low/1.ts"
+/// );
+/// assert_eq!(
+///     policy.apply("/other/1.ts"),
+///     "https://example.com/other/1.ts"
+/// );
+///
+/// let policy = UriPolicy::RelativeTo("https://example.com/video/index.m3u8".into());
+/// assert_eq!(
+///     policy.apply("https://example.com/video/low/1.ts"),
+///     "low/1.ts"
+/// );
+/// assert_eq!(
+///     policy.apply("https://example.com/other/1.ts"),
+///     "/other/1.ts"
+/// );
+/// assert_eq!(
+///     policy.apply("https://cdn.example.com/1.ts"),
+///     "https://cdn.example.com/1.ts"
+/// );
+/// ```
+#[non_exhaustive]
+#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UriPolicy {
+    /// The URIs are written as they are.
+    #[default]
+    AsIs,
+    /// URIs are written relative to the given base URI (usually the URI of
+    /// the playlist itself). URIs with a different origin are written as they
+    /// are.
+    RelativeTo(Cow<'static, str>),
+    /// Relative URIs are resolved against the given base URI (usually the URI
+    /// of the playlist itself) and written as absolute URIs.
+    Absolute(Cow<'static, str>),
+}
+
+impl UriPolicy {
+    /// Returns the URI as it would be written with this policy.
+    #[must_use]
+    pub fn apply<'a>(&self, uri: &'a str) -> Cow<'a, str> {
+        match self {
+            Self::AsIs => Cow::Borrowed(uri),
+            Self::RelativeTo(base) => Cow::Borrowed(relativize(base, uri)),
+            Self::Absolute(base) => resolve(base, uri),
+        }
+    }
+}
+
+/// Returns the length of the scheme (including the `:`) or `None` if the uri
+/// does not have a scheme (it is a relative reference).
+fn scheme_len(uri: &str) -> Option<usize> {
+    let end = uri.find([':', '/', '?', '#'])?;
+
+    if uri[end..].starts_with(':')
+        && uri[..end].starts_with(|c: char| c.is_ascii_alphabetic())
+        && uri[..end]
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+    {
+        Some(end + 1)
+    } else {
+        None
+    }
+}
+
+/// Returns the scheme and the authority of an uri (`https://example.com`).
+fn origin(uri: &str) -> &str {
+    let scheme = scheme_len(uri).unwrap_or(0);
+
+    if uri[scheme..].starts_with("//") {
+        let authority = scheme + 2;
+        let end = uri[authority..]
+            .find(['/', '?', '#'])
+            .map_or(uri.len(), |i| i + authority);
+
+        &uri[..end]
+    } else {
+        &uri[..scheme]
+    }
+}
+
+/// Returns everything of the uri up to and including the last `/` of the path.
+fn directory(uri: &str) -> &str {
+    let origin = origin(uri).len();
+    let path = uri[origin..]
+        .find(['?', '#'])
+        .map_or(uri, |i| &uri[..origin + i]);
+
+    path.rfind('/')
+        .filter(|i| *i >= origin)
+        .map_or(&path[..origin], |i| &path[..=i])
+}
+
+/// Removes `.` and `..` segments from a path, as described in
+/// [rfc3986#section-5.2.4](https://tools.ietf.org/html/rfc3986#section-5.2.4).
+fn remove_dot_segments(path: &str) -> String {
+    let mut output = Vec::new();
+    let mut segments = path.split('/').peekable();
+
+    while let Some(segment) = segments.next() {
+        let is_last = segments.peek().is_none();
+
+        match segment {
+            "." => {}
+            ".." => {
+                // the leading empty segment of an absolute path must stay:
+                if output.len() > 1 || output.first().is_some_and(|s: &&str| !s.is_empty()) {
+                    output.pop();
+                }
+            }
+            _ => {
+                output.push(segment);
+                continue;
+            }
+        }
+
+        if is_last {
+            output.push("");
+        }
+    }
+
+    output.join("/")
+}
+
+fn resolve<'a>(base: &str, uri: &'a str) -> Cow<'a, str> {
+    if scheme_len(uri).is_some() {
+        return Cow::Borrowed(uri);
+    }
+
+    let (path, rest) = uri.split_at(uri.find(['?', '#']).unwrap_or(uri.len()));
+
+    if uri.starts_with("//") {
+        Cow::Owned(format!("{}{}", &base[..scheme_len(base).unwrap_or(0)], uri))
+    } else if path.starts_with('/') {
+        Cow::Owned(format!(
+            "{}{}{}",
+            origin(base),
+            remove_dot_segments(path),
+            rest
+        ))
+    } else if path.is_empty() {
+        let mut base = base.find('#').map_or(base, |i| &base[..i]);
+
+        if rest.starts_with('?') {
+            base = base.find('?').map_or(base, |i| &base[..i]);
+        }
+
+        Cow::Owned(format!("{}{}", base, rest))
+    } else {
+        let directory = directory(base);
+        let origin = origin(directory);
+        let mut merged = directory[origin.len()..].to_string();
+
+        if merged.is_empty() && !origin.is_empty() {
+            merged.push('/');
+        }
+
+        merged.push_str(path);
+
+        Cow::Owned(format!(
+            "{}{}{}",
+            origin,
+            remove_dot_segments(&merged),
+            rest
+        ))
+    }
+}
+
+fn relativize<'a>(base: &str, uri: &'a str) -> &'a str {
+    let directory = directory(base);
+    let origin = origin(base);
+
+    // the directory has to end at a path segment boundary, otherwise
+    // `https://example.com` would be a prefix of `https://example.com.evil/`:
+    let rest = Some(directory)
+        .filter(|directory| directory.ends_with('/'))
+        .and_then(|directory| uri.strip_prefix(directory))
+        .filter(|rest| !rest.is_empty());
+
+    if let Some(rest) = rest {
+        rest
+    } else if origin.contains("//")
+        && uri.starts_with(origin)
+        && uri[origin.len()..].starts_with('/')
+    {
+        &uri[origin.len()..]
+    } else {
+        uri
+    }
+}
+
+/// The kind of tag (or line), an uri belongs to.
+///
+/// This is passed to the hook of [`Writer::set_sign_uri`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UriKind {
+    /// The uri of a [`MediaSegment`](crate::MediaSegment).
+    Segment,
+    /// The uri of an [`ExtXMap`](crate::tags::ExtXMap).
+    Map,
+    /// The uri of an [`ExtXKey`](crate::tags::ExtXKey).
+    Key,
+    /// The uri of an [`ExtXSessionKey`](crate::tags::ExtXSessionKey).
+    SessionKey,
+    /// The uri of an [`ExtXSessionData`](crate::tags::ExtXSessionData).
+    SessionData,
+    /// The uri of an [`ExtXMedia`](crate::tags::ExtXMedia).
+    Media,
+    /// The uri of a [`VariantStream::ExtXStreamInf`].
+    ///
+    /// [`VariantStream::ExtXStreamInf`]:
+    /// crate::tags::VariantStream::ExtXStreamInf
+    StreamInf,
+    /// The uri of a [`VariantStream::ExtXIFrame`].
+    ///
+    /// [`VariantStream::ExtXIFrame`]: crate::tags::VariantStream::ExtXIFrame
+    IFrame,
+}
+
+/// Normalizes the URIs of a playlist.
+///
+/// A normalizer can be set with [`Writer::set_uri_normalizer`], which
+/// applies it to every uri, that is written, and with
+/// [`Validator::set_uri_normalizer`], which warns about every uri, that is
+/// not normalized.
+///
+/// Parsing is not affected by a normalizer: a parsed playlist keeps its uris
+/// exactly as they appear in the input (this crate does not resolve uris
+/// against a base uri), so they are only normalized when the playlist is
+/// written again.
+///
+/// It is implemented for closures, so custom rules (like enforcing `https`)
+/// can be combined with the [`DefaultUriNormalizer`]:
+///
+/// ```
+/// use hls_m3u8::{DefaultUriNormalizer, UriKind, UriNormalizer};
+///
+/// let normalizer = |kind: UriKind, uri: &str| {
+///     let uri = DefaultUriNormalizer.normalize(kind, uri);
+///
+///     match uri.strip_prefix("http://") {
+///         Some(rest) => format!("https://{}", rest),
+///         None => uri.into_owned(),
+///     }
+/// };
+///
+/// assert_eq!(
+///     normalizer.normalize(UriKind::Segment, "http://example.com/a/../1.ts"),
+///     "https://example.com/1.ts"
+/// );
+/// ```
+///
+/// [`Validator::set_uri_normalizer`]: crate::Validator::set_uri_normalizer
+pub trait UriNormalizer: Send + Sync + RefUnwindSafe {
+    /// Returns the normalized form of the `uri`.
+    fn normalize<'a>(&self, kind: UriKind, uri: &'a str) -> Cow<'a, str>;
+}
+
+impl<F> UriNormalizer for F
+where
+    F: Fn(UriKind, &str) -> String + Send + Sync + RefUnwindSafe,
+{
+    fn normalize<'a>(&self, kind: UriKind, uri: &'a str) -> Cow<'a, str> {
+        Cow::Owned(self(kind, uri))
+    }
+}
+
+/// A [`UriNormalizer`], that removes the `.` and `..` segments from the path
+/// of absolute uris and percent-encodes all characters, that are not allowed
+/// in an uri (like spaces), as described in [rfc3986#section-6.2.2].
+///
+/// The dot segments of a relative path (`../1.ts`) are kept, because they
+/// can only be removed after the uri has been resolved.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::{DefaultUriNormalizer, UriKind, UriNormalizer};
+///
+/// assert_eq!(
+///     DefaultUriNormalizer.normalize(UriKind::Segment, "https://example.com/a/./b/../1 2.ts"),
+///     "https://example.com/a/1%202.ts"
+/// );
+/// assert_eq!(
+///     DefaultUriNormalizer.normalize(UriKind::Segment, "../low/1.ts?a=%c3%a4"),
+///     "../low/1.ts?a=%C3%A4"
+/// );
+/// ```
+///
+/// [rfc3986#section-6.2.2]: https://tools.ietf.org/html/rfc3986#section-6.2.2
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DefaultUriNormalizer;
+
+impl UriNormalizer for DefaultUriNormalizer {
+    fn normalize<'a>(&self, _: UriKind, uri: &'a str) -> Cow<'a, str> {
+        match normalize_path(uri) {
+            Cow::Borrowed(uri) => percent_encode(uri),
+            Cow::Owned(uri) => Cow::Owned(percent_encode(&uri).into_owned()),
+        }
+    }
+}
+
+/// Removes the dot segments from the path of an absolute uri or an absolute
+/// path.
+fn normalize_path(uri: &str) -> Cow<'_, str> {
+    let origin = origin(uri).len();
+    let end = uri[origin..]
+        .find(['?', '#'])
+        .map_or(uri.len(), |i| i + origin);
+    let path = &uri[origin..end];
+
+    if (origin == 0 && !path.starts_with('/'))
+        || !path
+            .split('/')
+            .any(|segment| segment == "." || segment == "..")
+    {
+        return Cow::Borrowed(uri);
+    }
+
+    Cow::Owned(format!(
+        "{}{}{}",
+        &uri[..origin],
+        remove_dot_segments(path),
+        &uri[end..]
+    ))
+}
+
+/// Returns `true`, if the byte can appear in an uri without being
+/// percent-encoded (the unreserved and the reserved characters of
+/// [rfc3986#section-2](https://tools.ietf.org/html/rfc3986#section-2)).
+fn is_uri_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"-._~:/?#[]@!$&'()*+,;=".contains(&byte)
+}
+
+/// Percent-encodes all bytes, that are not allowed in an uri, and writes the
+/// hexadecimal digits of percent-encoded bytes in uppercase.
+fn percent_encode(uri: &str) -> Cow<'_, str> {
+    let bytes = uri.as_bytes();
+    let is_encoded = |i: usize| {
+        bytes
+            .get(i + 1..i + 3)
+            .is_some_and(|digits| digits.iter().all(u8::is_ascii_hexdigit))
+    };
+
+    if bytes.iter().enumerate().all(|(i, byte)| {
+        is_uri_byte(*byte)
+            || (*byte == b'%'
+                && is_encoded(i)
+                && !bytes[i + 1..i + 3].iter().any(u8::is_ascii_lowercase))
+    }) {
+        return Cow::Borrowed(uri);
+    }
+
+    let mut result = String::with_capacity(uri.len() + 8);
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' && is_encoded(i) {
+            result.push('%');
+            result.push(char::from(bytes[i + 1].to_ascii_uppercase()));
+            result.push(char::from(bytes[i + 2].to_ascii_uppercase()));
+            i += 3;
+            continue;
+        }
+
+        if is_uri_byte(bytes[i]) {
+            result.push(char::from(bytes[i]));
+        } else {
+            result.push_str(&format!("%{:02X}", bytes[i]));
+        }
+
+        i += 1;
+    }
+
+    Cow::Owned(result)
+}
+
+type SignUri = Arc<dyn Fn(UriKind, &str) -> String + Send + Sync>;
+
+/// Writes playlists with a non-default output format.
+///
+/// The [`Display`] implementations of the playlists use
+/// [`Writer::default`].
+///
+/// # Example
+///
+/// ```
+/// # use hls_m3u8::MediaPlaylist;
+/// use hls_m3u8::{KeyWritePolicy, Writer};
+/// use std::time::Duration;
+///
+/// let playlist = MediaPlaylist::builder()
+///     .target_duration(Duration::from_secs(10))
+///     .segments(vec![])
+///     .build()?;
+///
+/// let mut writer = Writer::new();
+/// writer.set_key_policy(KeyWritePolicy::PerSegment);
+///
+/// let mut output = String::new();
+/// writer.write_media_playlist(&mut output, &playlist)?;
+///
+/// assert_eq!(output, playlist.to_string());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// [`Display`]: core::fmt::Display
+#[derive(ShortHand, Default, Clone)]
+#[shorthand(enable(must_use))]
+#[non_exhaustive]
+pub struct Writer {
+    /// When the `#EXT-X-KEY` tags of a [`MediaPlaylist`] should be written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::{KeyWritePolicy, Writer};
+    ///
+    /// let mut writer = Writer::new();
+    /// # assert_eq!(writer.key_policy(), KeyWritePolicy::Changed);
+    /// writer.set_key_policy(KeyWritePolicy::Always);
+    ///
+    /// assert_eq!(writer.key_policy(), KeyWritePolicy::Always);
+    /// ```
+    #[shorthand(enable(copy))]
+    key_policy: KeyWritePolicy,
+    /// How the URIs of a playlist should be written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::{UriPolicy, Writer};
+    ///
+    /// let mut writer = Writer::new();
+    /// # assert_eq!(writer.uri_policy(), &UriPolicy::AsIs);
+    /// writer.set_uri_policy(UriPolicy::Absolute("https://example.com/".into()));
+    ///
+    /// assert_eq!(
+    ///     writer.uri_policy(),
+    ///     &UriPolicy::Absolute("https://example.com/".into())
+    /// );
+    /// ```
+    uri_policy: UriPolicy,
+    /// The [`ProtocolVersion`], that is written in the `#EXT-X-VERSION` tag
+    /// instead of the required version.
+    ///
+    /// Writing a playlist fails, if the override is lower than the
+    /// [`ProtocolVersion`], the playlist requires. The error lists the
+    /// features, that have to be removed (see [`Error::version_reasons`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::types::ProtocolVersion;
+    /// use hls_m3u8::{MediaSegment, Writer};
+    /// use std::time::Duration;
+    ///
+    /// let playlist = MediaPlaylist::builder()
+    ///     .target_duration(Duration::from_secs(10))
+    ///     .segments(vec![MediaSegment::builder()
+    ///         .duration(Duration::from_secs_f64(9.5))
+    ///         .uri("1.ts")
+    ///         .build()?])
+    ///     .build()?;
+    ///
+    /// let mut writer = Writer::new();
+    /// writer.set_version_override(Some(ProtocolVersion::V2));
+    ///
+    /// let error = writer.media_playlist_to_string(&playlist).unwrap_err();
+    /// assert_eq!(
+    ///     error.version_reasons()[0].to_string(),
+    ///     "decimal EXTINF duration requires V3"
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[shorthand(enable(copy))]
+    version_override: Option<ProtocolVersion>,
+    /// The maximum length of a line in bytes.
+    ///
+    /// Some legacy middleboxes truncate long lines (usually the
+    /// `#EXT-X-STREAM-INF` tags). Lines, that are longer, are shortened by
+    /// removing the [`Writer::droppable_attributes`] and the lines, that are
+    /// still too long, can be found with [`Writer::check_line_lengths`].
+    ///
+    /// By default the length is not limited.
+    #[shorthand(enable(copy))]
+    max_line_length: Option<usize>,
+    /// How the durations of the `#EXTINF` tags are written instead of the
+    /// [`MediaPlaylist::duration_precision`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::{DurationPrecision, MediaSegment, Writer};
+    /// use std::time::Duration;
+    ///
+    /// let playlist = MediaPlaylist::builder()
+    ///     .target_duration(Duration::from_secs(10))
+    ///     .segments(vec![MediaSegment::builder()
+    ///         .duration(Duration::from_secs_f64(9.5))
+    ///         .uri("1.ts")
+    ///         .build()?])
+    ///     .build()?;
+    ///
+    /// let mut writer = Writer::new();
+    /// writer.set_duration_precision(Some(DurationPrecision::Decimals(3)));
+    ///
+    /// assert!(writer
+    ///     .media_playlist_to_string(&playlist)?
+    ///     .contains("\n#EXTINF:9.500,\n"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[shorthand(enable(copy))]
+    duration_precision: Option<DurationPrecision>,
+    /// The names of the attributes, that are removed (in this order) from a
+    /// tag, that is longer than the [`Writer::max_line_length`], until it
+    /// fits.
+    ///
+    /// Only optional attributes should be listed here (for example
+    /// `"FRAME-RATE"` or `"AVERAGE-BANDWIDTH"`), because the attributes are
+    /// removed without checking, whether the tag is still valid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::tags::VariantStream;
+    /// use hls_m3u8::types::{StreamData, UFloat};
+    /// use hls_m3u8::{MasterPlaylist, Writer};
+    ///
+    /// let playlist = MasterPlaylist::builder()
+    ///     .variant_streams(vec![VariantStream::ExtXStreamInf {
+    ///         uri: "low.m3u8".into(),
+    ///         frame_rate: Some(UFloat::new(25.0)),
+    ///         audio: None,
+    ///         subtitles: None,
+    ///         closed_captions: None,
+    ///         stream_data: StreamData::builder()
+    ///             .bandwidth(150_000)
+    ///             .average_bandwidth(140_000)
+    ///             .build()?,
+    ///     }])
+    ///     .build()?;
+    ///
+    /// let mut writer = Writer::new();
+    /// writer.set_max_line_length(Some(60));
+    /// writer.set_droppable_attributes(vec!["FRAME-RATE".into(), "AVERAGE-BANDWIDTH".into()]);
+    ///
+    /// let output = writer.master_playlist_to_string(&playlist)?;
+    ///
+    /// assert!(output.contains("\n#EXT-X-STREAM-INF:BANDWIDTH=150000,AVERAGE-BANDWIDTH=140000\n"));
+    /// assert!(writer.check_line_lengths(&output).is_empty());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    droppable_attributes: Vec<Cow<'static, str>>,
+    /// The number of decimal places of the seconds of the
+    /// `#EXT-X-PROGRAM-DATE-TIME` tags.
+    ///
+    /// Missing decimal places are filled with zeros and additional decimal
+    /// places are truncated. By default the date-times are written like they
+    /// have been read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::Writer;
+    /// use std::convert::TryFrom;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXT-X-PROGRAM-DATE-TIME:2010-02-19T14:54:23+08:00\n",
+    ///     "#EXTINF:10,\n",
+    ///     "1.ts\n",
+    /// ))?;
+    ///
+    /// let mut writer = Writer::new();
+    /// writer.set_date_time_decimals(Some(3));
+    ///
+    /// assert!(writer
+    ///     .media_playlist_to_string(&playlist)?
+    ///     .contains("\n#EXT-X-PROGRAM-DATE-TIME:2010-02-19T14:54:23.000+08:00\n"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[shorthand(enable(copy))]
+    date_time_decimals: Option<u8>,
+    /// How the offset of the `#EXT-X-PROGRAM-DATE-TIME` tags in UTC is
+    /// written. Other offsets are not changed.
+    ///
+    /// By default the date-times are written like they have been read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::{UtcFormat, Writer};
+    /// use std::convert::TryFrom;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXT-X-PROGRAM-DATE-TIME:2010-02-19T06:54:23.031Z\n",
+    ///     "#EXTINF:10,\n",
+    ///     "1.ts\n",
+    /// ))?;
+    ///
+    /// let mut writer = Writer::new();
+    /// writer.set_utc_format(Some(UtcFormat::Numeric));
+    ///
+    /// assert!(writer
+    ///     .media_playlist_to_string(&playlist)?
+    ///     .contains("\n#EXT-X-PROGRAM-DATE-TIME:2010-02-19T06:54:23.031+00:00\n"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[shorthand(enable(copy))]
+    utc_format: Option<UtcFormat>,
+    /// The values, that are written instead of the placeholders in the
+    /// playlist.
+    ///
+    /// A placeholder is the name of the value in curly braces (like
+    /// `{TOKEN}`) and can appear anywhere in the playlist (for example in a
+    /// uri). Placeholders without a value are written unchanged. This allows
+    /// to keep a single playlist as a template, that is personalized for every
+    /// request.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::{MediaSegment, Writer};
+    /// use std::time::Duration;
+    ///
+    /// let playlist = MediaPlaylist::builder()
+    ///     .target_duration(Duration::from_secs(10))
+    ///     .segments(vec![MediaSegment::builder()
+    ///         .duration(Duration::from_secs(10))
+    ///         .uri("1.ts?token={TOKEN}&region={REGION}")
+    ///         .build()?])
+    ///     .build()?;
+    ///
+    /// let mut writer = Writer::new();
+    /// writer.insert_placeholder("TOKEN", "abc");
+    ///
+    /// assert!(writer
+    ///     .media_playlist_to_string(&playlist)?
+    ///     .contains("\n1.ts?token=abc&region={REGION}\n"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    placeholders: BTreeMap<Cow<'static, str>, Cow<'static, str>>,
+    #[shorthand(enable(skip))]
+    uri_normalizer: Option<Arc<dyn UriNormalizer>>,
+    #[shorthand(enable(skip))]
+    sign_uri: Option<SignUri>,
+}
+
+impl fmt::Debug for Writer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Writer")
+            .field("key_policy", &self.key_policy)
+            .field("uri_policy", &self.uri_policy)
+            .field("version_override", &self.version_override)
+            .field("max_line_length", &self.max_line_length)
+            .field("duration_precision", &self.duration_precision)
+            .field("droppable_attributes", &self.droppable_attributes)
+            .field("date_time_decimals", &self.date_time_decimals)
+            .field("utc_format", &self.utc_format)
+            .field("placeholders", &self.placeholders)
+            .field(
+                "uri_normalizer",
+                &self.uri_normalizer.as_ref().map(|_| ".."),
+            )
+            .field("sign_uri", &self.sign_uri.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+impl Writer {
+    /// Makes a new [`Writer`] with the default settings.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::Writer;
+    /// let writer = Writer::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self { Self::default() }
+
+    /// Writes the [`MediaPlaylist`] to the provided [`fmt::Write`].
+    ///
+    /// # Errors
+    ///
+    /// This function fails, if writing to `output` fails, if the
+    /// [`Writer::version_override`] is lower than the required version, if
+    /// the value of a [`Writer::placeholders`] contains a line break or a
+    /// double quote or if the numbers of the segments have a gap (see
+    /// [`MediaPlaylist::merge_updates`]).
+    pub fn write_media_playlist<W: fmt::Write>(
+        &self,
+        output: &mut W,
+        playlist: &MediaPlaylist<'_>,
+    ) -> crate::Result<()> {
+        self.check_version(playlist)?;
+        playlist.check_numbering()?;
+        self.check_placeholders()?;
+
+        if self.rewrites_lines() {
+            let mut output = LineRewriter::new(output, self);
+            playlist
+                .write_to(&mut output, self)
+                .and_then(|()| output.finish())
+        } else {
+            playlist.write_to(output, self)
+        }
+        .map_err(Error::custom)
+    }
+
+    /// Writes the [`MediaPlaylist`] to a [`String`].
+    ///
+    /// # Errors
+    ///
+    /// This function fails, if the [`Writer::version_override`] is lower than
+    /// the required version, if the value of a [`Writer::placeholders`]
+    /// contains a line break or a double quote or if the numbers of the
+    /// segments have a gap (see [`MediaPlaylist::merge_updates`]).
+    pub fn media_playlist_to_string(&self, playlist: &MediaPlaylist<'_>) -> crate::Result<String> {
+        let mut result = String::new();
+        self.write_media_playlist(&mut result, playlist)?;
+        Ok(result)
+    }
+
+    /// Writes the [`MasterPlaylist`] to the provided [`fmt::Write`].
+    ///
+    /// # Errors
+    ///
+    /// This function fails, if writing to `output` fails, if the
+    /// [`Writer::version_override`] is lower than the required version or if
+    /// the value of a [`Writer::placeholders`] contains a line break or a
+    /// double quote.
+    pub fn write_master_playlist<W: fmt::Write>(
+        &self,
+        output: &mut W,
+        playlist: &MasterPlaylist<'_>,
+    ) -> crate::Result<()> {
+        self.check_version(playlist)?;
+        self.check_placeholders()?;
+
+        if self.rewrites_lines() {
+            let mut output = LineRewriter::new(output, self);
+            playlist
+                .write_to(&mut output, self)
+                .and_then(|()| output.finish())
+        } else {
+            playlist.write_to(output, self)
+        }
+        .map_err(Error::custom)
+    }
+
+    /// Writes the [`MasterPlaylist`] to a [`String`].
+    ///
+    /// # Errors
+    ///
+    /// This function fails, if the [`Writer::version_override`] is lower than
+    /// the required version or if the value of a [`Writer::placeholders`]
+    /// contains a line break or a double quote.
+    pub fn master_playlist_to_string(
+        &self,
+        playlist: &MasterPlaylist<'_>,
+    ) -> crate::Result<String> {
+        let mut result = String::new();
+        self.write_master_playlist(&mut result, playlist)?;
+        Ok(result)
+    }
+
+    fn check_version<T: RequiredVersion>(&self, playlist: &T) -> crate::Result<()> {
+        let required = playlist.required_version();
+
+        match self.version_override {
+            Some(specified) if specified < required => {
+                Err(Error::version_error(
+                    required,
+                    specified,
+                    playlist
+                        .required_version_reasons()
+                        .into_iter()
+                        .filter(|reason| reason.version() > specified)
+                        .collect(),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns a warning for every line of the `output`, that is longer than
+    /// the [`Writer::max_line_length`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::Writer;
+    ///
+    /// let mut writer = Writer::new();
+    /// writer.set_max_line_length(Some(16));
+    ///
+    /// let report = writer.check_line_lengths("#EXTM3U\n#EXT-X-TARGETDURATION:10\n");
+    /// assert_eq!(
+    ///     report.to_string(),
+    ///     "warning: line 2 is 24 bytes long, which is more than the maximum of 16 bytes\n"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn check_line_lengths(&self, output: &str) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        if let Some(max) = self.max_line_length {
+            for (i, line) in output.lines().enumerate() {
+                if line.len() > max {
+                    report.push(
+                        Severity::Warning,
+                        None,
+                        format!(
+                            "line {} is {} bytes long, which is more than the maximum of {} bytes",
+                            i + 1,
+                            line.len(),
+                            max
+                        ),
+                    );
+                }
+            }
+        }
+
+        report
+    }
+
+    /// Sets the value of a [`Writer::placeholders`].
+    pub fn insert_placeholder<K, V>(&mut self, name: K, value: V) -> &mut Self
+    where
+        K: Into<Cow<'static, str>>,
+        V: Into<Cow<'static, str>>,
+    {
+        self.placeholders.insert(name.into(), value.into());
+        self
+    }
+
+    /// Fails, if the value of a placeholder could end the line or the quoted
+    /// string, in which it is written.
+    fn check_placeholders(&self) -> crate::Result<()> {
+        for (name, value) in &self.placeholders {
+            if value.contains(['\n', '\r', '"']) {
+                return Err(Error::custom(format!(
+                    "the value of the placeholder {:?} contains a line break or a double quote: {:?}",
+                    name, value
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn rewrites_lines(&self) -> bool {
+        (self.max_line_length.is_some() && !self.droppable_attributes.is_empty())
+            || !self.placeholders.is_empty()
+    }
+
+    /// Replaces the [`Writer::placeholders`] in the `line` with their values.
+    fn substitute<'b>(&self, line: &'b str) -> Cow<'b, str> {
+        if self.placeholders.is_empty() || !line.contains('{') {
+            return Cow::Borrowed(line);
+        }
+
+        let mut result = String::with_capacity(line.len());
+        let mut rest = line;
+
+        while let Some(start) = rest.find('{') {
+            result.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let value = rest
+                .find('}')
+                .and_then(|end| Some((self.placeholders.get(&rest[1..end])?, end)));
+
+            if let Some((value, end)) = value {
+                result.push_str(value);
+                rest = &rest[end + 1..];
+            } else {
+                result.push('{');
+                rest = &rest[1..];
+            }
+        }
+
+        result.push_str(rest);
+        Cow::Owned(result)
+    }
+
+    /// Substitutes the placeholders of a line and shortens it.
+    fn rewrite_line(&self, line: &str) -> String {
+        let line = self.substitute(line);
+        self.fit_line(&line).into_owned()
+    }
+
+    /// Removes the [`Writer::droppable_attributes`] from the `line`, until it
+    /// is not longer than the [`Writer::max_line_length`].
+    fn fit_line<'b>(&self, line: &'b str) -> Cow<'b, str> {
+        let max = match self.max_line_length {
+            Some(max) if line.len() > max => max,
+            _ => return Cow::Borrowed(line),
+        };
+
+        let (prefix, attributes) = match line.split_once(':') {
+            Some(value) if line.starts_with("#EXT") => value,
+            _ => return Cow::Borrowed(line),
+        };
+
+        let mut pairs = AttributePairs::new(attributes).collect::<Vec<_>>();
+        let count = pairs.len();
+        // the length of `prefix:KEY=VALUE,KEY=VALUE`
+        let length = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(key, value)| key.len() + value.len() + 2)
+                .sum::<usize>()
+                + prefix.len()
+        };
+
+        for name in &self.droppable_attributes {
+            if length(&pairs) <= max {
+                break;
+            }
+
+            if let Some(i) = pairs.iter().position(|(key, _)| key == name) {
+                pairs.remove(i);
+            }
+        }
+
+        if pairs.len() == count {
+            return Cow::Borrowed(line);
+        }
+
+        let attributes = pairs
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        Cow::Owned(format!("{}:{}", prefix, attributes))
+    }
+
+    /// Returns the `#EXT-X-PROGRAM-DATE-TIME` tag, as it should be written.
+    pub(crate) fn program_date_time(&self, value: &ExtXProgramDateTime<'_>) -> String {
+        let line = value.to_string();
+
+        if self.date_time_decimals.is_none() && self.utc_format.is_none() {
+            return line;
+        }
+
+        format!(
+            "{}{}",
+            ExtXProgramDateTime::PREFIX,
+            self.date_time(&line[ExtXProgramDateTime::PREFIX.len()..])
+        )
+    }
+
+    /// Applies the [`Writer::date_time_decimals`] and the
+    /// [`Writer::utc_format`] to a date-time.
+    fn date_time(&self, input: &str) -> String {
+        let start = input.find(['T', 't', ' ']).unwrap_or(input.len());
+        let end = input[start..]
+            .find(['Z', 'z', '+', '-'])
+            .map_or(input.len(), |i| start + i);
+
+        let (date_time, offset) = input.split_at(end);
+        let (seconds, fraction) = date_time.split_once('.').unwrap_or((date_time, ""));
+
+        let fraction = match self.date_time_decimals {
+            Some(decimals) => {
+                let decimals = usize::from(decimals);
+
+                format!(
+                    "{:0<1$}",
+                    &fraction[..fraction.len().min(decimals)],
+                    decimals
+                )
+            }
+            None => fraction.to_string(),
+        };
+
+        let offset = match (self.utc_format, offset) {
+            (Some(UtcFormat::Z), "z" | "+00:00") => "Z",
+            (Some(UtcFormat::Numeric), "Z" | "z") => "+00:00",
+            _ => offset,
+        };
+
+        if fraction.is_empty() {
+            format!("{}{}", seconds, offset)
+        } else {
+            format!("{}.{}{}", seconds, fraction, offset)
+        }
+    }
+
+    /// Returns the version, that should be written in the `#EXT-X-VERSION`
+    /// tag.
+    pub(crate) fn version(&self, required: ProtocolVersion) -> ProtocolVersion {
+        self.version_override.unwrap_or(required)
+    }
+
+    /// Sets a hook, that is called for every uri, that is written (after the
+    /// [`Writer::uri_policy`] has been applied). The returned string is
+    /// written instead of the uri.
+    ///
+    /// This can for example be used to sign uris with a token, that is
+    /// different for each request, without having to clone the playlist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::{MediaSegment, UriKind, Writer};
+    /// use std::time::Duration;
+    ///
+    /// let playlist = MediaPlaylist::builder()
+    ///     .target_duration(Duration::from_secs(10))
+    ///     .segments(vec![MediaSegment::builder()
+    ///         .duration(Duration::from_secs(10))
+    ///         .uri("1.ts")
+    ///         .build()?])
+    ///     .build()?;
+    ///
+    /// let mut writer = Writer::new();
+    /// writer.set_sign_uri(|kind, uri| {
+    ///     if kind == UriKind::Segment {
+    ///         format!("{}?token=abc", uri)
+    ///     } else {
+    ///         uri.to_string()
+    ///     }
+    /// });
+    ///
+    /// assert!(writer
+    ///     .media_playlist_to_string(&playlist)?
+    ///     .contains("\n1.ts?token=abc\n"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_sign_uri<F>(&mut self, hook: F) -> &mut Self
+    where
+        F: Fn(UriKind, &str) -> String + Send + Sync + 'static,
+    {
+        self.sign_uri = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sets the [`UriNormalizer`], that is applied to every uri after the
+    /// [`Writer::uri_policy`] and before the [`Writer::set_sign_uri`] hook.
+    ///
+    /// By default the URIs are not normalized.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use hls_m3u8::{DefaultUriNormalizer, MediaSegment, UriPolicy, Writer};
+    /// use std::time::Duration;
+    ///
+    /// let playlist = MediaPlaylist::builder()
+    ///     .target_duration(Duration::from_secs(10))
+    ///     .segments(vec![MediaSegment::builder()
+    ///         .duration(Duration::from_secs(10))
+    ///         .uri("./low/first segment.ts")
+    ///         .build()?])
+    ///     .build()?;
+    ///
+    /// let mut writer = Writer::new();
+    /// writer.set_uri_policy(UriPolicy::Absolute("https://example.com/index.m3u8".into()));
+    /// writer.set_uri_normalizer(DefaultUriNormalizer);
+    ///
+    /// assert!(writer
+    ///     .media_playlist_to_string(&playlist)?
+    ///     .contains("\nhttps://example.com/low/first%20segment.ts\n"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_uri_normalizer<N: UriNormalizer + 'static>(&mut self, normalizer: N) -> &mut Self {
+        self.uri_normalizer = Some(Arc::new(normalizer));
+        self
+    }
+
+    /// Returns the uri, that should be written instead of the provided one.
+    ///
+    /// The [`Writer::placeholders`] are substituted first, so the
+    /// [`UriNormalizer`] and the [`Writer::set_sign_uri`] hook receive the uri,
+    /// that is requested by the client.
+    pub(crate) fn uri<'b>(&self, kind: UriKind, uri: &'b str) -> Cow<'b, str> {
+        let mut uri = match self.substitute(uri) {
+            Cow::Borrowed(uri) => self.uri_policy.apply(uri),
+            Cow::Owned(uri) => Cow::Owned(self.uri_policy.apply(&uri).into_owned()),
+        };
+
+        if let Some(normalizer) = &self.uri_normalizer {
+            uri = match uri {
+                Cow::Borrowed(uri) => normalizer.normalize(kind, uri),
+                Cow::Owned(uri) => Cow::Owned(normalizer.normalize(kind, &uri).into_owned()),
+            };
+        }
+
+        if let Some(sign_uri) = &self.sign_uri {
+            Cow::Owned(sign_uri(kind, &uri))
+        } else {
+            uri
+        }
+    }
+}
+
+/// Passes the output line by line through [`Writer::rewrite_line`].
+struct LineRewriter<'w, W> {
+    output: &'w mut W,
+    writer: &'w Writer,
+    /// The part of the current line, that has been written so far.
+    line: String,
+}
+
+impl<'w, W: fmt::Write> LineRewriter<'w, W> {
+    fn new(output: &'w mut W, writer: &'w Writer) -> Self {
+        Self {
+            output,
+            writer,
+            line: String::new(),
+        }
+    }
+
+    /// Writes the last line, which does not end with a newline.
+    fn finish(mut self) -> fmt::Result {
+        let line = std::mem::take(&mut self.line);
+        self.output.write_str(&self.writer.rewrite_line(&line))
+    }
+}
+
+impl<'w, W: fmt::Write> fmt::Write for LineRewriter<'w, W> {
+    fn write_str(&mut self, mut value: &str) -> fmt::Result {
+        while let Some(i) = value.find('\n') {
+            self.line.push_str(&value[..i]);
+
+            let line = std::mem::take(&mut self.line);
+            self.output.write_str(&self.writer.rewrite_line(&line))?;
+            self.output.write_char('\n')?;
+
+            value = &value[i + 1..];
+        }
+
+        self.line.push_str(value);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tags::ExtXKey;
+    use crate::tags::{ExtXSessionKey, VariantStream};
+    use crate::types::{DecryptionKey, EncryptionMethod, KeyFormat, StreamData};
+    use crate::{MediaSegment, VersionReason};
+    use pretty_assertions::assert_eq;
+
+    fn playlist() -> MediaPlaylist<'static> {
+        let key = ExtXKey::new(DecryptionKey::new(
+            EncryptionMethod::Aes128,
+            "https://www.example.com/key",
+        ));
+
+        MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(10))
+            .segments(vec![
+                MediaSegment::builder()
+                    .duration(Duration::from_secs(10))
+                    .keys(vec![key.clone()])
+                    .uri("1.ts")
+                    .build()
+                    .unwrap(),
+                MediaSegment::builder()
+                    .duration(Duration::from_secs(10))
+                    .keys(vec![key])
+                    .uri("2.ts")
+                    .build()
+                    .unwrap(),
+                MediaSegment::builder()
+                    .duration(Duration::from_secs(10))
+                    .keys(vec![ExtXKey::empty()])
+                    .uri("3.ts")
+                    .build()
+                    .unwrap(),
+                MediaSegment::builder()
+                    .duration(Duration::from_secs(10))
+                    .keys(vec![ExtXKey::empty()])
+                    .uri("4.ts")
+                    .build()
+                    .unwrap(),
+            ])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_resolve() {
+        // examples from https://tools.ietf.org/html/rfc3986#section-5.4
+        let base = "http://a/b/c/d;p?q";
+
+        for (uri, expected) in &[
+            ("g:h", "g:h"),
+            ("g", "http://a/b/c/g"),
+            ("./g", "http://a/b/c/g"),
+            ("g/", "http://a/b/c/g/"),
+            ("/g", "http://a/g"),
+            ("//g", "http://g"),
+            ("?y", "http://a/b/c/d;p?y"),
+            ("g?y", "http://a/b/c/g?y"),
+            ("#s", "http://a/b/c/d;p?q#s"),
+            ("g#s", "http://a/b/c/g#s"),
+            ("g?y#s", "http://a/b/c/g?y#s"),
+            (";x", "http://a/b/c/;x"),
+            ("g;x", "http://a/b/c/g;x"),
+            ("", "http://a/b/c/d;p?q"),
+            (".", "http://a/b/c/"),
+            ("./", "http://a/b/c/"),
+            ("..", "http://a/b/"),
+            ("../", "http://a/b/"),
+            ("../g", "http://a/b/g"),
+            ("../..", "http://a/"),
+            ("../../", "http://a/"),
+            ("../../g", "http://a/g"),
+            ("../../../g", "http://a/g"),
+            ("/./g", "http://a/g"),
+            ("/../g", "http://a/g"),
+            ("g.", "http://a/b/c/g."),
+            ("g..", "http://a/b/c/g.."),
+            ("./../g", "http://a/b/g"),
+            ("g/./h", "http://a/b/c/g/h"),
+            ("g/../h", "http://a/b/c/h"),
+        ] {
+            assert_eq!(resolve(base, uri), *expected, "{:?}", uri);
+        }
+
+        assert_eq!(resolve("http://a", "g"), "http://a/g");
+    }
+
+    #[test]
+    fn test_relativize() {
+        let base = "https://example.com/video/index.m3u8?token=1";
+
+        assert_eq!(relativize(base, "https://example.com/video/1.ts"), "1.ts");
+        assert_eq!(
+            relativize(base, "https://example.com/video/low/1.ts"),
+            "low/1.ts"
+        );
+        assert_eq!(
+            relativize(base, "https://example.com/audio/1.ts"),
+            "/audio/1.ts"
+        );
+        assert_eq!(
+            relativize(base, "https://example.org/video/1.ts"),
+            "https://example.org/video/1.ts"
+        );
+        assert_eq!(relativize(base, "1.ts"), "1.ts");
+        assert_eq!(relativize(base, "https://example.com/video/"), "/video/");
+
+        // the base does not end with a `/`:
+        let base = "https://example.com";
+
+        assert_eq!(relativize(base, "https://example.com/1.ts"), "/1.ts");
+        assert_eq!(
+            relativize(base, "https://example.com.evil/1.ts"),
+            "https://example.com.evil/1.ts"
+        );
+        assert_eq!(
+            relativize(
+                "https://example.com/video",
+                "https://example.com/videos/1.ts"
+            ),
+            "videos/1.ts"
+        );
+    }
+
+    #[test]
+    fn test_default_uri_normalizer() {
+        for (uri, expected) in &[
+            ("https://example.com/1.ts", "https://example.com/1.ts"),
+            (
+                "https://example.com/a/./b/../1.ts",
+                "https://example.com/a/1.ts",
+            ),
+            ("https://example.com/../1.ts", "https://example.com/1.ts"),
+            ("/a/../1.ts?b=../c", "/1.ts?b=../c"),
+            ("../1.ts", "../1.ts"),
+            ("./a/../1.ts", "./a/../1.ts"),
+            ("first segment.ts", "first%20segment.ts"),
+            ("\u{e4}.ts", "%C3%A4.ts"),
+            ("1.ts?a=%2f&b=%zz", "1.ts?a=%2F&b=%25zz"),
+            ("1.ts?a=\"b\"", "1.ts?a=%22b%22"),
+            ("data:text/plain;base64,AAAA", "data:text/plain;base64,AAAA"),
+        ] {
+            assert_eq!(
+                DefaultUriNormalizer.normalize(UriKind::Segment, uri),
+                *expected,
+                "{:?}",
+                uri
+            );
+        }
+
+        assert!(matches!(
+            DefaultUriNormalizer.normalize(UriKind::Segment, "https://example.com/1.ts%2F"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_uri_normalizer() {
+        let playlist = MasterPlaylist::builder()
+            .variant_streams(vec![VariantStream::ExtXStreamInf {
+                uri: "http://example.com/low/../high/index.m3u8".into(),
+                frame_rate: None,
+                audio: None,
+                subtitles: None,
+                closed_captions: None,
+                stream_data: StreamData::new(1_000_000),
+            }])
+            .build()
+            .unwrap();
+
+        let mut writer = Writer::new();
+        writer.set_uri_normalizer(|kind: UriKind, uri: &str| {
+            assert_eq!(kind, UriKind::StreamInf);
+            let uri = DefaultUriNormalizer.normalize(kind, uri);
+            uri.replacen("http://", "https://", 1)
+        });
+
+        assert_eq!(
+            writer.master_playlist_to_string(&playlist).unwrap(),
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-STREAM-INF:BANDWIDTH=1000000\n",
+                "https://example.com/high/index.m3u8\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_uri_policy() {
+        let mut writer = Writer::new();
+        writer.set_uri_policy(UriPolicy::Absolute(
+            "https://example.com/video/index.m3u8".into(),
+        ));
+
+        let output = writer.media_playlist_to_string(&playlist()).unwrap();
+
+        assert!(output.contains("\nhttps://example.com/video/1.ts\n"));
+        assert!(output.contains("URI=\"https://www.example.com/key\""));
+
+        writer.set_uri_policy(UriPolicy::RelativeTo("https://www.example.com/".into()));
+
+        let output = writer.media_playlist_to_string(&playlist()).unwrap();
+
+        assert!(output.contains("\n1.ts\n"));
+        assert!(output.contains("URI=\"key\""));
+    }
+
+    #[test]
+    fn test_sign_uri() {
+        let mut writer = Writer::new();
+        writer
+            .set_uri_policy(UriPolicy::Absolute("https://example.com/".into()))
+            .set_sign_uri(|kind, uri| format!("{}?kind={:?}", uri, kind));
+
+        let output = writer.media_playlist_to_string(&playlist()).unwrap();
+
+        assert!(output.contains("\nhttps://example.com/1.ts?kind=Segment\n"));
+        assert!(output.contains("URI=\"https://www.example.com/key?kind=Key\""));
+
+        let playlist = MasterPlaylist::builder()
+            .variant_streams(vec![VariantStream::ExtXStreamInf {
+                uri: "low/index.m3u8".into(),
+                frame_rate: None,
+                audio: None,
+                subtitles: None,
+                closed_captions: None,
+                stream_data: StreamData::new(150_000),
+            }])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            writer.master_playlist_to_string(&playlist).unwrap(),
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+                "https://example.com/low/index.m3u8?kind=StreamInf\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_version_override() {
+        let mut writer = Writer::new();
+        writer.set_version_override(Some(ProtocolVersion::V3));
+
+        assert!(writer
+            .media_playlist_to_string(&playlist())
+            .unwrap()
+            .starts_with("#EXTM3U\n#EXT-X-VERSION:3\n"));
+
+        let playlist = MasterPlaylist::builder()
+            .session_keys(vec![ExtXSessionKey::new(
+                DecryptionKey::builder()
+                    .method(EncryptionMethod::Aes128)
+                    .uri("https://www.example.com/key")
+                    .iv([0; 16])
+                    .format(KeyFormat::Identity)
+                    .build()
+                    .unwrap(),
+            )])
+            .build()
+            .unwrap();
+
+        writer.set_version_override(Some(ProtocolVersion::V1));
+        let error = writer.master_playlist_to_string(&playlist).unwrap_err();
+
+        assert_eq!(
+            error.version_reasons(),
+            &[
+                VersionReason::new("IV", ProtocolVersion::V2),
+                VersionReason::new("KEYFORMAT", ProtocolVersion::V5),
+            ]
+        );
+        assert_eq!(
+            error.to_string(),
+            concat!(
+                "the playlist requires V5, but V1 has been specified",
+                "; IV requires V2; drop IV or raise version",
+                "; KEYFORMAT requires V5; drop KEYFORMAT or raise version"
+            )
+        );
+
+        writer.set_version_override(Some(ProtocolVersion::V2));
+        assert_eq!(
+            writer
+                .master_playlist_to_string(&playlist)
+                .unwrap_err()
+                .version_reasons(),
+            &[VersionReason::new("KEYFORMAT", ProtocolVersion::V5)]
+        );
+    }
+
+    #[test]
+    fn test_key_policy_changed() {
+        assert_eq!(
+            Writer::new().media_playlist_to_string(&playlist()).unwrap(),
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-VERSION:2\n",
+                "#EXT-X-TARGETDURATION:10\n",
+                "#EXT-X-KEY:METHOD=AES-128,URI=\"https://www.example.com/key\"\n",
+                "#EXTINF:10,\n",
+                "1.ts\n",
+                "#EXTINF:10,\n",
+                "2.ts\n",
+                "#EXT-X-KEY:METHOD=NONE\n",
+                "#EXTINF:10,\n",
+                "3.ts\n",
+                "#EXT-X-KEY:METHOD=NONE\n",
+                "#EXTINF:10,\n",
+                "4.ts\n",
+            )
+        );
+
+        assert_eq!(
+            Writer::new().media_playlist_to_string(&playlist()).unwrap(),
+            playlist().to_string()
+        );
+    }
+
+    #[test]
+    fn test_key_policy_dedup() {
+        let mut writer = Writer::new();
+        writer.set_key_policy(KeyWritePolicy::Dedup);
+
+        assert_eq!(
+            writer.media_playlist_to_string(&playlist()).unwrap(),
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-VERSION:2\n",
+                "#EXT-X-TARGETDURATION:10\n",
+                "#EXT-X-KEY:METHOD=AES-128,URI=\"https://www.example.com/key\"\n",
+                "#EXTINF:10,\n",
+                "1.ts\n",
+                "#EXTINF:10,\n",
+                "2.ts\n",
+                "#EXT-X-KEY:METHOD=NONE\n",
+                "#EXTINF:10,\n",
+                "3.ts\n",
+                "#EXTINF:10,\n",
+                "4.ts\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_key_policy_per_segment() {
+        let mut writer = Writer::new();
+        writer.set_key_policy(KeyWritePolicy::PerSegment);
+
+        assert_eq!(
+            writer.media_playlist_to_string(&playlist()).unwrap(),
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-VERSION:2\n",
+                "#EXT-X-TARGETDURATION:10\n",
+                "#EXT-X-KEY:METHOD=AES-128,URI=\"https://www.example.com/key\"\n",
+                "#EXTINF:10,\n",
+                "1.ts\n",
+                "#EXT-X-KEY:METHOD=AES-128,URI=\"https://www.example.com/key\"\n",
+                "#EXTINF:10,\n",
+                "2.ts\n",
+                "#EXT-X-KEY:METHOD=NONE\n",
+                "#EXTINF:10,\n",
+                "3.ts\n",
+                "#EXTINF:10,\n",
+                "4.ts\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_key_policy_always() {
+        let mut writer = Writer::new();
+        writer.set_key_policy(KeyWritePolicy::Always);
+
+        assert_eq!(
+            writer.media_playlist_to_string(&playlist()).unwrap(),
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-VERSION:2\n",
+                "#EXT-X-TARGETDURATION:10\n",
+                "#EXT-X-KEY:METHOD=AES-128,URI=\"https://www.example.com/key\"\n",
+                "#EXTINF:10,\n",
+                "1.ts\n",
+                "#EXT-X-KEY:METHOD=AES-128,URI=\"https://www.example.com/key\"\n",
+                "#EXTINF:10,\n",
+                "2.ts\n",
+                "#EXT-X-KEY:METHOD=NONE\n",
+                "#EXTINF:10,\n",
+                "3.ts\n",
+                "#EXT-X-KEY:METHOD=NONE\n",
+                "#EXTINF:10,\n",
+                "4.ts\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_max_line_length() {
+        let mut writer = Writer::new();
+        writer.set_max_line_length(Some(60));
+        writer.set_droppable_attributes(vec!["IV".into(), "KEYFORMAT".into()]);
+
+        let line = concat!(
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"https://www.example.com/key\",",
+            "IV=0x10ef8f758ca555115584bb5b3c687f52"
+        );
+        assert_eq!(
+            writer.fit_line(line),
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"https://www.example.com/key\""
+        );
+
+        // the line is too long, but there are no attributes to remove:
+        let line = "#EXT-X-KEY:METHOD=AES-128,URI=\"https://www.example.com/a/long/key\"";
+        assert_eq!(writer.fit_line(line), line);
+        assert_eq!(writer.fit_line("#EXTINF:10,"), "#EXTINF:10,");
+
+        let output = writer.media_playlist_to_string(&playlist()).unwrap();
+        assert_eq!(output, playlist().to_string());
+        assert!(writer.check_line_lengths(&output).is_empty());
+
+        writer.set_max_line_length(Some(20));
+        assert_eq!(writer.check_line_lengths(&output).warnings().count(), 4);
+
+        writer.set_max_line_length(None);
+        assert!(writer.check_line_lengths(&output).is_empty());
+    }
+
+    #[test]
+    fn test_date_time() {
+        let mut writer = Writer::new();
+        assert_eq!(
+            writer.date_time("2010-02-19T14:54:23.031+08:00"),
+            "2010-02-19T14:54:23.031+08:00"
+        );
+
+        writer.set_date_time_decimals(Some(3));
+        assert_eq!(
+            writer.date_time("2010-02-19T14:54:23+08:00"),
+            "2010-02-19T14:54:23.000+08:00"
+        );
+        assert_eq!(
+            writer.date_time("2010-02-19T14:54:23.5-05:00"),
+            "2010-02-19T14:54:23.500-05:00"
+        );
+        assert_eq!(
+            writer.date_time("2010-02-19T14:54:23.123456Z"),
+            "2010-02-19T14:54:23.123Z"
+        );
+
+        writer.set_date_time_decimals(Some(0));
+        assert_eq!(
+            writer.date_time("2010-02-19T14:54:23.123Z"),
+            "2010-02-19T14:54:23Z"
+        );
+
+        writer.set_date_time_decimals(None);
+        writer.set_utc_format(Some(UtcFormat::Numeric));
+        assert_eq!(
+            writer.date_time("2010-02-19T06:54:23.031Z"),
+            "2010-02-19T06:54:23.031+00:00"
+        );
+        assert_eq!(
+            writer.date_time("2010-02-19T14:54:23.031+08:00"),
+            "2010-02-19T14:54:23.031+08:00"
+        );
+
+        writer.set_utc_format(Some(UtcFormat::Z));
+        assert_eq!(
+            writer.date_time("2010-02-19T06:54:23.031+00:00"),
+            "2010-02-19T06:54:23.031Z"
+        );
+        // an unknown local offset is not UTC:
+        assert_eq!(
+            writer.date_time("2010-02-19T06:54:23.031-00:00"),
+            "2010-02-19T06:54:23.031-00:00"
+        );
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let mut writer = Writer::new();
+        writer
+            .insert_placeholder("TOKEN", "abc")
+            .insert_placeholder("CDN", "https://cdn.example.com");
+
+        assert_eq!(writer.substitute("1.ts"), "1.ts");
+        assert_eq!(
+            writer.substitute("{CDN}/1.ts?token={TOKEN}"),
+            "https://cdn.example.com/1.ts?token=abc"
+        );
+        assert_eq!(writer.substitute("{UNKNOWN}{TOKEN}{"), "{UNKNOWN}abc{");
+        assert_eq!(writer.substitute("{{TOKEN}}"), "{abc}");
+
+        let playlist = MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(10))
+            .segments(vec![MediaSegment::builder()
+                .duration(Duration::from_secs(10))
+                .uri("{CDN}/1.ts?token={TOKEN}")
+                .build()
+                .unwrap()])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            writer.media_playlist_to_string(&playlist).unwrap(),
+            playlist.to_string().replace(
+                "{CDN}/1.ts?token={TOKEN}",
+                "https://cdn.example.com/1.ts?token=abc"
+            )
+        );
+
+        // the placeholders are substituted before the uri is normalized and signed:
+        let mut writer = Writer::new();
+        writer
+            .insert_placeholder("CDN", "cdn.example.com")
+            .set_uri_normalizer(DefaultUriNormalizer)
+            .set_sign_uri(|_, uri| format!("{}?sig={}", uri, uri.len()));
+
+        let playlist = MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(10))
+            .segments(vec![MediaSegment::builder()
+                .duration(Duration::from_secs(10))
+                .uri("https://{CDN}/1.ts")
+                .build()
+                .unwrap()])
+            .build()
+            .unwrap();
+
+        assert!(writer
+            .media_playlist_to_string(&playlist)
+            .unwrap()
+            .contains("\nhttps://cdn.example.com/1.ts?sig=28\n"));
+
+        // values, that would inject lines or end a quoted string, are rejected:
+        for value in &["abc\n#EXT-X-ENDLIST", "abc\r", "abc\",URI=\"x"] {
+            writer.insert_placeholder("TOKEN", *value);
+
+            assert!(writer.media_playlist_to_string(&playlist).is_err());
+            assert!(writer
+                .master_playlist_to_string(&MasterPlaylist::default())
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_duration_precision() {
+        assert_eq!(
+            DurationPrecision::infer("#EXTINF:4,\n1.ts\n#EXTINF:4.5,\n2.ts"),
+            DurationPrecision::Shortest
+        );
+        assert_eq!(
+            DurationPrecision::infer("#EXTINF:4.000,\n1.ts\n#EXTINF:4.500,title\n2.ts"),
+            DurationPrecision::Decimals(3)
+        );
+        // the precision differs, so it can not be reproduced:
+        assert_eq!(
+            DurationPrecision::infer("#EXTINF:4.000,\n1.ts\n#EXTINF:4.50,\n2.ts"),
+            DurationPrecision::Shortest
+        );
+        assert_eq!(DurationPrecision::infer(""), DurationPrecision::Shortest);
+
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-VERSION:3\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXTINF:4.000,\n",
+            "1.ts\n",
+            "#EXTINF:3.960,\n",
+            "2.ts\n",
+        );
+
+        let playlist = MediaPlaylist::try_from(input).unwrap();
+        assert_eq!(playlist.duration_precision, DurationPrecision::Decimals(3));
+        assert_eq!(playlist.to_string(), input);
+
+        let mut writer = Writer::new();
+        writer.set_duration_precision(Some(DurationPrecision::Shortest));
+
+        assert_eq!(
+            writer.media_playlist_to_string(&playlist).unwrap(),
+            input.replace("4.000", "4").replace("3.960", "3.96")
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! Compile time checks, that the public types implement the expected auto
+//! traits. If a type stops implementing one of them (for example, because it
+//! contains an `Rc`), this file will fail to compile.
+use std::panic::{RefUnwindSafe, UnwindSafe};
+
+use hls_m3u8::attribute::AttributePairs;
+use hls_m3u8::builder::tags::{ExtXDateRangeBuilder, ExtXMediaBuilder, ExtXSessionDataBuilder};
+use hls_m3u8::builder::types::{DecryptionKeyBuilder, StreamDataBuilder};
+use hls_m3u8::builder::{MasterPlaylistBuilder, MediaPlaylistBuilder, MediaSegmentBuilder};
+use hls_m3u8::bundle::{BitrateStats, Bundle, PdtAlignment};
+use hls_m3u8::chapters::Chapter;
+use hls_m3u8::generator::Config as GeneratorConfig;
+use hls_m3u8::interstitial::{InterstitialBuilder, Restriction, Snap};
+use hls_m3u8::tags::{
+    CueMarker, ExtInf, ExtXByteRange, ExtXContentSteering, ExtXDateRange, ExtXKey, ExtXMap,
+    ExtXMedia, ExtXPart, ExtXPreloadHint, ExtXProgramDateTime, ExtXRenditionReport,
+    ExtXServerControl, ExtXSessionData, ExtXSessionKey, ExtXSkip, ExtXStart, ExtXVersion,
+    SessionData, VariantStream,
+};
+use hls_m3u8::types::{
+    ByteRange, ChannelUsage, Channels, ClosedCaptions, Codecs, Cue, CueHint, DecryptionKey,
+    EncryptionMethod, Feature, Float, HdcpLevel, InStreamId, InitializationVector, KeyFormat,
+    KeyFormatVersions, MediaType, PlaylistType, PreloadHintType, ProtocolVersion, Resolution,
+    SessionDataFormat, StreamData, UFloat, UriRef, Value, VideoLayout, VideoLayoutEntry,
+    VideoRange,
+};
+use hls_m3u8::{
+    Capabilities, Compression, DefaultUriNormalizer, DurationPrecision, Error, Ignored,
+    MasterPlaylist, MasterPlaylistChange, MasterPlaylistEvent, MasterPlaylistParser, MediaPlaylist,
+    MediaSegment, ParseOptions, PendingSegment, PlaylistHeader, PlaylistKind, ProtocolCoverage,
+    Strictness, SystemClock, UpdateViolation, UtcFormat, ValidationReport, Validator, Writer,
+};
+
+fn assert_auto_traits<T: Send + Sync + Unpin + UnwindSafe + RefUnwindSafe>() {}
+
+macro_rules! assert_auto_traits {
+    ( $( $type:ty ),+ $(,)* ) => {
+        $(
+            assert_auto_traits::<$type>();
+        )+
+    };
+}
+
+#[test]
+fn test_playlists() {
+    assert_auto_traits![
+        MasterPlaylist<'static>,
+        MasterPlaylistChange,
+        MasterPlaylistEvent,
+        MasterPlaylistParser,
+        MediaPlaylist<'static>,
+        MediaSegment<'static>,
+        PendingSegment<'static>,
+        PlaylistHeader,
+        PlaylistKind,
+        MasterPlaylistBuilder<'static>,
+        MediaPlaylistBuilder<'static>,
+        MediaSegmentBuilder<'static>,
+        Bundle<'static>,
+        PdtAlignment,
+        BitrateStats,
+        Chapter,
+        GeneratorConfig,
+        InterstitialBuilder<'static>,
+        Restriction,
+        Snap,
+        Error,
+        Ignored<'static>,
+        ParseOptions,
+        Strictness,
+        DurationPrecision,
+        ProtocolCoverage,
+        Capabilities,
+        Compression,
+        ValidationReport,
+        Validator,
+        SystemClock,
+        DefaultUriNormalizer,
+        UpdateViolation,
+        UtcFormat,
+        AttributePairs<'static>,
+    ];
+
+    // the sign hook of a `Writer` is not required to be unwind safe:
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Writer>();
+}
+
+#[test]
+fn test_tags() {
+    assert_auto_traits![
+        ExtInf<'static>,
+        ExtXByteRange,
+        ExtXContentSteering<'static>,
+        ExtXDateRange<'static>,
+        CueMarker<'static>,
+        ExtXDateRangeBuilder<'static>,
+        ExtXKey<'static>,
+        ExtXMap<'static>,
+        ExtXMedia<'static>,
+        ExtXMediaBuilder<'static>,
+        ExtXPart<'static>,
+        ExtXPreloadHint<'static>,
+        ExtXRenditionReport<'static>,
+        ExtXProgramDateTime<'static>,
+        ExtXSessionData<'static>,
+        ExtXSessionDataBuilder<'static>,
+        ExtXSessionKey<'static>,
+        ExtXServerControl,
+        ExtXSkip<'static>,
+        ExtXStart,
+        ExtXVersion,
+        SessionData<'static>,
+        VariantStream<'static>,
+    ];
+}
+
+#[test]
+fn test_types() {
+    assert_auto_traits![
+        ByteRange,
+        ChannelUsage,
+        Channels,
+        ClosedCaptions<'static>,
+        Codecs<'static>,
+        Cue,
+        CueHint,
+        DecryptionKey<'static>,
+        DecryptionKeyBuilder<'static>,
+        EncryptionMethod,
+        Feature,
+        Float,
+        HdcpLevel,
+        InStreamId,
+        InitializationVector,
+        KeyFormat<'static>,
+        KeyFormatVersions,
+        MediaType,
+        PlaylistType,
+        PreloadHintType,
+        ProtocolVersion,
+        Resolution,
+        SessionDataFormat,
+        StreamData<'static>,
+        StreamDataBuilder<'static>,
+        UFloat,
+        UriRef<'static>,
+        Value<'static>,
+        VideoLayout,
+        VideoLayoutEntry,
+        VideoRange,
+    ];
+}
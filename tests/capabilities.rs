@@ -0,0 +1,13 @@
+#![cfg(feature = "serde")]
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_capabilities_json() {
+    let value = serde_json::to_value(hls_m3u8::capabilities()).unwrap();
+
+    assert_eq!(value["crate_version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(value["specification"], "draft-pantos-hls-rfc8216bis-13");
+    assert_eq!(value["protocol_versions"][6], "V7");
+    assert_eq!(value["tags"][0]["name"], "EXTM3U");
+    assert_eq!(value["tags"][0]["is_supported"], true);
+}
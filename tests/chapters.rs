@@ -0,0 +1,27 @@
+#![cfg(feature = "serde")]
+use std::time::Duration;
+
+use hls_m3u8::chapters::Chapter;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_chapters_json() {
+    let chapters: Vec<Chapter> = serde_json::from_str(concat!(
+        "[",
+        "{\"start-time\": 0.0, \"title\": \"Intro\"},",
+        "{\"start-time\": 90.5, \"title\": \"Main\", \"image\": \"main.jpg\"}",
+        "]"
+    ))
+    .unwrap();
+
+    let mut main = Chapter::new(Duration::from_secs_f64(90.5), "Main");
+    main.set_image(Some("main.jpg"));
+
+    let expected = vec![Chapter::new(Duration::ZERO, "Intro"), main];
+
+    assert_eq!(chapters, expected);
+    assert_eq!(
+        serde_json::to_string(&expected[0]).unwrap(),
+        "{\"start-time\":0.0,\"title\":\"Intro\"}"
+    );
+}
@@ -0,0 +1,27 @@
+#![cfg(feature = "serde")]
+use hls_m3u8::types::ClosedCaptions;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_closed_captions_json() {
+    let group_id = ClosedCaptions::group_id("cc");
+
+    assert_eq!(
+        serde_json::to_string(&group_id).unwrap(),
+        "{\"kind\":\"group_id\",\"group_id\":\"cc\"}"
+    );
+    assert_eq!(
+        serde_json::to_string(&ClosedCaptions::None).unwrap(),
+        "{\"kind\":\"none\"}"
+    );
+
+    assert_eq!(
+        serde_json::from_str::<ClosedCaptions<'_>>("{\"kind\":\"group_id\",\"group_id\":\"cc\"}")
+            .unwrap(),
+        group_id
+    );
+    assert_eq!(
+        serde_json::from_str::<ClosedCaptions<'_>>("{\"kind\":\"none\"}").unwrap(),
+        ClosedCaptions::None
+    );
+}
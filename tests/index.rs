@@ -0,0 +1,37 @@
+#![cfg(feature = "serde")]
+use std::convert::TryFrom;
+
+use hls_m3u8::index::SegmentIndex;
+use hls_m3u8::MediaPlaylist;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_segment_index_json() {
+    let playlist = MediaPlaylist::try_from(concat!(
+        "#EXTM3U\n",
+        "#EXT-X-TARGETDURATION:10\n",
+        "#EXT-X-VERSION:4\n",
+        "#EXTINF:9.5,\n",
+        "#EXT-X-BYTERANGE:1000@0\n",
+        "video.ts\n",
+        "#EXTINF:10,\n",
+        "2.ts\n",
+    ))
+    .unwrap();
+
+    let index = SegmentIndex::from(&playlist);
+    let json = serde_json::to_string(&index).unwrap();
+
+    assert_eq!(
+        json,
+        concat!(
+            "{\"target_duration\":10.0,\"media_sequence\":0,\"entries\":[",
+            "{\"sequence\":0,\"uri\":\"video.ts\",\"byte_range\":{\"start\":0,\"end\":1000},",
+            "\"duration\":9.5,\"pts_offset\":0.0},",
+            "{\"sequence\":1,\"uri\":\"2.ts\",\"byte_range\":null,",
+            "\"duration\":10.0,\"pts_offset\":9.5}",
+            "]}"
+        )
+    );
+    assert_eq!(serde_json::from_str::<SegmentIndex>(&json).unwrap(), index);
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "serde")]
+use std::time::Duration;
+
+use hls_m3u8::interstitial::{Asset, AssetList};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_asset_list_json() {
+    let list: AssetList = serde_json::from_str(concat!(
+        "{\"ASSETS\": [",
+        "{\"URI\": \"https://example.com/ad1.m3u8\", \"DURATION\": 15.0},",
+        "{\"URI\": \"https://example.com/ad2.m3u8\", \"DURATION\": 30.5}",
+        "]}"
+    ))
+    .unwrap();
+
+    let expected = AssetList::new(vec![
+        Asset::new("https://example.com/ad1.m3u8", Duration::from_secs(15)),
+        Asset::new(
+            "https://example.com/ad2.m3u8",
+            Duration::from_secs_f64(30.5),
+        ),
+    ]);
+
+    assert_eq!(list, expected);
+    assert_eq!(
+        serde_json::from_str::<AssetList>(&serde_json::to_string(&expected).unwrap()).unwrap(),
+        expected
+    );
+}
@@ -24,6 +24,7 @@ fn parse() {
                     closed_captions: None,
                     stream_data: StreamData::builder()
                         .bandwidth(10000000)
+                        .program_id(1)
                         .build()
                         .unwrap()
                 }
@@ -129,3 +129,36 @@ generate_tests! {
         )
     }
 }
+
+#[test]
+fn test_comment_before_variant_uri() {
+    assert_eq!(
+        MasterPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+            "# the uri follows\n",
+            "\n",
+            "low/index.m3u8\n",
+        ))
+        .unwrap(),
+        MasterPlaylist::builder()
+            .variant_streams(vec![VariantStream::ExtXStreamInf {
+                uri: "low/index.m3u8".into(),
+                frame_rate: None,
+                audio: None,
+                subtitles: None,
+                closed_captions: None,
+                stream_data: StreamData::new(150_000),
+            }])
+            .push_unknown_tag(0, "# the uri follows")
+            .build()
+            .unwrap()
+    );
+
+    assert!(MasterPlaylist::try_from(concat!(
+        "#EXTM3U\n",
+        "#EXT-X-STREAM-INF:BANDWIDTH=150000\n",
+        "# the uri is missing\n",
+    ))
+    .is_err());
+}
@@ -230,10 +230,7 @@ generate_tests! {
                     .unwrap(),
             ])
             .has_end_list(true)
-            .unknown(vec![
-                // deprecated tag:
-                "#EXT-X-ALLOW-CACHE:YES".into()
-            ])
+            .allow_cache(true)
             .build()
             .unwrap(),
         concat!(
@@ -242,6 +239,7 @@ generate_tests! {
             "#EXT-X-TARGETDURATION:10\n",
             "#EXT-X-MEDIA-SEQUENCE:1\n",
             "#EXT-X-PLAYLIST-TYPE:VOD\n",
+            "#EXT-X-ALLOW-CACHE:YES\n",
 
             "#EXT-X-BYTERANGE:522828@0\n",
             "#EXTINF:10,\n",
@@ -311,7 +309,6 @@ generate_tests! {
             "#EXTINF:1.4167,\n",
             "hls_450k_video.ts\n",
 
-            "#EXT-X-ALLOW-CACHE:YES\n",
             "#EXT-X-ENDLIST\n"
         )
     },